@@ -1,28 +1,138 @@
 use crate::errors::*;
-use crate::commands::{self, Result};
-use crate::input::KeyMap;
+use crate::commands::{self, Command, Result};
+use crate::input::{Key, KeyMap};
+use smallvec::SmallVec;
 use scribe::Buffer;
+use scribe::buffer::{LineRange, Position, Range};
 use std::mem;
 use crate::models::application::{Application, Mode};
 use crate::models::application::modes::*;
 use crate::util;
+use crate::view::SpellChecker;
+use unicode_segmentation::UnicodeSegmentation;
 
 pub fn handle_input(app: &mut Application) -> Result {
     // Listen for and respond to user input.
-    let commands = app.view.last_key().as_ref().and_then(|key| {
-        app.mode_str().and_then(|mode| {
-            app.preferences.borrow().keymap().commands_for(&mode, &key)
-        })
-    });
+    let (key, mode) = match (app.view.last_key().clone(), app.mode_str()) {
+        (Some(key), Some(mode)) => (key, mode),
+        _ => return Ok(()),
+    };
 
-    if let Some(coms) = commands {
-        // Run all commands, stopping at the first error encountered, if any.
-        for com in coms {
-            com(app)?;
+    // Accumulate digits into a count for the next command (e.g. the 5 in
+    // 5j), unless they're bound to something else. A zero can only follow
+    // other digits, as it's bound to command mode (and a count of zero is
+    // meaningless, anyway).
+    if let Key::Char(character) = key {
+        if let Some(digit) = character.to_digit(10) {
+            let bound = app.preferences.borrow().keymap().commands_for(mode, &key).is_some();
+            let continues_count = app.count.is_some() || (digit > 0 && !bound);
+            if app.pending_keys.is_empty() && continues_count {
+                app.count = Some(
+                    app.count.unwrap_or(0).saturating_mul(10).saturating_add(digit as usize)
+                );
+                return Ok(());
+            }
         }
     }
 
-    Ok(())
+    // Add the key to any pending keys, and check them against multi-key bindings.
+    let mut keys = app.pending_keys.take();
+    keys.push(key);
+    let (starts_sequence, sequence_commands) = {
+        let preferences = app.preferences.borrow();
+        let keymap = preferences.keymap();
+
+        (keymap.starts_sequence(mode, &keys), keymap.commands_for_sequence(mode, &keys))
+    };
+
+    if starts_sequence {
+        // Wait for the rest of the sequence.
+        app.pending_keys.set(keys);
+        return Ok(());
+    } else if let Some(coms) = sequence_commands {
+        return run_commands(app, coms);
+    }
+
+    // The sequence didn't pan out; handle the keys that led
+    // up to it on their own, and start over with the latest.
+    if keys.len() > 1 {
+        let key = keys.pop();
+        run_individual_keys(app, keys)?;
+        app.view.last_key = key;
+        return handle_input(app);
+    }
+
+    let commands = app.preferences.borrow().keymap().commands_for(mode, &keys[0]);
+    match commands {
+        Some(coms) => run_commands(app, coms),
+        None => {
+            app.count = None;
+            Ok(())
+        }
+    }
+}
+
+/// Handles keys that have been waiting on the rest of a multi-key binding
+/// for too long, running the binding they make up, if there is one, or
+/// running their individual bindings otherwise.
+pub(crate) fn handle_pending_keys(app: &mut Application) -> Result {
+    let keys = app.pending_keys.take();
+    let sequence_commands = app.mode_str().and_then(|mode| {
+        app.preferences.borrow().keymap().commands_for_sequence(mode, &keys)
+    });
+
+    if let Some(coms) = sequence_commands {
+        run_commands(app, coms)
+    } else {
+        run_individual_keys(app, keys)
+    }
+}
+
+fn run_individual_keys(app: &mut Application, keys: Vec<Key>) -> Result {
+    let last_key = app.view.last_key.take();
+    let result = keys.into_iter().try_for_each(|key| {
+        let commands = app.mode_str().and_then(|mode| {
+            app.preferences.borrow().keymap().commands_for(mode, &key)
+        });
+        app.view.last_key = Some(key);
+
+        commands.map_or(Ok(()), |coms| run_commands(app, coms))
+    });
+    app.view.last_key = last_key;
+
+    result
+}
+
+fn run_commands(app: &mut Application, commands: SmallVec<[Command; 4]>) -> Result {
+    // Run all commands, stopping at the first error encountered, if any.
+    let result = commands.into_iter().try_for_each(|com| com(app));
+
+    // A count only applies to the binding that follows it,
+    // whether or not its commands made use of it.
+    app.count = None;
+    app.clipboard.expire_paste();
+
+    result
+}
+
+/// Repeats the last change by replaying the key presses that made it up,
+/// starting from the cursor's current position (and the current mode).
+pub fn repeat_last_change(app: &mut Application) -> Result {
+    let keys = app.last_change.keys().to_vec();
+    if keys.is_empty() {
+        bail!("No change to repeat");
+    }
+
+    let last_key = app.view.last_key.take();
+    app.last_change.set_replaying(true);
+    let result = keys.into_iter().try_for_each(|key| {
+        app.view.last_key = Some(key);
+        handle_input(app)
+    });
+    app.last_change.set_replaying(false);
+    app.view.last_key = last_key;
+
+    result
 }
 
 pub fn switch_to_normal_mode(app: &mut Application) -> Result {
@@ -34,6 +144,7 @@ pub fn switch_to_normal_mode(app: &mut Application) -> Result {
 
 pub fn switch_to_insert_mode(app: &mut Application) -> Result {
     if app.workspace.current_buffer().is_some() {
+        util::ensure_writable(app)?;
         commands::buffer::start_command_group(app)?;
         app.mode = Mode::Insert;
         commands::view::scroll_to_cursor(app)?;
@@ -94,8 +205,32 @@ pub fn switch_to_line_jump_mode(app: &mut Application) -> Result {
     Ok(())
 }
 
+pub fn switch_to_set_mark_mode(app: &mut Application) -> Result {
+    if app.workspace.current_buffer().is_some() {
+        app.mode = Mode::Mark(MarkMode::new(MarkAction::Set));
+    } else {
+        bail!(BUFFER_MISSING);
+    }
+
+    Ok(())
+}
+
+pub fn switch_to_jump_to_mark_mode(app: &mut Application) -> Result {
+    if app.workspace.current_buffer().is_some() {
+        app.mode = Mode::Mark(MarkMode::new(MarkAction::Jump));
+    } else {
+        bail!(BUFFER_MISSING);
+    }
+
+    Ok(())
+}
+
 pub fn switch_to_open_mode(app: &mut Application) -> Result {
-    let exclusions = app.preferences.borrow().open_mode_exclusions()?;
+    let mut exclusions = app.preferences.borrow().open_mode_exclusions()?;
+    if !app.preferences.borrow().open_mode_include_ignored() {
+        let ignored = open::exclusions::ignored(&app.workspace.path);
+        exclusions = Some(exclusions.unwrap_or_default().into_iter().chain(ignored).collect());
+    }
     let config = app.preferences.borrow().search_select_config();
     app.mode = Mode::Open(OpenMode::new(app.workspace.path.clone(), exclusions, app.event_channel.clone(), config));
     commands::search_select::search(app)?;
@@ -139,6 +274,31 @@ pub fn switch_to_theme_mode(app: &mut Application) -> Result {
     Ok(())
 }
 
+/// Lists suggested replacements for the misspelled word under the cursor.
+pub fn switch_to_spelling_mode(app: &mut Application) -> Result {
+    let buffer = app.workspace.current_buffer().ok_or(BUFFER_MISSING)?;
+    let line = buffer.cursor.line;
+    let content = buffer.data().lines().nth(line).map(str::to_string).unwrap_or_default();
+    let (offset, word) = SpellChecker::word_at(&content, buffer.cursor.offset)
+        .ok_or("No word under the cursor")?;
+    let range = Range::new(
+        Position { line, offset },
+        Position { line, offset: offset + word.graphemes(true).count() }
+    );
+
+    let config = app.preferences.borrow().search_select_config();
+    let spell_checker = app.view.spell_checker()?;
+    if spell_checker.is_correct(word) {
+        app.message = Some(format!("\"{}\" is spelled correctly", word));
+        return Ok(());
+    }
+    let suggestions = spell_checker.suggestions(word, config.max_results);
+    app.mode = Mode::Spelling(SpellingMode::new(range, suggestions, config));
+    commands::search_select::search(app)?;
+
+    Ok(())
+}
+
 pub fn switch_to_select_mode(app: &mut Application) -> Result {
     if let Some(buffer) = app.workspace.current_buffer() {
         app.mode = Mode::Select(SelectMode::new(*buffer.cursor.clone()));
@@ -149,6 +309,83 @@ pub fn switch_to_select_mode(app: &mut Application) -> Result {
     Ok(())
 }
 
+pub fn switch_to_surround_mode(app: &mut Application) -> Result {
+    util::ensure_writable(app)?;
+    let buffer = app.workspace.current_buffer().ok_or(BUFFER_MISSING)?;
+    let range = match app.mode {
+        Mode::Select(ref mode) => Range::new(*buffer.cursor, mode.anchor),
+        _ => bail!("Can't surround content outside of select mode"),
+    };
+    app.mode = Mode::Surround(SurroundMode::new(range));
+
+    Ok(())
+}
+
+pub fn switch_to_align_mode(app: &mut Application) -> Result {
+    util::ensure_writable(app)?;
+    let buffer = app.workspace.current_buffer().ok_or(BUFFER_MISSING)?;
+    let range = match app.mode {
+        Mode::SelectLine(ref mode) => {
+            util::inclusive_range(
+                &LineRange::new(mode.anchor, buffer.cursor.line),
+                buffer
+            )
+        },
+        _ => bail!("Can't align lines outside of select line mode"),
+    };
+    app.mode = Mode::Align(AlignMode::new(range));
+
+    Ok(())
+}
+
+pub fn switch_to_filter_mode(app: &mut Application) -> Result {
+    util::ensure_writable(app)?;
+    let buffer = app.workspace.current_buffer().ok_or(BUFFER_MISSING)?;
+    let range = match app.mode {
+        Mode::Select(ref mode) => Range::new(*buffer.cursor, mode.anchor),
+        Mode::SelectLine(ref mode) => {
+            util::inclusive_range(
+                &LineRange::new(mode.anchor, buffer.cursor.line),
+                buffer
+            )
+        },
+        _ => bail!("Can't filter content outside of select modes"),
+    };
+    app.mode = Mode::Filter(FilterMode::new(range));
+
+    Ok(())
+}
+
+pub fn switch_to_select_inside_mode(app: &mut Application) -> Result {
+    if app.workspace.current_buffer().is_some() {
+        app.mode = Mode::TextObject(TextObjectMode::new(TextObjectScope::Inside));
+    } else {
+        bail!(BUFFER_MISSING);
+    }
+
+    Ok(())
+}
+
+pub fn switch_to_select_around_mode(app: &mut Application) -> Result {
+    if app.workspace.current_buffer().is_some() {
+        app.mode = Mode::TextObject(TextObjectMode::new(TextObjectScope::Around));
+    } else {
+        bail!(BUFFER_MISSING);
+    }
+
+    Ok(())
+}
+
+pub fn switch_to_select_block_mode(app: &mut Application) -> Result {
+    if let Some(buffer) = app.workspace.current_buffer() {
+        app.mode = Mode::SelectBlock(SelectBlockMode::new(*buffer.cursor.clone()));
+    } else {
+        bail!(BUFFER_MISSING);
+    }
+
+    Ok(())
+}
+
 pub fn switch_to_select_line_mode(app: &mut Application) -> Result {
     if let Some(buffer) = app.workspace.current_buffer() {
         app.mode = Mode::SelectLine(SelectLineMode::new(buffer.cursor.line));
@@ -160,10 +397,37 @@ pub fn switch_to_select_line_mode(app: &mut Application) -> Result {
 }
 
 pub fn switch_to_search_mode(app: &mut Application) -> Result {
+    if let Some(buffer) = app.workspace.current_buffer() {
+        let mut mode = SearchMode::new(app.search_query.clone());
+        mode.original_position = Some(*buffer.cursor.clone());
+        mode.regex = app.preferences.borrow().search_regex();
+        mode.case_sensitivity = app.preferences.borrow().search_case_sensitivity();
+        mode.whole_word = app.preferences.borrow().search_whole_word();
+
+        app.mode = Mode::Search(mode);
+    } else {
+        bail!(BUFFER_MISSING);
+    }
+
+    Ok(())
+}
+
+pub fn switch_to_replace_mode(app: &mut Application) -> Result {
+    util::ensure_writable(app)?;
+    if app.workspace.current_buffer().is_none() {
+        bail!(BUFFER_MISSING);
+    }
+    if app.search_query.is_none() {
+        bail!(SEARCH_QUERY_MISSING);
+    }
+    app.mode = Mode::Replace(ReplaceMode::new(app.replacement.clone()));
+
+    Ok(())
+}
+
+pub fn switch_to_replace_character_mode(app: &mut Application) -> Result {
     if app.workspace.current_buffer().is_some() {
-        app.mode = Mode::Search(
-            SearchMode::new(app.search_query.clone())
-        );
+        app.mode = Mode::ReplaceCharacter;
     } else {
         bail!(BUFFER_MISSING);
     }
@@ -189,6 +453,80 @@ pub fn switch_to_path_mode(app: &mut Application) -> Result {
     Ok(())
 }
 
+/// Prompts for a path to write the selection (or selected lines) to,
+/// leaving the buffer untouched.
+pub fn switch_to_write_selection_mode(app: &mut Application) -> Result {
+    let buffer = app.workspace.current_buffer().ok_or(BUFFER_MISSING)?;
+    let range = match app.mode {
+        Mode::Select(ref mode) => Range::new(*buffer.cursor, mode.anchor),
+        Mode::SelectLine(ref mode) => {
+            util::inclusive_range(
+                &LineRange::new(mode.anchor, buffer.cursor.line),
+                buffer
+            )
+        },
+        _ => bail!("Can't write content outside of select modes"),
+    };
+    let mut mode = PathMode::new(format!("{}/", app.workspace.path.to_string_lossy()));
+    mode.write_range = Some(range);
+    app.mode = Mode::Path(mode);
+
+    Ok(())
+}
+
+/// Prompts for a path whose contents are inserted at the cursor.
+pub fn switch_to_insert_file_mode(app: &mut Application) -> Result {
+    app.workspace.current_buffer().ok_or(BUFFER_MISSING)?;
+    util::ensure_writable(app)?;
+    let mut mode = PathMode::new(format!("{}/", app.workspace.path.to_string_lossy()));
+    mode.insert_on_accept = true;
+    app.mode = Mode::Path(mode);
+
+    Ok(())
+}
+
+/// Lists the open buffers from most to least recently used, for switching between them.
+pub fn switch_to_buffer_switcher_mode(app: &mut Application) -> Result {
+    let workspace_path = app.workspace.path.clone();
+    let mut buffers = Vec::new();
+    util::for_each_buffer(app, |app| {
+        let buffer = app.workspace.current_buffer().ok_or(BUFFER_MISSING)?;
+        let id = buffer.id.ok_or(BUFFER_ID_MISSING)?;
+        let label = match buffer.path {
+            Some(ref path) => path
+                .strip_prefix(&workspace_path)
+                .unwrap_or(path)
+                .to_string_lossy()
+                .into_owned(),
+            None => String::from("untitled"),
+        };
+        buffers.push(BufferEntry { id, label });
+
+        Ok(())
+    })?;
+    if buffers.is_empty() {
+        bail!(BUFFER_MISSING);
+    }
+
+    // Rank the current buffer first, even if it hasn't been focused by a key press yet.
+    let current_id = buffers[0].id;
+    buffers.sort_by_key(|entry| (entry.id != current_id, app.buffer_history.rank(entry.id)));
+
+    let config = app.preferences.borrow().search_select_config();
+    app.mode = Mode::BufferSwitcher(BufferSwitcherMode::new(buffers, config));
+    commands::search_select::search(app)?;
+
+    Ok(())
+}
+
+pub fn switch_to_recent_mode(app: &mut Application) -> Result {
+    let config = app.preferences.borrow().search_select_config();
+    app.mode = Mode::Recent(RecentMode::new(app.recent_files.paths(), config));
+    commands::search_select::search(app)?;
+
+    Ok(())
+}
+
 pub fn switch_to_syntax_mode(app: &mut Application) -> Result {
     // We'll need a buffer to apply the syntax,
     // so check before entering syntax mode.
@@ -274,16 +612,106 @@ pub fn suspend(app: &mut Application) -> Result {
 pub fn exit(app: &mut Application) -> Result {
     app.mode = Mode::Exit;
 
-    Ok(())
+    // Remember where we left off in each of the open buffers.
+    util::for_each_buffer(app, util::record_cursor_position)
 }
 
 #[cfg(test)]
 mod tests {
+    use crate::input::Key;
     use crate::models::Application;
     use crate::models::application::Mode;
     use scribe::Buffer;
+    use scribe::buffer::Position;
     use std::path::PathBuf;
 
+    #[test]
+    fn repeat_last_change_replays_inserted_text_at_the_cursor() {
+        let mut app = Application::new(&Vec::new()).unwrap();
+        let mut buffer = Buffer::new();
+        buffer.insert("amp\neditor");
+        app.workspace.add_buffer(buffer);
+
+        // Simulate entering insert mode, typing, and returning to normal mode.
+        for key in vec![Key::Char('i'), Key::Char('a'), Key::Char('b'), Key::Esc] {
+            let normal_mode = match app.mode {
+                Mode::Normal => true,
+                _ => false,
+            };
+            app.last_change.record_key(key.clone(), normal_mode);
+            app.view.last_key = Some(key);
+            super::handle_input(&mut app).unwrap();
+        }
+        app.last_change.finish_sequence();
+
+        app.workspace.current_buffer().unwrap().cursor.move_to(Position {
+            line: 1,
+            offset: 0,
+        });
+        super::repeat_last_change(&mut app).unwrap();
+
+        assert_eq!(app.workspace.current_buffer().unwrap().data(), "abamp\nabeditor");
+        assert!(match app.mode {
+            Mode::Normal => true,
+            _ => false,
+        });
+        assert_eq!(app.last_change.keys().len(), 4);
+    }
+
+    #[test]
+    fn handle_input_passes_counts_to_the_next_command() {
+        let mut app = Application::new(&Vec::new()).unwrap();
+        let mut buffer = Buffer::new();
+        buffer.insert((0..15).map(|line| format!("{}\n", line)).collect::<String>());
+        app.workspace.add_buffer(buffer);
+
+        for key in vec![Key::Char('1'), Key::Char('0'), Key::Char('j')] {
+            app.view.last_key = Some(key);
+            super::handle_input(&mut app).unwrap();
+        }
+        assert_eq!(app.workspace.current_buffer().unwrap().cursor.line, 10);
+        assert_eq!(app.count, None);
+
+        // Commands that don't take a count ignore it.
+        for key in vec![Key::Char('3'), Key::Char('H'), Key::Char('k')] {
+            app.view.last_key = Some(key);
+            super::handle_input(&mut app).unwrap();
+        }
+        assert_eq!(app.workspace.current_buffer().unwrap().cursor.line, 9);
+    }
+
+    #[test]
+    fn sequences_that_leave_the_buffer_untouched_dont_replace_the_last_change() {
+        let mut app = Application::new(&Vec::new()).unwrap();
+        let mut buffer = Buffer::new();
+        buffer.insert("amp\neditor");
+        app.workspace.add_buffer(buffer);
+
+        // Type something, and then enter and leave insert mode without typing.
+        let keys = vec![Key::Char('i'), Key::Char('a'), Key::Esc, Key::Char('i'), Key::Esc];
+        for key in keys {
+            let normal_mode = match app.mode {
+                Mode::Normal => true,
+                _ => false,
+            };
+            app.last_change.record_key(key.clone(), normal_mode);
+            app.view.last_key = Some(key);
+            super::handle_input(&mut app).unwrap();
+            if let Mode::Normal = app.mode {
+                app.last_change.finish_sequence();
+            }
+        }
+
+        assert_eq!(app.last_change.keys(), &[Key::Char('i'), Key::Char('a'), Key::Esc]);
+    }
+
+    #[test]
+    fn repeat_last_change_fails_without_a_recorded_change() {
+        let mut app = Application::new(&Vec::new()).unwrap();
+
+        assert!(super::repeat_last_change(&mut app).is_err());
+    }
+
     #[test]
     fn display_available_commands_creates_a_new_buffer() {
         let mut app = Application::new(&Vec::new()).unwrap();
@@ -372,4 +800,38 @@ mod tests {
 
         assert!(super::switch_to_path_mode(&mut app).is_err());
     }
+
+    #[test]
+    fn switch_to_recent_mode_lists_recent_files_to_reopen() {
+        let mut app = Application::new(&Vec::new()).unwrap();
+        let path = std::fs::canonicalize("Cargo.toml").unwrap();
+        app.recent_files.push(&path).unwrap();
+
+        super::switch_to_recent_mode(&mut app).unwrap();
+        crate::commands::search_select::accept(&mut app).unwrap();
+
+        assert_eq!(app.workspace.current_buffer().unwrap().path, Some(path));
+    }
+
+    #[test]
+    fn switch_to_buffer_switcher_mode_lists_buffers_by_recency() {
+        let mut app = Application::new(&Vec::new()).unwrap();
+        for path in &["amp", "editor", "buffer"] {
+            let mut buffer = Buffer::new();
+            buffer.path = Some(PathBuf::from(path));
+            app.workspace.add_buffer(buffer);
+            let id = app.workspace.current_buffer().unwrap().id.unwrap();
+            app.buffer_history.focus(id);
+        }
+
+        // Return to the first buffer, making "buffer" the previously used one.
+        app.workspace.next_buffer();
+        let id = app.workspace.current_buffer().unwrap().id.unwrap();
+        app.buffer_history.focus(id);
+
+        super::switch_to_buffer_switcher_mode(&mut app).unwrap();
+        crate::commands::search_select::accept(&mut app).unwrap();
+
+        assert_eq!(app.workspace.current_buffer().unwrap().path, Some(PathBuf::from("buffer")));
+    }
 }
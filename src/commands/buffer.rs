@@ -1,16 +1,33 @@
 use crate::errors::*;
+use crate::errors;
 use crate::commands::{self, Result};
+use std::cell::RefCell;
 use std::mem;
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
+use std::time::SystemTime;
 use crate::input::Key;
 use crate::util;
 use crate::util::token::{Direction, adjacent_token_position};
-use crate::models::application::{Application, ClipboardContent, Mode};
+use crate::models::application::{Application, ClipboardContent, Mode, Paste};
 use crate::models::application::modes::ConfirmMode;
-use scribe::buffer::{Buffer, Position, Range};
+use scribe::buffer::{Buffer, LineRange, Position, Range};
+use unicode_segmentation::UnicodeSegmentation;
+
+const DEFAULT_LINE_COMMENT_PREFIX: &str = "//";
+
+/// Opening and closing characters inserted together when
+/// the auto_close_pairs preference is enabled.
+const AUTO_CLOSED_PAIRS: [(char, char); 5] = [
+    ('(', ')'),
+    ('[', ']'),
+    ('{', '}'),
+    ('"', '"'),
+    ('\'', '\''),
+];
 
 pub fn save(app: &mut Application) -> Result {
-    remove_trailing_whitespace(app)?;
-    ensure_trailing_newline(app)?;
+    util::ensure_writable(app)?;
 
     // Slight duplication here, but we need to check for a buffer path without
     // borrowing the buffer for the full scope of this save command. That will
@@ -23,11 +40,7 @@ pub fn save(app: &mut Application) -> Result {
         .path.is_some();
 
     if path_set {
-        app.workspace
-            .current_buffer()
-            .ok_or(BUFFER_MISSING)?
-            .save()
-            .chain_err(|| "Unable to save buffer")
+        write_current_buffer(app)
     } else {
         commands::application::switch_to_path_mode(app)?;
         if let Mode::Path(ref mut mode) = app.mode {
@@ -38,20 +51,327 @@ pub fn save(app: &mut Application) -> Result {
     }
 }
 
+/// Writes the current buffer to its path, after formatting and cleaning up its
+/// content as configured, and backing up the existing file if enabled. This is
+/// shared with autosaving, so that both produce the same file.
+pub(crate) fn write_current_buffer(app: &mut Application) -> Result {
+    let format_on_save = app.preferences.borrow().format_on_save();
+    if format_on_save && current_formatter(app).is_some() {
+        apply_formatter(app).chain_err(|| "Couldn't format buffer; it hasn't been saved")?;
+    }
+    strip_trailing_whitespace(app)?;
+    add_trailing_newline(app)?;
+
+    let buffer = app.workspace.current_buffer().ok_or(BUFFER_MISSING)?;
+    if app.preferences.borrow().backup_on_save() {
+        if let Some(ref path) = buffer.path {
+            util::backup_file(path)?;
+        }
+    }
+
+    buffer.save().chain_err(|| "Unable to save buffer")?;
+    app.modification_times.record(buffer);
+    app.cursor_positions.record(buffer)?;
+    util::record_recent_file(app)
+}
+
+/// Replaces the buffer's content with that of its file, keeping the cursor
+/// as close to its original position as possible. Reloading a modified buffer
+/// requires confirmation, since its unsaved changes will be discarded.
 pub fn reload(app: &mut Application) -> Result {
-    app.workspace.current_buffer().ok_or(BUFFER_MISSING)?.reload().chain_err(|| {
-        "Unable to reload buffer."
-    })
+    let modified = app.workspace.current_buffer().ok_or(BUFFER_MISSING)?.modified();
+    let confirm_mode = if let Mode::Confirm(_) = app.mode {
+        true
+    } else {
+        false
+    };
+    if modified && !confirm_mode {
+        app.mode = Mode::Confirm(ConfirmMode::new(reload));
+        return Ok(());
+    }
+
+    let buffer = app.workspace.current_buffer().ok_or(BUFFER_MISSING)?;
+    let position = *buffer.cursor;
+    buffer.reload().chain_err(|| "Unable to reload buffer.")?;
+    app.modification_times.record(buffer);
+    util::move_cursor_to_nearest(buffer, position);
+
+    commands::view::scroll_to_cursor(app)
+}
+
+/// Replaces the buffer's content with the output of the formatter configured
+/// for its file type, keeping the cursor as close to its original position
+/// as possible. The buffer is left untouched if the formatter fails.
+pub fn format(app: &mut Application) -> Result {
+    util::ensure_writable(app)?;
+    if apply_formatter(app)? {
+        util::record_edit(app);
+    }
+
+    Ok(())
+}
+
+// Formats the buffer without recording an edit, so that formatting on save
+// doesn't count as a change; returns whether the formatter changed anything.
+fn apply_formatter(app: &mut Application) -> errors::Result<bool> {
+    let formatter = current_formatter(app)
+        .ok_or("No formatter configured for the current buffer's file type")?;
+    let workspace_path = app.workspace.path.clone();
+    let buffer = app.workspace.current_buffer().ok_or(BUFFER_MISSING)?;
+
+    let data = buffer.data();
+    let formatted_data = util::shell::filter(&formatter, &data, &workspace_path)?;
+    if formatted_data == data {
+        return Ok(false);
+    }
+
+    let position = *buffer.cursor;
+    let last_line = buffer.line_count().saturating_sub(1);
+    let buffer_range = util::inclusive_range(&LineRange::new(0, last_line), buffer);
+    buffer.start_operation_group();
+    buffer.delete_range(buffer_range);
+    buffer.cursor.move_to(Position { line: 0, offset: 0 });
+    buffer.insert(formatted_data);
+    buffer.end_operation_group();
+    util::move_cursor_to_nearest(buffer, position);
+    commands::view::scroll_to_cursor(app)?;
+
+    Ok(true)
+}
+
+/// The formatter configured for the current buffer's file type, if any.
+fn current_formatter(app: &mut Application) -> Option<String> {
+    let preferences = app.preferences.borrow();
+
+    app.workspace
+        .current_buffer()
+        .and_then(|buffer| buffer.path.as_ref())
+        .and_then(|path| preferences.formatter(path))
+}
+
+/// Flags the current buffer as read-only (or lifts the flag), for viewing
+/// files without risking edits. Files that aren't writable are flagged
+/// automatically.
+pub fn toggle_read_only(app: &mut Application) -> Result {
+    let buffer = app.workspace.current_buffer().ok_or(BUFFER_MISSING)?;
+    let read_only = app.read_only_buffers.toggle(buffer);
+    app.message = Some(if read_only {
+        "Buffer is now read-only".to_string()
+    } else {
+        "Buffer is now writable".to_string()
+    });
+
+    Ok(())
+}
+
+/// Opens the file referenced by the path around the cursor, resolved relative
+/// to the current buffer's directory or, failing that, the workspace root.
+/// Paths with a line number suffix (e.g. `src/main.rs:42`, as found in stack
+/// traces) move the cursor to that line (and column, if one is specified). Paths that don't exist are opened as
+/// new, empty buffers, creating the file when saved.
+pub fn open_path_under_cursor(app: &mut Application) -> Result {
+    let buffer = app.workspace.current_buffer().ok_or(BUFFER_MISSING)?;
+    let token = path_under_cursor(buffer).ok_or("No path found under the cursor")?;
+    let (path_name, position) = util::split_path_position(&token);
+
+    let buffer_directory = buffer
+        .path
+        .as_ref()
+        .and_then(|path| path.parent())
+        .map(|directory| directory.to_path_buf());
+    let candidates: Vec<PathBuf> = if Path::new(path_name).is_absolute() {
+        vec![PathBuf::from(path_name)]
+    } else {
+        buffer_directory
+            .into_iter()
+            .chain(Some(app.workspace.path.clone()))
+            .map(|directory| directory.join(path_name))
+            .collect()
+    };
+    let existing_path = candidates.iter().find(|path| path.is_file()).cloned();
+
+    let syntax_reference = app.preferences
+        .borrow()
+        .syntax_definition_name(&candidates[0])
+        .and_then(|name| app.workspace.syntax_set.find_syntax_by_name(&name).cloned());
+
+    if let Some(ref path) = existing_path {
+        let already_open = util::has_buffer_for_path(app, path)?;
+        app.workspace
+            .open_buffer(path)
+            .chain_err(|| "Couldn't open a buffer for the specified path.")?;
+        let buffer = app.workspace.current_buffer().ok_or(BUFFER_MISSING)?;
+
+        // Only override the default syntax definition if the user provided
+        // a valid one in their preferences.
+        if syntax_reference.is_some() {
+            buffer.syntax_reference = syntax_reference;
+        }
+        app.view.initialize_buffer(buffer)?;
+        if position.is_none() && !already_open {
+            util::restore_cursor_position(app)?;
+        }
+        util::record_recent_file(app)?;
+    } else if candidates[0].is_dir() {
+        bail!(format!("{} is a directory", candidates[0].to_string_lossy()));
+    } else {
+        let mut buffer = Buffer::new();
+        buffer.path = Some(candidates[0].clone());
+        buffer.syntax_set = Some(app.workspace.syntax_set.clone());
+        buffer.syntax_reference = syntax_reference;
+        util::add_buffer(buffer, app)?;
+        app.message = Some(format!(
+            "{} doesn't exist; save the buffer to create it",
+            candidates[0].to_string_lossy()
+        ));
+    }
+
+    if let Some(position) = position {
+        let buffer = app.workspace.current_buffer().ok_or(BUFFER_MISSING)?;
+        util::move_cursor_to_nearest(buffer, position);
+        commands::view::scroll_cursor_to_center(app)?;
+    }
+
+    Ok(())
+}
+
+// Finds the run of path characters surrounding the cursor on its line.
+fn path_under_cursor(buffer: &Buffer) -> Option<String> {
+    let data = buffer.data();
+    let line: Vec<&str> = data.lines().nth(buffer.cursor.line)?.graphemes(true).collect();
+    let is_path_grapheme = |grapheme: &str| {
+        !grapheme.chars().any(|c| c.is_whitespace() || "\"'`()<>[]{},;|".contains(c))
+    };
+
+    let offset = buffer.cursor.offset;
+    if !line.get(offset).map(|g| is_path_grapheme(*g)).unwrap_or(false) {
+        return None;
+    }
+    let start = line[..offset]
+        .iter()
+        .rposition(|g| !is_path_grapheme(*g))
+        .map(|index| index + 1)
+        .unwrap_or(0);
+    let end = line[offset..]
+        .iter()
+        .position(|g| !is_path_grapheme(*g))
+        .map(|index| offset + index)
+        .unwrap_or_else(|| line.len());
+
+    Some(line[start..end].concat())
 }
 
 pub fn delete(app: &mut Application) -> Result {
-    app.workspace.current_buffer().ok_or(BUFFER_MISSING)?.delete();
+    util::ensure_writable(app)?;
+    let count = app.take_count();
+    if has_secondary_cursors(app) {
+        // Deleting at the end of a line would join it with the next.
+        return edit_at_cursors(app, |buffer| {
+            if buffer.cursor.offset >= line_length(buffer, buffer.cursor.line) {
+                return None;
+            }
+            buffer.delete();
+            Some(-1)
+        });
+    }
+
+    let buffer = app.workspace.current_buffer().ok_or(BUFFER_MISSING)?;
+    if count > 1 {
+        // Repeated deletions stop at the end of the line,
+        // rather than joining it with those that follow.
+        let remaining = line_length(buffer, buffer.cursor.line)
+            .saturating_sub(buffer.cursor.offset);
+        buffer.start_operation_group();
+        for _ in 0..count.min(remaining).max(1) {
+            buffer.delete();
+        }
+        buffer.end_operation_group();
+    } else {
+        buffer.delete();
+    }
+    util::record_edit(app);
     commands::view::scroll_to_cursor(app)?;
 
     Ok(())
 }
 
+/// Toggles the case of the character under the cursor (or as many as the
+/// count, up to the end of the line), and moves past it. Characters without
+/// case are left as-is. The cursor won't move beyond the line's last character.
+pub fn toggle_case(app: &mut Application) -> Result {
+    let count = app.take_count();
+    let buffer = app.workspace.current_buffer().ok_or(BUFFER_MISSING)?;
+    let line = buffer.cursor.line;
+    let data = buffer.data();
+    let content: Vec<&str> = data
+        .lines()
+        .nth(line)
+        .map(|content| content.graphemes(true).collect())
+        .unwrap_or_default();
+    if content.is_empty() {
+        return Ok(());
+    }
+
+    // At the end of the line, toggle its last character.
+    let start = buffer.cursor.offset.min(content.len() - 1);
+    let end = (start + count).min(content.len());
+    let original = content[start..end].concat();
+    let toggled = util::swap_case(&original);
+    let toggled_length = toggled.graphemes(true).count();
+    let line_length = content.len() - (end - start) + toggled_length;
+
+    if toggled != original {
+        util::ensure_writable(app)?;
+        let buffer = app.workspace.current_buffer().ok_or(BUFFER_MISSING)?;
+        let start_position = Position { line, offset: start };
+        buffer.start_operation_group();
+        buffer.delete_range(Range::new(start_position, Position { line, offset: end }));
+        buffer.cursor.move_to(start_position);
+        buffer.insert(toggled.as_str());
+        buffer.end_operation_group();
+        util::record_edit(app);
+    }
+
+    let buffer = app.workspace.current_buffer().ok_or(BUFFER_MISSING)?;
+    buffer.cursor.move_to(Position {
+        line,
+        offset: (start + toggled_length).min(line_length - 1),
+    });
+    commands::view::scroll_to_cursor(app)
+}
+
+/// Replaces the character under the cursor with the last key press (inserting
+/// it at the end of the line, where there's nothing to replace), and returns
+/// to normal mode. Replacing a character with a newline splits the line.
+pub fn replace_character(app: &mut Application) -> Result {
+    let replacement = match *app.view.last_key() {
+        Some(Key::Char(character)) => character.to_string(),
+        Some(Key::Enter) => String::from("\n"),
+        Some(Key::Tab) => String::from("\t"),
+        _ => bail!("Last key press wasn't a character"),
+    };
+    util::ensure_writable(app)?;
+    let buffer = app.workspace.current_buffer().ok_or(BUFFER_MISSING)?;
+    let position = *buffer.cursor;
+
+    buffer.start_operation_group();
+    if position.offset < line_length(buffer, position.line) {
+        buffer.delete();
+    }
+    buffer.insert(replacement.as_str());
+    if replacement == "\n" {
+        buffer.cursor.move_to(Position { line: position.line + 1, offset: 0 });
+    }
+    buffer.end_operation_group();
+    util::record_edit(app);
+
+    commands::application::switch_to_normal_mode(app)?;
+    commands::view::scroll_to_cursor(app)
+}
+
 pub fn delete_token(app: &mut Application) -> Result {
+    util::ensure_writable(app)?;
+    let count = app.take_count();
     let mut subsequent_token_on_line = false;
 
     if let Some(buffer) = app.workspace.current_buffer() {
@@ -67,6 +387,11 @@ pub fn delete_token(app: &mut Application) -> Result {
     if subsequent_token_on_line {
         commands::application::switch_to_select_mode(app)?;
         commands::cursor::move_to_start_of_next_token(app)?;
+        for _ in 1..count {
+            if commands::cursor::move_to_start_of_next_token(app).is_err() {
+                break;
+            }
+        }
         commands::selection::copy_and_delete(app)?;
         commands::application::switch_to_normal_mode(app)?;
         commands::view::scroll_to_cursor(app)?;
@@ -78,7 +403,8 @@ pub fn delete_token(app: &mut Application) -> Result {
 }
 
 pub fn delete_current_line(app: &mut Application) -> Result {
-    commands::application::switch_to_select_line_mode(app)?;
+    util::ensure_writable(app)?;
+    select_lines(app)?;
     commands::selection::copy_and_delete(app)?;
     commands::application::switch_to_normal_mode(app)?;
     commands::view::scroll_to_cursor(app)?;
@@ -87,15 +413,167 @@ pub fn delete_current_line(app: &mut Application) -> Result {
 }
 
 pub fn copy_current_line(app: &mut Application) -> Result {
-    commands::application::switch_to_select_line_mode(app)?;
+    let cursor_position = *app.workspace.current_buffer().ok_or(BUFFER_MISSING)?.cursor;
+    select_lines(app)?;
     commands::selection::copy(app)?;
     commands::application::switch_to_normal_mode(app)?;
+    app.workspace.current_buffer().ok_or(BUFFER_MISSING)?.cursor.move_to(cursor_position);
     commands::view::scroll_to_cursor(app)?;
 
     Ok(())
 }
 
+// Selects the current line, along with any others included by a count.
+fn select_lines(app: &mut Application) -> Result {
+    let count = app.take_count();
+    commands::application::switch_to_select_line_mode(app)?;
+    let buffer = app.workspace.current_buffer().ok_or(BUFFER_MISSING)?;
+    for _ in 1..count {
+        buffer.cursor.move_down();
+    }
+
+    Ok(())
+}
+
+/// Inserts a copy of the current line (or selection) after itself, moving the
+/// cursor to the equivalent position in the copy, as a single operation.
+pub fn duplicate(app: &mut Application) -> Result {
+    util::ensure_writable(app)?;
+    let buffer = app.workspace.current_buffer().ok_or(BUFFER_MISSING)?;
+    let cursor_position = *buffer.cursor.clone();
+
+    let (range, line_count) = match app.mode {
+        Mode::Normal => {
+            let line_range = LineRange::new(cursor_position.line, cursor_position.line);
+            (util::inclusive_range(&line_range, buffer), Some(1))
+        },
+        Mode::SelectLine(ref mode) => {
+            let line_range = LineRange::new(mode.anchor, cursor_position.line);
+            let line_count = line_range.end() - line_range.start() + 1;
+            (util::inclusive_range(&line_range, buffer), Some(line_count))
+        },
+        Mode::Select(ref mode) => (Range::new(cursor_position, mode.anchor), None),
+        _ => bail!("Can't duplicate content in the current mode"),
+    };
+
+    let content = buffer.read(&range)
+        .ok_or("Couldn't read content to duplicate from buffer")?;
+
+    buffer.start_operation_group();
+    buffer.cursor.move_to(range.end());
+    match line_count {
+        Some(line_count) => {
+            // Lines without a trailing newline (i.e. the last
+            // one) need separating from their copy.
+            if content.ends_with('\n') {
+                buffer.insert(content);
+            } else {
+                buffer.insert(format!("\n{}", content));
+            }
+
+            buffer.cursor.move_to(Position {
+                line: cursor_position.line + line_count,
+                offset: cursor_position.offset,
+            });
+        },
+        None => buffer.insert(content),
+    }
+    buffer.end_operation_group();
+    util::record_edit(app);
+
+    commands::application::switch_to_normal_mode(app)?;
+    commands::view::scroll_to_cursor(app)
+}
+
+pub fn move_line_up(app: &mut Application) -> Result {
+    move_lines(app, Direction::Backward)
+}
+
+pub fn move_line_down(app: &mut Application) -> Result {
+    move_lines(app, Direction::Forward)
+}
+
+/// Swaps the current line (or selected lines) with the adjacent line in the
+/// specified direction, as a single operation, carrying the cursor and
+/// selection along with them. This is a no-op at the edges of the buffer.
+fn move_lines(app: &mut Application, direction: Direction) -> Result {
+    util::ensure_writable(app)?;
+    let buffer = app.workspace.current_buffer().ok_or(BUFFER_MISSING)?;
+
+    let line_range = match app.mode {
+        Mode::Normal => LineRange::new(buffer.cursor.line, buffer.cursor.line),
+        Mode::SelectLine(ref mode) => LineRange::new(mode.anchor, buffer.cursor.line),
+        _ => bail!("Can't move lines outside of normal and select line modes"),
+    };
+
+    // Determine the lines affected by the swap, bailing out
+    // early if there's no line to swap with. The empty line
+    // following a trailing newline doesn't count as one.
+    let data = buffer.data();
+    if data.lines().nth(line_range.end()).is_none() {
+        return Ok(());
+    }
+    let affected_range = match direction {
+        Direction::Backward => {
+            if line_range.start() == 0 {
+                return Ok(());
+            }
+            LineRange::new(line_range.start() - 1, line_range.end())
+        },
+        Direction::Forward => {
+            if data.lines().nth(line_range.end() + 1).is_none() {
+                return Ok(());
+            }
+            LineRange::new(line_range.start(), line_range.end() + 1)
+        },
+    };
+
+    let range = util::inclusive_range(&affected_range, buffer);
+    let content = buffer.read(&range)
+        .ok_or("Couldn't read lines to move from buffer")?;
+
+    // Move the adjacent line to the other side of the moved lines.
+    let mut lines: Vec<&str> = content.split_terminator('\n').collect();
+    match direction {
+        Direction::Backward => lines.rotate_left(1),
+        Direction::Forward => lines.rotate_right(1),
+    }
+    let mut moved_lines = lines.join("\n");
+
+    // Re-add the trailing newline if we've consumed one.
+    if content.ends_with('\n') {
+        moved_lines.push('\n');
+    }
+
+    let cursor_position = *buffer.cursor.clone();
+    let target_line = match direction {
+        Direction::Backward => cursor_position.line - 1,
+        Direction::Forward => cursor_position.line + 1,
+    };
+
+    buffer.start_operation_group();
+    buffer.delete_range(range.clone());
+    buffer.cursor.move_to(range.start());
+    buffer.insert(moved_lines);
+    buffer.cursor.move_to(Position {
+        line: target_line,
+        offset: cursor_position.offset,
+    });
+    buffer.end_operation_group();
+    util::record_edit(app);
+
+    if let Mode::SelectLine(ref mut mode) = app.mode {
+        mode.anchor = match direction {
+            Direction::Backward => mode.anchor - 1,
+            Direction::Forward => mode.anchor + 1,
+        };
+    }
+
+    commands::view::scroll_to_cursor(app)
+}
+
 pub fn merge_next_line(app: &mut Application) -> Result {
+    util::ensure_writable(app)?;
     let buffer = app.workspace.current_buffer().ok_or(BUFFER_MISSING)?;
     let current_line = buffer.cursor.line;
     let data = buffer.data();
@@ -146,6 +624,7 @@ pub fn merge_next_line(app: &mut Application) -> Result {
     buffer.insert(merged_lines);
     buffer.cursor.move_to(target_position);
     buffer.end_operation_group();
+    util::record_edit(app);
 
     Ok(())
 }
@@ -166,24 +645,47 @@ pub fn close(app: &mut Application) -> Result {
         };
 
     if unmodified || empty || confirm_mode {
-        // Clean up view-related data for the buffer.
-        app.view.forget_buffer(
-            app.workspace.current_buffer().ok_or(BUFFER_MISSING)?
-        )?;
-        app.workspace.close_current_buffer();
+        force_close(app)
     } else {
         // Display a confirmation prompt before closing a modified buffer.
         let confirm_mode = ConfirmMode::new(close);
         app.mode = Mode::Confirm(confirm_mode);
+
+        Ok(())
     }
+}
 
-    Ok(())
+/// Closes the current buffer without confirmation, discarding any unsaved
+/// changes. The next buffer is selected; closing the last one leaves the
+/// workspace empty.
+pub fn force_close(app: &mut Application) -> Result {
+    // Clean up view-related data and marks for the buffer, remembering
+    // its cursor position (reporting persistence errors once closed).
+    let buffer = app.workspace.current_buffer().ok_or(BUFFER_MISSING)?;
+    let position_result = app.cursor_positions.record(buffer);
+    app.view.forget_buffer(buffer)?;
+    if let Some(id) = buffer.id {
+        forget_buffer(app, id);
+    }
+    app.workspace.close_current_buffer();
+
+    position_result
+}
+
+// Discards the application's per-buffer data (marks, modification
+// times, etc.) for a buffer that's being closed.
+fn forget_buffer(app: &mut Application, buffer_id: usize) {
+    app.marks.forget_buffer(buffer_id);
+    app.modification_times.forget_buffer(buffer_id);
+    app.read_only_buffers.forget_buffer(buffer_id);
+    app.buffer_history.forget_buffer(buffer_id);
 }
 
 pub fn close_others(app: &mut Application) -> Result {
     // Get the current buffer's ID so we know what *not* to close.
     let id = app.workspace.current_buffer().map(|b| b.id).ok_or(BUFFER_MISSING)?;
     let mut modified_buffer = false;
+    let mut position_result = Ok(());
 
     loop {
         // Try to advance to the next buffer. Handles two important states:
@@ -207,7 +709,11 @@ pub fn close_others(app: &mut Application) -> Result {
             } else if buf.modified() && !buf.data().is_empty() {
                 modified_buffer = true;
             } else {
+                position_result = position_result.and(app.cursor_positions.record(buf));
                 app.view.forget_buffer(buf)?;
+                if let Some(buffer_id) = buf.id {
+                    forget_buffer(app, buffer_id);
+                }
             }
         }
 
@@ -223,24 +729,53 @@ pub fn close_others(app: &mut Application) -> Result {
         app.workspace.close_current_buffer();
     }
 
-    Ok(())
+    position_result
 }
 
 pub fn close_others_confirm(app: &mut Application) -> Result {
+    let mut position_result = Ok(());
     if let Some(buf) = app.workspace.current_buffer() {
+        position_result = app.cursor_positions.record(buf);
         app.view.forget_buffer(buf)?;
+        if let Some(id) = buf.id {
+            forget_buffer(app, id);
+        }
     }
     app.workspace.close_current_buffer();
     commands::application::switch_to_normal_mode(app)?;
 
-    Ok(())
+    position_result
 }
 
 pub fn backspace(app: &mut Application) -> Result {
+    util::ensure_writable(app)?;
+    if has_secondary_cursors(app) {
+        // Backspacing at the start of a line would join it with the previous.
+        return edit_at_cursors(app, |buffer| {
+            if buffer.cursor.offset == 0 {
+                return None;
+            }
+            buffer.cursor.move_left();
+            buffer.delete();
+            Some(-1)
+        });
+    }
+
     let mut outdent = false;
+    let auto_close_pairs = app.preferences.borrow().auto_close_pairs();
 
     if let Some(buffer) = app.workspace.current_buffer() {
-        if buffer.cursor.offset == 0 {
+        let (previous_character, next_character) = adjacent_characters(buffer);
+        let empty_pair = AUTO_CLOSED_PAIRS.iter().any(|&(opener, closer)| {
+            previous_character == Some(opener) && next_character == Some(closer)
+        });
+
+        if auto_close_pairs && empty_pair {
+            // Remove both halves of an empty pair.
+            buffer.cursor.move_left();
+            buffer.delete();
+            buffer.delete();
+        } else if buffer.cursor.offset == 0 {
             buffer.cursor.move_up();
             buffer.cursor.move_to_end_of_line();
             buffer.delete();
@@ -263,16 +798,53 @@ pub fn backspace(app: &mut Application) -> Result {
 
     if outdent {
         commands::buffer::outdent_line(app)?;
+    } else {
+        util::record_edit(app);
     }
     commands::view::scroll_to_cursor(app)
 }
 
 pub fn insert_char(app: &mut Application) -> Result {
-    if let Some(buffer) = app.workspace.current_buffer() {
-        if let Some(Key::Char(character)) = *app.view.last_key() {
-            // TODO: Drop explicit call to to_string().
+    util::ensure_writable(app)?;
+    if has_secondary_cursors(app) {
+        let character = match *app.view.last_key() {
+            Some(Key::Char(character)) => character,
+            _ => bail!("No character to insert"),
+        };
+        return edit_at_cursors(app, |buffer| {
             buffer.insert(character.to_string());
             buffer.cursor.move_right();
+            Some(1)
+        });
+    }
+
+    let auto_close_pairs = app.preferences.borrow().auto_close_pairs();
+    if let Some(buffer) = app.workspace.current_buffer() {
+        if let Some(Key::Char(character)) = *app.view.last_key() {
+            let (previous_character, next_character) = adjacent_characters(buffer);
+            let closes_pair = AUTO_CLOSED_PAIRS.iter().any(|&(_, closer)| closer == character);
+
+            if auto_close_pairs && closes_pair && next_character == Some(character) {
+                // Type over the closing character, rather than doubling it up.
+                buffer.cursor.move_right();
+            } else {
+                // TODO: Drop explicit call to to_string().
+                buffer.insert(character.to_string());
+                buffer.cursor.move_right();
+
+                if auto_close_pairs {
+                    // Quotes following a word are more likely to be
+                    // apostrophes (or lifetimes) than the start of a string.
+                    let follows_word = previous_character.map_or(false, char::is_alphanumeric);
+                    let pair = AUTO_CLOSED_PAIRS.iter().find(|&&(opener, closer)| {
+                        opener == character && !(opener == closer && follows_word)
+                    });
+                    if let Some(&(_, closer)) = pair {
+                        buffer.insert(closer.to_string());
+                    }
+                }
+                util::record_edit(app);
+            }
         } else {
             bail!("No character to insert");
         }
@@ -284,6 +856,97 @@ pub fn insert_char(app: &mut Application) -> Result {
     Ok(())
 }
 
+fn has_secondary_cursors(app: &mut Application) -> bool {
+    match app.workspace.current_buffer() {
+        Some(buffer) => !app.secondary_cursors.positions(buffer.id).is_empty(),
+        None => false,
+    }
+}
+
+fn line_length(buffer: &Buffer, line: usize) -> usize {
+    buffer.data()
+        .lines()
+        .nth(line)
+        .map(|content| content.graphemes(true).count())
+        .unwrap_or(0)
+}
+
+/// The characters immediately before and after the cursor, on its line.
+fn adjacent_characters(buffer: &Buffer) -> (Option<char>, Option<char>) {
+    let data = buffer.data();
+    let graphemes: Vec<&str> = data
+        .lines()
+        .nth(buffer.cursor.line)
+        .map(|line| line.graphemes(true).collect())
+        .unwrap_or_default();
+    let character_at = |offset: usize| {
+        graphemes.get(offset).and_then(|grapheme| grapheme.chars().next())
+    };
+
+    let previous_character = buffer.cursor.offset.checked_sub(1).and_then(character_at);
+    (previous_character, character_at(buffer.cursor.offset))
+}
+
+/// Applies an edit at the primary cursor and each of the secondary cursors.
+/// Edits are made from the end of the buffer backwards, so that they don't
+/// affect the positions at which the remaining edits are made; the cursors
+/// are then shifted to account for edits made earlier on their lines. Edits
+/// return the change in their line's length, or None if they couldn't be
+/// made without joining lines, which isn't supported with multiple cursors.
+fn edit_at_cursors<F>(app: &mut Application, edit: F) -> Result
+    where F: Fn(&mut Buffer) -> Option<isize>
+{
+    let buffer = app.workspace.current_buffer().ok_or(BUFFER_MISSING)?;
+    let primary = *buffer.cursor;
+    let mut positions = app.secondary_cursors.positions(buffer.id).to_vec();
+    positions.push(primary);
+    positions.sort_by_key(|position| (position.line, position.offset));
+
+    // Insert mode holds an operation group open for its duration.
+    let grouped = match app.mode {
+        Mode::Insert => false,
+        _ => true,
+    };
+    if grouped {
+        buffer.start_operation_group();
+    }
+    let mut edits = Vec::with_capacity(positions.len());
+    for &position in positions.iter().rev() {
+        buffer.cursor.move_to(position);
+        let delta = edit(buffer);
+        edits.push((position, *buffer.cursor, delta));
+    }
+    if grouped {
+        buffer.end_operation_group();
+    }
+
+    let mut primary_position = primary;
+    let mut secondary_positions = Vec::new();
+    for &(position, edited_position, _) in edits.iter() {
+        let shift: isize = edits.iter()
+            .filter(|&&(other, _, _)| other.line == position.line && other.offset < position.offset)
+            .filter_map(|&(_, _, delta)| delta)
+            .sum();
+        let shifted_position = Position {
+            line: edited_position.line,
+            offset: (edited_position.offset as isize + shift) as usize,
+        };
+
+        if position == primary {
+            primary_position = shifted_position;
+        } else {
+            secondary_positions.push(shifted_position);
+        }
+    }
+    buffer.cursor.move_to(primary_position);
+    if edits.iter().any(|&(_, _, delta)| delta.is_some()) {
+        util::record_edit(app);
+    }
+    app.secondary_cursors.update(primary_position, secondary_positions);
+
+    commands::view::scroll_to_cursor(app)
+}
+
 pub fn display_current_scope(app: &mut Application) -> Result {
     let scope_display_buffer = {
         let buffer = app.workspace.current_buffer().ok_or(BUFFER_MISSING)?;
@@ -302,11 +965,78 @@ pub fn display_current_scope(app: &mut Application) -> Result {
     util::add_buffer(scope_display_buffer, app)
 }
 
+/// Reports the number of lines, words (runs of non-whitespace characters),
+/// and characters in the selection, or in the buffer outside of select modes.
+pub fn display_word_count(app: &mut Application) -> Result {
+    let buffer = app.workspace.current_buffer().ok_or(BUFFER_MISSING)?;
+    let (content, scope) = match app.mode {
+        Mode::Select(ref mode) => {
+            let range = Range::new(*buffer.cursor, mode.anchor);
+            (buffer.read(&range).unwrap_or_default(), "selection")
+        }
+        Mode::SelectBlock(ref mode) => {
+            let rows: Vec<String> = mode.to_ranges(&*buffer.cursor, &buffer.data())
+                .iter()
+                .map(|range| buffer.read(range).unwrap_or_default())
+                .collect();
+            (rows.join("\n"), "selection")
+        }
+        Mode::SelectLine(ref mode) => {
+            let range = util::inclusive_range(
+                &LineRange::new(mode.anchor, buffer.cursor.line),
+                buffer
+            );
+            (buffer.read(&range).unwrap_or_default(), "selection")
+        }
+        _ => (buffer.data(), "buffer"),
+    };
+
+    let counts = [
+        (content.lines().count(), "line"),
+        (content.split_whitespace().count(), "word"),
+        (content.graphemes(true).count(), "character"),
+    ];
+    let counts: Vec<String> = counts
+        .iter()
+        .map(|&(count, noun)| format!("{} {}{}", count, noun, if count == 1 { "" } else { "s" }))
+        .collect();
+    app.message = Some(format!("{} in {}", counts.join(", "), scope));
+
+    Ok(())
+}
+
 /// Inserts a newline character at the current cursor position.
 /// Also performs automatic indentation, basing the indent off
 /// of the previous line's leading whitespace.
 pub fn insert_newline(app: &mut Application) -> Result {
+    util::ensure_writable(app)?;
     if let Some(buffer) = app.workspace.current_buffer() {
+        let tab_content = app.preferences.borrow().tab_content(buffer.path.as_ref());
+        let current_line = buffer.data()
+            .lines()
+            .nth(buffer.cursor.line)
+            .map(|line| line.to_string())
+            .unwrap_or_default();
+
+        // Clear lines that only contain (likely auto-inserted) indentation,
+        // rather than leaving trailing whitespace behind.
+        if !current_line.is_empty() && current_line.trim().is_empty() {
+            let line = buffer.cursor.line;
+            buffer.delete_range(Range::new(
+                Position { line, offset: 0 },
+                Position { line, offset: current_line.graphemes(true).count() }
+            ));
+            buffer.cursor.move_to(Position { line, offset: 0 });
+        }
+
+        // Lines ending with an opening bracket start a more deeply indented block.
+        let opens_block = current_line
+            .graphemes(true)
+            .take(buffer.cursor.offset)
+            .collect::<String>()
+            .trim_end()
+            .ends_with(|c: char| c == '{' || c == '(' || c == '[');
+
         // Insert the newline character.
         buffer.insert("\n");
 
@@ -327,10 +1057,13 @@ pub fn insert_newline(app: &mut Application) -> Result {
 
         // Searching backwards, copy the nearest non-blank line's indent content.
         let nearest_non_blank_line = previous_content.lines().rev().find(|line| !line.is_empty());
-        let indent_content = match nearest_non_blank_line {
+        let mut indent_content: String = match nearest_non_blank_line {
             Some(line) => line.chars().take_while(|&c| c.is_whitespace()).collect(),
             None => String::new(),
         };
+        if opens_block {
+            indent_content.push_str(&tab_content);
+        }
 
         // Insert and move to the end of the indent content.
         let indent_length = indent_content.chars().count();
@@ -339,6 +1072,7 @@ pub fn insert_newline(app: &mut Application) -> Result {
             line: position.line + 1,
             offset: indent_length,
         });
+        util::record_edit(app);
     } else {
         bail!(BUFFER_MISSING);
     }
@@ -348,6 +1082,7 @@ pub fn insert_newline(app: &mut Application) -> Result {
 }
 
 pub fn indent_line(app: &mut Application) -> Result {
+    util::ensure_writable(app)?;
     let buffer = app.workspace.current_buffer().ok_or(BUFFER_MISSING)?;
     let tab_content = app.preferences.borrow().tab_content(buffer.path.as_ref());
 
@@ -374,9 +1109,15 @@ pub fn indent_line(app: &mut Application) -> Result {
         _ => buffer.cursor.line..buffer.cursor.line + 1,
     };
 
-    // Move to the start of the current line and
-    // insert the content, as a single operation.
-    buffer.start_operation_group();
+    // Move to the start of the current line and insert the content, as a
+    // single operation (insert mode holds one open for its duration).
+    let grouped = match app.mode {
+        Mode::Insert => false,
+        _ => true,
+    };
+    if grouped {
+        buffer.start_operation_group();
+    }
     for line in lines {
         buffer.cursor.move_to(Position {
             line,
@@ -384,15 +1125,19 @@ pub fn indent_line(app: &mut Application) -> Result {
         });
         buffer.insert(tab_content.clone());
     }
-    buffer.end_operation_group();
+    if grouped {
+        buffer.end_operation_group();
+    }
 
     // Move to the original position, shifted to compensate for the indent.
     buffer.cursor.move_to(target_position);
+    util::record_edit(app);
 
     Ok(())
 }
 
 pub fn outdent_line(app: &mut Application) -> Result {
+    util::ensure_writable(app)?;
     let buffer = app.workspace.current_buffer().ok_or(BUFFER_MISSING)?;
     let tab_content = app.preferences.borrow().tab_content(buffer.path.as_ref());
 
@@ -412,9 +1157,17 @@ pub fn outdent_line(app: &mut Application) -> Result {
         _ => buffer.cursor.line..buffer.cursor.line + 1,
     };
 
-    // Group the individual outdent operations as one.
-    buffer.start_operation_group();
+    // Group the individual outdent operations as one
+    // (insert mode holds a group open for its duration).
+    let grouped = match app.mode {
+        Mode::Insert => false,
+        _ => true,
+    };
+    if grouped {
+        buffer.start_operation_group();
+    }
 
+    let mut outdented = false;
     for line in lines {
         if let Some(content) = data.lines().nth(line) {
             let mut space_char_count = 0;
@@ -459,24 +1212,33 @@ pub fn outdent_line(app: &mut Application) -> Result {
                     line: target_line,
                     offset: target_offset,
                 });
+                outdented = true;
             }
         }
     }
 
     // Finish grouping the individual outdent operations as one.
-    buffer.end_operation_group();
+    if grouped {
+        buffer.end_operation_group();
+    }
+    if outdented {
+        util::record_edit(app);
+    }
 
     Ok(())
 }
 
 pub fn toggle_line_comment(app: &mut Application) -> Result {
+    util::ensure_writable(app)?;
     let buffer = app.workspace.current_buffer().ok_or(BUFFER_MISSING)?;
     let original_cursor = *buffer.cursor.clone();
 
     let comment_prefix = {
-        let path = buffer.path.as_ref().ok_or(BUFFER_PATH_MISSING)?;
-        let prefix = app.preferences.borrow().line_comment_prefix(path)
-            .ok_or("No line comment prefix for the current buffer")?;
+        // Fall back to a C-style prefix for buffers
+        // without a path or a configured comment prefix.
+        let prefix = buffer.path.as_ref()
+            .and_then(|path| app.preferences.borrow().line_comment_prefix(path))
+            .unwrap_or_else(|| String::from(DEFAULT_LINE_COMMENT_PREFIX));
 
         prefix + " " // implicitly add trailing space
     };
@@ -536,6 +1298,9 @@ pub fn toggle_line_comment(app: &mut Application) -> Result {
 
     // Restore original cursor
     buffer.cursor.move_to(original_cursor);
+    if !lines.is_empty() {
+        util::record_edit(app);
+    }
 
     Ok(())
 }
@@ -566,39 +1331,84 @@ fn remove_line_comment(buffer: &mut Buffer, lines: &[(usize, &str)], prefix: &st
 }
 
 pub fn change_token(app: &mut Application) -> Result {
+    util::ensure_writable(app)?;
     commands::buffer::delete_token(app)?;
     commands::application::switch_to_insert_mode(app)?;
 
     Ok(())
 }
 
+/// Deletes from the cursor to the end of its line, leaving the line's newline.
 pub fn delete_rest_of_line(app: &mut Application) -> Result {
+    util::ensure_writable(app)?;
     let buffer = app.workspace.current_buffer().ok_or(BUFFER_MISSING)?;
-
-    // Create a range extending from the
-    // cursor's current position to the next line.
-    let starting_position = *buffer.cursor;
-    let target_line = buffer.cursor.line + 1;
-    buffer.start_operation_group();
-    buffer.delete_range(Range::new(starting_position,
-                                   Position {
-                                       line: target_line,
-                                       offset: 0,
-                                   }));
-
-    // Since we've removed a newline as part of the range, re-add it.
-    buffer.insert("\n");
+    if let Some(range) = rest_of_line_range(buffer) {
+        buffer.start_operation_group();
+        buffer.delete_range(range);
+        buffer.end_operation_group();
+        util::record_edit(app);
+    }
 
     Ok(())
 }
 
 pub fn change_rest_of_line(app: &mut Application) -> Result {
-    commands::buffer::delete_rest_of_line(app)?;
+    // Insert mode holds an operation group open for its duration,
+    // so the deletion is undone along with whatever is typed.
     commands::application::switch_to_insert_mode(app)?;
+    let buffer = app.workspace.current_buffer().ok_or(BUFFER_MISSING)?;
+    if let Some(range) = rest_of_line_range(buffer) {
+        buffer.delete_range(range);
+        util::record_edit(app);
+    }
 
     Ok(())
 }
 
+/// Deletes from the cursor back to the start of its line's content (i.e.
+/// leaving its indentation), or forward to it if the cursor is within the
+/// indentation, leaving the cursor where the deleted content started.
+pub fn delete_to_start_of_line(app: &mut Application) -> Result {
+    util::ensure_writable(app)?;
+    let buffer = app.workspace.current_buffer().ok_or(BUFFER_MISSING)?;
+    let line = buffer.cursor.line;
+    let content_offset = buffer.data()
+        .lines()
+        .nth(line)
+        .map(|content| {
+            content
+                .graphemes(true)
+                .take_while(|grapheme| grapheme.chars().all(char::is_whitespace))
+                .count()
+        })
+        .unwrap_or(0);
+    let cursor_offset = buffer.cursor.offset.min(line_length(buffer, line));
+    if cursor_offset == content_offset {
+        return Ok(());
+    }
+
+    let start = Position { line, offset: cursor_offset.min(content_offset) };
+    let end = Position { line, offset: cursor_offset.max(content_offset) };
+    buffer.start_operation_group();
+    buffer.delete_range(Range::new(start, end));
+    buffer.cursor.move_to(start);
+    buffer.end_operation_group();
+    util::record_edit(app);
+
+    commands::view::scroll_to_cursor(app)
+}
+
+// The range from the cursor to the end of its line, or None if it's empty.
+fn rest_of_line_range(buffer: &Buffer) -> Option<Range> {
+    let line = buffer.cursor.line;
+    let end = line_length(buffer, line);
+    if buffer.cursor.offset >= end {
+        return None;
+    }
+
+    Some(Range::new(*buffer.cursor, Position { line, offset: end }))
+}
+
 pub fn start_command_group(app: &mut Application) -> Result {
     app.workspace
         .current_buffer()
@@ -617,35 +1427,211 @@ pub fn end_command_group(app: &mut Application) -> Result {
     Ok(())
 }
 
+/// Inserts the current time at the cursor, using the configured format.
+pub fn insert_timestamp(app: &mut Application) -> Result {
+    let format = app.preferences.borrow().timestamp_format().to_string();
+    insert_time(app, &format)
+}
+
+pub fn insert_date(app: &mut Application) -> Result {
+    insert_time(app, "%Y-%m-%d")
+}
+
+pub fn insert_datetime(app: &mut Application) -> Result {
+    insert_time(app, "%Y-%m-%d %H:%M:%S")
+}
+
+pub fn insert_unix_timestamp(app: &mut Application) -> Result {
+    insert_time(app, "%s")
+}
+
+// Inserts the formatted time, moving the cursor past it. In insert mode, this
+// joins the current command group, so that it's undone along with the typing.
+fn insert_time(app: &mut Application, format: &str) -> Result {
+    util::ensure_writable(app)?;
+    let timestamp = util::time::format(SystemTime::now(), format);
+    let buffer = app.workspace.current_buffer().ok_or(BUFFER_MISSING)?;
+    let position = *buffer.cursor;
+    buffer.insert(timestamp.clone());
+    buffer.cursor.move_to(Position {
+        line: position.line,
+        offset: position.offset + timestamp.graphemes(true).count(),
+    });
+    util::record_edit(app);
+
+    commands::view::scroll_to_cursor(app)
+}
+
+/// Reverts the last operation (or operation group, so that multi-step
+/// commands are undone in a single step), describing what was reverted.
 pub fn undo(app: &mut Application) -> Result {
-    app.workspace.current_buffer().ok_or(BUFFER_MISSING)?.undo();
+    util::ensure_writable(app)?;
+    let buffer = app.workspace.current_buffer().ok_or(BUFFER_MISSING)?;
+    app.message = Some(step_and_describe(buffer, "Undo", "revert", |b| b.undo()));
+
+    // Secondary cursors don't follow changes that are undone (or redone).
+    app.secondary_cursors.clear();
+
     commands::view::scroll_to_cursor(app).chain_err(|| {
         "Couldn't scroll to cursor after undoing."
     })
 }
 
 pub fn redo(app: &mut Application) -> Result {
-    app.workspace.current_buffer().ok_or(BUFFER_MISSING)?.redo();
+    util::ensure_writable(app)?;
+    let buffer = app.workspace.current_buffer().ok_or(BUFFER_MISSING)?;
+    app.message = Some(step_and_describe(buffer, "Redo", "reapply", |b| b.redo()));
+    app.secondary_cursors.clear();
+
     commands::view::scroll_to_cursor(app).chain_err(|| {
         "Couldn't scroll to cursor after redoing."
     })
 }
 
-pub fn paste(app: &mut Application) -> Result {
-    let insert_below = match app.mode {
-        Mode::Select(_) | Mode::SelectLine(_) | Mode::Search(_) => {
-            commands::selection::delete(app).chain_err(|| {
-                "Couldn't delete selection prior to pasting."
-            })?;
-            false
+/// Discards every change made since the buffer was last saved (or opened),
+/// by stepping through its history until reaching the saved content. Unlike
+/// reloading, the file isn't re-read and the discarded changes can be redone.
+pub fn undo_to_save_point(app: &mut Application) -> Result {
+    util::ensure_writable(app)?;
+    let buffer = app.workspace.current_buffer().ok_or(BUFFER_MISSING)?;
+    if !buffer.modified() {
+        return Ok(());
+    }
+
+    // The save point is usually behind us, but it's ahead of
+    // us if changes were undone past it before being edited.
+    let data = buffer.data();
+    let reached = step_through_history(buffer, |b| b.undo(), |b| !b.modified()) ||
+        (step_through_history(buffer, |b| b.redo(), |b| b.data() == data) &&
+         step_through_history(buffer, |b| b.redo(), |b| !b.modified()));
+    if !reached {
+        step_through_history(buffer, |b| b.undo(), |b| b.data() == data);
+        bail!("Couldn't find the save point in the buffer's history");
+    }
+    app.message = Some(String::from("Reverted to save point"));
+    app.secondary_cursors.clear();
+
+    commands::view::scroll_to_cursor(app).chain_err(|| {
+        "Couldn't scroll to cursor after reverting to save point."
+    })
+}
+
+// Undoes/redoes until the buffer satisfies the condition, returning false
+// if the end of its history is reached first (i.e. the step has no effect).
+fn step_through_history<S, C>(buffer: &mut Buffer, step: S, condition: C) -> bool
+where
+    S: Fn(&mut Buffer),
+    C: Fn(&Buffer) -> bool,
+{
+    while !condition(buffer) {
+        let data = buffer.data();
+        step(buffer);
+        if buffer.data() == data {
+            return false;
         }
-        _ => true,
+    }
+
+    true
+}
+
+// Undoes/redoes the last change, summarizing where it took effect (e.g.
+// "Undo: removed 3 lines at line 5"). Changed lines are collected using the
+// buffer's change callback, which is wrapped (rather than replaced) for the
+// duration of the step, so that the view is still notified of the change.
+fn step_and_describe<F>(buffer: &mut Buffer, action: &str, verb: &str, step: F) -> String
+where
+    F: FnOnce(&mut Buffer),
+{
+    let changed_lines = Rc::new(RefCell::new(Vec::new()));
+    let original_callback = Rc::new(buffer.change_callback.take());
+    let (lines, callback) = (changed_lines.clone(), original_callback.clone());
+    buffer.change_callback = Some(Box::new(move |position| {
+        lines.borrow_mut().push(position.line);
+        if let Some(ref callback) = *callback {
+            callback(position);
+        }
+    }));
+
+    let line_count = buffer.line_count();
+    step(buffer);
+
+    // Drop the wrapper before reclaiming the original callback.
+    buffer.change_callback = None;
+    buffer.change_callback = Rc::try_unwrap(original_callback).unwrap_or(None);
+
+    let first_line = match changed_lines.borrow().iter().min() {
+        Some(&line) => line,
+        None => return format!("{}: nothing to {}", action, verb),
+    };
+    let delta = buffer.line_count() as isize - line_count as isize;
+    let change = if delta == 0 {
+        String::from("changed text")
+    } else {
+        let count = delta.abs() as usize;
+        format!(
+            "{} {} line{}",
+            if delta > 0 { "added" } else { "removed" },
+            count,
+            if count == 1 { "" } else { "s" }
+        )
+    };
+
+    format!("{}: {} at line {}", action, change, first_line + 1)
+}
+
+pub fn paste(app: &mut Application) -> Result {
+    util::ensure_writable(app)?;
+
+    // Pasting over a selection replaces it, as a single operation.
+    let (replacing, replacing_lines) = match app.mode {
+        Mode::SelectLine(_) => (true, true),
+        Mode::Select(_) | Mode::SelectBlock(_) | Mode::Search(_) => (true, false),
+        _ => (false, false),
     };
+    let mut replaced_ranges = Vec::new();
+    if replacing {
+        app.workspace.current_buffer().ok_or(BUFFER_MISSING)?.start_operation_group();
+        match commands::selection::delete_selection(app) {
+            Ok(ranges) => replaced_ranges = ranges,
+            Err(error) => {
+                if let Some(buffer) = app.workspace.current_buffer() {
+                    buffer.end_operation_group();
+                }
+                return Err(error).chain_err(|| "Couldn't delete selection prior to pasting.");
+            }
+        }
+    }
+
+    insert_clipboard_content(app, replaced_ranges, replacing_lines)
+}
+
+// Inserts the clipboard content at the cursor, after the replaced ranges (if
+// any) have been deleted, ending the operation group started before deleting
+// them. Tracks the paste, so that it can be cycled.
+fn insert_clipboard_content(
+    app: &mut Application,
+    replaced_ranges: Vec<Range>,
+    replacing_lines: bool,
+) -> Result {
+    let replacing = !replaced_ranges.is_empty();
+    let insert_below = !replacing;
 
     // TODO: Clean up duplicate buffer.insert(content.clone()) calls.
     if let Some(buffer) = app.workspace.current_buffer() {
-        match *app.clipboard.get_content() {
+        let paste_position = *buffer.cursor.clone();
+        let content = app.clipboard.get_content();
+        let pasted = replacing || *content != ClipboardContent::None;
+
+        match *content {
+            // Inline content replacing whole lines is kept on a line of its own.
+            ClipboardContent::Inline(ref content) if replacing_lines => {
+                buffer.insert(format!("{}\n", content))
+            }
             ClipboardContent::Inline(ref content) => buffer.insert(content.clone()),
+            // Block content replacing part of a line is put on lines of its own.
+            ClipboardContent::Block(ref content) if replacing && !replacing_lines => {
+                buffer.insert(format!("\n{}", content))
+            }
             ClipboardContent::Block(ref content) => {
                 let original_cursor_position = *buffer.cursor.clone();
                 let line = original_cursor_position.line;
@@ -678,8 +1664,25 @@ pub fn paste(app: &mut Application) -> Result {
                     buffer.insert(content.clone());
                 }
             }
+            ClipboardContent::Rectangular(ref rows) => paste_rows(buffer, rows),
             ClipboardContent::None => (),
         }
+        if replacing {
+            buffer.end_operation_group();
+        }
+
+        // Track where we pasted, so that the content can be cycled.
+        if let Some(buffer_id) = buffer.id {
+            app.clipboard.set_paste(Paste {
+                buffer_id,
+                position: paste_position,
+                replaced_ranges,
+                replaced_lines: replacing_lines,
+            });
+        }
+        if pasted {
+            util::record_edit(app);
+        }
     } else {
         bail!(BUFFER_MISSING);
     }
@@ -688,7 +1691,73 @@ pub fn paste(app: &mut Application) -> Result {
     Ok(())
 }
 
+/// Replaces the content inserted by the preceding paste with the previous
+/// clipboard entry. This relies on undo to remove the pasted content, so it
+/// has to immediately follow a paste (or another cycle) in the same buffer.
+/// Undoing a paste over a selection also restores the selected content,
+/// so the replacement is replayed, deleting it again.
+pub fn cycle_paste(app: &mut Application) -> Result {
+    util::ensure_writable(app)?;
+    let buffer = app.workspace.current_buffer().ok_or(BUFFER_MISSING)?;
+    let id = buffer.id.ok_or(BUFFER_ID_MISSING)?;
+    let paste = app.clipboard.paste(id)
+        .cloned()
+        .ok_or("Content must be pasted before it can be cycled")?;
+    if !app.clipboard.cycle_content() {
+        bail!("No previous clipboard content to paste");
+    }
+
+    buffer.undo();
+    if !paste.replaced_ranges.is_empty() {
+        buffer.start_operation_group();
+        for range in paste.replaced_ranges.iter() {
+            buffer.delete_range(range.clone());
+        }
+    }
+    buffer.cursor.move_to(paste.position);
+
+    insert_clipboard_content(app, paste.replaced_ranges, paste.replaced_lines)
+}
+
+/// Inserts each row at the cursor's column on successive lines, as a single
+/// operation, adding lines to the end of the buffer when we run out of them.
+/// Lines shorter than the cursor's column are padded with spaces.
+fn paste_rows(buffer: &mut Buffer, rows: &[String]) {
+    let original_cursor_position = *buffer.cursor.clone();
+
+    buffer.start_operation_group();
+    for (index, row) in rows.iter().enumerate() {
+        let line = original_cursor_position.line + index;
+        let line_length = buffer.data()
+            .lines()
+            .nth(line)
+            .map(|content| content.graphemes(true).count())
+            .unwrap_or(0);
+        let target = Position {
+            line,
+            offset: original_cursor_position.offset.min(line_length),
+        };
+
+        // Pad lines that end short of the cursor's column,
+        // so that the pasted rows stay aligned.
+        let padding = " ".repeat(original_cursor_position.offset - target.offset);
+
+        if buffer.cursor.move_to(target) {
+            buffer.insert(format!("{}{}", padding, row));
+        } else {
+            // We've run out of lines; add one.
+            buffer.cursor.move_to_last_line();
+            buffer.cursor.move_to_end_of_line();
+            buffer.insert(format!("\n{}{}", padding, row));
+        }
+    }
+    buffer.end_operation_group();
+
+    buffer.cursor.move_to(original_cursor_position);
+}
+
 pub fn paste_above(app: &mut Application) -> Result {
+    util::ensure_writable(app)?;
     let buffer = app.workspace.current_buffer().ok_or(BUFFER_MISSING)?;
 
     if let ClipboardContent::Block(ref content) = *app.clipboard.get_content() {
@@ -702,16 +1771,28 @@ pub fn paste_above(app: &mut Application) -> Result {
         mem::swap(&mut *buffer.cursor, &mut start_of_line);
         buffer.insert(content.clone());
         mem::swap(&mut *buffer.cursor, &mut start_of_line);
+        util::record_edit(app);
     }
 
     Ok(())
 }
 
 pub fn remove_trailing_whitespace(app: &mut Application) -> Result {
+    util::ensure_writable(app)?;
+    if strip_trailing_whitespace(app)? {
+        util::record_edit(app);
+    }
+
+    Ok(())
+}
+
+// Removes trailing whitespace (if configured to) without recording an edit,
+// for the same reason as apply_formatter; returns whether any was removed.
+fn strip_trailing_whitespace(app: &mut Application) -> errors::Result<bool> {
     let buffer = app.workspace.current_buffer().ok_or(BUFFER_MISSING)?;
 
     if !app.preferences.borrow().remove_trailing_whitespace(buffer.path.as_ref()) {
-        return Ok(());
+        return Ok(false);
     }
 
     let mut line = 0;
@@ -764,22 +1845,49 @@ pub fn remove_trailing_whitespace(app: &mut Application) -> Result {
                                }));
     }
 
+    // Leave the buffer (and its modified status) alone if there's nothing to do.
+    if ranges.is_empty() {
+        return Ok(false);
+    }
+
+    // Keep the cursor where it is, pulling it back to the
+    // end of its line if it's in whitespace we're removing.
+    let mut cursor_position = *buffer.cursor;
+    if let Some(range) = ranges.iter().find(|range| range.start().line == cursor_position.line) {
+        cursor_position.offset = cursor_position.offset.min(range.start().offset);
+    }
+
     // Step through the whitespace ranges in reverse order
     // and remove them from the buffer. We do this in
     // reverse as deletions would shift/invalidate ranges
-    // that occur after the deleted range.
+    // that occur after the deleted range. Grouping the
+    // deletions allows them to be undone in a single step.
+    buffer.start_operation_group();
     for range in ranges.into_iter().rev() {
         buffer.delete_range(range);
     }
+    buffer.end_operation_group();
+    buffer.cursor.move_to(cursor_position);
+
+    Ok(true)
+}
+
+pub fn ensure_trailing_newline(app: &mut Application) -> Result {
+    util::ensure_writable(app)?;
+    if add_trailing_newline(app)? {
+        util::record_edit(app);
+    }
 
     Ok(())
 }
 
-pub fn ensure_trailing_newline(app: &mut Application) -> Result {
+// Adds (or collapses) trailing newlines as configured, without recording an
+// edit, for the same reason as apply_formatter; returns whether any changed.
+fn add_trailing_newline(app: &mut Application) -> errors::Result<bool> {
     let buffer = app.workspace.current_buffer().ok_or(BUFFER_MISSING)?;
 
     if !app.preferences.borrow().ensure_trailing_newline(buffer.path.as_ref()) {
-        return Ok(());
+        return Ok(false);
     }
 
     // Find end of buffer position.
@@ -808,12 +1916,121 @@ pub fn ensure_trailing_newline(app: &mut Application) -> Result {
         buffer.insert("\n"); // Empty buffer
     }
 
+    if app.preferences.borrow().collapse_trailing_newlines() {
+        collapse_trailing_newlines(buffer);
+    }
+
+    Ok(buffer.data() != data)
+}
+
+// Removes all but the first of the newlines at the end of the buffer.
+fn collapse_trailing_newlines(buffer: &mut Buffer) {
+    let data = buffer.data();
+    let content = data.trim_end_matches('\n');
+    let trailing_newline_count = data.len() - content.len();
+    if trailing_newline_count < 2 {
+        return;
+    }
+
+    // Keep the newline ending the last line of content.
+    let last_line = content.matches('\n').count();
+    let start = Position { line: last_line + 1, offset: 0 };
+    let end = Position { line: last_line + trailing_newline_count, offset: 0 };
+    let cursor_position = *buffer.cursor;
+    buffer.delete_range(Range::new(start, end));
+
+    // Pull the cursor back if it was on one of the removed lines.
+    buffer.cursor.move_to(if cursor_position > start { start } else { cursor_position });
+}
+
+pub fn convert_tabs_to_spaces(app: &mut Application) -> Result {
+    convert_indentation(app, |width, _| " ".repeat(width))
+}
+
+pub fn convert_spaces_to_tabs(app: &mut Application) -> Result {
+    convert_indentation(app, |width, tab_width| {
+        format!("{}{}", "\t".repeat(width / tab_width), " ".repeat(width % tab_width))
+    })
+}
+
+/// Rewrites the leading indentation of every line in the buffer, as a single
+/// operation. The provided function is given the indentation's width (with
+/// tabs expanded to the configured tab width) and the tab width, and returns
+/// its replacement. Whitespace after the indentation is left untouched.
+fn convert_indentation<F>(app: &mut Application, indentation_for: F) -> Result
+    where F: Fn(usize, usize) -> String
+{
+    util::ensure_writable(app)?;
+    let buffer = app.workspace.current_buffer().ok_or(BUFFER_MISSING)?;
+    let tab_width = app.preferences.borrow().tab_width(buffer.path.as_ref()).max(1);
+    let original_cursor_position = *buffer.cursor.clone();
+    let mut cursor_position = original_cursor_position;
+
+    let data = buffer.data();
+    let mut converted = false;
+    buffer.start_operation_group();
+    for (line, content) in data.lines().enumerate() {
+        let indentation: String = content.chars()
+            .take_while(|&character| character == ' ' || character == '\t')
+            .collect();
+        let width = indentation.chars().fold(0, |width, character| {
+            if character == '\t' {
+                width + tab_width - width % tab_width
+            } else {
+                width + 1
+            }
+        });
+        let converted_indentation = indentation_for(width, tab_width);
+        if converted_indentation == indentation {
+            continue;
+        }
+
+        let indentation_length = indentation.chars().count();
+        buffer.delete_range(Range::new(
+            Position { line, offset: 0 },
+            Position { line, offset: indentation_length }
+        ));
+        buffer.cursor.move_to(Position { line, offset: 0 });
+        buffer.insert(converted_indentation.clone());
+        converted = true;
+
+        // Keep the cursor on the same content, if it's on this line.
+        if line == cursor_position.line {
+            let converted_length = converted_indentation.chars().count();
+            cursor_position.offset = if cursor_position.offset >= indentation_length {
+                cursor_position.offset - indentation_length + converted_length
+            } else {
+                cursor_position.offset.min(converted_length)
+            };
+        }
+    }
+    buffer.end_operation_group();
+    buffer.cursor.move_to(cursor_position);
+    if converted {
+        util::record_edit(app);
+    }
+
     Ok(())
 }
 
+/// Inserts the configured indent unit at the cursor. Within a line's leading
+/// whitespace, soft tabs only go as far as the next indent level, so that
+/// indentation stays a multiple of the configured width.
 pub fn insert_tab(app: &mut Application) -> Result {
+    util::ensure_writable(app)?;
     let buffer = app.workspace.current_buffer().ok_or(BUFFER_MISSING)?;
-    let tab_content = app.preferences.borrow().tab_content(buffer.path.as_ref());
+    let mut tab_content = app.preferences.borrow().tab_content(buffer.path.as_ref());
+
+    let data = buffer.data();
+    let in_indentation = data.lines()
+        .nth(buffer.cursor.line)
+        .map(|line| line.graphemes(true).take(buffer.cursor.offset).all(|g| g == " "))
+        .unwrap_or(true);
+    if in_indentation && tab_content.starts_with(' ') {
+        let overshoot = buffer.cursor.offset % tab_content.len();
+        tab_content.truncate(tab_content.len() - overshoot);
+    }
+
     let tab_content_width = tab_content.chars().count();
     buffer.insert(tab_content.clone());
 
@@ -821,6 +2038,7 @@ pub fn insert_tab(app: &mut Application) -> Result {
     for _ in 0..tab_content_width {
         buffer.cursor.move_right();
     }
+    util::record_edit(app);
 
     Ok(())
 }
@@ -828,11 +2046,16 @@ pub fn insert_tab(app: &mut Application) -> Result {
 #[cfg(test)]
 mod tests {
     use crate::commands;
+    use crate::input::Key;
     use crate::models::Application;
     use crate::models::application::{ClipboardContent, Mode};
     use scribe::Buffer;
     use scribe::buffer::Position;
+    use std::env;
+    use std::fs;
     use std::path::Path;
+    use crate::models::application::Preferences;
+    use crate::yaml::yaml::YamlLoader;
 
     #[test]
     fn insert_newline_uses_current_line_indentation() {
@@ -900,6 +2123,38 @@ mod tests {
         );
     }
 
+    #[test]
+    fn insert_newline_increases_indentation_after_an_opening_bracket() {
+        let mut app = Application::new(&Vec::new()).unwrap();
+        let mut buffer = Buffer::new();
+        buffer.insert("  fn amp() {");
+        buffer.cursor.move_to(Position { line: 0, offset: 12 });
+        app.workspace.add_buffer(buffer);
+
+        super::insert_newline(&mut app).unwrap();
+
+        assert_eq!(app.workspace.current_buffer().unwrap().data(),
+                   "  fn amp() {\n    ");
+        assert_eq!(*app.workspace.current_buffer().unwrap().cursor,
+                   Position { line: 1, offset: 4 });
+    }
+
+    #[test]
+    fn insert_newline_clears_whitespace_only_lines() {
+        let mut app = Application::new(&Vec::new()).unwrap();
+        let mut buffer = Buffer::new();
+        buffer.insert("    amp\n    ");
+        buffer.cursor.move_to(Position { line: 1, offset: 4 });
+        app.workspace.add_buffer(buffer);
+
+        super::insert_newline(&mut app).unwrap();
+
+        assert_eq!(app.workspace.current_buffer().unwrap().data(),
+                   "    amp\n\n    ");
+        assert_eq!(*app.workspace.current_buffer().unwrap().cursor,
+                   Position { line: 2, offset: 4 });
+    }
+
     #[test]
     fn change_rest_of_line_removes_content_and_switches_to_insert_mode() {
         let mut app = Application::new(&Vec::new()).unwrap();
@@ -935,6 +2190,46 @@ mod tests {
                    "    amp\neditor");
     }
 
+    #[test]
+    fn delete_rest_of_line_deletes_to_the_end_of_the_line_in_a_single_step() {
+        let mut app = Application::new(&Vec::new()).unwrap();
+        let mut buffer = Buffer::new();
+        buffer.insert("amp\n\neditor");
+        buffer.cursor.move_to(Position{ line: 2, offset: 2 });
+        app.workspace.add_buffer(buffer);
+
+        super::delete_rest_of_line(&mut app).unwrap();
+        assert_eq!(app.workspace.current_buffer().unwrap().data(), "amp\n\ned");
+        assert_eq!(*app.workspace.current_buffer().unwrap().cursor, Position{ line: 2, offset: 2 });
+
+        // Empty lines are left as-is.
+        app.workspace.current_buffer().unwrap().cursor.move_to(Position{ line: 1, offset: 0 });
+        super::delete_rest_of_line(&mut app).unwrap();
+        assert_eq!(app.workspace.current_buffer().unwrap().data(), "amp\n\ned");
+
+        super::undo(&mut app).unwrap();
+        assert_eq!(app.workspace.current_buffer().unwrap().data(), "amp\n\neditor");
+    }
+
+    #[test]
+    fn delete_to_start_of_line_leaves_indentation() {
+        let mut app = Application::new(&Vec::new()).unwrap();
+        let mut buffer = Buffer::new();
+        buffer.insert("    amp editor");
+        buffer.cursor.move_to(Position{ line: 0, offset: 8 });
+        app.workspace.add_buffer(buffer);
+
+        super::delete_to_start_of_line(&mut app).unwrap();
+        assert_eq!(app.workspace.current_buffer().unwrap().data(), "    editor");
+        assert_eq!(*app.workspace.current_buffer().unwrap().cursor, Position{ line: 0, offset: 4 });
+
+        // Within the indentation, content up to its start is deleted.
+        app.workspace.current_buffer().unwrap().cursor.move_to(Position{ line: 0, offset: 2 });
+        super::delete_to_start_of_line(&mut app).unwrap();
+        assert_eq!(app.workspace.current_buffer().unwrap().data(), "  editor");
+        assert_eq!(*app.workspace.current_buffer().unwrap().cursor, Position{ line: 0, offset: 2 });
+    }
+
     #[test]
     fn delete_token_deletes_current_token_and_trailing_whitespace() {
         let mut app = Application::new(&Vec::new()).unwrap();
@@ -965,6 +2260,73 @@ mod tests {
         assert_eq!(app.workspace.current_buffer().unwrap().data(), "\neditor");
     }
 
+    #[test]
+    fn toggle_case_toggles_and_advances_past_the_character_under_the_cursor() {
+        let mut app = Application::new(&Vec::new()).unwrap();
+        let mut buffer = Buffer::new();
+        buffer.insert("a-p");
+        app.workspace.add_buffer(buffer);
+
+        super::toggle_case(&mut app).unwrap();
+        assert_eq!(app.workspace.current_buffer().unwrap().data(), "A-p");
+        assert_eq!(*app.workspace.current_buffer().unwrap().cursor, Position{ line: 0, offset: 1 });
+
+        // Characters without case are skipped over.
+        super::toggle_case(&mut app).unwrap();
+        assert_eq!(app.workspace.current_buffer().unwrap().data(), "A-p");
+        assert_eq!(*app.workspace.current_buffer().unwrap().cursor, Position{ line: 0, offset: 2 });
+
+        // The cursor stays on the last character.
+        super::toggle_case(&mut app).unwrap();
+        assert_eq!(app.workspace.current_buffer().unwrap().data(), "A-P");
+        assert_eq!(*app.workspace.current_buffer().unwrap().cursor, Position{ line: 0, offset: 2 });
+
+        // Each toggle is undone in a single step.
+        super::undo(&mut app).unwrap();
+        assert_eq!(app.workspace.current_buffer().unwrap().data(), "A-p");
+    }
+
+    #[test]
+    fn replace_character_replaces_the_character_under_the_cursor() {
+        let mut app = Application::new(&Vec::new()).unwrap();
+        let mut buffer = Buffer::new();
+        buffer.insert("amp");
+        buffer.cursor.move_to(Position{ line: 0, offset: 1 });
+        app.workspace.add_buffer(buffer);
+
+        commands::application::switch_to_replace_character_mode(&mut app).unwrap();
+        app.view.last_key = Some(Key::Char('a'));
+        super::replace_character(&mut app).unwrap();
+        assert_eq!(app.workspace.current_buffer().unwrap().data(), "aap");
+        assert_eq!(*app.workspace.current_buffer().unwrap().cursor, Position{ line: 0, offset: 1 });
+        assert!(match app.mode {
+            Mode::Normal => true,
+            _ => false,
+        });
+
+        super::undo(&mut app).unwrap();
+        assert_eq!(app.workspace.current_buffer().unwrap().data(), "amp");
+    }
+
+    #[test]
+    fn replace_character_splits_lines_and_inserts_at_the_end_of_the_line() {
+        let mut app = Application::new(&Vec::new()).unwrap();
+        let mut buffer = Buffer::new();
+        buffer.insert("amp");
+        buffer.cursor.move_to(Position{ line: 0, offset: 1 });
+        app.workspace.add_buffer(buffer);
+
+        app.view.last_key = Some(Key::Enter);
+        super::replace_character(&mut app).unwrap();
+        assert_eq!(app.workspace.current_buffer().unwrap().data(), "a\np");
+        assert_eq!(*app.workspace.current_buffer().unwrap().cursor, Position{ line: 1, offset: 0 });
+
+        app.workspace.current_buffer().unwrap().cursor.move_to(Position{ line: 1, offset: 1 });
+        app.view.last_key = Some(Key::Char('s'));
+        super::replace_character(&mut app).unwrap();
+        assert_eq!(app.workspace.current_buffer().unwrap().data(), "a\nps");
+    }
+
     #[test]
     fn delete_current_line_deletes_current_line() {
         let mut app = Application::new(&Vec::new()).unwrap();
@@ -988,12 +2350,31 @@ mod tests {
     }
 
     #[test]
-    fn indent_line_inserts_two_spaces_at_start_of_line() {
+    fn delete_current_line_deletes_counted_lines_in_a_single_step() {
         let mut app = Application::new(&Vec::new()).unwrap();
         let mut buffer = Buffer::new();
-        buffer.insert("amp\neditor");
-        buffer.cursor.move_to(Position {
-            line: 1,
+        buffer.insert("amp\neditor\nbuffer\ncursor");
+        app.workspace.add_buffer(buffer);
+
+        app.count = Some(3);
+        super::delete_current_line(&mut app).unwrap();
+        assert_eq!(app.workspace.current_buffer().unwrap().data(), "cursor");
+        assert_eq!(app.count, None);
+
+        super::undo(&mut app).unwrap();
+        assert_eq!(
+            app.workspace.current_buffer().unwrap().data(),
+            "amp\neditor\nbuffer\ncursor"
+        );
+    }
+
+    #[test]
+    fn indent_line_inserts_two_spaces_at_start_of_line() {
+        let mut app = Application::new(&Vec::new()).unwrap();
+        let mut buffer = Buffer::new();
+        buffer.insert("amp\neditor");
+        buffer.cursor.move_to(Position {
+            line: 1,
             offset: 2,
         });
 
@@ -1007,6 +2388,26 @@ mod tests {
                    "amp\n  editor");
     }
 
+    #[test]
+    fn indent_line_uses_the_indentation_configured_for_the_buffer_type() {
+        let mut app = Application::new(&Vec::new()).unwrap();
+        let data = YamlLoader::load_from_str(
+            "types:\n  go:\n    soft_tabs: false\n  rs:\n    tab_width: 4"
+        ).unwrap();
+        *app.preferences.borrow_mut() = Preferences::new(data.into_iter().nth(0));
+
+        let expectations = vec![("main.go", "\tamp"), ("main.rs", "    amp"), ("main.js", "  amp")];
+        for (path, indented_data) in expectations {
+            let mut buffer = Buffer::new();
+            buffer.insert("amp");
+            buffer.path = Some(Path::new(path).to_path_buf());
+            app.workspace.add_buffer(buffer);
+            super::indent_line(&mut app).unwrap();
+
+            assert_eq!(app.workspace.current_buffer().unwrap().data(), indented_data);
+        }
+    }
+
     #[test]
     fn indent_line_works_in_select_line_mode() {
         let mut app = Application::new(&Vec::new()).unwrap();
@@ -1272,6 +2673,36 @@ mod tests {
                    "\t\tamp\n\neditor");
     }
 
+    #[test]
+    fn remove_trailing_whitespace_preserves_cursor_and_can_be_undone() {
+        let mut app = Application::new(&Vec::new()).unwrap();
+        let mut buffer = Buffer::new();
+        buffer.insert("amp  \neditor  ");
+        buffer.cursor.move_to(Position { line: 1, offset: 7 });
+        app.workspace.add_buffer(buffer);
+        super::remove_trailing_whitespace(&mut app).unwrap();
+
+        // The cursor was in the removed whitespace, so it's
+        // pulled back to the end of the trimmed line.
+        assert_eq!(*app.workspace.current_buffer().unwrap().cursor,
+                   Position { line: 1, offset: 6 });
+
+        app.workspace.current_buffer().unwrap().undo();
+        assert_eq!(app.workspace.current_buffer().unwrap().data(),
+                   "amp  \neditor  ");
+    }
+
+    #[test]
+    fn remove_trailing_whitespace_does_not_modify_clean_buffers() {
+        let mut app = Application::new(&Vec::new()).unwrap();
+        let path = Path::new("Cargo.toml");
+        let buffer = Buffer::from_file(path).unwrap();
+        app.workspace.add_buffer(buffer);
+        super::remove_trailing_whitespace(&mut app).unwrap();
+
+        assert!(!app.workspace.current_buffer().unwrap().modified());
+    }
+
     #[test]
     fn save_removes_trailing_whitespace_and_adds_newlines() {
         let mut app = Application::new(&Vec::new()).unwrap();
@@ -1288,6 +2719,176 @@ mod tests {
                    "amp\neditor\n");
     }
 
+    #[test]
+    fn save_backs_up_existing_file_when_enabled() {
+        let path = env::temp_dir().join("amp_save_backup_test");
+        let backup_path = env::temp_dir().join("amp_save_backup_test~");
+        fs::write(&path, "amp\n").unwrap();
+
+        let mut app = Application::new(&Vec::new()).unwrap();
+        let data = YamlLoader::load_from_str("backup_on_save: true").unwrap();
+        *app.preferences.borrow_mut() = Preferences::new(data.into_iter().nth(0));
+        let mut buffer = Buffer::from_file(&path).unwrap();
+        buffer.insert("editor ");
+        app.workspace.add_buffer(buffer);
+        super::save(&mut app).unwrap();
+
+        assert_eq!(fs::read_to_string(&backup_path).unwrap(), "amp\n");
+        assert_eq!(fs::read_to_string(&path).unwrap(), "editor amp\n");
+        fs::remove_file(&path).unwrap();
+        fs::remove_file(&backup_path).unwrap();
+    }
+
+    #[test]
+    fn undo_to_save_point_discards_changes_made_since_saving() {
+        let path = env::temp_dir().join("amp_undo_to_save_point_test");
+        fs::write(&path, "amp\n").unwrap();
+
+        let mut app = Application::new(&Vec::new()).unwrap();
+        let mut buffer = Buffer::from_file(&path).unwrap();
+        buffer.insert("editor ");
+        app.workspace.add_buffer(buffer);
+        super::save(&mut app).unwrap();
+        app.workspace.current_buffer().unwrap().insert("text ");
+        app.workspace.current_buffer().unwrap().insert("buffer ");
+
+        super::undo_to_save_point(&mut app).unwrap();
+        assert_eq!(app.workspace.current_buffer().unwrap().data(), "editor amp\n");
+        assert!(!app.workspace.current_buffer().unwrap().modified());
+
+        // Reverting an unmodified buffer is a no-op.
+        super::undo_to_save_point(&mut app).unwrap();
+        assert_eq!(app.workspace.current_buffer().unwrap().data(), "editor amp\n");
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn undo_to_save_point_redoes_changes_undone_past_the_save_point() {
+        let path = env::temp_dir().join("amp_undo_to_save_point_redo_test");
+        fs::write(&path, "amp\n").unwrap();
+
+        let mut app = Application::new(&Vec::new()).unwrap();
+        let mut buffer = Buffer::from_file(&path).unwrap();
+        buffer.insert("editor ");
+        app.workspace.add_buffer(buffer);
+        super::save(&mut app).unwrap();
+        app.workspace.current_buffer().unwrap().undo();
+        assert_eq!(app.workspace.current_buffer().unwrap().data(), "amp\n");
+
+        super::undo_to_save_point(&mut app).unwrap();
+        assert_eq!(app.workspace.current_buffer().unwrap().data(), "editor amp\n");
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn reload_replaces_content_and_preserves_cursor_position() {
+        let path = env::temp_dir().join("amp_reload_test");
+        fs::write(&path, "amp\neditor\n").unwrap();
+
+        let mut app = Application::new(&Vec::new()).unwrap();
+        let mut buffer = Buffer::from_file(&path).unwrap();
+        buffer.cursor.move_to(Position { line: 1, offset: 4 });
+        app.workspace.add_buffer(buffer);
+        fs::write(&path, "amp\ned\n").unwrap();
+        super::reload(&mut app).unwrap();
+
+        assert_eq!(app.workspace.current_buffer().unwrap().data(), "amp\ned\n");
+        assert_eq!(*app.workspace.current_buffer().unwrap().cursor,
+                   Position { line: 1, offset: 2 });
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn reload_requires_confirmation_for_modified_buffers() {
+        let path = env::temp_dir().join("amp_reload_modified_test");
+        fs::write(&path, "amp\n").unwrap();
+
+        let mut app = Application::new(&Vec::new()).unwrap();
+        let mut buffer = Buffer::from_file(&path).unwrap();
+        buffer.insert("editor ");
+        app.workspace.add_buffer(buffer);
+        super::reload(&mut app).unwrap();
+
+        assert_eq!(app.workspace.current_buffer().unwrap().data(), "editor amp\n");
+        if let Mode::Confirm(_) = app.mode {
+        } else {
+            panic!("Failed to switch to confirm mode");
+        }
+
+        commands::confirm::confirm_command(&mut app).unwrap();
+        assert_eq!(app.workspace.current_buffer().unwrap().data(), "amp\n");
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn open_path_under_cursor_opens_relative_paths_at_line_numbers() {
+        let path = env::temp_dir().join("amp_open_path_test");
+        fs::write(&path, "amp\neditor\namp\n").unwrap();
+
+        let mut app = Application::new(&Vec::new()).unwrap();
+        let mut buffer = Buffer::new();
+        buffer.path = Some(env::temp_dir().join("amp_open_path_origin"));
+        buffer.insert("see (amp_open_path_test:2:5) for details");
+        buffer.cursor.move_to(Position{ line: 0, offset: 8 });
+        app.workspace.add_buffer(buffer);
+        super::open_path_under_cursor(&mut app).unwrap();
+
+        let buffer = app.workspace.current_buffer().unwrap();
+        assert_eq!(buffer.path, Some(path.clone()));
+        assert_eq!(*buffer.cursor, Position{ line: 1, offset: 0 });
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn open_path_under_cursor_opens_new_buffers_for_missing_files() {
+        let mut app = Application::new(&Vec::new()).unwrap();
+        let mut buffer = Buffer::new();
+        buffer.insert("amp_missing_path_test");
+        app.workspace.add_buffer(buffer);
+        super::open_path_under_cursor(&mut app).unwrap();
+
+        let expected_path = app.workspace.path.join("amp_missing_path_test");
+        let buffer = app.workspace.current_buffer().unwrap();
+        assert_eq!(buffer.path, Some(expected_path));
+        assert_eq!(buffer.data(), "");
+        assert!(app.message.is_some());
+    }
+
+    #[test]
+    fn open_path_under_cursor_fails_without_a_path() {
+        let mut app = Application::new(&Vec::new()).unwrap();
+        let mut buffer = Buffer::new();
+        buffer.insert("amp editor");
+        buffer.cursor.move_to(Position{ line: 0, offset: 3 });
+        app.workspace.add_buffer(buffer);
+
+        assert!(super::open_path_under_cursor(&mut app).is_err());
+    }
+
+    #[test]
+    fn read_only_buffers_reject_modifications_but_allow_navigation() {
+        let mut app = Application::new(&Vec::new()).unwrap();
+        let mut buffer = Buffer::new();
+        buffer.insert("amp\neditor");
+        app.workspace.add_buffer(buffer);
+        super::toggle_read_only(&mut app).unwrap();
+
+        assert!(super::delete(&mut app).is_err());
+        assert!(super::delete_current_line(&mut app).is_err());
+        assert!(commands::application::switch_to_insert_mode(&mut app).is_err());
+        assert!(commands::cursor::move_down(&mut app).is_ok());
+        assert_eq!(app.workspace.current_buffer().unwrap().data(), "amp\neditor");
+        if let Mode::Normal = app.mode {
+        } else {
+            panic!("Switched out of normal mode");
+        }
+
+        // Lifting the flag allows modifications again.
+        super::toggle_read_only(&mut app).unwrap();
+        super::delete_current_line(&mut app).unwrap();
+        assert_eq!(app.workspace.current_buffer().unwrap().data(), "amp\n");
+    }
+
     #[test]
     fn save_switches_to_path_mode_when_path_is_missing() {
         let mut app = Application::new(&Vec::new()).unwrap();
@@ -1562,6 +3163,35 @@ mod tests {
                    "amp\neditor\n");
     }
 
+    #[test]
+    fn ensure_trailing_newline_collapses_trailing_blank_lines_when_enabled() {
+        let mut app = Application::new(&Vec::new()).unwrap();
+        let data = YamlLoader::load_from_str("collapse_trailing_newlines: true").unwrap();
+        *app.preferences.borrow_mut() = Preferences::new(data.into_iter().nth(0));
+        let mut buffer = Buffer::new();
+        buffer.insert("amp\neditor\n\n\n");
+        buffer.cursor.move_to(Position { line: 4, offset: 0 });
+        app.workspace.add_buffer(buffer);
+        commands::buffer::ensure_trailing_newline(&mut app).unwrap();
+
+        assert_eq!(app.workspace.current_buffer().unwrap().data(),
+                   "amp\neditor\n");
+        assert_eq!(*app.workspace.current_buffer().unwrap().cursor,
+                   Position { line: 2, offset: 0 });
+    }
+
+    #[test]
+    fn ensure_trailing_newline_keeps_trailing_blank_lines_by_default() {
+        let mut app = Application::new(&Vec::new()).unwrap();
+        let mut buffer = Buffer::new();
+        buffer.insert("amp\neditor\n\n");
+        app.workspace.add_buffer(buffer);
+        commands::buffer::ensure_trailing_newline(&mut app).unwrap();
+
+        assert_eq!(app.workspace.current_buffer().unwrap().data(),
+                   "amp\neditor\n\n");
+    }
+
     #[test]
     fn paste_with_inline_content_replaces_selection() {
         let mut app = Application::new(&Vec::new()).unwrap();
@@ -1579,9 +3209,9 @@ mod tests {
         // Ensure that the content is replaced
         assert_eq!(app.workspace.current_buffer().unwrap().data(), "editor");
 
-        // TODO: Ensure that the operation is treated atomically.
-        // commands::buffer::undo(&mut app);
-        // assert_eq!(app.workspace.current_buffer().unwrap().data(), "amp");
+        // Ensure that the operation is treated atomically.
+        commands::buffer::undo(&mut app).unwrap();
+        assert_eq!(app.workspace.current_buffer().unwrap().data(), "amp");
     }
 
     #[test]
@@ -1601,9 +3231,41 @@ mod tests {
         assert_eq!(app.workspace.current_buffer().unwrap().data(),
                    "paste amp\neditor");
 
-        // TODO: Ensure that the operation is treated atomically.
-        // commands::buffer::undo(&mut app);
-        // assert_eq!(app.workspace.current_buffer().unwrap().data(), "amp");
+        // Ensure that the operation is treated atomically.
+        commands::buffer::undo(&mut app).unwrap();
+        assert_eq!(app.workspace.current_buffer().unwrap().data(), "amp\neditor");
+    }
+
+    #[test]
+    fn paste_with_block_content_puts_it_on_its_own_lines_when_replacing_inline_selection() {
+        let mut app = Application::new(&Vec::new()).unwrap();
+        let mut buffer = Buffer::new();
+        buffer.insert("amp editor");
+        app.clipboard.set_content(ClipboardContent::Block("paste\n".to_string())).unwrap();
+
+        app.workspace.add_buffer(buffer);
+        commands::application::switch_to_select_mode(&mut app).unwrap();
+        app.workspace.current_buffer().unwrap().cursor.move_to(Position {
+            line: 0,
+            offset: 4,
+        });
+        commands::buffer::paste(&mut app).unwrap();
+
+        assert_eq!(app.workspace.current_buffer().unwrap().data(), "\npaste\neditor");
+    }
+
+    #[test]
+    fn paste_with_inline_content_keeps_it_on_its_own_line_when_replacing_lines() {
+        let mut app = Application::new(&Vec::new()).unwrap();
+        let mut buffer = Buffer::new();
+        buffer.insert("amp\neditor");
+        app.clipboard.set_content(ClipboardContent::Inline("paste".to_string())).unwrap();
+
+        app.workspace.add_buffer(buffer);
+        commands::application::switch_to_select_line_mode(&mut app).unwrap();
+        commands::buffer::paste(&mut app).unwrap();
+
+        assert_eq!(app.workspace.current_buffer().unwrap().data(), "paste\neditor");
     }
 
     #[test]
@@ -1646,6 +3308,53 @@ mod tests {
         }
     }
 
+    #[test]
+    fn insert_date_inserts_the_current_date_and_moves_past_it() {
+        let mut app = Application::new(&Vec::new()).unwrap();
+        let mut buffer = Buffer::new();
+        buffer.insert("amp");
+        app.workspace.add_buffer(buffer);
+        super::insert_date(&mut app).unwrap();
+
+        let buffer = app.workspace.current_buffer().unwrap();
+        let data = buffer.data();
+        assert_eq!(data.len(), 13);
+        assert!(data.ends_with("amp"));
+        assert_eq!(data.matches('-').count(), 2);
+        assert_eq!(*buffer.cursor, Position { line: 0, offset: 10 });
+    }
+
+    #[test]
+    fn undo_describes_the_reverted_change() {
+        let mut app = Application::new(&Vec::new()).unwrap();
+        let mut buffer = Buffer::new();
+        buffer.insert("amp\neditor\n");
+        app.workspace.add_buffer(buffer);
+        super::delete_current_line(&mut app).unwrap();
+
+        super::undo(&mut app).unwrap();
+        assert_eq!(app.message, Some(String::from("Undo: added 1 line at line 1")));
+
+        super::redo(&mut app).unwrap();
+        assert_eq!(app.message, Some(String::from("Redo: removed 1 line at line 1")));
+
+        super::redo(&mut app).unwrap();
+        assert_eq!(app.message, Some(String::from("Redo: nothing to reapply")));
+    }
+
+    #[test]
+    fn force_close_discards_modified_buffers_and_leaves_an_empty_workspace() {
+        let mut app = Application::new(&Vec::new()).unwrap();
+        let mut buffer = Buffer::new();
+        buffer.insert("data");
+        app.workspace.add_buffer(buffer);
+        commands::buffer::force_close(&mut app).unwrap();
+
+        assert!(app.workspace.current_buffer().is_none());
+        assert!(commands::buffer::force_close(&mut app).is_err());
+        assert!(commands::cursor::move_down(&mut app).is_err());
+    }
+
     #[test]
     fn close_skips_confirmation_when_buffer_is_empty() {
         let mut app = Application::new(&Vec::new()).unwrap();
@@ -1678,6 +3387,27 @@ mod tests {
         assert!(app.workspace.current_buffer().is_none());
     }
 
+    #[test]
+    fn close_records_the_cursor_position_to_restore_when_reopening() {
+        let path = env::temp_dir().join("amp_restore_cursor_test");
+        fs::write(&path, "amp\neditor\n").unwrap();
+
+        let mut app = Application::new(&Vec::new()).unwrap();
+        let mut buffer = Buffer::from_file(&path).unwrap();
+        buffer.cursor.move_to(Position{ line: 1, offset: 3 });
+        app.workspace.add_buffer(buffer);
+        commands::buffer::close(&mut app).unwrap();
+
+        let mut buffer = Buffer::new();
+        buffer.path = Some(env::temp_dir().join("amp_restore_cursor_origin"));
+        buffer.insert("amp_restore_cursor_test");
+        app.workspace.add_buffer(buffer);
+        super::open_path_under_cursor(&mut app).unwrap();
+
+        assert_eq!(*app.workspace.current_buffer().unwrap().cursor, Position{ line: 1, offset: 3 });
+        fs::remove_file(&path).unwrap();
+    }
+
     #[test]
     fn close_others_skips_confirmation_when_all_other_buffers_are_empty_or_unmodified() {
         let mut app = Application::new(&Vec::new()).unwrap();
@@ -1777,6 +3507,81 @@ mod tests {
         assert_eq!(app.workspace.current_buffer().unwrap().data(), "two");
     }
 
+    #[test]
+    fn cycle_paste_replaces_pasted_content_with_previous_clipboard_entries() {
+        let mut app = Application::new(&Vec::new()).unwrap();
+        let mut buffer = Buffer::new();
+        buffer.insert("amp\n");
+        app.workspace.add_buffer(buffer);
+
+        app.clipboard.set_content(ClipboardContent::Block("editor\n".to_string())).unwrap();
+        app.clipboard.set_content(ClipboardContent::Inline("buffer ".to_string())).unwrap();
+        commands::buffer::paste(&mut app).unwrap();
+        assert_eq!(app.workspace.current_buffer().unwrap().data(), "buffer amp\n");
+
+        // Block content retains its type when cycled back to.
+        commands::buffer::cycle_paste(&mut app).unwrap();
+        assert_eq!(app.workspace.current_buffer().unwrap().data(), "amp\neditor\n");
+
+        commands::buffer::cycle_paste(&mut app).unwrap();
+        assert_eq!(app.workspace.current_buffer().unwrap().data(), "buffer amp\n");
+    }
+
+    #[test]
+    fn cycle_paste_replays_the_replacement_of_a_selection() {
+        let mut app = Application::new(&Vec::new()).unwrap();
+        let mut buffer = Buffer::new();
+        buffer.insert("amp\neditor\n");
+        app.workspace.add_buffer(buffer);
+
+        app.clipboard.set_content(ClipboardContent::Inline("buffer".to_string())).unwrap();
+        app.clipboard.set_content(ClipboardContent::Inline("paste".to_string())).unwrap();
+        commands::application::switch_to_select_line_mode(&mut app).unwrap();
+        commands::buffer::paste(&mut app).unwrap();
+        commands::application::switch_to_normal_mode(&mut app).unwrap();
+        assert_eq!(app.workspace.current_buffer().unwrap().data(), "paste\neditor\n");
+
+        // The previous entry replaces the selected line, too.
+        commands::buffer::cycle_paste(&mut app).unwrap();
+        assert_eq!(app.workspace.current_buffer().unwrap().data(), "buffer\neditor\n");
+
+        commands::buffer::undo(&mut app).unwrap();
+        assert_eq!(app.workspace.current_buffer().unwrap().data(), "amp\neditor\n");
+    }
+
+    #[test]
+    fn cycle_paste_leaves_edits_made_after_the_paste_alone() {
+        let mut app = Application::new(&Vec::new()).unwrap();
+        let mut buffer = Buffer::new();
+        buffer.insert("amp\n");
+        app.workspace.add_buffer(buffer);
+
+        app.clipboard.set_content(ClipboardContent::Inline("editor ".to_string())).unwrap();
+        app.clipboard.set_content(ClipboardContent::Inline("buffer ".to_string())).unwrap();
+
+        // Paste, delete a character, and then try to cycle the pasted content.
+        for key in vec![Key::Char('p'), Key::Char('x'), Key::Ctrl('p')] {
+            app.view.last_key = Some(key);
+            let _ = commands::application::handle_input(&mut app);
+        }
+
+        assert_eq!(app.workspace.current_buffer().unwrap().data(), "uffer amp\n");
+    }
+
+    #[test]
+    fn cycle_paste_fails_without_a_preceding_paste() {
+        let mut app = Application::new(&Vec::new()).unwrap();
+        let mut buffer = Buffer::new();
+        buffer.insert("amp\n");
+        app.workspace.add_buffer(buffer);
+
+        app.clipboard.set_content(ClipboardContent::Inline("editor".to_string())).unwrap();
+        app.clipboard.set_content(ClipboardContent::Inline("buffer".to_string())).unwrap();
+
+        assert!(commands::buffer::cycle_paste(&mut app).is_err());
+        assert_eq!(app.workspace.current_buffer().unwrap().data(), "amp\n");
+    }
+
     #[test]
     fn toggle_line_comment_add_single_in_normal_mode() {
         let mut app = Application::new(&Vec::new()).unwrap();
@@ -1799,6 +3604,21 @@ mod tests {
                    Position { line: 0, offset: 1 });
     }
 
+    #[test]
+    fn toggle_line_comment_falls_back_to_default_prefix() {
+        let mut app = Application::new(&Vec::new()).unwrap();
+        let mut buffer = Buffer::new();
+        buffer.insert("amp\neditor\n");
+
+        // Now that we've set up the buffer, add it
+        // to the application and call the command.
+        app.workspace.add_buffer(buffer);
+        super::toggle_line_comment(&mut app).unwrap();
+
+        assert_eq!(app.workspace.current_buffer().unwrap().data(),
+                   "// amp\neditor\n");
+    }
+
     #[test]
     fn toggle_line_comment_add_multiple_in_select_line_mode() {
         let mut app = Application::new(&Vec::new()).unwrap();
@@ -1960,4 +3780,285 @@ mod tests {
         assert_eq!(app.workspace.current_buffer().unwrap().cursor.position,
                    Position { line: 2, offset: 0 });
     }
+
+    #[test]
+    fn duplicate_copies_the_current_line_below_itself() {
+        let mut app = Application::new(&Vec::new()).unwrap();
+        let mut buffer = Buffer::new();
+        buffer.insert("amp\neditor\nbuffer");
+        buffer.cursor.move_to(Position { line: 1, offset: 2 });
+        app.workspace.add_buffer(buffer);
+
+        super::duplicate(&mut app).unwrap();
+
+        assert_eq!(app.workspace.current_buffer().unwrap().data(),
+                   "amp\neditor\neditor\nbuffer");
+        assert_eq!(*app.workspace.current_buffer().unwrap().cursor,
+                   Position { line: 2, offset: 2 });
+
+        // The duplication should be undone in a single step.
+        app.workspace.current_buffer().unwrap().undo();
+        assert_eq!(app.workspace.current_buffer().unwrap().data(),
+                   "amp\neditor\nbuffer");
+    }
+
+    #[test]
+    fn duplicate_copies_selected_lines_below_themselves() {
+        let mut app = Application::new(&Vec::new()).unwrap();
+        let mut buffer = Buffer::new();
+        buffer.insert("amp\neditor\nbuffer");
+        app.workspace.add_buffer(buffer);
+
+        commands::application::switch_to_select_line_mode(&mut app).unwrap();
+        app.workspace.current_buffer().unwrap().cursor.move_to(Position {
+            line: 2,
+            offset: 1,
+        });
+        super::duplicate(&mut app).unwrap();
+
+        assert_eq!(app.workspace.current_buffer().unwrap().data(),
+                   "amp\neditor\nbuffer\namp\neditor\nbuffer");
+        assert_eq!(*app.workspace.current_buffer().unwrap().cursor,
+                   Position { line: 5, offset: 1 });
+        assert!(match app.mode {
+            Mode::Normal => true,
+            _ => false,
+        });
+    }
+
+    #[test]
+    fn duplicate_copies_the_selection_after_itself() {
+        let mut app = Application::new(&Vec::new()).unwrap();
+        let mut buffer = Buffer::new();
+        buffer.insert("amp editor");
+        app.workspace.add_buffer(buffer);
+
+        commands::application::switch_to_select_mode(&mut app).unwrap();
+        app.workspace.current_buffer().unwrap().cursor.move_to(Position {
+            line: 0,
+            offset: 3,
+        });
+        super::duplicate(&mut app).unwrap();
+
+        assert_eq!(app.workspace.current_buffer().unwrap().data(), "ampamp editor");
+        assert_eq!(*app.workspace.current_buffer().unwrap().cursor,
+                   Position { line: 0, offset: 3 });
+    }
+
+    #[test]
+    fn move_line_up_swaps_the_current_line_with_the_one_above() {
+        let mut app = Application::new(&Vec::new()).unwrap();
+        let mut buffer = Buffer::new();
+        buffer.insert("amp\neditor\nbuffer");
+        buffer.cursor.move_to(Position { line: 2, offset: 3 });
+        app.workspace.add_buffer(buffer);
+
+        super::move_line_up(&mut app).unwrap();
+
+        assert_eq!(app.workspace.current_buffer().unwrap().data(),
+                   "amp\nbuffer\neditor");
+        assert_eq!(*app.workspace.current_buffer().unwrap().cursor,
+                   Position { line: 1, offset: 3 });
+
+        // The move should be undone in a single step.
+        app.workspace.current_buffer().unwrap().undo();
+        assert_eq!(app.workspace.current_buffer().unwrap().data(),
+                   "amp\neditor\nbuffer");
+    }
+
+    #[test]
+    fn move_line_up_does_nothing_on_the_first_line() {
+        let mut app = Application::new(&Vec::new()).unwrap();
+        let mut buffer = Buffer::new();
+        buffer.insert("amp\neditor\n");
+        app.workspace.add_buffer(buffer);
+
+        super::move_line_up(&mut app).unwrap();
+
+        assert_eq!(app.workspace.current_buffer().unwrap().data(), "amp\neditor\n");
+        assert_eq!(*app.workspace.current_buffer().unwrap().cursor,
+                   Position { line: 0, offset: 0 });
+    }
+
+    #[test]
+    fn move_line_down_moves_selected_lines_and_their_selection() {
+        let mut app = Application::new(&Vec::new()).unwrap();
+        let mut buffer = Buffer::new();
+        buffer.insert("amp\neditor\nbuffer\n");
+        app.workspace.add_buffer(buffer);
+
+        commands::application::switch_to_select_line_mode(&mut app).unwrap();
+        app.workspace.current_buffer().unwrap().cursor.move_to(Position {
+            line: 1,
+            offset: 0,
+        });
+        super::move_line_down(&mut app).unwrap();
+
+        assert_eq!(app.workspace.current_buffer().unwrap().data(),
+                   "buffer\namp\neditor\n");
+        assert_eq!(*app.workspace.current_buffer().unwrap().cursor,
+                   Position { line: 2, offset: 0 });
+        match app.mode {
+            Mode::SelectLine(ref mode) => assert_eq!(mode.anchor, 1),
+            _ => panic!("Not in select line mode"),
+        }
+
+        // We're at the end of the buffer; moving further should do nothing.
+        super::move_line_down(&mut app).unwrap();
+        assert_eq!(app.workspace.current_buffer().unwrap().data(),
+                   "buffer\namp\neditor\n");
+    }
+
+    #[test]
+    fn format_replaces_buffer_content_with_formatter_output() {
+        let mut app = Application::new(&Vec::new()).unwrap();
+        let data = YamlLoader::load_from_str("types:\n  rs:\n    formatter: tr a-z A-Z").unwrap();
+        *app.preferences.borrow_mut() = Preferences::new(data.into_iter().nth(0));
+        let mut buffer = Buffer::new();
+        buffer.insert("amp\neditor\n");
+        buffer.path = Some(Path::new("amp.rs").to_path_buf());
+        buffer.cursor.move_to(Position { line: 1, offset: 2 });
+        app.workspace.add_buffer(buffer);
+
+        super::format(&mut app).unwrap();
+
+        assert_eq!(app.workspace.current_buffer().unwrap().data(), "AMP\nEDITOR\n");
+        assert_eq!(*app.workspace.current_buffer().unwrap().cursor,
+                   Position { line: 1, offset: 2 });
+
+        // The formatting should be undone in a single step.
+        app.workspace.current_buffer().unwrap().undo();
+        assert_eq!(app.workspace.current_buffer().unwrap().data(), "amp\neditor\n");
+    }
+
+    #[test]
+    fn format_leaves_buffer_untouched_when_formatter_fails() {
+        let mut app = Application::new(&Vec::new()).unwrap();
+        let data = YamlLoader::load_from_str("types:\n  rs:\n    formatter: exit 1").unwrap();
+        *app.preferences.borrow_mut() = Preferences::new(data.into_iter().nth(0));
+        let mut buffer = Buffer::new();
+        buffer.insert("amp\n");
+        buffer.path = Some(Path::new("amp.rs").to_path_buf());
+        app.workspace.add_buffer(buffer);
+
+        assert!(super::format(&mut app).is_err());
+        assert_eq!(app.workspace.current_buffer().unwrap().data(), "amp\n");
+    }
+
+    #[test]
+    fn insert_char_closes_pairs_and_types_over_closing_characters() {
+        let mut app = Application::new(&Vec::new()).unwrap();
+        let data = YamlLoader::load_from_str("auto_close_pairs: true").unwrap();
+        *app.preferences.borrow_mut() = Preferences::new(data.into_iter().nth(0));
+        app.workspace.add_buffer(Buffer::new());
+
+        for character in "(a)".chars() {
+            app.view.last_key = Some(Key::Char(character));
+            super::insert_char(&mut app).unwrap();
+        }
+
+        assert_eq!(app.workspace.current_buffer().unwrap().data(), "(a)");
+        assert_eq!(*app.workspace.current_buffer().unwrap().cursor,
+                   Position { line: 0, offset: 3 });
+
+        // Quotes following a word aren't paired.
+        for character in "don't".chars() {
+            app.view.last_key = Some(Key::Char(character));
+            super::insert_char(&mut app).unwrap();
+        }
+        assert_eq!(app.workspace.current_buffer().unwrap().data(), "(a)don't");
+    }
+
+    #[test]
+    fn backspace_deletes_both_halves_of_an_empty_pair() {
+        let mut app = Application::new(&Vec::new()).unwrap();
+        let data = YamlLoader::load_from_str("auto_close_pairs: true").unwrap();
+        *app.preferences.borrow_mut() = Preferences::new(data.into_iter().nth(0));
+        let mut buffer = Buffer::new();
+        buffer.insert("amp[]");
+        buffer.cursor.move_to(Position { line: 0, offset: 4 });
+        app.workspace.add_buffer(buffer);
+
+        super::backspace(&mut app).unwrap();
+
+        assert_eq!(app.workspace.current_buffer().unwrap().data(), "amp");
+        assert_eq!(*app.workspace.current_buffer().unwrap().cursor,
+                   Position { line: 0, offset: 3 });
+    }
+
+    #[test]
+    fn insert_tab_aligns_indentation_to_the_next_indent_level() {
+        let mut app = Application::new(&Vec::new()).unwrap();
+        let data = YamlLoader::load_from_str("tab_width: 4").unwrap();
+        *app.preferences.borrow_mut() = Preferences::new(data.into_iter().nth(0));
+        let mut buffer = Buffer::new();
+        buffer.insert("   amp");
+        buffer.cursor.move_to(Position { line: 0, offset: 3 });
+        app.workspace.add_buffer(buffer);
+
+        super::insert_tab(&mut app).unwrap();
+        assert_eq!(app.workspace.current_buffer().unwrap().data(), "    amp");
+        assert_eq!(*app.workspace.current_buffer().unwrap().cursor,
+                   Position { line: 0, offset: 4 });
+
+        // Past the indentation, a full indent unit is inserted.
+        app.workspace.current_buffer().unwrap().cursor.move_to(Position { line: 0, offset: 5 });
+        super::insert_tab(&mut app).unwrap();
+        assert_eq!(app.workspace.current_buffer().unwrap().data(), "    a    mp");
+    }
+
+    #[test]
+    fn convert_tabs_to_spaces_only_converts_leading_indentation() {
+        let mut app = Application::new(&Vec::new()).unwrap();
+        let data = YamlLoader::load_from_str("tab_width: 4").unwrap();
+        *app.preferences.borrow_mut() = Preferences::new(data.into_iter().nth(0));
+        let mut buffer = Buffer::new();
+        buffer.insert("amp\n\teditor\t\"\t\"\n  \tbuffer\n");
+        buffer.cursor.move_to(Position { line: 1, offset: 1 });
+        app.workspace.add_buffer(buffer);
+
+        super::convert_tabs_to_spaces(&mut app).unwrap();
+
+        assert_eq!(app.workspace.current_buffer().unwrap().data(),
+                   "amp\n    editor\t\"\t\"\n    buffer\n");
+        assert_eq!(*app.workspace.current_buffer().unwrap().cursor,
+                   Position { line: 1, offset: 4 });
+    }
+
+    #[test]
+    fn convert_spaces_to_tabs_keeps_remaining_spaces() {
+        let mut app = Application::new(&Vec::new()).unwrap();
+        let data = YamlLoader::load_from_str("tab_width: 4").unwrap();
+        *app.preferences.borrow_mut() = Preferences::new(data.into_iter().nth(0));
+        let mut buffer = Buffer::new();
+        buffer.insert("      amp    editor\n");
+        app.workspace.add_buffer(buffer);
+
+        super::convert_spaces_to_tabs(&mut app).unwrap();
+
+        assert_eq!(app.workspace.current_buffer().unwrap().data(),
+                   "\t  amp    editor\n");
+
+        // The conversion should be undone in a single step.
+        app.workspace.current_buffer().unwrap().undo();
+        assert_eq!(app.workspace.current_buffer().unwrap().data(),
+                   "      amp    editor\n");
+    }
+
+    #[test]
+    fn display_word_count_counts_the_selection_or_the_buffer() {
+        let mut app = Application::new(&Vec::new()).unwrap();
+        let mut buffer = Buffer::new();
+        buffer.insert("  héllo,   wörld\n\tamp  editor\n");
+        app.workspace.add_buffer(buffer);
+
+        super::display_word_count(&mut app).unwrap();
+        assert_eq!(app.message,
+                   Some(String::from("2 lines, 4 words, 30 characters in buffer")));
+
+        commands::application::switch_to_select_line_mode(&mut app).unwrap();
+        super::display_word_count(&mut app).unwrap();
+        assert_eq!(app.message,
+                   Some(String::from("1 line, 2 words, 17 characters in selection")));
+    }
 }
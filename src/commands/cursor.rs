@@ -1,27 +1,163 @@
 use crate::errors::*;
+use crate::errors;
 use crate::commands::{self, Result};
+use crate::util::delimiter::matching_bracket_position;
 use crate::util::token::{Direction, adjacent_token_position};
-use crate::models::application::Application;
-use scribe::buffer::Position;
+use crate::util::word::Words;
+use crate::util;
+use crate::models::application::{Application, Mode};
+use scribe::buffer::{Position, Range};
 use super::{application, buffer};
+use unicode_segmentation::UnicodeSegmentation;
 
 pub fn move_up(app: &mut Application) -> Result {
-    app.workspace.current_buffer().ok_or(BUFFER_MISSING)?.cursor.move_up();
-    commands::view::scroll_to_cursor(app).chain_err(|| SCROLL_TO_CURSOR_FAILED)
+    let count = app.take_count();
+    move_vertically(app, Direction::Backward, count)
 }
 
 pub fn move_down(app: &mut Application) -> Result {
-    app.workspace.current_buffer().ok_or(BUFFER_MISSING)?.cursor.move_down();
+    let count = app.take_count();
+    move_vertically(app, Direction::Forward, count)
+}
+
+fn move_vertically(app: &mut Application, direction: Direction, count: usize) -> Result {
+    for _ in 0..count {
+        if !move_to_adjacent_visual_row(app, direction)? {
+            let cursor = &mut app.workspace.current_buffer().ok_or(BUFFER_MISSING)?.cursor;
+            match direction {
+                Direction::Forward => cursor.move_down(),
+                Direction::Backward => cursor.move_up(),
+            };
+        }
+    }
     commands::view::scroll_to_cursor(app).chain_err(|| SCROLL_TO_CURSOR_FAILED)
 }
 
+/// When lines are wrapped, moves the cursor to the row above or below it as
+/// displayed, rather than the adjacent line, keeping its on-screen column.
+/// Returns false without moving the cursor if neither the current line nor
+/// the adjacent one wraps, leaving regular (sticky offset) line movement
+/// to handle it.
+fn move_to_adjacent_visual_row(app: &mut Application, direction: Direction) -> errors::Result<bool> {
+    if !app.preferences.borrow().line_wrapping() {
+        return Ok(false);
+    }
+
+    let buffer = app.workspace.current_buffer().ok_or(BUFFER_MISSING)?;
+    let width = app.view.buffer_content_width(buffer);
+    let tab_width = app.preferences.borrow().tab_width(buffer.path.as_ref());
+    let data = buffer.data();
+    let lines: Vec<&str> = data.lines().collect();
+    let rows = |line: usize| {
+        lines.get(line).map(|content| visual_positions(content, width, tab_width)).unwrap_or_default()
+    };
+
+    let cursor = *buffer.cursor;
+    let current_rows = rows(cursor.line);
+    let (row, column) = visual_position(&current_rows, cursor.offset);
+    let target = match direction {
+        Direction::Forward if row < last_row(&current_rows) => {
+            Position{ line: cursor.line, offset: offset_at(&current_rows, row + 1, column) }
+        },
+        Direction::Backward if row > 0 => {
+            Position{ line: cursor.line, offset: offset_at(&current_rows, row - 1, column) }
+        },
+        Direction::Forward if cursor.line + 1 < buffer.line_count() => {
+            let next_rows = rows(cursor.line + 1);
+            if last_row(&current_rows) == 0 && last_row(&next_rows) == 0 {
+                return Ok(false);
+            }
+            Position{ line: cursor.line + 1, offset: offset_at(&next_rows, 0, column) }
+        },
+        Direction::Backward if cursor.line > 0 => {
+            let previous_rows = rows(cursor.line - 1);
+            if last_row(&current_rows) == 0 && last_row(&previous_rows) == 0 {
+                return Ok(false);
+            }
+            let previous_row = last_row(&previous_rows);
+            Position{ line: cursor.line - 1, offset: offset_at(&previous_rows, previous_row, column) }
+        },
+        _ => return Ok(false),
+    };
+
+    Ok(buffer.cursor.move_to(target))
+}
+
+// Maps each of the line's graphemes to the (row, column) at which it's
+// displayed when wrapped at the specified width, mirroring the renderer.
+fn visual_positions(line: &str, width: usize, tab_width: usize) -> Vec<(usize, usize)> {
+    let tab_width = tab_width.max(1);
+    let mut positions = Vec::new();
+    let (mut row, mut column) = (0, 0);
+
+    for grapheme in line.graphemes(true) {
+        if width > 0 && column >= width {
+            row += 1;
+            column = 0;
+        }
+        positions.push((row, column));
+
+        column = if grapheme == "\t" {
+            ((column / tab_width + 1) * tab_width).min(width.max(1))
+        } else {
+            column + 1
+        };
+    }
+
+    positions
+}
+
+// Offsets beyond the end of the line (i.e. in insert mode) are displayed just after its last grapheme.
+fn visual_position(positions: &[(usize, usize)], offset: usize) -> (usize, usize) {
+    match positions.get(offset) {
+        Some(&position) => position,
+        None => positions.last().map(|&(row, column)| (row, column + 1)).unwrap_or((0, 0)),
+    }
+}
+
+fn last_row(positions: &[(usize, usize)]) -> usize {
+    positions.last().map(|&(row, _)| row).unwrap_or(0)
+}
+
+// Finds the offset of the grapheme displayed in the row at (or closest
+// before) the column, falling back to the end of the line if the column
+// extends beyond its last row.
+fn offset_at(positions: &[(usize, usize)], row: usize, column: usize) -> usize {
+    let mut offset = None;
+    for (index, &(r, c)) in positions.iter().enumerate() {
+        if r != row {
+            continue;
+        }
+        if c > column {
+            break;
+        }
+        offset = Some(index);
+    }
+
+    match offset {
+        Some(index) if row == last_row(positions) && index + 1 == positions.len() && column > positions[index].1 => {
+            positions.len()
+        },
+        Some(index) => index,
+        None => positions.iter().position(|&(r, _)| r == row).unwrap_or(positions.len()),
+    }
+}
+
 pub fn move_left(app: &mut Application) -> Result {
-    app.workspace.current_buffer().ok_or(BUFFER_MISSING)?.cursor.move_left();
+    let count = app.take_count();
+    let buffer = app.workspace.current_buffer().ok_or(BUFFER_MISSING)?;
+    for _ in 0..count {
+        buffer.cursor.move_left();
+    }
     commands::view::scroll_to_cursor(app).chain_err(|| SCROLL_TO_CURSOR_FAILED)
 }
 
 pub fn move_right(app: &mut Application) -> Result {
-    app.workspace.current_buffer().ok_or(BUFFER_MISSING)?.cursor.move_right();
+    let count = app.take_count();
+    let buffer = app.workspace.current_buffer().ok_or(BUFFER_MISSING)?;
+    for _ in 0..count {
+        buffer.cursor.move_right();
+    }
     commands::view::scroll_to_cursor(app).chain_err(|| SCROLL_TO_CURSOR_FAILED)
 }
 
@@ -96,6 +232,7 @@ pub fn move_to_first_word_of_line(app: &mut Application) -> Result {
 }
 
 pub fn insert_at_end_of_line(app: &mut Application) -> Result {
+    util::ensure_writable(app)?;
     move_to_end_of_line(app)?;
     application::switch_to_insert_mode(app)?;
     commands::view::scroll_to_cursor(app)?;
@@ -104,6 +241,7 @@ pub fn insert_at_end_of_line(app: &mut Application) -> Result {
 }
 
 pub fn insert_at_first_word_of_line(app: &mut Application) -> Result {
+    util::ensure_writable(app)?;
     move_to_first_word_of_line(app)?;
     application::switch_to_insert_mode(app)?;
     commands::view::scroll_to_cursor(app)?;
@@ -111,7 +249,10 @@ pub fn insert_at_first_word_of_line(app: &mut Application) -> Result {
     Ok(())
 }
 
+/// Opens a new line below the current one, indented like it (or more deeply,
+/// if it opens a block), and switches to insert mode, as a single operation.
 pub fn insert_with_newline(app: &mut Application) -> Result {
+    util::ensure_writable(app)?;
     move_to_end_of_line(app)?;
     buffer::start_command_group(app)?;
     buffer::insert_newline(app)?;
@@ -121,25 +262,31 @@ pub fn insert_with_newline(app: &mut Application) -> Result {
     Ok(())
 }
 
+/// Opens a new line above the current one, indented like it (or like the
+/// nearest non-blank line above, if it's blank), and switches to insert mode,
+/// as a single operation.
 pub fn insert_with_newline_above(app: &mut Application) -> Result {
-    let current_line_number = app
-        .workspace
-        .current_buffer()
-        .map(|b| b.cursor.line)
-        .ok_or(BUFFER_MISSING)?;
-
-    if current_line_number == 0 {
-        buffer::start_command_group(app)?;
-        move_to_start_of_line(app)?;
-        buffer::insert_newline(app)?;
-        move_up(app)?;
-        move_to_end_of_line(app)?;
-        application::switch_to_insert_mode(app)?;
-        commands::view::scroll_to_cursor(app)?;
-    } else {
-        move_up(app)?;
-        insert_with_newline(app)?;
-    }
+    util::ensure_writable(app)?;
+    let buffer = app.workspace.current_buffer().ok_or(BUFFER_MISSING)?;
+    let line = buffer.cursor.line;
+    let data = buffer.data();
+    let indent_content: String = data
+        .lines()
+        .take(line + 1)
+        .collect::<Vec<_>>()
+        .into_iter()
+        .rev()
+        .find(|content| !content.is_empty())
+        .map(|content| content.chars().take_while(|&c| c.is_whitespace()).collect())
+        .unwrap_or_default();
+
+    buffer.start_operation_group();
+    buffer.cursor.move_to(Position { line, offset: 0 });
+    buffer.insert(format!("{}\n", indent_content));
+    buffer.cursor.move_to(Position { line, offset: indent_content.chars().count() });
+    util::record_edit(app);
+    application::switch_to_insert_mode(app)?;
+    commands::view::scroll_to_cursor(app)?;
 
     Ok(())
 }
@@ -189,16 +336,383 @@ pub fn move_to_end_of_current_token(app: &mut Application) -> Result {
     commands::view::scroll_to_cursor(app).chain_err(|| SCROLL_TO_CURSOR_FAILED)
 }
 
+pub fn move_to_next_word(app: &mut Application) -> Result {
+    move_to_word_boundary(app, |words, position| words.next_word_start(position))
+        .chain_err(|| "Couldn't find next word")
+}
+
+pub fn move_to_previous_word(app: &mut Application) -> Result {
+    move_to_word_boundary(app, |words, position| words.previous_word_start(position))
+        .chain_err(|| "Couldn't find previous word")
+}
+
+pub fn move_to_end_of_word(app: &mut Application) -> Result {
+    move_to_word_boundary(app, |words, position| words.end_of_word(position))
+        .chain_err(|| "Couldn't find end of word")
+}
+
+fn move_to_word_boundary<F>(app: &mut Application, boundary: F) -> Result
+    where F: Fn(&Words, Position) -> Option<Position>
+{
+    let count = app.take_count();
+    let buffer = app.workspace.current_buffer().ok_or(BUFFER_MISSING)?;
+    let data = buffer.data();
+    let words = Words::new(&data);
+    let mut position = boundary(&words, *buffer.cursor).ok_or("No word boundary found")?;
+
+    // Counts stop short at the last boundary, rather than failing.
+    for _ in 1..count {
+        match boundary(&words, position) {
+            Some(next_position) => position = next_position,
+            None => break,
+        }
+    }
+    buffer.cursor.move_to(position);
+
+    commands::view::scroll_to_cursor(app).chain_err(|| SCROLL_TO_CURSOR_FAILED)
+}
+
+/// Moves the cursor to the next blank line that follows a non-blank one,
+/// or the last line of the buffer if there is no such line.
+pub fn move_to_next_paragraph(app: &mut Application) -> Result {
+    let count = app.take_count();
+    let buffer = app.workspace.current_buffer().ok_or(BUFFER_MISSING)?;
+    let blank_lines = blank_lines(&buffer.data());
+    let is_blank = |line: usize| blank_lines.get(line).cloned().unwrap_or(true);
+    let last_line = buffer.line_count().saturating_sub(1);
+
+    // Skip the current boundary, so that consecutive blank lines are
+    // treated as one, and then find the next one.
+    let mut target_line = buffer.cursor.line;
+    for _ in 0..count {
+        target_line = (target_line..last_line + 1)
+            .skip_while(|&line| is_blank(line))
+            .find(|&line| is_blank(line))
+            .unwrap_or(last_line);
+    }
+    buffer.cursor.move_to(Position { line: target_line, offset: 0 });
+
+    commands::view::scroll_to_cursor(app).chain_err(|| SCROLL_TO_CURSOR_FAILED)
+}
+
+/// Moves the cursor to the previous blank line that precedes a non-blank
+/// one, or the first line of the buffer if there is no such line.
+pub fn move_to_previous_paragraph(app: &mut Application) -> Result {
+    let count = app.take_count();
+    let buffer = app.workspace.current_buffer().ok_or(BUFFER_MISSING)?;
+    let blank_lines = blank_lines(&buffer.data());
+    let is_blank = |line: usize| blank_lines.get(line).cloned().unwrap_or(true);
+
+    let mut target_line = buffer.cursor.line;
+    for _ in 0..count {
+        target_line = (0..target_line + 1)
+            .rev()
+            .skip_while(|&line| is_blank(line))
+            .find(|&line| is_blank(line))
+            .unwrap_or(0);
+    }
+    buffer.cursor.move_to(Position { line: target_line, offset: 0 });
+
+    commands::view::scroll_to_cursor(app).chain_err(|| SCROLL_TO_CURSOR_FAILED)
+}
+
+// Flags each line that is empty or contains only whitespace.
+fn blank_lines(data: &str) -> Vec<bool> {
+    data.lines().map(|line| line.trim().is_empty()).collect()
+}
+
 pub fn append_to_current_token(app: &mut Application) -> Result {
+    util::ensure_writable(app)?;
     move_to_end_of_current_token(app)?;
     application::switch_to_insert_mode(app)
 }
 
+/// Moves the cursor to the start of the line entered in line jump mode.
+/// Inputs prefixed with `+` or `-` are relative to the current line, and
+/// targets beyond either end of the buffer are clamped to its first/last line.
+pub fn goto_line(app: &mut Application) -> Result {
+    let input = if let Mode::LineJump(ref mode) = app.mode {
+        mode.input.trim().to_string()
+    } else {
+        bail!("Can't go to line outside of line jump mode.");
+    };
+
+    let buffer = app.workspace.current_buffer().ok_or(BUFFER_MISSING)?;
+    let current_line = buffer.cursor.line;
+    let target_line = if input.starts_with('+') {
+        current_line.saturating_add(parse_line_number(&input[1..])?)
+    } else if input.starts_with('-') {
+        current_line.saturating_sub(parse_line_number(&input[1..])?)
+    } else {
+        // Input values won't be zero-indexed; map the value so
+        // that we can use it for a zero-indexed buffer position.
+        parse_line_number(&input)?.saturating_sub(1)
+    };
+    let last_line = buffer.line_count().saturating_sub(1);
+
+    // Remember where we were, so that we can jump back.
+    if let Some(id) = buffer.id {
+        app.marks.record_jump(id, *buffer.cursor);
+    }
+    buffer.cursor.move_to(Position {
+        line: target_line.min(last_line),
+        offset: 0,
+    });
+
+    application::switch_to_normal_mode(app)?;
+    commands::view::scroll_cursor_to_center(app)
+}
+
+// Parses an unsigned line number, treating values too
+// large to represent as (clamped) out-of-range lines.
+fn parse_line_number(input: &str) -> errors::Result<usize> {
+    if input.is_empty() || !input.bytes().all(|b| b.is_ascii_digit()) {
+        bail!("Couldn't parse a line number from the provided input.");
+    }
+
+    Ok(input.parse().unwrap_or(std::usize::MAX))
+}
+
+/// Moves the cursor to the bracket paired with the one under it (or, if the
+/// cursor isn't on a bracket, the next one on the current line), accounting
+/// for nesting. Unbalanced brackets leave the cursor in place.
+pub fn move_to_matching_bracket(app: &mut Application) -> Result {
+    let buffer = app.workspace.current_buffer().ok_or(BUFFER_MISSING)?;
+    let data = buffer.data();
+    let lines: Vec<Vec<&str>> = data
+        .lines()
+        .map(|line| line.graphemes(true).collect())
+        .collect();
+    let line = buffer.cursor.line;
+    let content = lines.get(line).ok_or(CURRENT_LINE_MISSING)?;
+    let (offset, (bracket, partner, forward)) = (buffer.cursor.offset..content.len())
+        .find_map(|offset| bracket_pair(content[offset]).map(|pair| (offset, pair)))
+        .ok_or("No bracket found at or after the cursor")?;
+    let start = Position { line, offset };
+
+    let partner_position = if forward {
+        matching_bracket_position(
+            lines
+                .iter()
+                .enumerate()
+                .skip(line)
+                .flat_map(|(line, graphemes)| {
+                    graphemes.iter().enumerate().map(move |(offset, grapheme)| {
+                        (Position { line, offset }, *grapheme)
+                    })
+                })
+                .skip_while(|&(position, _)| position < start),
+            bracket,
+            partner
+        )
+    } else {
+        matching_bracket_position(
+            lines
+                .iter()
+                .enumerate()
+                .take(line + 1)
+                .rev()
+                .flat_map(|(line, graphemes)| {
+                    graphemes.iter().enumerate().rev().map(move |(offset, grapheme)| {
+                        (Position { line, offset }, *grapheme)
+                    })
+                })
+                .skip_while(|&(position, _)| position > start),
+            bracket,
+            partner
+        )
+    };
+
+    if let Some(position) = partner_position {
+        buffer.cursor.move_to(position);
+    } else {
+        app.message = Some(format!("No matching bracket found for \"{}\"", bracket));
+    }
+
+    commands::view::scroll_to_cursor(app).chain_err(|| SCROLL_TO_CURSOR_FAILED)
+}
+
+// Returns the grapheme's partner, along with the direction
+// in which it should be found, if the grapheme is a bracket.
+fn bracket_pair(grapheme: &str) -> Option<(&'static str, &'static str, bool)> {
+    match grapheme {
+        "(" => Some(("(", ")", true)),
+        "[" => Some(("[", "]", true)),
+        "{" => Some(("{", "}", true)),
+        ")" => Some((")", "(", false)),
+        "]" => Some(("]", "[", false)),
+        "}" => Some(("}", "{", false)),
+        _ => None,
+    }
+}
+
+pub fn increment_number(app: &mut Application) -> Result {
+    adjust_number(app, 1)
+}
+
+pub fn decrement_number(app: &mut Application) -> Result {
+    adjust_number(app, -1)
+}
+
+/// Finds the decimal integer under or after the cursor on the current line,
+/// and replaces it with the sum of its value and the provided delta. Leading
+/// minus signs are honoured, and zero-padded numbers retain their width.
+fn adjust_number(app: &mut Application, delta: i64) -> Result {
+    util::ensure_writable(app)?;
+    let buffer = app.workspace.current_buffer().ok_or(BUFFER_MISSING)?;
+    let line = buffer.cursor.line;
+    let data = buffer.data();
+    let content: Vec<&str> = data
+        .lines()
+        .nth(line)
+        .ok_or(CURRENT_LINE_MISSING)?
+        .graphemes(true)
+        .collect();
+    let is_digit = |grapheme: &str| grapheme.len() == 1 && grapheme.as_bytes()[0].is_ascii_digit();
+
+    // Find the first digit at or after the cursor, and
+    // then back up to the start of the number it belongs to.
+    let mut start = (buffer.cursor.offset..content.len())
+        .find(|&offset| is_digit(content[offset]))
+        .ok_or("No number found at or after the cursor")?;
+    while start > 0 && is_digit(content[start - 1]) {
+        start -= 1;
+    }
+    let mut end = start;
+    while end < content.len() && is_digit(content[end]) {
+        end += 1;
+    }
+
+    let digits = content[start..end].concat();
+    let negative = start > 0 && content[start - 1] == "-";
+    if negative {
+        start -= 1;
+    }
+
+    let magnitude: i64 = digits.parse().chain_err(|| "Couldn't parse number")?;
+    let value = if negative { -magnitude } else { magnitude };
+    let adjusted_value = value.checked_add(delta).ok_or("Number is out of range")?;
+
+    // Zero-padded numbers keep their original width.
+    let width = if digits.starts_with('0') { digits.len() } else { 0 };
+    let mut replacement = format!("{:01$}", adjusted_value.abs(), width);
+    if adjusted_value < 0 {
+        replacement.insert(0, '-');
+    }
+
+    let start_position = Position { line, offset: start };
+    buffer.start_operation_group();
+    buffer.delete_range(Range::new(start_position, Position { line, offset: end }));
+    buffer.cursor.move_to(start_position);
+    buffer.insert(replacement.as_str());
+    buffer.end_operation_group();
+
+    // Leave the cursor on the last digit of the adjusted number.
+    buffer.cursor.move_to(Position {
+        line,
+        offset: start + replacement.len() - 1,
+    });
+    util::record_edit(app);
+
+    commands::view::scroll_to_cursor(app).chain_err(|| SCROLL_TO_CURSOR_FAILED)
+}
+
+/// Swaps the character before the cursor with the one under it, advancing the
+/// cursor past both. At the end of a line, its last two characters are swapped
+/// instead. There's nothing to swap with at the start of a line.
+pub fn transpose_chars(app: &mut Application) -> Result {
+    util::ensure_writable(app)?;
+    let buffer = app.workspace.current_buffer().ok_or(BUFFER_MISSING)?;
+    let Position { line, offset } = *buffer.cursor;
+    let line_length = buffer.data()
+        .lines()
+        .nth(line)
+        .map(|content| content.graphemes(true).count())
+        .unwrap_or(0);
+
+    if offset == 0 || line_length < 2 {
+        return Ok(());
+    }
+    let start = offset.min(line_length - 1) - 1;
+    let range = Range::new(
+        Position { line, offset: start },
+        Position { line, offset: start + 2 }
+    );
+    let swapped: String = buffer.read(&range)
+        .ok_or("Couldn't read characters to transpose from buffer")?
+        .graphemes(true)
+        .rev()
+        .collect();
+
+    buffer.start_operation_group();
+    buffer.delete_range(range.clone());
+    buffer.cursor.move_to(range.start());
+    buffer.insert(swapped);
+    buffer.cursor.move_to(Position { line, offset: start + 2 });
+    buffer.end_operation_group();
+    util::record_edit(app);
+
+    Ok(())
+}
+
+/// Reports the codepoints that make up the character under the cursor,
+/// as well as its byte offset in the buffer. Invisible and otherwise
+/// easily-confused characters are also identified by name.
+pub fn describe_character(app: &mut Application) -> Result {
+    let buffer = app.workspace.current_buffer().ok_or(BUFFER_MISSING)?;
+    let data = buffer.data();
+    let (offset, _) = util::byte_offsets(&data, &*buffer.cursor);
+
+    app.message = Some(match data[offset..].graphemes(true).next() {
+        None => format!("End of buffer at byte {}", offset),
+        Some("\n") | Some("\r\n") => format!("End of line at byte {}", offset),
+        Some(grapheme) => {
+            let codepoints: Vec<String> = grapheme
+                .chars()
+                .map(|character| match character_name(character) {
+                    Some(name) => format!("U+{:04X} {}", character as u32, name),
+                    None => format!("U+{:04X}", character as u32),
+                })
+                .collect();
+
+            format!("{:?} {} at byte {}", grapheme, codepoints.join(", "), offset)
+        }
+    });
+
+    Ok(())
+}
+
+// Names for characters that are invisible or easily mistaken for others.
+fn character_name(character: char) -> Option<&'static str> {
+    match character {
+        '\t' => Some("CHARACTER TABULATION"),
+        '\r' => Some("CARRIAGE RETURN"),
+        ' ' => Some("SPACE"),
+        '\u{00A0}' => Some("NO-BREAK SPACE"),
+        '\u{00AD}' => Some("SOFT HYPHEN"),
+        '\u{200B}' => Some("ZERO WIDTH SPACE"),
+        '\u{200C}' => Some("ZERO WIDTH NON-JOINER"),
+        '\u{200D}' => Some("ZERO WIDTH JOINER"),
+        '\u{200E}' => Some("LEFT-TO-RIGHT MARK"),
+        '\u{200F}' => Some("RIGHT-TO-LEFT MARK"),
+        '\u{2013}' => Some("EN DASH"),
+        '\u{2014}' => Some("EM DASH"),
+        '\u{2018}' => Some("LEFT SINGLE QUOTATION MARK"),
+        '\u{2019}' => Some("RIGHT SINGLE QUOTATION MARK"),
+        '\u{201C}' => Some("LEFT DOUBLE QUOTATION MARK"),
+        '\u{201D}' => Some("RIGHT DOUBLE QUOTATION MARK"),
+        '\u{2060}' => Some("WORD JOINER"),
+        '\u{FEFF}' => Some("ZERO WIDTH NO-BREAK SPACE"),
+        _ => None,
+    }
+}
+
 #[cfg(test)]
 mod tests {
+    use crate::commands;
     use scribe::Buffer;
     use scribe::buffer::Position;
-    use crate::models::application::Application;
+    use crate::models::application::{Application, Mode};
 
     #[test]
     fn move_to_first_word_of_line_works() {
@@ -339,6 +853,56 @@ mod tests {
         });
     }
 
+    #[test]
+    fn insert_with_newline_opens_an_indented_line_below_in_a_single_step() {
+        let mut app = set_up_application("    amp\neditor");
+        app.workspace.current_buffer().unwrap().cursor.move_to(Position { line: 0, offset: 1 });
+        super::insert_with_newline(&mut app).unwrap();
+
+        assert_eq!(&*app.workspace.current_buffer().unwrap().data(), "    amp\n    \neditor");
+        assert_eq!(
+            *app.workspace.current_buffer().unwrap().cursor,
+            Position { line: 1, offset: 4 }
+        );
+        assert!(match app.mode {
+            crate::models::application::Mode::Insert => true,
+            _ => false,
+        });
+
+        commands::application::switch_to_normal_mode(&mut app).unwrap();
+        commands::buffer::undo(&mut app).unwrap();
+        assert_eq!(&*app.workspace.current_buffer().unwrap().data(), "    amp\neditor");
+    }
+
+    #[test]
+    fn insert_with_newline_above_uses_the_current_lines_indent() {
+        let mut app = set_up_application("amp:\n    editor");
+        app.workspace.current_buffer().unwrap().cursor.move_to(Position { line: 1, offset: 6 });
+        super::insert_with_newline_above(&mut app).unwrap();
+
+        assert_eq!(&*app.workspace.current_buffer().unwrap().data(), "amp:\n    \n    editor");
+        assert_eq!(
+            *app.workspace.current_buffer().unwrap().cursor,
+            Position { line: 1, offset: 4 }
+        );
+
+        commands::application::switch_to_normal_mode(&mut app).unwrap();
+        commands::buffer::undo(&mut app).unwrap();
+        assert_eq!(&*app.workspace.current_buffer().unwrap().data(), "amp:\n    editor");
+    }
+
+    #[test]
+    fn insert_with_newline_above_works_on_the_first_line() {
+        let mut app = set_up_application("  amp");
+        super::insert_with_newline_above(&mut app).unwrap();
+
+        assert_eq!(&*app.workspace.current_buffer().unwrap().data(), "  \n  amp");
+        assert_eq!(
+            *app.workspace.current_buffer().unwrap().cursor,
+            Position { line: 0, offset: 2 }
+        );
+    }
+
     #[test]
     fn insert_with_newline_above_finds_nearest_non_blank_indent() {
         // Set up the application.
@@ -373,6 +937,444 @@ mod tests {
         });
     }
 
+    #[test]
+    fn increment_number_increments_the_number_after_the_cursor() {
+        let mut app = set_up_application("amp 41 editor");
+        super::increment_number(&mut app).unwrap();
+
+        assert_eq!(
+            &*app.workspace.current_buffer().unwrap().data(),
+            "amp 42 editor"
+        );
+        assert_eq!(
+            *app.workspace.current_buffer().unwrap().cursor,
+            Position { line: 0, offset: 5 }
+        );
+    }
+
+    #[test]
+    fn increment_number_handles_the_cursor_in_the_middle_of_a_number() {
+        let mut app = set_up_application("amp 199");
+        app.workspace.current_buffer().unwrap().cursor.move_to(Position {
+            line: 0,
+            offset: 5,
+        });
+        super::increment_number(&mut app).unwrap();
+
+        assert_eq!(&*app.workspace.current_buffer().unwrap().data(), "amp 200");
+    }
+
+    #[test]
+    fn increment_number_preserves_zero_padding() {
+        let mut app = set_up_application("amp 007");
+        super::increment_number(&mut app).unwrap();
+
+        assert_eq!(&*app.workspace.current_buffer().unwrap().data(), "amp 008");
+    }
+
+    #[test]
+    fn decrement_number_handles_negative_numbers() {
+        let mut app = set_up_application("x = -9;\n");
+        super::decrement_number(&mut app).unwrap();
+
+        assert_eq!(&*app.workspace.current_buffer().unwrap().data(), "x = -10;\n");
+        assert_eq!(
+            *app.workspace.current_buffer().unwrap().cursor,
+            Position { line: 0, offset: 6 }
+        );
+    }
+
+    #[test]
+    fn decrement_number_crosses_zero() {
+        let mut app = set_up_application("0");
+        super::decrement_number(&mut app).unwrap();
+
+        assert_eq!(&*app.workspace.current_buffer().unwrap().data(), "-1");
+    }
+
+    #[test]
+    fn increment_number_fails_without_a_number_on_the_line() {
+        let mut app = set_up_application("amp 1\neditor");
+        app.workspace.current_buffer().unwrap().cursor.move_to(Position {
+            line: 1,
+            offset: 0,
+        });
+
+        assert!(super::increment_number(&mut app).is_err());
+        assert_eq!(&*app.workspace.current_buffer().unwrap().data(), "amp 1\neditor");
+    }
+
+    #[test]
+    fn transpose_chars_swaps_characters_around_the_cursor() {
+        let mut app = set_up_application("mpa");
+        app.workspace.current_buffer().unwrap().cursor.move_to(Position {
+            line: 0,
+            offset: 1,
+        });
+        super::transpose_chars(&mut app).unwrap();
+
+        assert_eq!(&*app.workspace.current_buffer().unwrap().data(), "pma");
+        assert_eq!(
+            *app.workspace.current_buffer().unwrap().cursor,
+            Position { line: 0, offset: 2 }
+        );
+
+        // The swap should be undone in a single step.
+        app.workspace.current_buffer().unwrap().undo();
+        assert_eq!(&*app.workspace.current_buffer().unwrap().data(), "mpa");
+    }
+
+    #[test]
+    fn transpose_chars_swaps_the_last_two_characters_at_the_end_of_a_line() {
+        let mut app = set_up_application("apm\neditor");
+        app.workspace.current_buffer().unwrap().cursor.move_to(Position {
+            line: 0,
+            offset: 3,
+        });
+        super::transpose_chars(&mut app).unwrap();
+
+        assert_eq!(&*app.workspace.current_buffer().unwrap().data(), "amp\neditor");
+        assert_eq!(
+            *app.workspace.current_buffer().unwrap().cursor,
+            Position { line: 0, offset: 3 }
+        );
+    }
+
+    #[test]
+    fn transpose_chars_does_nothing_at_the_start_of_the_buffer() {
+        let mut app = set_up_application("amp");
+        super::transpose_chars(&mut app).unwrap();
+
+        assert_eq!(&*app.workspace.current_buffer().unwrap().data(), "amp");
+        assert_eq!(
+            *app.workspace.current_buffer().unwrap().cursor,
+            Position { line: 0, offset: 0 }
+        );
+    }
+
+    #[test]
+    fn goto_line_moves_to_the_start_of_the_requested_line() {
+        let mut app = set_up_application("amp\neditor\namp");
+        app.workspace.current_buffer().unwrap().cursor.move_to(Position {
+            line: 0,
+            offset: 2,
+        });
+        set_line_jump_input(&mut app, "2");
+        super::goto_line(&mut app).unwrap();
+
+        assert_eq!(
+            *app.workspace.current_buffer().unwrap().cursor,
+            Position { line: 1, offset: 0 }
+        );
+        assert!(match app.mode {
+            Mode::Normal => true,
+            _ => false,
+        });
+    }
+
+    #[test]
+    fn goto_line_clamps_out_of_range_input() {
+        let mut app = set_up_application("amp\neditor\namp");
+        set_line_jump_input(&mut app, "100000000000000000000000");
+        super::goto_line(&mut app).unwrap();
+        assert_eq!(
+            *app.workspace.current_buffer().unwrap().cursor,
+            Position { line: 2, offset: 0 }
+        );
+
+        set_line_jump_input(&mut app, "0");
+        super::goto_line(&mut app).unwrap();
+        assert_eq!(
+            *app.workspace.current_buffer().unwrap().cursor,
+            Position { line: 0, offset: 0 }
+        );
+    }
+
+    #[test]
+    fn goto_line_supports_relative_input() {
+        let mut app = set_up_application("a\nb\nc\nd\ne");
+        set_line_jump_input(&mut app, "+3");
+        super::goto_line(&mut app).unwrap();
+        assert_eq!(
+            *app.workspace.current_buffer().unwrap().cursor,
+            Position { line: 3, offset: 0 }
+        );
+
+        set_line_jump_input(&mut app, "-2");
+        super::goto_line(&mut app).unwrap();
+        assert_eq!(
+            *app.workspace.current_buffer().unwrap().cursor,
+            Position { line: 1, offset: 0 }
+        );
+
+        set_line_jump_input(&mut app, "-5");
+        super::goto_line(&mut app).unwrap();
+        assert_eq!(
+            *app.workspace.current_buffer().unwrap().cursor,
+            Position { line: 0, offset: 0 }
+        );
+    }
+
+    #[test]
+    fn goto_line_rejects_non_numeric_input() {
+        let mut app = set_up_application("amp\neditor");
+        set_line_jump_input(&mut app, "+x");
+
+        assert!(super::goto_line(&mut app).is_err());
+    }
+
+    #[test]
+    fn move_to_next_word_separates_words_and_punctuation() {
+        let mut app = set_up_application("foo.bar(baz)  qux\n\n  amp");
+        let mut positions = Vec::new();
+        while super::move_to_next_word(&mut app).is_ok() {
+            positions.push(*app.workspace.current_buffer().unwrap().cursor);
+        }
+
+        assert_eq!(positions, vec![
+            Position { line: 0, offset: 3 },
+            Position { line: 0, offset: 4 },
+            Position { line: 0, offset: 7 },
+            Position { line: 0, offset: 8 },
+            Position { line: 0, offset: 11 },
+            Position { line: 0, offset: 14 },
+            Position { line: 1, offset: 0 },
+            Position { line: 2, offset: 2 },
+        ]);
+    }
+
+    #[test]
+    fn move_to_previous_word_separates_words_and_punctuation() {
+        let mut app = set_up_application("foo.bar(baz)  qux\n\n  amp");
+        app.workspace.current_buffer().unwrap().cursor.move_to(Position {
+            line: 2,
+            offset: 4,
+        });
+        let mut positions = Vec::new();
+        while super::move_to_previous_word(&mut app).is_ok() {
+            positions.push(*app.workspace.current_buffer().unwrap().cursor);
+        }
+
+        assert_eq!(positions, vec![
+            Position { line: 2, offset: 2 },
+            Position { line: 1, offset: 0 },
+            Position { line: 0, offset: 14 },
+            Position { line: 0, offset: 11 },
+            Position { line: 0, offset: 8 },
+            Position { line: 0, offset: 7 },
+            Position { line: 0, offset: 4 },
+            Position { line: 0, offset: 3 },
+            Position { line: 0, offset: 0 },
+        ]);
+    }
+
+    #[test]
+    fn move_to_end_of_word_skips_empty_lines() {
+        let mut app = set_up_application("foo.bar  qux\n\n  amp");
+        let mut positions = Vec::new();
+        while super::move_to_end_of_word(&mut app).is_ok() {
+            positions.push(*app.workspace.current_buffer().unwrap().cursor);
+        }
+
+        assert_eq!(positions, vec![
+            Position { line: 0, offset: 2 },
+            Position { line: 0, offset: 3 },
+            Position { line: 0, offset: 6 },
+            Position { line: 0, offset: 11 },
+            Position { line: 2, offset: 4 },
+        ]);
+    }
+
+    #[test]
+    fn move_to_next_word_wraps_to_the_next_line() {
+        let mut app = set_up_application("amp\neditor");
+        super::move_to_next_word(&mut app).unwrap();
+
+        assert_eq!(
+            *app.workspace.current_buffer().unwrap().cursor,
+            Position { line: 1, offset: 0 }
+        );
+    }
+
+    #[test]
+    fn move_to_next_paragraph_treats_consecutive_blank_lines_as_one_boundary() {
+        let mut app = set_up_application("amp\neditor\n\n  \namp\n\neditor");
+        super::move_to_next_paragraph(&mut app).unwrap();
+        assert_eq!(
+            *app.workspace.current_buffer().unwrap().cursor,
+            Position { line: 2, offset: 0 }
+        );
+
+        super::move_to_next_paragraph(&mut app).unwrap();
+        assert_eq!(
+            *app.workspace.current_buffer().unwrap().cursor,
+            Position { line: 5, offset: 0 }
+        );
+
+        // Without any further blank lines, we land on the last line.
+        super::move_to_next_paragraph(&mut app).unwrap();
+        assert_eq!(
+            *app.workspace.current_buffer().unwrap().cursor,
+            Position { line: 6, offset: 0 }
+        );
+    }
+
+    #[test]
+    fn move_to_previous_paragraph_treats_consecutive_blank_lines_as_one_boundary() {
+        let mut app = set_up_application("amp\neditor\n\n  \namp\n\neditor");
+        app.workspace.current_buffer().unwrap().cursor.move_to(Position {
+            line: 6,
+            offset: 3,
+        });
+        super::move_to_previous_paragraph(&mut app).unwrap();
+        assert_eq!(
+            *app.workspace.current_buffer().unwrap().cursor,
+            Position { line: 5, offset: 0 }
+        );
+
+        super::move_to_previous_paragraph(&mut app).unwrap();
+        assert_eq!(
+            *app.workspace.current_buffer().unwrap().cursor,
+            Position { line: 3, offset: 0 }
+        );
+
+        // Without any further blank lines, we land on the first line.
+        super::move_to_previous_paragraph(&mut app).unwrap();
+        assert_eq!(
+            *app.workspace.current_buffer().unwrap().cursor,
+            Position { line: 0, offset: 0 }
+        );
+    }
+
+    #[test]
+    fn move_to_matching_bracket_respects_nesting() {
+        let mut app = set_up_application("fn amp() {\n    if (a[0]) { b }\n}");
+        app.workspace.current_buffer().unwrap().cursor.move_to(Position {
+            line: 0,
+            offset: 9,
+        });
+        super::move_to_matching_bracket(&mut app).unwrap();
+        assert_eq!(
+            *app.workspace.current_buffer().unwrap().cursor,
+            Position { line: 2, offset: 0 }
+        );
+
+        // Moving back again searches in reverse.
+        super::move_to_matching_bracket(&mut app).unwrap();
+        assert_eq!(
+            *app.workspace.current_buffer().unwrap().cursor,
+            Position { line: 0, offset: 9 }
+        );
+    }
+
+    #[test]
+    fn move_to_matching_bracket_searches_forward_for_a_bracket() {
+        let mut app = set_up_application("amp (editor)");
+        super::move_to_matching_bracket(&mut app).unwrap();
+
+        assert_eq!(
+            *app.workspace.current_buffer().unwrap().cursor,
+            Position { line: 0, offset: 11 }
+        );
+    }
+
+    #[test]
+    fn move_to_matching_bracket_reports_unbalanced_brackets() {
+        let mut app = set_up_application("amp (editor");
+        super::move_to_matching_bracket(&mut app).unwrap();
+
+        assert_eq!(
+            *app.workspace.current_buffer().unwrap().cursor,
+            Position { line: 0, offset: 0 }
+        );
+        assert!(app.message.is_some());
+    }
+
+    #[test]
+    fn move_to_matching_bracket_fails_without_a_bracket_on_the_line() {
+        let mut app = set_up_application("amp\n(editor)");
+
+        assert!(super::move_to_matching_bracket(&mut app).is_err());
+    }
+
+    #[test]
+    fn move_to_next_word_moves_by_the_count_stopping_at_the_last_word() {
+        let mut app = set_up_application("amp editor buffer");
+        app.count = Some(2);
+        super::move_to_next_word(&mut app).unwrap();
+
+        assert_eq!(
+            *app.workspace.current_buffer().unwrap().cursor,
+            Position { line: 0, offset: 11 }
+        );
+
+        app.count = Some(5);
+        super::move_to_previous_word(&mut app).unwrap();
+        assert_eq!(
+            *app.workspace.current_buffer().unwrap().cursor,
+            Position { line: 0, offset: 0 }
+        );
+    }
+
+    fn set_line_jump_input(app: &mut Application, input: &str) {
+        commands::application::switch_to_line_jump_mode(app).unwrap();
+        if let Mode::LineJump(ref mut mode) = app.mode {
+            mode.input = input.to_string();
+        }
+    }
+
+    #[test]
+    fn move_up_and_down_navigate_wrapped_rows() {
+        // The test terminal is 10 columns wide; with a 4 column line number
+        // gutter, the first line wraps after "amp ed".
+        let mut app = set_up_application("amp editor\nx");
+        app.workspace.current_buffer().unwrap().cursor.move_to(Position{ line: 0, offset: 2 });
+
+        commands::cursor::move_down(&mut app).unwrap();
+        assert_eq!(*app.workspace.current_buffer().unwrap().cursor, Position{ line: 0, offset: 8 });
+        commands::cursor::move_down(&mut app).unwrap();
+        assert_eq!(*app.workspace.current_buffer().unwrap().cursor, Position{ line: 1, offset: 1 });
+        commands::cursor::move_up(&mut app).unwrap();
+        assert_eq!(*app.workspace.current_buffer().unwrap().cursor, Position{ line: 0, offset: 7 });
+        commands::cursor::move_up(&mut app).unwrap();
+        assert_eq!(*app.workspace.current_buffer().unwrap().cursor, Position{ line: 0, offset: 1 });
+    }
+
+    #[test]
+    fn describe_character_reports_multibyte_codepoints_and_byte_offsets() {
+        let mut app = set_up_application("amp\n\u{200B}e\u{301}");
+        app.workspace.current_buffer().unwrap().cursor.move_to(Position {
+            line: 1,
+            offset: 0,
+        });
+
+        super::describe_character(&mut app).unwrap();
+        assert_eq!(
+            app.message,
+            Some(String::from("\"\\u{200b}\" U+200B ZERO WIDTH SPACE at byte 4"))
+        );
+
+        app.workspace.current_buffer().unwrap().cursor.move_right();
+        super::describe_character(&mut app).unwrap();
+        assert_eq!(app.message, Some(String::from("\"e\\u{301}\" U+0065, U+0301 at byte 7")));
+    }
+
+    #[test]
+    fn describe_character_handles_line_and_buffer_ends() {
+        let mut app = set_up_application("amp\n");
+        app.workspace.current_buffer().unwrap().cursor.move_to_end_of_line();
+
+        super::describe_character(&mut app).unwrap();
+        assert_eq!(app.message, Some(String::from("End of line at byte 3")));
+
+        app.workspace.current_buffer().unwrap().cursor.move_to(Position {
+            line: 1,
+            offset: 0,
+        });
+        super::describe_character(&mut app).unwrap();
+        assert_eq!(app.message, Some(String::from("End of buffer at byte 4")));
+    }
+
     fn set_up_application(content: &str) -> Application {
         let mut app = Application::new(&Vec::new()).unwrap();
         let mut buffer = Buffer::new();
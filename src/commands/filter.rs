@@ -0,0 +1,30 @@
+use crate::errors::*;
+use crate::input::Key;
+use crate::commands::Result;
+use crate::models::application::{Application, Mode};
+
+pub fn push_char(app: &mut Application) -> Result {
+    let key = app.view.last_key().as_ref().ok_or("View hasn't tracked a key press")?;
+
+    if let Key::Char(c) = *key {
+        if let Mode::Filter(ref mut mode) = app.mode {
+            mode.input.push(c)
+        } else {
+            bail!("Can't push command character outside of filter mode")
+        }
+    } else {
+        bail!("Last key press wasn't a character")
+    }
+
+    Ok(())
+}
+
+pub fn pop_char(app: &mut Application) -> Result {
+    if let Mode::Filter(ref mut mode) = app.mode {
+        mode.input.pop()
+    } else {
+        bail!("Can't pop command character outside of filter mode")
+    };
+
+    Ok(())
+}
@@ -16,6 +16,11 @@ pub fn accept_input(app: &mut Application) -> Result {
         if line_number > 0 {
             let buffer = app.workspace.current_buffer().ok_or(BUFFER_MISSING)?;
 
+            // Remember where we were, so that we can jump back.
+            if let Some(id) = buffer.id {
+                app.marks.record_jump(id, *buffer.cursor);
+            }
+
             // Input values won't be zero-indexed; map the value so
             // that we can use it for a zero-indexed buffer position.
             let target_line = line_number - 1;
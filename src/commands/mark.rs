@@ -0,0 +1,257 @@
+use crate::errors::*;
+use crate::input::Key;
+use crate::commands::{self, Result};
+use crate::models::application::{Application, Mode};
+use crate::models::application::modes::MarkAction;
+
+/// Sets or jumps to the mark named by the last key press,
+/// depending on the action with which mark mode was entered.
+pub fn accept_char(app: &mut Application) -> Result {
+    let name = match *app.view.last_key() {
+        Some(Key::Char(c)) if c.is_ascii_lowercase() => c,
+        Some(Key::Char(_)) => bail!("Marks must be named using a lowercase letter"),
+        _ => bail!("Last key press wasn't a character"),
+    };
+    let action = if let Mode::Mark(ref mode) = app.mode {
+        mode.action
+    } else {
+        bail!("Can't accept mark name outside of mark mode");
+    };
+
+    let buffer = app.workspace.current_buffer().ok_or(BUFFER_MISSING)?;
+    let id = buffer.id.ok_or(BUFFER_ID_MISSING)?;
+    match action {
+        MarkAction::Set => app.marks.set(id, name, *buffer.cursor),
+        MarkAction::Jump => {
+            let position = app.marks
+                .get(id, name)
+                .ok_or_else(|| format!("Mark '{}' isn't set", name))?;
+            let previous_position = *buffer.cursor;
+            if !buffer.cursor.move_to(position) {
+                bail!(format!("Mark '{}' is no longer in the buffer", name));
+            }
+            app.marks.record_jump(id, previous_position);
+        }
+    }
+
+    commands::application::switch_to_normal_mode(app)?;
+    commands::view::scroll_cursor_to_center(app)
+}
+
+/// Steps back through the jump list, returning to the position the cursor
+/// was at prior to the last jump (e.g. a search, line jump, or mark jump).
+pub fn jump_back(app: &mut Application) -> Result {
+    let buffer = app.workspace.current_buffer().ok_or(BUFFER_MISSING)?;
+    let id = buffer.id.ok_or(BUFFER_ID_MISSING)?;
+    let position = app.marks
+        .previous_jump(id, *buffer.cursor)
+        .ok_or("No previous jump position")?;
+    if !buffer.cursor.move_to(position) {
+        bail!("Previous jump position is no longer in the buffer");
+    }
+
+    commands::view::scroll_cursor_to_center(app)
+}
+
+/// Steps forward through the jump list, reversing the last `jump_back`.
+pub fn jump_forward(app: &mut Application) -> Result {
+    let buffer = app.workspace.current_buffer().ok_or(BUFFER_MISSING)?;
+    let id = buffer.id.ok_or(BUFFER_ID_MISSING)?;
+    let position = app.marks
+        .next_jump(id, *buffer.cursor)
+        .ok_or("No next jump position")?;
+    if !buffer.cursor.move_to(position) {
+        bail!("Next jump position is no longer in the buffer");
+    }
+
+    commands::view::scroll_cursor_to_center(app)
+}
+
+/// Moves the cursor to the position of the next oldest change
+/// in the buffer's change list, starting with the newest.
+pub fn previous_change(app: &mut Application) -> Result {
+    let buffer = app.workspace.current_buffer().ok_or(BUFFER_MISSING)?;
+    let id = buffer.id.ok_or(BUFFER_ID_MISSING)?;
+    let position = app.marks.previous_change(id).ok_or("No previous change position")?;
+    if !buffer.cursor.move_to(position) {
+        bail!("Previous change position is no longer in the buffer");
+    }
+
+    commands::view::scroll_cursor_to_center(app)
+}
+
+/// Moves the cursor to the position of the next newest change
+/// in the buffer's change list, reversing `previous_change`.
+pub fn next_change(app: &mut Application) -> Result {
+    let buffer = app.workspace.current_buffer().ok_or(BUFFER_MISSING)?;
+    let id = buffer.id.ok_or(BUFFER_ID_MISSING)?;
+    let position = app.marks.next_change(id).ok_or("No next change position")?;
+    if !buffer.cursor.move_to(position) {
+        bail!("Next change position is no longer in the buffer");
+    }
+
+    commands::view::scroll_cursor_to_center(app)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::commands;
+    use crate::input::Key;
+    use scribe::Buffer;
+    use scribe::buffer::Position;
+    use crate::models::application::{Application, Mode};
+
+    #[test]
+    fn accept_char_sets_and_jumps_to_named_marks() {
+        let mut app = Application::new(&Vec::new()).unwrap();
+        let mut buffer = Buffer::new();
+        buffer.insert("amp\neditor\namp");
+        buffer.cursor.move_to(Position{ line: 1, offset: 2 });
+        app.workspace.add_buffer(buffer);
+
+        commands::application::switch_to_set_mark_mode(&mut app).unwrap();
+        app.view.last_key = Some(Key::Char('a'));
+        commands::mark::accept_char(&mut app).unwrap();
+        app.workspace.current_buffer().unwrap().cursor.move_to(Position{ line: 2, offset: 1 });
+        commands::application::switch_to_jump_to_mark_mode(&mut app).unwrap();
+        commands::mark::accept_char(&mut app).unwrap();
+
+        assert_eq!(*app.workspace.current_buffer().unwrap().cursor,
+                   Position {
+                       line: 1,
+                       offset: 2,
+                   });
+        assert!(match app.mode {
+            Mode::Normal => true,
+            _ => false,
+        });
+
+        // The mark jump can be reversed, and then replayed.
+        commands::mark::jump_back(&mut app).unwrap();
+        assert_eq!(*app.workspace.current_buffer().unwrap().cursor,
+                   Position {
+                       line: 2,
+                       offset: 1,
+                   });
+        commands::mark::jump_forward(&mut app).unwrap();
+        assert_eq!(*app.workspace.current_buffer().unwrap().cursor,
+                   Position {
+                       line: 1,
+                       offset: 2,
+                   });
+    }
+
+    #[test]
+    fn accept_char_rejects_invalid_mark_names() {
+        let mut app = Application::new(&Vec::new()).unwrap();
+        app.workspace.add_buffer(Buffer::new());
+
+        commands::application::switch_to_set_mark_mode(&mut app).unwrap();
+        app.view.last_key = Some(Key::Char('A'));
+        assert!(commands::mark::accept_char(&mut app).is_err());
+    }
+
+    #[test]
+    fn jump_back_returns_to_position_prior_to_line_jump() {
+        let mut app = Application::new(&Vec::new()).unwrap();
+        let mut buffer = Buffer::new();
+        buffer.insert("amp\neditor\namp");
+        buffer.cursor.move_to(Position{ line: 1, offset: 2 });
+        app.workspace.add_buffer(buffer);
+
+        commands::application::switch_to_line_jump_mode(&mut app).unwrap();
+        match app.mode {
+            Mode::LineJump(ref mut mode) => mode.input = "3".to_string(),
+            _ => (),
+        };
+        commands::line_jump::accept_input(&mut app).unwrap();
+        commands::mark::jump_back(&mut app).unwrap();
+
+        assert_eq!(*app.workspace.current_buffer().unwrap().cursor,
+                   Position {
+                       line: 1,
+                       offset: 2,
+                   });
+    }
+
+    #[test]
+    fn jump_forward_is_unavailable_after_editing() {
+        let mut app = Application::new(&Vec::new()).unwrap();
+        let mut buffer = Buffer::new();
+        buffer.insert("amp\neditor\namp");
+        app.workspace.add_buffer(buffer);
+
+        commands::application::switch_to_line_jump_mode(&mut app).unwrap();
+        match app.mode {
+            Mode::LineJump(ref mut mode) => mode.input = "3".to_string(),
+            _ => (),
+        };
+        commands::line_jump::accept_input(&mut app).unwrap();
+        commands::mark::jump_back(&mut app).unwrap();
+        app.view.last_key = Some(Key::Char('x'));
+        commands::buffer::insert_char(&mut app).unwrap();
+
+        assert!(commands::mark::jump_forward(&mut app).is_err());
+    }
+
+    #[test]
+    fn jump_forward_is_still_available_after_entering_and_leaving_insert_mode() {
+        let mut app = Application::new(&Vec::new()).unwrap();
+        let mut buffer = Buffer::new();
+        buffer.insert("amp\neditor\namp");
+        app.workspace.add_buffer(buffer);
+
+        commands::application::switch_to_line_jump_mode(&mut app).unwrap();
+        match app.mode {
+            Mode::LineJump(ref mut mode) => mode.input = "3".to_string(),
+            _ => (),
+        };
+        commands::line_jump::accept_input(&mut app).unwrap();
+        commands::mark::jump_back(&mut app).unwrap();
+        commands::application::switch_to_insert_mode(&mut app).unwrap();
+        commands::application::switch_to_normal_mode(&mut app).unwrap();
+        commands::mark::jump_forward(&mut app).unwrap();
+
+        assert_eq!(app.workspace.current_buffer().unwrap().cursor.line, 2);
+    }
+
+    #[test]
+    fn previous_change_returns_to_the_position_of_an_edit() {
+        let mut app = Application::new(&Vec::new()).unwrap();
+        let mut buffer = Buffer::new();
+        buffer.insert("amp\neditor\namp");
+        buffer.cursor.move_to(Position{ line: 1, offset: 2 });
+        app.workspace.add_buffer(buffer);
+
+        app.view.last_key = Some(Key::Char('x'));
+        commands::buffer::insert_char(&mut app).unwrap();
+        app.workspace.current_buffer().unwrap().cursor.move_to(Position{ line: 2, offset: 0 });
+        commands::mark::previous_change(&mut app).unwrap();
+
+        // The cursor's position after the edit is recorded.
+        assert_eq!(*app.workspace.current_buffer().unwrap().cursor,
+                   Position {
+                       line: 1,
+                       offset: 3,
+                   });
+        assert!(commands::mark::next_change(&mut app).is_err());
+    }
+
+    #[test]
+    fn previous_change_skips_commands_that_leave_the_buffer_untouched() {
+        let mut app = Application::new(&Vec::new()).unwrap();
+        let mut buffer = Buffer::new();
+        buffer.insert("amp\neditor\namp");
+        buffer.cursor.move_to(Position{ line: 1, offset: 2 });
+        app.workspace.add_buffer(buffer);
+
+        app.view.last_key = Some(Key::Char('x'));
+        commands::buffer::insert_char(&mut app).unwrap();
+        app.workspace.current_buffer().unwrap().cursor.move_to(Position{ line: 2, offset: 0 });
+        commands::application::switch_to_insert_mode(&mut app).unwrap();
+        commands::application::switch_to_normal_mode(&mut app).unwrap();
+        commands::mark::previous_change(&mut app).unwrap();
+
+        assert_eq!(app.workspace.current_buffer().unwrap().cursor.line, 1);
+    }
+}
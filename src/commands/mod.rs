@@ -6,14 +6,18 @@ pub mod application;
 pub mod buffer;
 pub mod confirm;
 pub mod cursor;
+pub mod filter;
 pub mod git;
 pub mod jump;
 pub mod line_jump;
+pub mod mark;
 pub mod path;
 pub mod preferences;
+pub mod replace;
 pub mod search;
 pub mod selection;
 pub mod search_select;
+pub mod text_object;
 pub mod view;
 pub mod workspace;
 
@@ -2,7 +2,10 @@ use crate::errors::*;
 use crate::commands::{self, Result};
 use crate::input::Key;
 use crate::models::application::{Application, Mode};
-use std::path::PathBuf;
+use crate::util;
+use scribe::buffer::Range;
+use std::fs;
+use std::path::{Path, PathBuf};
 
 pub fn push_char(app: &mut Application) -> Result {
     let last_key = app.view.last_key().as_ref().ok_or("View hasn't tracked a key press")?;
@@ -28,6 +31,16 @@ pub fn pop_char(app: &mut Application) -> Result {
 }
 
 pub fn accept_path(app: &mut Application) -> Result {
+    let (write_range, insert_on_accept) = match app.mode {
+        Mode::Path(ref mode) => (mode.write_range.clone(), mode.insert_on_accept),
+        _ => (None, false),
+    };
+    if let Some(range) = write_range {
+        return write_selection(app, range);
+    } else if insert_on_accept {
+        return insert_file(app);
+    }
+
     let save_on_accept =
         if let Mode::Path(ref mut mode) = app.mode {
             let current_buffer = app.workspace.current_buffer().ok_or(BUFFER_MISSING)?;
@@ -53,6 +66,76 @@ pub fn accept_path(app: &mut Application) -> Result {
     }
 }
 
+// Inserts the contents of the entered path at the cursor, as a single
+// operation. The buffer is left untouched if the file can't be read.
+fn insert_file(app: &mut Application) -> Result {
+    util::ensure_writable(app)?;
+    let path_name = match app.mode {
+        Mode::Path(ref mode) => mode.input.clone(),
+        _ => bail!("Cannot insert a file outside of path mode"),
+    };
+    if path_name.is_empty() {
+        bail!("Please provide a non-empty path")
+    }
+    let content = fs::read_to_string(&path_name)
+        .chain_err(|| format!("Couldn't read {}", path_name))?;
+
+    let buffer = app.workspace.current_buffer().ok_or(BUFFER_MISSING)?;
+    let line_count = content.lines().count();
+    buffer.insert(content);
+    util::record_edit(app);
+    app.message = Some(format!(
+        "Inserted {} line{} from {}",
+        line_count,
+        if line_count == 1 { "" } else { "s" },
+        path_name
+    ));
+    app.mode = Mode::Normal;
+
+    commands::view::scroll_to_cursor(app)
+}
+
+// Writes the range to the entered path, warning (and requiring
+// the path to be accepted again) before overwriting a file.
+fn write_selection(app: &mut Application, range: Range) -> Result {
+    let path_name = match app.mode {
+        Mode::Path(ref mut mode) => {
+            let path_name = mode.input.clone();
+            if path_name.is_empty() {
+                bail!("Please provide a non-empty path")
+            }
+            if Path::new(&path_name).is_dir() {
+                bail!(format!("{} is a directory", path_name));
+            }
+            if Path::new(&path_name).exists() && !mode.overwrite_warned {
+                mode.overwrite_warned = true;
+                app.message = Some(format!(
+                    "{} already exists; press enter again to overwrite it",
+                    path_name
+                ));
+                return Ok(());
+            }
+
+            path_name
+        }
+        _ => bail!("Cannot write selection outside of path mode"),
+    };
+
+    let buffer = app.workspace.current_buffer().ok_or(BUFFER_MISSING)?;
+    let content = buffer.read(&range).ok_or("Couldn't read selected data from buffer")?;
+    fs::write(&path_name, &content).chain_err(|| "Couldn't write selection to file")?;
+    let line_count = content.lines().count();
+    app.message = Some(format!(
+        "Wrote {} line{} to {}",
+        line_count,
+        if line_count == 1 { "" } else { "s" },
+        path_name
+    ));
+    app.mode = Mode::Normal;
+
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use crate::commands;
@@ -86,6 +169,66 @@ mod tests {
         }
     }
 
+    #[test]
+    fn accept_path_writes_the_selection_and_warns_before_overwriting() {
+        let path = std::env::temp_dir().join("amp_write_selection_test");
+        let _ = std::fs::remove_file(&path);
+        let mut app = Application::new(&Vec::new()).unwrap();
+        let mut buffer = Buffer::new();
+        buffer.insert("amp\neditor\nbuffer\n");
+        app.workspace.add_buffer(buffer);
+
+        // Write the first two lines.
+        commands::application::switch_to_select_line_mode(&mut app).unwrap();
+        commands::cursor::move_down(&mut app).unwrap();
+        commands::application::switch_to_write_selection_mode(&mut app).unwrap();
+        if let Mode::Path(ref mut mode) = app.mode {
+            mode.input = path.to_string_lossy().into();
+        }
+        super::accept_path(&mut app).unwrap();
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "amp\neditor\n");
+        assert_eq!(app.workspace.current_buffer().unwrap().data(), "amp\neditor\nbuffer\n");
+
+        // Writing to the same path again requires a second confirmation.
+        commands::application::switch_to_select_line_mode(&mut app).unwrap();
+        commands::application::switch_to_write_selection_mode(&mut app).unwrap();
+        if let Mode::Path(ref mut mode) = app.mode {
+            mode.input = path.to_string_lossy().into();
+        }
+        super::accept_path(&mut app).unwrap();
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "amp\neditor\n");
+        super::accept_path(&mut app).unwrap();
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "editor\n");
+
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn accept_path_inserts_file_contents_at_the_cursor() {
+        let path = std::env::temp_dir().join("amp_insert_file_test");
+        std::fs::write(&path, "editor\n").unwrap();
+        let mut app = Application::new(&Vec::new()).unwrap();
+        let mut buffer = Buffer::new();
+        buffer.insert("amp\n");
+        app.workspace.add_buffer(buffer);
+
+        commands::application::switch_to_insert_file_mode(&mut app).unwrap();
+        if let Mode::Path(ref mut mode) = app.mode {
+            mode.input = path.to_string_lossy().into();
+        }
+        super::accept_path(&mut app).unwrap();
+        assert_eq!(app.workspace.current_buffer().unwrap().data(), "editor\namp\n");
+
+        // Missing files leave the buffer (and mode) alone.
+        std::fs::remove_file(&path).unwrap();
+        commands::application::switch_to_insert_file_mode(&mut app).unwrap();
+        if let Mode::Path(ref mut mode) = app.mode {
+            mode.input = path.to_string_lossy().into();
+        }
+        assert!(super::accept_path(&mut app).is_err());
+        assert_eq!(app.workspace.current_buffer().unwrap().data(), "editor\namp\n");
+    }
+
     #[test]
     fn accept_path_respects_save_on_accept_flag() {
         let mut app = Application::new(&Vec::new()).unwrap();
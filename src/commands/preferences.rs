@@ -8,5 +8,5 @@ pub fn edit(app: &mut Application) -> Result {
 }
 
 pub fn reload(app: &mut Application) -> Result {
-    app.preferences.borrow_mut().reload()
+    app.reload_preferences()
 }
@@ -0,0 +1,76 @@
+use crate::errors::*;
+use crate::input::Key;
+use crate::commands::{self, Result};
+use crate::models::application::{Application, Mode};
+
+pub fn accept_input(app: &mut Application) -> Result {
+    if let Mode::Replace(ref mode) = app.mode {
+        app.replacement = Some(mode.input.clone());
+    } else {
+        bail!("Can't accept replacement outside of replace mode");
+    }
+
+    // Return to search mode, restoring the query results
+    // to which the replacement will be applied.
+    commands::application::switch_to_search_mode(app)?;
+    commands::search::accept_query(app)
+}
+
+pub fn push_char(app: &mut Application) -> Result {
+    let key = app.view.last_key().as_ref().ok_or("View hasn't tracked a key press")?;
+
+    if let Key::Char(c) = *key {
+        if let Mode::Replace(ref mut mode) = app.mode {
+            mode.input.push(c)
+        } else {
+            bail!("Can't push replacement character outside of replace mode")
+        }
+    } else {
+        bail!("Last key press wasn't a character")
+    }
+
+    Ok(())
+}
+
+pub fn pop_char(app: &mut Application) -> Result {
+    if let Mode::Replace(ref mut mode) = app.mode {
+        mode.input.pop();
+    } else {
+        bail!("Can't pop replacement character outside of replace mode")
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::commands;
+    use scribe::Buffer;
+    use crate::models::application::{Application, Mode};
+
+    #[test]
+    fn accept_input_stores_replacement_and_returns_to_search_mode() {
+        let mut app = Application::new(&Vec::new()).unwrap();
+        let mut buffer = Buffer::new();
+        buffer.insert("amp editor");
+        app.workspace.add_buffer(buffer);
+        app.search_query = Some(String::from("editor"));
+
+        commands::application::switch_to_replace_mode(&mut app).unwrap();
+        match app.mode {
+            Mode::Replace(ref mut mode) => mode.input = "buffer".to_string(),
+            _ => (),
+        };
+        commands::replace::accept_input(&mut app).unwrap();
+
+        assert_eq!(app.replacement, Some(String::from("buffer")));
+        match app.mode {
+            Mode::Search(ref mode) => {
+                assert_eq!(mode.input, Some(String::from("editor")));
+                assert!(!mode.insert);
+                assert_eq!(mode.results.as_ref().unwrap().len(), 1);
+            }
+            _ => panic!("Not in search mode"),
+        }
+    }
+}
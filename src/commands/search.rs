@@ -1,32 +1,98 @@
+use crate::errors;
 use crate::errors::*;
 use crate::input::Key;
 use crate::commands::{self, Result};
 use crate::models::application::{Application, Mode};
+use crate::util;
 
 pub fn move_to_previous_result(app: &mut Application) -> Result {
-    if let Mode::Search(ref mut mode) = app.mode {
-        mode.results.as_mut().ok_or(NO_SEARCH_RESULTS)?.select_previous();
+    let wrapped = if let Mode::Search(ref mut mode) = app.mode {
+        let results = mode.results.as_mut().ok_or(NO_SEARCH_RESULTS)?;
+        if results.is_empty() {
+            bail!(NO_SEARCH_RESULTS);
+        }
+        let wrapped = results.selected_index() == 0;
+        results.select_previous();
+
+        wrapped
     } else {
         bail!("Can't move to search result outside of search mode");
-    }
+    };
 
     commands::view::scroll_cursor_to_center(app)
         .chain_err(|| SCROLL_TO_CURSOR_FAILED)?;
-    move_to_current_result(app)
+    move_to_current_result(app)?;
+    report_wrapping(app, wrapped);
+
+    Ok(())
 }
 
 pub fn move_to_next_result(app: &mut Application) -> Result {
-    if let Mode::Search(ref mut mode) = app.mode {
-        mode.results.as_mut().ok_or(NO_SEARCH_RESULTS)?.select_next();
+    let wrapped = if let Mode::Search(ref mut mode) = app.mode {
+        let results = mode.results.as_mut().ok_or(NO_SEARCH_RESULTS)?;
+        if results.is_empty() {
+            bail!(NO_SEARCH_RESULTS);
+        }
+        let wrapped = results.selected_index() == results.len() - 1;
+        results.select_next();
+
+        wrapped
     } else {
         bail!("Can't move to search result outside of search mode");
-    }
+    };
 
     commands::view::scroll_cursor_to_center(app)
         .chain_err(|| SCROLL_TO_CURSOR_FAILED)?;
+    move_to_current_result(app)?;
+    report_wrapping(app, wrapped);
+
+    Ok(())
+}
+
+/// Leaves a secondary cursor at the current result, and moves to the next one.
+/// Characters inserted and deleted thereafter are repeated at every cursor.
+pub fn add_cursor_at_next_result(app: &mut Application) -> Result {
+    let buffer = app.workspace.current_buffer().ok_or(BUFFER_MISSING)?;
+    let id = buffer.id.ok_or(BUFFER_ID_MISSING)?;
+    let position = *buffer.cursor;
+
+    move_to_next_result(app)?;
+    let primary = *app.workspace.current_buffer().ok_or(BUFFER_MISSING)?.cursor;
+    if primary == position {
+        bail!("No other results at which to add a cursor");
+    }
+    app.secondary_cursors.add(id, primary, position);
+
+    Ok(())
+}
+
+/// Groups the current result with the next one, moving to the latter,
+/// so that they (and any others grouped with them) can be copied or
+/// deleted at once.
+pub fn select_next_result(app: &mut Application) -> Result {
+    if let Mode::Search(ref mut mode) = app.mode {
+        mode.select_current_result();
+        mode.results.as_mut().ok_or(NO_SEARCH_RESULTS)?.select_next();
+        mode.select_current_result();
+    } else {
+        bail!("Can't select search results outside of search mode");
+    }
+
     move_to_current_result(app)
 }
 
+/// Groups every result, so that they can be copied or deleted at once.
+pub fn select_all_results(app: &mut Application) -> Result {
+    if let Mode::Search(ref mut mode) = app.mode {
+        let results = mode.results.as_ref().ok_or(NO_SEARCH_RESULTS)?;
+        mode.selected_results = results.iter().cloned().collect();
+    } else {
+        bail!("Can't select search results outside of search mode");
+    }
+
+    Ok(())
+}
+
 pub fn move_to_current_result(app: &mut Application) -> Result {
     if let Mode::Search(ref mut mode) = app.mode {
         let buffer = app.workspace.current_buffer().ok_or(BUFFER_MISSING)?;
@@ -48,13 +114,94 @@ pub fn move_to_current_result(app: &mut Application) -> Result {
 }
 
 pub fn accept_query(app: &mut Application) -> Result {
+    let history_result = record_query(app)?;
+    run(app)?;
+
+    history_result
+}
+
+/// Accepts the query like `accept_query`, but moves to the closest
+/// result before the cursor instead of the one after it.
+pub fn accept_query_in_reverse(app: &mut Application) -> Result {
+    let history_result = record_query(app)?;
+    search_buffer(app)?;
+    let wrapped = select_closest_result_before_cursor(app)?;
+    move_to_current_result(app)?;
+    report_wrapping(app, wrapped);
+
+    history_result
+}
+
+// Leaves insert sub-mode and records the query in the search history. The
+// outer result covers the query's acceptance; the inner one any persistence
+// errors, which are deferred so that they don't prevent the search.
+fn record_query(app: &mut Application) -> errors::Result<Result> {
     if let Mode::Search(ref mut mode) = app.mode {
         // Disable insert sub-mode.
         mode.insert = false;
+
+        // Return to where the search started, so that the previewed match
+        // is selected (rather than the one after it) when we run the search.
+        let buffer = app.workspace.current_buffer().ok_or(BUFFER_MISSING)?;
+        if let Some(position) = mode.original_position.take() {
+            buffer.cursor.move_to(position);
+        }
+
+        // Remember where we were, so that we can jump back.
+        if let Some(id) = buffer.id {
+            app.marks.record_jump(id, *buffer.cursor);
+        }
+
+        // Record the query, deferring any persistence
+        // errors so that they don't prevent the search.
+        mode.history_index = None;
+        Ok(match mode.input {
+            Some(ref query) => app.search_history.push(query.as_str()),
+            None => Ok(()),
+        })
     } else {
         bail!("Can't accept search query outside of search mode");
     }
-    run(app)?;
+}
+
+pub fn previous_query(app: &mut Application) -> Result {
+    if let Mode::Search(ref mut mode) = app.mode {
+        let age = mode.history_index.map(|index| index + 1).unwrap_or(0);
+        let query = app.search_history.get(age).ok_or("No earlier search queries")?;
+
+        mode.history_index = Some(age);
+        mode.input = Some(query.clone());
+        app.search_query = Some(query.clone());
+    } else {
+        bail!("Can't browse search history outside of search mode");
+    }
+
+    Ok(())
+}
+
+pub fn next_query(app: &mut Application) -> Result {
+    if let Mode::Search(ref mut mode) = app.mode {
+        match mode.history_index {
+            Some(0) => {
+                // We've returned to the present; clear the query.
+                mode.history_index = None;
+                mode.input = None;
+                app.search_query = None;
+            }
+            Some(index) => {
+                let query = app.search_history
+                    .get(index - 1)
+                    .ok_or("Couldn't find search history entry")?;
+
+                mode.history_index = Some(index - 1);
+                mode.input = Some(query.clone());
+                app.search_query = Some(query.clone());
+            }
+            None => bail!("No later search queries"),
+        }
+    } else {
+        bail!("Can't browse search history outside of search mode");
+    }
 
     Ok(())
 }
@@ -85,7 +232,7 @@ pub fn push_search_char(app: &mut Application) -> Result {
         bail!("Last key press wasn't a character")
     }
 
-    Ok(())
+    preview_results(app)
 }
 
 pub fn pop_search_char(app: &mut Application) -> Result {
@@ -98,23 +245,202 @@ pub fn pop_search_char(app: &mut Application) -> Result {
         bail!("Can't pop search character outside of search mode");
     };
 
-    Ok(())
+    preview_results(app)
+}
+
+/// Abandons the search, returning the cursor to where the search started.
+pub fn cancel(app: &mut Application) -> Result {
+    if let Mode::Search(ref mode) = app.mode {
+        if let Some(position) = mode.original_position {
+            app.workspace.current_buffer().ok_or(BUFFER_MISSING)?.cursor.move_to(position);
+        }
+    } else {
+        bail!("Can't cancel search outside of search mode");
+    }
+
+    commands::application::switch_to_normal_mode(app)?;
+    commands::view::scroll_to_cursor(app)
+}
+
+// Searches using the in-progress query, highlighting its results and moving
+// the cursor to the closest one after the position at which the search was
+// started. Without any results, the cursor is returned to that position.
+fn preview_results(app: &mut Application) -> Result {
+    let has_results = if let Mode::Search(ref mut mode) = app.mode {
+        let buffer = app.workspace.current_buffer().ok_or(BUFFER_MISSING)?;
+        if let Some(position) = mode.original_position {
+            buffer.cursor.move_to(position);
+        }
+
+        if mode.input.as_ref().map(|query| query.is_empty()).unwrap_or(true) {
+            mode.results = None;
+        } else {
+            mode.search(&buffer)?;
+        }
+
+        mode.results.as_ref().map(|r| !r.is_empty()).unwrap_or(false)
+    } else {
+        bail!("Can't preview search results outside of search mode");
+    };
+
+    if has_results {
+        select_closest_result(app)?;
+        move_to_current_result(app)
+    } else {
+        commands::view::scroll_to_cursor(app)
+    }
 }
 
 pub fn run(app: &mut Application) -> Result {
+    search_buffer(app)?;
+    let wrapped = select_closest_result(app)?;
+    move_to_current_result(app)?;
+    report_wrapping(app, wrapped);
+
+    Ok(())
+}
+
+fn search_buffer(app: &mut Application) -> Result {
     if let Mode::Search(ref mut mode) = app.mode {
-        // Search the buffer.
         let buffer = app.workspace.current_buffer().ok_or(BUFFER_MISSING)?;
         mode.search(&buffer)?;
     } else {
         bail!("Can't run search outside of search mode");
     }
 
-    select_closest_result(app)?;
-    move_to_current_result(app)
+    Ok(())
+}
+
+pub fn toggle_regex(app: &mut Application) -> Result {
+    let (regex, has_query) = if let Mode::Search(ref mut mode) = app.mode {
+        mode.regex = !mode.regex;
+        (mode.regex, mode.input.is_some())
+    } else {
+        bail!("Can't toggle regex search outside of search mode");
+    };
+    app.preferences.borrow_mut().set_search_regex(regex);
+
+    // Refresh any existing results using the new matching mode.
+    if has_query {
+        run(app)?;
+    }
+
+    Ok(())
+}
+
+pub fn toggle_whole_word(app: &mut Application) -> Result {
+    let (whole_word, has_query) = if let Mode::Search(ref mut mode) = app.mode {
+        mode.whole_word = !mode.whole_word;
+        (mode.whole_word, mode.input.is_some())
+    } else {
+        bail!("Can't toggle whole word search outside of search mode");
+    };
+    app.preferences.borrow_mut().set_search_whole_word(whole_word);
+
+    // Refresh any existing results using the new matching mode.
+    if has_query {
+        run(app)?;
+    }
+
+    Ok(())
+}
+
+pub fn toggle_case_sensitivity(app: &mut Application) -> Result {
+    let (case_sensitivity, has_query) = if let Mode::Search(ref mut mode) = app.mode {
+        mode.case_sensitivity = mode.case_sensitivity.next();
+        (mode.case_sensitivity, mode.input.is_some())
+    } else {
+        bail!("Can't toggle search case sensitivity outside of search mode");
+    };
+    app.preferences.borrow_mut().set_search_case_sensitivity(case_sensitivity);
+
+    // Refresh any existing results using the new matching mode.
+    if has_query {
+        run(app)?;
+    }
+
+    Ok(())
+}
+
+pub fn replace_current(app: &mut Application) -> Result {
+    util::ensure_writable(app)?;
+    if let Mode::Search(ref mut mode) = app.mode {
+        let buffer = app.workspace.current_buffer().ok_or(BUFFER_MISSING)?;
+        let replacement = app.replacement.as_ref().ok_or(REPLACEMENT_MISSING)?;
+        let result = mode.results
+            .as_ref()
+            .and_then(|r| r.selection())
+            .ok_or(NO_SEARCH_RESULTS)?
+            .clone();
+
+        let content = mode.replacement_for(&buffer.data(), &result, replacement)?;
+
+        buffer.start_operation_group();
+        buffer.delete_range(result.clone());
+        buffer.cursor.move_to(result.start());
+        buffer.insert(content);
+        buffer.end_operation_group();
+    } else {
+        bail!("Can't replace search results outside of search mode");
+    }
+    util::record_edit(app);
+
+    refresh_results(app)
+}
+
+pub fn replace_all(app: &mut Application) -> Result {
+    util::ensure_writable(app)?;
+    if let Mode::Search(ref mut mode) = app.mode {
+        let buffer = app.workspace.current_buffer().ok_or(BUFFER_MISSING)?;
+        let replacement = app.replacement.as_ref().ok_or(REPLACEMENT_MISSING)?;
+        let results = mode.results.as_ref().ok_or(NO_SEARCH_RESULTS)?;
+        let first_result = results.first().ok_or(NO_SEARCH_RESULTS)?.clone();
+
+        // Replace results in reverse order, so that replacements changing the
+        // length of the text don't shift the positions of pending results.
+        // Build all of the replacements up front, so
+        // that errors don't leave a partial batch behind.
+        let replacements = mode.replacements_for(&buffer.data(), results, replacement)?;
+
+        buffer.start_operation_group();
+        for (result, content) in results.iter().zip(replacements).rev() {
+            buffer.delete_range(result.clone());
+            buffer.cursor.move_to(result.start());
+            buffer.insert(content);
+        }
+        buffer.end_operation_group();
+
+        buffer.cursor.move_to(first_result.start());
+    } else {
+        bail!("Can't replace search results outside of search mode");
+    }
+    util::record_edit(app);
+
+    refresh_results(app)
+}
+
+// Re-runs the search after the buffer has been modified; unlike the run
+// command, having no remaining results isn't considered an error.
+fn refresh_results(app: &mut Application) -> Result {
+    let has_results = if let Mode::Search(ref mut mode) = app.mode {
+        let buffer = app.workspace.current_buffer().ok_or(BUFFER_MISSING)?;
+        mode.search(&buffer)?;
+        mode.results.as_ref().map(|r| !r.is_empty()).unwrap_or(false)
+    } else {
+        bail!("Can't run search outside of search mode");
+    };
+
+    if has_results {
+        select_closest_result(app)?;
+        move_to_current_result(app)?;
+    }
+
+    Ok(())
 }
 
-fn select_closest_result(app: &mut Application) -> Result {
+// Selects the first result after the cursor, returning
+// whether the search wrapped around to the start of the buffer.
+fn select_closest_result(app: &mut Application) -> errors::Result<bool> {
     if let Mode::Search(ref mut mode) = app.mode {
         let buffer = app.workspace.current_buffer().ok_or(BUFFER_MISSING)?;
         let results = mode.results.as_mut().ok_or(NO_SEARCH_RESULTS)?;
@@ -127,9 +453,43 @@ fn select_closest_result(app: &mut Application) -> Result {
         for _ in 0..skip_count {
             results.select_next();
         }
+
+        return Ok(!results.is_empty() && skip_count == results.len());
     }
 
-    Ok(())
+    Ok(false)
+}
+
+// Selects the last result before the cursor, returning
+// whether the search wrapped around to the end of the buffer.
+fn select_closest_result_before_cursor(app: &mut Application) -> errors::Result<bool> {
+    if let Mode::Search(ref mut mode) = app.mode {
+        let buffer = app.workspace.current_buffer().ok_or(BUFFER_MISSING)?;
+        let results = mode.results.as_mut().ok_or(NO_SEARCH_RESULTS)?;
+        if results.is_empty() {
+            return Ok(false);
+        }
+
+        let preceding_count = results
+            .iter()
+            .filter(|r| r.start() < *buffer.cursor)
+            .count();
+        if preceding_count == 0 {
+            results.select_previous();
+            return Ok(true);
+        }
+        for _ in 1..preceding_count {
+            results.select_next();
+        }
+    }
+
+    Ok(false)
+}
+
+fn report_wrapping(app: &mut Application, wrapped: bool) {
+    if wrapped {
+        app.message = Some(String::from("Search wrapped"));
+    }
 }
 
 #[cfg(test)]
@@ -137,8 +497,109 @@ mod tests {
     use scribe::Buffer;
     use scribe::buffer::Position;
     use crate::models::Application;
-    use crate::models::application::Mode;
+    use crate::models::application::{ClipboardContent, Mode};
+    use crate::models::application::modes::CaseSensitivity;
     use crate::commands;
+    use crate::input::Key;
+
+    #[test]
+    fn add_cursor_at_next_result_repeats_insertions_at_each_result() {
+        let mut app = Application::new(&Vec::new()).unwrap();
+        let mut buffer = Buffer::new();
+        buffer.insert("amp editor\namp amp");
+        app.workspace.add_buffer(buffer);
+
+        commands::application::switch_to_search_mode(&mut app).unwrap();
+        if let Mode::Search(ref mut mode) = app.mode {
+            mode.input = Some(String::from("amp"));
+        }
+        commands::search::accept_query(&mut app).unwrap();
+        commands::search::add_cursor_at_next_result(&mut app).unwrap();
+        commands::search::add_cursor_at_next_result(&mut app).unwrap();
+        commands::application::switch_to_insert_mode(&mut app).unwrap();
+        app.view.last_key = Some(Key::Char('x'));
+        commands::buffer::insert_char(&mut app).unwrap();
+        commands::buffer::backspace(&mut app).unwrap();
+        commands::buffer::insert_char(&mut app).unwrap();
+
+        assert_eq!(app.workspace.current_buffer().unwrap().data(), "xamp editor\nxamp xamp");
+        assert_eq!(*app.workspace.current_buffer().unwrap().cursor,
+                   Position{ line: 0, offset: 1 });
+        assert_eq!(
+            app.secondary_cursors.positions(app.workspace.current_buffer().unwrap().id),
+            &[Position{ line: 1, offset: 6 }, Position{ line: 1, offset: 1 }]
+        );
+    }
+
+    #[test]
+    fn add_cursor_at_next_result_keeps_cursors_when_entering_insert_mode() {
+        let mut app = Application::new(&Vec::new()).unwrap();
+        let mut buffer = Buffer::new();
+        buffer.insert("amp editor\namp amp");
+        app.workspace.add_buffer(buffer);
+
+        commands::application::switch_to_search_mode(&mut app).unwrap();
+        if let Mode::Search(ref mut mode) = app.mode {
+            mode.input = Some(String::from("amp"));
+        }
+        commands::search::accept_query(&mut app).unwrap();
+        commands::search::add_cursor_at_next_result(&mut app).unwrap();
+        commands::application::switch_to_insert_mode(&mut app).unwrap();
+
+        // The guard on entering insert mode isn't an edit.
+        let buffer = app.workspace.current_buffer().unwrap();
+        let (id, primary) = (buffer.id, *buffer.cursor);
+        app.secondary_cursors.clear_if_stale(id, Some(primary));
+        assert_eq!(app.secondary_cursors.positions(id), &[Position{ line: 1, offset: 0 }]);
+    }
+
+    #[test]
+    fn select_next_result_groups_results_to_be_deleted_at_once() {
+        let mut app = Application::new(&Vec::new()).unwrap();
+        let mut buffer = Buffer::new();
+        buffer.insert("amp editor\namp amp");
+        app.workspace.add_buffer(buffer);
+
+        commands::application::switch_to_search_mode(&mut app).unwrap();
+        if let Mode::Search(ref mut mode) = app.mode {
+            mode.input = Some(String::from("amp"));
+        }
+        commands::search::accept_query(&mut app).unwrap();
+        commands::search::select_next_result(&mut app).unwrap();
+        if let Mode::Search(ref mode) = app.mode {
+            assert_eq!(mode.selected_results.len(), 2);
+        }
+        commands::selection::delete(&mut app).unwrap();
+
+        assert_eq!(app.workspace.current_buffer().unwrap().data(), "amp editor\n ");
+        assert_eq!(*app.workspace.current_buffer().unwrap().cursor,
+                   Position{ line: 1, offset: 0 });
+
+        // The deletion should be undone in a single step.
+        app.workspace.current_buffer().unwrap().undo();
+        assert_eq!(app.workspace.current_buffer().unwrap().data(), "amp editor\namp amp");
+    }
+
+    #[test]
+    fn select_all_results_groups_every_result_to_be_copied() {
+        let mut app = Application::new(&Vec::new()).unwrap();
+        let mut buffer = Buffer::new();
+        buffer.insert("amp editor\namp amp");
+        app.workspace.add_buffer(buffer);
+
+        commands::application::switch_to_search_mode(&mut app).unwrap();
+        if let Mode::Search(ref mut mode) = app.mode {
+            mode.input = Some(String::from("amp"));
+        }
+        commands::search::accept_query(&mut app).unwrap();
+        commands::search::select_all_results(&mut app).unwrap();
+        commands::selection::copy(&mut app).unwrap();
+
+        match *app.clipboard.get_content() {
+            ClipboardContent::Inline(ref content) => assert_eq!(content, "amp\namp\namp"),
+            _ => panic!("Clipboard content is not inline"),
+        }
+    }
 
     #[test]
     fn move_to_previous_result_moves_cursor_to_previous_result() {
@@ -253,6 +714,52 @@ mod tests {
                    });
     }
 
+    #[test]
+    fn move_to_next_result_reports_wrapping() {
+        let mut app = Application::new(&Vec::new()).unwrap();
+        let mut buffer = Buffer::new();
+        buffer.insert("amp editor\nedit");
+        app.workspace.add_buffer(buffer);
+
+        commands::application::switch_to_search_mode(&mut app).unwrap();
+        if let Mode::Search(ref mut mode) = app.mode {
+            mode.input = Some(String::from("ed"));
+        }
+        commands::search::accept_query(&mut app).unwrap();
+        commands::search::move_to_next_result(&mut app).unwrap();
+        assert!(app.message.is_none());
+
+        commands::search::move_to_next_result(&mut app).unwrap();
+        assert_eq!(app.message, Some(String::from("Search wrapped")));
+        assert_eq!(*app.workspace.current_buffer().unwrap().cursor,
+                   Position{ line: 0, offset: 4 });
+    }
+
+    #[test]
+    fn accept_query_in_reverse_moves_to_the_closest_result_before_the_cursor() {
+        let mut app = Application::new(&Vec::new()).unwrap();
+        let mut buffer = Buffer::new();
+        buffer.insert("amp editor\nedit\nedit");
+
+        // Start at a result, which shouldn't be selected itself.
+        buffer.cursor.move_to(Position{ line: 1, offset: 0 });
+        app.workspace.add_buffer(buffer);
+
+        app.search_query = Some(String::from("ed"));
+        commands::application::switch_to_search_mode(&mut app).unwrap();
+        commands::search::accept_query_in_reverse(&mut app).unwrap();
+        assert_eq!(*app.workspace.current_buffer().unwrap().cursor,
+                   Position{ line: 0, offset: 4 });
+        assert!(app.message.is_none());
+
+        // Repeating it from the first result wraps to the last.
+        commands::application::switch_to_search_mode(&mut app).unwrap();
+        commands::search::accept_query_in_reverse(&mut app).unwrap();
+        assert_eq!(*app.workspace.current_buffer().unwrap().cursor,
+                   Position{ line: 2, offset: 0 });
+        assert_eq!(app.message, Some(String::from("Search wrapped")));
+    }
+
     #[test]
     fn accept_query_disables_insert_sub_mode_and_moves_to_next_match() {
         let mut app = Application::new(&Vec::new()).unwrap();
@@ -285,4 +792,257 @@ mod tests {
                        offset: 0,
                    });
     }
+
+    #[test]
+    fn replace_current_replaces_selected_result_and_moves_to_next_match() {
+        let mut app = Application::new(&Vec::new()).unwrap();
+        let mut buffer = Buffer::new();
+        buffer.insert("amp ed\ned");
+        app.workspace.add_buffer(buffer);
+
+        app.search_query = Some(String::from("ed"));
+        app.replacement = Some(String::from("editor"));
+        commands::application::switch_to_search_mode(&mut app).unwrap();
+        commands::search::accept_query(&mut app).unwrap();
+        commands::search::replace_current(&mut app).unwrap();
+
+        assert_eq!(app.workspace.current_buffer().unwrap().data(), "amp editor\ned");
+        assert_eq!(*app.workspace.current_buffer().unwrap().cursor,
+                   Position {
+                       line: 1,
+                       offset: 0,
+                   });
+    }
+
+    #[test]
+    fn replace_all_handles_replacements_changing_text_length() {
+        let mut app = Application::new(&Vec::new()).unwrap();
+        let mut buffer = Buffer::new();
+        buffer.insert("amp ed ed\ned");
+        app.workspace.add_buffer(buffer);
+
+        app.search_query = Some(String::from("ed"));
+        app.replacement = Some(String::from("editor"));
+        commands::application::switch_to_search_mode(&mut app).unwrap();
+        commands::search::accept_query(&mut app).unwrap();
+        commands::search::replace_all(&mut app).unwrap();
+
+        assert_eq!(
+            app.workspace.current_buffer().unwrap().data(),
+            "amp editor editor\neditor"
+        );
+
+        // Ensure that the batch is reverted with a single undo.
+        commands::buffer::undo(&mut app).unwrap();
+        assert_eq!(app.workspace.current_buffer().unwrap().data(), "amp ed ed\ned");
+    }
+
+    #[test]
+    fn replace_all_succeeds_when_no_results_remain() {
+        let mut app = Application::new(&Vec::new()).unwrap();
+        let mut buffer = Buffer::new();
+        buffer.insert("amp ed");
+        app.workspace.add_buffer(buffer);
+
+        app.search_query = Some(String::from("ed"));
+        app.replacement = Some(String::from("buffer"));
+        commands::application::switch_to_search_mode(&mut app).unwrap();
+        commands::search::accept_query(&mut app).unwrap();
+        commands::search::replace_all(&mut app).unwrap();
+
+        assert_eq!(app.workspace.current_buffer().unwrap().data(), "amp buffer");
+    }
+
+    #[test]
+    fn toggle_regex_switches_matching_mode_for_subsequent_searches() {
+        let mut app = Application::new(&Vec::new()).unwrap();
+        let mut buffer = Buffer::new();
+        buffer.insert("amp 1\neditor 23");
+        app.workspace.add_buffer(buffer);
+
+        app.search_query = Some(String::from(r"\d+"));
+        commands::application::switch_to_search_mode(&mut app).unwrap();
+        commands::search::toggle_regex(&mut app).unwrap();
+
+        match app.mode {
+            Mode::Search(ref mode) => assert_eq!(mode.results.as_ref().unwrap().len(), 2),
+            _ => panic!("Not in search mode"),
+        }
+
+        // Ensure the setting is retained when re-entering search mode.
+        commands::application::switch_to_normal_mode(&mut app).unwrap();
+        commands::application::switch_to_search_mode(&mut app).unwrap();
+        assert!(match app.mode {
+            Mode::Search(ref mode) => mode.regex,
+            _ => false,
+        });
+    }
+
+    #[test]
+    fn replace_all_expands_regex_capture_groups() {
+        let mut app = Application::new(&Vec::new()).unwrap();
+        let mut buffer = Buffer::new();
+        buffer.insert("amp=1\neditor=23");
+        app.workspace.add_buffer(buffer);
+
+        app.search_query = Some(String::from(r"(\w+)=(\d+)"));
+        app.replacement = Some(String::from("$2=$1"));
+        commands::application::switch_to_search_mode(&mut app).unwrap();
+        commands::search::toggle_regex(&mut app).unwrap();
+        commands::search::replace_all(&mut app).unwrap();
+
+        assert_eq!(app.workspace.current_buffer().unwrap().data(), "1=amp\n23=editor");
+    }
+
+    #[test]
+    fn toggle_case_sensitivity_cycles_and_retains_the_setting() {
+        let mut app = Application::new(&Vec::new()).unwrap();
+        let mut buffer = Buffer::new();
+        buffer.insert("amp\nAmp");
+        app.workspace.add_buffer(buffer);
+
+        app.search_query = Some(String::from("amp"));
+        app.preferences.borrow_mut().set_search_case_sensitivity(CaseSensitivity::Smart);
+        commands::application::switch_to_search_mode(&mut app).unwrap();
+        commands::search::toggle_case_sensitivity(&mut app).unwrap();
+        commands::search::toggle_case_sensitivity(&mut app).unwrap();
+
+        match app.mode {
+            Mode::Search(ref mode) => {
+                assert_eq!(mode.case_sensitivity, CaseSensitivity::Sensitive);
+                assert_eq!(mode.results.as_ref().unwrap().len(), 1);
+            }
+            _ => panic!("Not in search mode"),
+        }
+
+        // Ensure the setting is retained when re-entering search mode.
+        commands::application::switch_to_normal_mode(&mut app).unwrap();
+        commands::application::switch_to_search_mode(&mut app).unwrap();
+        assert!(match app.mode {
+            Mode::Search(ref mode) => mode.case_sensitivity == CaseSensitivity::Sensitive,
+            _ => false,
+        });
+    }
+
+    #[test]
+    fn accept_query_adds_query_to_search_history() {
+        let mut app = Application::new(&Vec::new()).unwrap();
+        let mut buffer = Buffer::new();
+        buffer.insert("amp editor");
+        app.workspace.add_buffer(buffer);
+
+        app.search_query = Some(String::from("amp"));
+        commands::application::switch_to_search_mode(&mut app).unwrap();
+        commands::search::accept_query(&mut app).unwrap();
+        commands::application::switch_to_search_mode(&mut app).unwrap();
+        commands::search::accept_query(&mut app).unwrap();
+
+        assert_eq!(app.search_history.len(), 1);
+        assert_eq!(app.search_history.get(0), Some(&String::from("amp")));
+    }
+
+    #[test]
+    fn previous_and_next_query_cycle_through_search_history() {
+        let mut app = Application::new(&Vec::new()).unwrap();
+        let buffer = Buffer::new();
+        app.workspace.add_buffer(buffer);
+        app.search_history.push("amp").unwrap();
+        app.search_history.push("editor").unwrap();
+
+        commands::application::switch_to_search_mode(&mut app).unwrap();
+        commands::search::previous_query(&mut app).unwrap();
+        assert_eq!(app.search_query, Some(String::from("editor")));
+        commands::search::previous_query(&mut app).unwrap();
+        assert_eq!(app.search_query, Some(String::from("amp")));
+        assert!(commands::search::previous_query(&mut app).is_err());
+
+        commands::search::next_query(&mut app).unwrap();
+        assert_eq!(app.search_query, Some(String::from("editor")));
+        commands::search::next_query(&mut app).unwrap();
+        assert_eq!(app.search_query, None);
+        assert!(match app.mode {
+            Mode::Search(ref mode) => mode.input.is_none(),
+            _ => false,
+        });
+    }
+
+    #[test]
+    fn toggle_whole_word_refreshes_results() {
+        let mut app = Application::new(&Vec::new()).unwrap();
+        let mut buffer = Buffer::new();
+        buffer.insert("log\nlogin");
+        app.workspace.add_buffer(buffer);
+
+        app.search_query = Some(String::from("log"));
+        app.preferences.borrow_mut().set_search_whole_word(false);
+        commands::application::switch_to_search_mode(&mut app).unwrap();
+        commands::search::toggle_whole_word(&mut app).unwrap();
+
+        match app.mode {
+            Mode::Search(ref mode) => {
+                assert!(mode.whole_word);
+                assert_eq!(mode.results.as_ref().unwrap().len(), 1);
+            }
+            _ => panic!("Not in search mode"),
+        }
+    }
+
+    #[test]
+    fn push_search_char_previews_the_closest_result_below_the_cursor() {
+        let mut app = Application::new(&Vec::new()).unwrap();
+        let mut buffer = Buffer::new();
+        buffer.insert("amp editor\nedit\nedit");
+        buffer.cursor.move_to(Position{ line: 0, offset: 5 });
+        app.workspace.add_buffer(buffer);
+
+        commands::application::switch_to_search_mode(&mut app).unwrap();
+        commands::search::clear_query(&mut app).unwrap();
+        app.view.last_key = Some(Key::Char('e'));
+        commands::search::push_search_char(&mut app).unwrap();
+        app.view.last_key = Some(Key::Char('d'));
+        commands::search::push_search_char(&mut app).unwrap();
+
+        assert_eq!(*app.workspace.current_buffer().unwrap().cursor,
+                   Position {
+                       line: 1,
+                       offset: 0,
+                   });
+        match app.mode {
+            Mode::Search(ref mode) => assert_eq!(mode.results.as_ref().unwrap().len(), 3),
+            _ => panic!("Not in search mode"),
+        }
+
+        // Accepting the query keeps the previewed match.
+        commands::search::accept_query(&mut app).unwrap();
+        assert_eq!(*app.workspace.current_buffer().unwrap().cursor,
+                   Position {
+                       line: 1,
+                       offset: 0,
+                   });
+    }
+
+    #[test]
+    fn cancel_restores_the_original_cursor_position() {
+        let mut app = Application::new(&Vec::new()).unwrap();
+        let mut buffer = Buffer::new();
+        buffer.insert("amp editor\nedit\nedit");
+        buffer.cursor.move_to(Position{ line: 0, offset: 5 });
+        app.workspace.add_buffer(buffer);
+
+        commands::application::switch_to_search_mode(&mut app).unwrap();
+        commands::search::clear_query(&mut app).unwrap();
+        app.view.last_key = Some(Key::Char('e'));
+        commands::search::push_search_char(&mut app).unwrap();
+        commands::search::cancel(&mut app).unwrap();
+
+        assert_eq!(*app.workspace.current_buffer().unwrap().cursor,
+                   Position {
+                       line: 0,
+                       offset: 5,
+                   });
+        assert!(match app.mode {
+            Mode::Normal => true,
+            _ => false,
+        });
+    }
 }
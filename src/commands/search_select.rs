@@ -2,9 +2,11 @@ use crate::errors::*;
 use crate::commands::{self, application, Result};
 use crate::input::Key;
 use std::mem;
+use std::path::Path;
 use crate::models::application::modes::open::DisplayablePath;
 use crate::models::application::{Application, Mode};
 use crate::models::application::modes::SearchSelectMode;
+use crate::util;
 
 pub fn accept(app: &mut Application) -> Result {
     // Consume the application mode. This is necessary because the selection in
@@ -24,25 +26,18 @@ pub fn accept(app: &mut Application) -> Result {
                 .selection()
                 .ok_or("Couldn't find a selected path to open")?;
 
-            let syntax_reference =
-                app.preferences.borrow().syntax_definition_name(&path).and_then(|name| {
-                    app.workspace.syntax_set.find_syntax_by_name(&name).cloned()
-                });
-
-            app.workspace
-                .open_buffer(&path)
-                .chain_err(|| "Couldn't open a buffer for the specified path.")?;
-
-            let buffer = app.workspace.current_buffer().unwrap();
-
-            // Only override the default syntax definition if the user provided
-            // a valid one in their preferences.
-            if syntax_reference.is_some() {
-                buffer.syntax_reference = syntax_reference;
+            open_buffer(app, path)?;
+        },
+        Mode::BufferSwitcher(ref mut mode) => {
+            let id = mode.selection().ok_or("No buffer selected")?.id;
+            if !util::select_buffer(&mut app.workspace, id) {
+                bail!("Couldn't find the selected buffer");
             }
+        },
+        Mode::Recent(ref mut mode) => {
+            let path = mode.selection().ok_or("Couldn't find a selected path to open")?;
 
-            app.view.initialize_buffer(buffer)?;
-
+            open_buffer(app, Path::new(path))?;
         },
         Mode::Theme(ref mut mode) => {
             let theme_key = mode.selection().ok_or("No theme selected")?;
@@ -59,6 +54,17 @@ pub fn accept(app: &mut Application) -> Result {
                 bail!("Couldn't move to the selected symbol's position");
             }
         },
+        Mode::Spelling(ref mut mode) => {
+            let suggestion = mode.selection().ok_or("No suggestion selected")?;
+            util::ensure_writable(app)?;
+            let buffer = app.workspace.current_buffer().ok_or(BUFFER_MISSING)?;
+            buffer.start_operation_group();
+            buffer.delete_range(mode.range.clone());
+            buffer.cursor.move_to(mode.range.start());
+            buffer.insert(suggestion.as_str());
+            buffer.end_operation_group();
+            util::record_edit(app);
+        },
         Mode::Syntax(ref mut mode) => {
             let name = mode.selection().ok_or("No syntax selected")?;
             let syntax = app.workspace.syntax_set.find_syntax_by_name(name).cloned();
@@ -74,13 +80,45 @@ pub fn accept(app: &mut Application) -> Result {
     Ok(())
 }
 
+// Opens (or selects, if it's already open) a buffer for the path,
+// returning to where the cursor was left when it was last worked on.
+fn open_buffer(app: &mut Application, path: &Path) -> Result {
+    let syntax_reference =
+        app.preferences.borrow().syntax_definition_name(path).and_then(|name| {
+            app.workspace.syntax_set.find_syntax_by_name(&name).cloned()
+        });
+
+    let already_open = util::has_buffer_for_path(app, path)?;
+    app.workspace
+        .open_buffer(path)
+        .chain_err(|| "Couldn't open a buffer for the specified path.")?;
+
+    let buffer = app.workspace.current_buffer().unwrap();
+
+    // Only override the default syntax definition if the user provided
+    // a valid one in their preferences.
+    if syntax_reference.is_some() {
+        buffer.syntax_reference = syntax_reference;
+    }
+
+    app.view.initialize_buffer(buffer)?;
+    if !already_open {
+        util::restore_cursor_position(app)?;
+    }
+
+    util::record_recent_file(app)
+}
+
 pub fn search(app: &mut Application) -> Result {
     match app.mode {
         Mode::Command(ref mut mode) => mode.search(),
         Mode::Open(ref mut mode) => mode.search(),
         Mode::Theme(ref mut mode) => mode.search(),
+        Mode::BufferSwitcher(ref mut mode) => mode.search(),
+        Mode::Recent(ref mut mode) => mode.search(),
         Mode::SymbolJump(ref mut mode) => mode.search(),
         Mode::Syntax(ref mut mode) => mode.search(),
+        Mode::Spelling(ref mut mode) => mode.search(),
         _ => bail!("Can't search outside of search select mode."),
     };
 
@@ -92,8 +130,11 @@ pub fn select_next(app: &mut Application) -> Result {
         Mode::Command(ref mut mode) => mode.select_next(),
         Mode::Open(ref mut mode) => mode.select_next(),
         Mode::Theme(ref mut mode) => mode.select_next(),
+        Mode::BufferSwitcher(ref mut mode) => mode.select_next(),
+        Mode::Recent(ref mut mode) => mode.select_next(),
         Mode::SymbolJump(ref mut mode) => mode.select_next(),
         Mode::Syntax(ref mut mode) => mode.select_next(),
+        Mode::Spelling(ref mut mode) => mode.select_next(),
         _ => bail!("Can't change selection outside of search select mode."),
     }
 
@@ -105,8 +146,11 @@ pub fn select_previous(app: &mut Application) -> Result {
         Mode::Command(ref mut mode) => mode.select_previous(),
         Mode::Open(ref mut mode) => mode.select_previous(),
         Mode::Theme(ref mut mode) => mode.select_previous(),
+        Mode::BufferSwitcher(ref mut mode) => mode.select_previous(),
+        Mode::Recent(ref mut mode) => mode.select_previous(),
         Mode::SymbolJump(ref mut mode) => mode.select_previous(),
         Mode::Syntax(ref mut mode) => mode.select_previous(),
+        Mode::Spelling(ref mut mode) => mode.select_previous(),
         _ => bail!("Can't change selection outside of search select mode."),
     }
 
@@ -118,8 +162,11 @@ pub fn enable_insert(app: &mut Application) -> Result {
         Mode::Command(ref mut mode) => mode.set_insert_mode(true),
         Mode::Open(ref mut mode) => mode.set_insert_mode(true),
         Mode::Theme(ref mut mode) => mode.set_insert_mode(true),
+        Mode::BufferSwitcher(ref mut mode) => mode.set_insert_mode(true),
+        Mode::Recent(ref mut mode) => mode.set_insert_mode(true),
         Mode::SymbolJump(ref mut mode) => mode.set_insert_mode(true),
         Mode::Syntax(ref mut mode) => mode.set_insert_mode(true),
+        Mode::Spelling(ref mut mode) => mode.set_insert_mode(true),
         _ => bail!("Can't change search insert state outside of search select mode"),
     }
 
@@ -131,8 +178,11 @@ pub fn disable_insert(app: &mut Application) -> Result {
         Mode::Command(ref mut mode) => mode.set_insert_mode(false),
         Mode::Open(ref mut mode) => mode.set_insert_mode(false),
         Mode::Theme(ref mut mode) => mode.set_insert_mode(false),
+        Mode::BufferSwitcher(ref mut mode) => mode.set_insert_mode(false),
+        Mode::Recent(ref mut mode) => mode.set_insert_mode(false),
         Mode::SymbolJump(ref mut mode) => mode.set_insert_mode(false),
         Mode::Syntax(ref mut mode) => mode.set_insert_mode(false),
+        Mode::Spelling(ref mut mode) => mode.set_insert_mode(false),
         _ => bail!("Can't change search insert state outside of search select mode"),
     }
 
@@ -145,8 +195,11 @@ pub fn push_search_char(app: &mut Application) -> Result {
             Mode::Command(ref mut mode) => mode.push_search_char(c),
             Mode::Open(ref mut mode) => mode.push_search_char(c),
             Mode::Theme(ref mut mode) => mode.push_search_char(c),
+            Mode::BufferSwitcher(ref mut mode) => mode.push_search_char(c),
+            Mode::Recent(ref mut mode) => mode.push_search_char(c),
             Mode::SymbolJump(ref mut mode) => mode.push_search_char(c),
             Mode::Syntax(ref mut mode) => mode.push_search_char(c),
+            Mode::Spelling(ref mut mode) => mode.push_search_char(c),
             _ => bail!("Can't push search character outside of search select mode"),
         }
     }
@@ -160,8 +213,11 @@ pub fn pop_search_token(app: &mut Application) -> Result {
         Mode::Command(ref mut mode) => mode.pop_search_token(),
         Mode::Open(ref mut mode) => mode.pop_search_token(),
         Mode::Theme(ref mut mode) => mode.pop_search_token(),
+        Mode::BufferSwitcher(ref mut mode) => mode.pop_search_token(),
+        Mode::Recent(ref mut mode) => mode.pop_search_token(),
         Mode::SymbolJump(ref mut mode) => mode.pop_search_token(),
         Mode::Syntax(ref mut mode) => mode.pop_search_token(),
+        Mode::Spelling(ref mut mode) => mode.pop_search_token(),
         _ => bail!("Can't pop search token outside of search select mode"),
     }
 
@@ -174,8 +230,11 @@ pub fn step_back(app: &mut Application) -> Result {
         Mode::Command(ref mut mode) => mode.results().count(),
         Mode::Open(ref mut mode) => mode.results().count(),
         Mode::Theme(ref mut mode) => mode.results().count(),
+        Mode::BufferSwitcher(ref mut mode) => mode.results().count(),
+        Mode::Recent(ref mut mode) => mode.results().count(),
         Mode::SymbolJump(ref mut mode) => mode.results().count(),
         Mode::Syntax(ref mut mode) => mode.results().count(),
+        Mode::Spelling(ref mut mode) => mode.results().count(),
         _ => bail!("Can't pop search token outside of search select mode"),
     };
 
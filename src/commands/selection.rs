@@ -1,23 +1,84 @@
 use crate::models::application::{Application, ClipboardContent, Mode};
-use scribe::buffer::{LineRange, Range};
+use scribe::buffer::{LineRange, Position, Range};
 use super::application;
 use crate::errors::*;
+use crate::errors;
+use crate::input::Key;
 use crate::commands::{self, Result};
 use crate::util;
+use crate::util::delimiter;
+use crate::util::encoding;
+use crate::util::shell;
+use std::cmp::Ordering;
+use std::collections::HashSet;
+use unicode_segmentation::UnicodeSegmentation;
 
 pub fn delete(app: &mut Application) -> Result {
+    util::ensure_writable(app)?;
+    let buffer = app.workspace.current_buffer().ok_or(BUFFER_MISSING)?;
+    buffer.start_operation_group();
+    let result = delete_selection(app);
     if let Some(buffer) = app.workspace.current_buffer() {
+        buffer.end_operation_group();
+    }
+    if result.is_ok() {
+        util::record_edit(app);
+    }
+
+    result.map(|_| ())
+}
+
+/// Deletes the selected content without grouping the deletion as an
+/// operation of its own, so that callers can combine it with others.
+/// Returns the deleted ranges, in the order in which they were deleted.
+pub(crate) fn delete_selection(app: &mut Application) -> errors::Result<Vec<Range>> {
+    let deleted_ranges = if let Some(buffer) = app.workspace.current_buffer() {
         match app.mode {
             Mode::Select(ref select_mode) => {
                 let cursor_position = *buffer.cursor.clone();
                 let delete_range = Range::new(cursor_position, select_mode.anchor);
                 buffer.delete_range(delete_range.clone());
                 buffer.cursor.move_to(delete_range.start());
+
+                vec![delete_range]
+            }
+            Mode::SelectBlock(ref mode) => {
+                let delete_ranges = mode.to_ranges(&*buffer.cursor, &buffer.data());
+
+                // Rows are independent of one another, so
+                // there's no need to adjust subsequent ranges.
+                for delete_range in delete_ranges.iter() {
+                    buffer.delete_range(delete_range.clone());
+                }
+
+                if let Some(first_range) = delete_ranges.first() {
+                    buffer.cursor.move_to(first_range.start());
+                }
+
+                delete_ranges
             }
             Mode::SelectLine(ref mode) => {
                 let delete_range = mode.to_range(&*buffer.cursor);
                 buffer.delete_range(delete_range.clone());
                 buffer.cursor.move_to(delete_range.start());
+
+                vec![delete_range]
+            }
+            Mode::Search(ref mode) if !mode.selected_results.is_empty() => {
+                let mut delete_ranges = mode.selected_results.clone();
+                delete_ranges.sort_by_key(|range| (range.start().line, range.start().offset));
+
+                // Delete the ranges in reverse order, so that
+                // earlier deletions don't shift those pending.
+                for delete_range in delete_ranges.iter().rev() {
+                    buffer.delete_range(delete_range.clone());
+                }
+
+                if let Some(first_range) = delete_ranges.first() {
+                    buffer.cursor.move_to(first_range.start());
+                }
+
+                delete_ranges.into_iter().rev().collect()
             }
             Mode::Search(ref mode) => {
                 let selection = mode.results
@@ -25,14 +86,16 @@ pub fn delete(app: &mut Application) -> Result {
                     .and_then(|r| r.selection())
                     .ok_or("Can't delete in search mode without a selected result")?;
                 buffer.delete_range(selection.clone());
+
+                vec![selection.clone()]
             }
             _ => bail!("Can't delete selections outside of select mode"),
-        };
+        }
     } else {
         bail!(BUFFER_MISSING);
-    }
+    };
 
-    Ok(())
+    Ok(deleted_ranges)
 }
 
 pub fn copy_and_delete(app: &mut Application) -> Result {
@@ -80,6 +143,13 @@ fn copy_to_clipboard(app: &mut Application) -> Result {
                 .ok_or("Couldn't read selected data from buffer")?;
             app.clipboard.set_content(ClipboardContent::Inline(data))?;
         }
+        Mode::SelectBlock(ref mode) => {
+            let rows = mode.to_ranges(&*buffer.cursor, &buffer.data())
+                .iter()
+                .map(|range| buffer.read(range).unwrap_or_default())
+                .collect();
+            app.clipboard.set_content(ClipboardContent::Rectangular(rows))?;
+        }
         Mode::SelectLine(ref mode) => {
             let selected_range = util::inclusive_range(
                 &LineRange::new(mode.anchor, buffer.cursor.line),
@@ -90,6 +160,29 @@ fn copy_to_clipboard(app: &mut Application) -> Result {
                 .ok_or("Couldn't read selected data from buffer")?;
             app.clipboard.set_content(ClipboardContent::Block(data))?;
         }
+        Mode::Search(ref mode) => {
+            // Grouped results are copied one per line, in buffer order.
+            let mut selected_ranges = if mode.selected_results.is_empty() {
+                mode.results
+                    .as_ref()
+                    .and_then(|r| r.selection())
+                    .into_iter()
+                    .cloned()
+                    .collect()
+            } else {
+                mode.selected_results.clone()
+            };
+            if selected_ranges.is_empty() {
+                bail!("Can't copy in search mode without a selected result");
+            }
+            selected_ranges.sort_by_key(|range| (range.start().line, range.start().offset));
+
+            let data = selected_ranges.iter()
+                .map(|range| buffer.read(range).unwrap_or_default())
+                .collect::<Vec<String>>()
+                .join("\n");
+            app.clipboard.set_content(ClipboardContent::Inline(data))?;
+        }
         _ => bail!("Can't copy data to clipboard outside of select modes"),
     };
 
@@ -97,6 +190,23 @@ fn copy_to_clipboard(app: &mut Application) -> Result {
 }
 
 pub fn sort_lines(app: &mut Application) -> Result {
+    sort_selected_lines(app, |a, b| a.cmp(b))
+}
+
+pub fn sort_lines_descending(app: &mut Application) -> Result {
+    sort_selected_lines(app, |a, b| b.cmp(a))
+}
+
+pub fn sort_lines_case_insensitive(app: &mut Application) -> Result {
+    sort_selected_lines(app, |a, b| a.to_lowercase().cmp(&b.to_lowercase()))
+}
+
+/// Sorts the lines covered by the current line selection using the
+/// provided comparison, as a single operation, and returns to normal mode.
+fn sort_selected_lines<F>(app: &mut Application, compare: F) -> Result
+    where F: FnMut(&&str, &&str) -> Ordering
+{
+    util::ensure_writable(app)?;
     let buffer = app.workspace.current_buffer().ok_or(BUFFER_MISSING)?;
 
     let line_range = match app.mode {
@@ -110,22 +220,614 @@ pub fn sort_lines(app: &mut Application) -> Result {
     };
 
     // A temporary is needed to satisfy the lifetime checker
-    let lines = buffer.read(&line_range)
+    let content = buffer.read(&line_range)
         .ok_or("Couldn't read lines to sort from buffer")?;
 
-    let mut lines: Vec<&str> = lines
+    let mut lines: Vec<&str> = content
         .split_terminator('\n')
         .collect();
 
-    lines.sort();
+    lines.sort_by(compare);
     let mut lines = lines.join("\n");
     lines.push('\n'); // Add final newline again
 
+    // Leave the buffer untouched if the lines are already sorted.
+    if lines != content {
+        buffer.start_operation_group();
+        buffer.delete_range(line_range.clone());
+        buffer.cursor.move_to(line_range.start());
+        buffer.insert(lines);
+        buffer.end_operation_group();
+        util::record_edit(app);
+    }
+
+    application::switch_to_normal_mode(app)
+}
+
+pub fn deduplicate_lines(app: &mut Application) -> Result {
+    remove_duplicate_lines(app, true)
+}
+
+pub fn deduplicate_all_lines(app: &mut Application) -> Result {
+    remove_duplicate_lines(app, false)
+}
+
+/// Removes duplicate lines from the current line selection, keeping the first
+/// occurrence of each. Like `uniq`, only adjacent duplicates are removed when
+/// `adjacent_only` is set; otherwise, duplicates are removed regardless of
+/// where they appear in the selection.
+fn remove_duplicate_lines(app: &mut Application, adjacent_only: bool) -> Result {
+    util::ensure_writable(app)?;
+    let buffer = app.workspace.current_buffer().ok_or(BUFFER_MISSING)?;
+
+    let line_range = match app.mode {
+        Mode::SelectLine(ref mode) => {
+            util::inclusive_range(
+                &LineRange::new(mode.anchor, buffer.cursor.line),
+                buffer
+            )
+        },
+        _ => bail!("Can't deduplicate lines outside of select line mode"),
+    };
+
+    // A temporary is needed to satisfy the lifetime checker
+    let content = buffer.read(&line_range)
+        .ok_or("Couldn't read lines to deduplicate from buffer")?;
+
+    let mut unique_lines: Vec<&str> = Vec::new();
+    let mut seen_lines = HashSet::new();
+    for line in content.split_terminator('\n') {
+        let duplicate = if adjacent_only {
+            unique_lines.last() == Some(&line)
+        } else {
+            !seen_lines.insert(line)
+        };
+
+        if !duplicate {
+            unique_lines.push(line);
+        }
+    }
+
+    let mut lines = unique_lines.join("\n");
+    lines.push('\n'); // Add final newline again
+
+    // Leave the buffer untouched if there weren't any duplicates.
+    if lines != content {
+        buffer.start_operation_group();
+        buffer.delete_range(line_range.clone());
+        buffer.cursor.move_to(line_range.start());
+        buffer.insert(lines);
+        buffer.end_operation_group();
+        util::record_edit(app);
+    }
+
+    application::switch_to_normal_mode(app)
+}
+
+/// Prefixes each selected line with a sequential number, formatted according
+/// to the line number format preference. If every selected line has already
+/// been numbered using that format, the existing numbers are replaced.
+pub fn number_lines(app: &mut Application) -> Result {
+    util::ensure_writable(app)?;
+    let format = LineNumberFormat::parse(app.preferences.borrow().line_number_format())
+        .ok_or("Line number format must start with a number")?;
+    let buffer = app.workspace.current_buffer().ok_or(BUFFER_MISSING)?;
+
+    let line_range = match app.mode {
+        Mode::SelectLine(ref mode) => {
+            util::inclusive_range(
+                &LineRange::new(mode.anchor, buffer.cursor.line),
+                buffer
+            )
+        },
+        _ => bail!("Can't number lines outside of select line mode"),
+    };
+
+    let content = buffer.read(&line_range)
+        .ok_or("Couldn't read lines to number from buffer")?;
+    let lines: Vec<&str> = content.split_terminator('\n').collect();
+    let renumbering = lines.iter().all(|line| format.strip(line).is_some());
+
+    let mut numbered_lines = lines.iter()
+        .enumerate()
+        .map(|(index, line)| {
+            let line = if renumbering {
+                format.strip(line).unwrap_or(line)
+            } else {
+                line
+            };
+
+            format!("{}{}", format.prefix(format.start + index), line)
+        })
+        .collect::<Vec<String>>()
+        .join("\n");
+
+    // Re-add the trailing newline if we've consumed one.
+    if content.ends_with('\n') {
+        numbered_lines.push('\n');
+    }
+
+    let original_cursor_position = *buffer.cursor.clone();
+    buffer.start_operation_group();
+    buffer.delete_range(line_range.clone());
+    buffer.cursor.move_to(line_range.start());
+    buffer.insert(numbered_lines);
+    buffer.cursor.move_to(original_cursor_position);
+    buffer.end_operation_group();
+    util::record_edit(app);
+
+    application::switch_to_normal_mode(app)
+}
+
+/// The prefix added to lines by `number_lines`, derived from a sample of the
+/// first one (e.g. `1. `): its number is where numbering starts, leading
+/// spaces or zeros set the width to which numbers are padded, and whatever
+/// follows the number is added after each one.
+struct LineNumberFormat {
+    start: usize,
+    width: usize,
+    zero_padded: bool,
+    suffix: String,
+}
+
+impl LineNumberFormat {
+    fn parse(sample: &str) -> Option<LineNumberFormat> {
+        let unpadded_sample = sample.trim_start_matches(' ');
+        let padding = sample.len() - unpadded_sample.len();
+        let digit_count = unpadded_sample.chars()
+            .take_while(|character| character.is_ascii_digit())
+            .count();
+        let number = &unpadded_sample[..digit_count];
+
+        Some(LineNumberFormat {
+            start: number.parse().ok()?,
+            width: padding + digit_count,
+            zero_padded: padding == 0 && digit_count > 1 && number.starts_with('0'),
+            suffix: unpadded_sample[digit_count..].to_string(),
+        })
+    }
+
+    fn prefix(&self, number: usize) -> String {
+        if self.zero_padded {
+            format!("{:0width$}{}", number, self.suffix, width = self.width)
+        } else {
+            format!("{:>width$}{}", number, self.suffix, width = self.width)
+        }
+    }
+
+    /// Returns the line without its number, if it's been numbered using this format.
+    fn strip<'a>(&self, line: &'a str) -> Option<&'a str> {
+        let unpadded_line = line.trim_start_matches(' ');
+        let digit_count = unpadded_line.chars()
+            .take_while(|character| character.is_ascii_digit())
+            .count();
+        let remainder = &unpadded_line[digit_count..];
+
+        if digit_count > 0 && remainder.starts_with(self.suffix.as_str()) {
+            Some(&remainder[self.suffix.len()..])
+        } else {
+            None
+        }
+    }
+}
+
+pub fn indent(app: &mut Application) -> Result {
+    match app.mode {
+        Mode::SelectLine(_) => commands::buffer::indent_line(app),
+        _ => bail!("Can't indent selections outside of select line mode"),
+    }
+}
+
+pub fn outdent(app: &mut Application) -> Result {
+    match app.mode {
+        Mode::SelectLine(_) => commands::buffer::outdent_line(app),
+        _ => bail!("Can't outdent selections outside of select line mode"),
+    }
+}
+
+pub fn toggle_comment(app: &mut Application) -> Result {
+    match app.mode {
+        Mode::SelectLine(_) => commands::buffer::toggle_line_comment(app),
+        _ => bail!("Can't toggle comments outside of select line mode"),
+    }
+}
+
+pub fn join_lines(app: &mut Application) -> Result {
+    util::ensure_writable(app)?;
+    let buffer = app.workspace.current_buffer().ok_or(BUFFER_MISSING)?;
+
+    let (first_line, mut last_line) = match app.mode {
+        Mode::SelectLine(ref mode) => {
+            if mode.anchor >= buffer.cursor.line {
+                (buffer.cursor.line, mode.anchor)
+            } else {
+                (mode.anchor, buffer.cursor.line)
+            }
+        },
+        _ => bail!("Can't join lines outside of select line mode"),
+    };
+
+    // A single selected line is joined with the one below it.
+    if first_line == last_line {
+        last_line += 1;
+    }
+    if last_line >= buffer.line_count() {
+        bail!("No line below current line");
+    }
+
+    let line_range = util::inclusive_range(
+        &LineRange::new(first_line, last_line),
+        buffer
+    );
+    let content = buffer.read(&line_range)
+        .ok_or("Couldn't read lines to join from buffer")?;
+
+    // Collapse the lines, separating them with a single space
+    // and tracking the offset of the last join for the cursor.
+    let mut lines = content.split_terminator('\n');
+    let mut joined_lines = lines.next().unwrap_or("").to_string();
+    let mut join_offset = 0;
+    for line in lines {
+        join_offset = joined_lines.graphemes(true).count();
+
+        let trimmed_line = line.trim_start();
+        if !trimmed_line.is_empty() {
+            joined_lines.push(' ');
+            joined_lines.push_str(trimmed_line);
+        }
+    }
+
+    // Re-add the trailing newline if we've consumed one.
+    if content.ends_with('\n') {
+        joined_lines.push('\n');
+    }
+
     buffer.start_operation_group();
     buffer.delete_range(line_range.clone());
     buffer.cursor.move_to(line_range.start());
-    buffer.insert(lines);
+    buffer.insert(joined_lines);
+    buffer.cursor.move_to(Position {
+        line: first_line,
+        offset: join_offset,
+    });
+    buffer.end_operation_group();
+    util::record_edit(app);
+
+    application::switch_to_normal_mode(app)
+}
+
+pub fn hard_wrap(app: &mut Application) -> Result {
+    util::ensure_writable(app)?;
+    let column = app.preferences.borrow().hard_wrap_column();
+    let buffer = app.workspace.current_buffer().ok_or(BUFFER_MISSING)?;
+
+    let line_range = match app.mode {
+        Mode::SelectLine(ref mode) => {
+            util::inclusive_range(
+                &LineRange::new(mode.anchor, buffer.cursor.line),
+                buffer
+            )
+        },
+        _ => bail!("Can't wrap lines outside of select line mode"),
+    };
+
+    let content = buffer.read(&line_range)
+        .ok_or("Couldn't read selected lines from buffer")?;
+
+    // Reflow each paragraph independently, leaving the
+    // blank lines that separate them where they are.
+    let mut wrapped_lines = Vec::new();
+    let mut paragraph = Vec::new();
+    for line in content.split_terminator('\n') {
+        if line.trim().is_empty() {
+            wrapped_lines.extend(wrap_paragraph(&paragraph, column));
+            wrapped_lines.push(line.to_string());
+            paragraph.clear();
+        } else {
+            paragraph.push(line);
+        }
+    }
+    wrapped_lines.extend(wrap_paragraph(&paragraph, column));
+
+    let mut wrapped_content = wrapped_lines.join("\n");
+
+    // Re-add the trailing newline if we've consumed one.
+    if content.ends_with('\n') {
+        wrapped_content.push('\n');
+    }
+
+    // Leave the buffer untouched if the lines are already wrapped.
+    if wrapped_content != content {
+        buffer.start_operation_group();
+        buffer.delete_range(line_range.clone());
+        buffer.cursor.move_to(line_range.start());
+        buffer.insert(wrapped_content);
+        buffer.end_operation_group();
+        util::record_edit(app);
+    }
+
+    application::switch_to_normal_mode(app)
+}
+
+/// Greedily fills lines with the paragraph's words, up to the specified column,
+/// carrying over the indentation of its first line. Words that are too long to
+/// fit on a line of their own are left unbroken.
+fn wrap_paragraph(lines: &[&str], column: usize) -> Vec<String> {
+    let first_line = match lines.first() {
+        Some(line) => line,
+        None => return Vec::new(),
+    };
+    let indentation: String = first_line.chars()
+        .take_while(|character| character.is_whitespace())
+        .collect();
+    let indentation_width = indentation.graphemes(true).count();
+
+    let mut wrapped_lines = Vec::new();
+    let mut current_line = indentation.clone();
+    let mut current_width = indentation_width;
+    for word in lines.iter().flat_map(|line| line.split_whitespace()) {
+        let word_width = word.graphemes(true).count();
+
+        if current_width == indentation_width {
+            current_line.push_str(word);
+            current_width += word_width;
+        } else if current_width + 1 + word_width <= column {
+            current_line.push(' ');
+            current_line.push_str(word);
+            current_width += 1 + word_width;
+        } else {
+            wrapped_lines.push(current_line);
+            current_line = format!("{}{}", indentation, word);
+            current_width = indentation_width + word_width;
+        }
+    }
+    wrapped_lines.push(current_line);
+
+    wrapped_lines
+}
+
+/// Wraps the selection captured by surround mode with
+/// the delimiter pair named by the last key press.
+pub fn surround(app: &mut Application) -> Result {
+    util::ensure_writable(app)?;
+    let (opener, closer) = match *app.view.last_key() {
+        Some(Key::Char(c)) => delimiter::delimiter_pair(c),
+        _ => bail!("Last key press wasn't a character"),
+    };
+    let range = match app.mode {
+        Mode::Surround(ref mode) => mode.range.clone(),
+        _ => bail!("Can't surround content outside of surround mode"),
+    };
+    let buffer = app.workspace.current_buffer().ok_or(BUFFER_MISSING)?;
+
+    // Insert the closer first, so that the opener
+    // doesn't shift the position at which it belongs.
+    buffer.start_operation_group();
+    buffer.cursor.move_to(range.end());
+    buffer.insert(closer.to_string());
+    buffer.cursor.move_to(range.start());
+    buffer.insert(opener.to_string());
     buffer.end_operation_group();
+    util::record_edit(app);
+
+    application::switch_to_normal_mode(app)
+}
+
+/// Pads the lines captured by align mode so that the first occurrence of the
+/// delimiter named by the last key press lines up across all of them.
+/// Lines without the delimiter are left unchanged.
+pub fn align_on(app: &mut Application) -> Result {
+    util::ensure_writable(app)?;
+    let delimiter = match *app.view.last_key() {
+        Some(Key::Char(c)) => c,
+        _ => bail!("Last key press wasn't a character"),
+    };
+    let range = match app.mode {
+        Mode::Align(ref mode) => mode.range.clone(),
+        _ => bail!("Can't align lines outside of align mode"),
+    };
+    let buffer = app.workspace.current_buffer().ok_or(BUFFER_MISSING)?;
+
+    let content = buffer.read(&range)
+        .ok_or("Couldn't read lines to align from buffer")?;
+    let lines: Vec<&str> = content.split_terminator('\n').collect();
+
+    // Delimiter columns are measured in graphemes, to match cursor offsets.
+    let columns: Vec<Option<usize>> = lines.iter()
+        .map(|line| line.find(delimiter).map(|index| line[..index].graphemes(true).count()))
+        .collect();
+    let alignment_column = match columns.iter().filter_map(|&column| column).max() {
+        Some(column) => column,
+        None => return application::switch_to_normal_mode(app),
+    };
+
+    let mut aligned_lines = lines.iter()
+        .zip(columns.iter())
+        .map(|(line, column)| match *column {
+            Some(column) => {
+                let index = line.find(delimiter).unwrap_or(0);
+                format!("{}{}{}", &line[..index], " ".repeat(alignment_column - column), &line[index..])
+            },
+            None => line.to_string(),
+        })
+        .collect::<Vec<String>>()
+        .join("\n");
+
+    // Re-add the trailing newline if we've consumed one.
+    if content.ends_with('\n') {
+        aligned_lines.push('\n');
+    }
+
+    // Leave the buffer untouched if the lines are already aligned.
+    if aligned_lines != content {
+        buffer.start_operation_group();
+        buffer.delete_range(range.clone());
+        buffer.cursor.move_to(range.start());
+        buffer.insert(aligned_lines);
+        buffer.end_operation_group();
+        util::record_edit(app);
+    }
+
+    application::switch_to_normal_mode(app)
+}
+
+/// Pipes the selection captured by filter mode through the shell command
+/// that was entered, replacing it with the command's output. If the command
+/// fails, the buffer is left untouched and filter mode remains active,
+/// so that the command can be corrected.
+pub fn filter_through_command(app: &mut Application) -> Result {
+    util::ensure_writable(app)?;
+    let (command, range) = match app.mode {
+        Mode::Filter(ref mode) => (mode.input.clone(), mode.range.clone()),
+        _ => bail!("Can't filter content outside of filter mode"),
+    };
+    if command.trim().is_empty() {
+        bail!("Please provide a command to filter through");
+    }
+    let workspace_path = app.workspace.path.clone();
+    let buffer = app.workspace.current_buffer().ok_or(BUFFER_MISSING)?;
+
+    let content = buffer.read(&range)
+        .ok_or("Couldn't read selected data from buffer")?;
+    let output = shell::filter(&command, &content, &workspace_path)?;
+
+    // Leave the buffer untouched if the command didn't change anything.
+    if output != content {
+        buffer.start_operation_group();
+        buffer.delete_range(range.clone());
+        buffer.cursor.move_to(range.start());
+        buffer.insert(output);
+        buffer.end_operation_group();
+        util::record_edit(app);
+    }
+
+    application::switch_to_normal_mode(app)
+}
+
+pub fn uppercase(app: &mut Application) -> Result {
+    transform_selection(app, |content| content.to_uppercase())
+}
+
+pub fn lowercase(app: &mut Application) -> Result {
+    transform_selection(app, |content| content.to_lowercase())
+}
+
+pub fn swap_case(app: &mut Application) -> Result {
+    transform_selection(app, |content| util::swap_case(content))
+}
+
+/// Removes leading and trailing whitespace from each selected line or,
+/// in select mode, from the selection as a whole.
+pub fn strip_whitespace(app: &mut Application) -> Result {
+    if let Mode::SelectLine(_) = app.mode {
+        transform_selection(app, |content| map_lines(content, |line| line.trim().to_string()))
+    } else {
+        transform_selection(app, |content| content.trim().to_string())
+    }
+}
+
+/// Collapses runs of whitespace within each selected line to a single
+/// space, leaving indentation and trailing whitespace untouched.
+pub fn collapse_whitespace(app: &mut Application) -> Result {
+    transform_selection(app, |content| {
+        map_lines(content, |line| {
+            let content_start = line.len() - line.trim_start().len();
+            let content_end = line.trim_end().len().max(content_start);
+            let collapsed: Vec<&str> = line[content_start..content_end]
+                .split_whitespace()
+                .collect();
+
+            format!("{}{}{}", &line[..content_start], collapsed.join(" "), &line[content_end..])
+        })
+    })
+}
+
+/// Applies the provided transformation to each line, preserving the newline
+/// characters between them (including a trailing one, if present).
+fn map_lines<F>(content: &str, transform: F) -> String
+    where F: Fn(&str) -> String
+{
+    let mut lines = content.split_terminator('\n')
+        .map(transform)
+        .collect::<Vec<String>>()
+        .join("\n");
+
+    // Re-add the trailing newline if we've consumed one.
+    if content.ends_with('\n') {
+        lines.push('\n');
+    }
+
+    lines
+}
+
+pub fn encode_base64(app: &mut Application) -> Result {
+    transform_selection(app, encoding::base64_encode)
+}
+
+pub fn decode_base64(app: &mut Application) -> Result {
+    try_transform_selection(app, encoding::base64_decode)
+}
+
+pub fn encode_url(app: &mut Application) -> Result {
+    transform_selection(app, encoding::url_encode)
+}
+
+pub fn decode_url(app: &mut Application) -> Result {
+    try_transform_selection(app, encoding::url_decode)
+}
+
+pub fn encode_hex(app: &mut Application) -> Result {
+    transform_selection(app, encoding::hex_encode)
+}
+
+pub fn decode_hex(app: &mut Application) -> Result {
+    try_transform_selection(app, encoding::hex_decode)
+}
+
+/// Replaces the selected content with the result of the provided
+/// transformation, as a single operation, and returns to normal mode.
+fn transform_selection<F>(app: &mut Application, transform: F) -> Result
+    where F: Fn(&str) -> String
+{
+    try_transform_selection(app, |content| Ok(transform(content)))
+}
+
+/// Like `transform_selection`, but for transformations that can fail,
+/// in which case the buffer is left untouched and the error is returned.
+fn try_transform_selection<F>(app: &mut Application, transform: F) -> Result
+    where F: Fn(&str) -> errors::Result<String>
+{
+    util::ensure_writable(app)?;
+    let buffer = app.workspace.current_buffer().ok_or(BUFFER_MISSING)?;
+
+    let selected_range = match app.mode {
+        Mode::Select(ref mode) => Range::new(*buffer.cursor, mode.anchor),
+        Mode::SelectLine(ref mode) => {
+            util::inclusive_range(
+                &LineRange::new(mode.anchor, buffer.cursor.line),
+                buffer
+            )
+        },
+        _ => bail!("Can't transform selections outside of select modes"),
+    };
+
+    let content = buffer.read(&selected_range)
+        .ok_or("Couldn't read selected data from buffer")?;
+
+    // Leave the buffer untouched if there's nothing to
+    // transform, or the transformation doesn't change it.
+    if !content.is_empty() {
+        let transformed_content = transform(&content)?;
+
+        if transformed_content != content {
+            buffer.start_operation_group();
+            buffer.delete_range(selected_range.clone());
+            buffer.cursor.move_to(selected_range.start());
+            buffer.insert(transformed_content);
+            buffer.end_operation_group();
+            util::record_edit(app);
+        }
+    }
 
     application::switch_to_normal_mode(app)
 }
@@ -133,7 +835,9 @@ pub fn sort_lines(app: &mut Application) -> Result {
 #[cfg(test)]
 mod tests {
     use crate::commands;
-    use crate::models::application::{Application, Mode};
+    use crate::input::Key;
+    use crate::models::application::{Application, ClipboardContent, Mode, Preferences};
+    use crate::yaml::yaml::YamlLoader;
     use scribe::Buffer;
     use scribe::buffer::Position;
 
@@ -249,4 +953,730 @@ mod tests {
             String::from("amp\nitor\nbuffer")
         )
     }
+
+    #[test]
+    fn uppercase_converts_the_selection_and_restores_cursor_to_its_start() {
+        let mut app = Application::new(&Vec::new()).unwrap();
+        let mut buffer = Buffer::new();
+        buffer.insert("amp editor");
+        app.workspace.add_buffer(buffer);
+
+        // Select "amp" and convert it.
+        commands::application::switch_to_select_mode(&mut app).unwrap();
+        app.workspace.current_buffer().unwrap().cursor.move_to(Position {
+            line: 0,
+            offset: 3,
+        });
+        commands::selection::uppercase(&mut app).unwrap();
+
+        assert_eq!(
+            app.workspace.current_buffer().unwrap().data(),
+            String::from("AMP editor")
+        );
+        assert_eq!(
+            *app.workspace.current_buffer().unwrap().cursor,
+            Position { line: 0, offset: 0 }
+        );
+        assert!(match app.mode {
+            Mode::Normal => true,
+            _ => false,
+        });
+    }
+
+    #[test]
+    fn lowercase_converts_the_selected_lines_in_select_line_mode() {
+        let mut app = Application::new(&Vec::new()).unwrap();
+        let mut buffer = Buffer::new();
+        buffer.insert("AMP\nEDITOR\nBUFFER");
+        buffer.cursor.move_to(Position {
+            line: 1,
+            offset: 0,
+        });
+        app.workspace.add_buffer(buffer);
+
+        commands::application::switch_to_select_line_mode(&mut app).unwrap();
+        commands::selection::lowercase(&mut app).unwrap();
+
+        assert_eq!(
+            app.workspace.current_buffer().unwrap().data(),
+            String::from("AMP\neditor\nBUFFER")
+        );
+    }
+
+    #[test]
+    fn swap_case_handles_non_ascii_characters() {
+        let mut app = Application::new(&Vec::new()).unwrap();
+        let mut buffer = Buffer::new();
+        buffer.insert("Ämp ÉDITÖR\n");
+        app.workspace.add_buffer(buffer);
+
+        commands::application::switch_to_select_line_mode(&mut app).unwrap();
+        commands::selection::swap_case(&mut app).unwrap();
+
+        assert_eq!(
+            app.workspace.current_buffer().unwrap().data(),
+            String::from("äMP éditör\n")
+        );
+    }
+
+    #[test]
+    fn swap_case_does_nothing_with_an_empty_selection() {
+        let mut app = Application::new(&Vec::new()).unwrap();
+        let mut buffer = Buffer::new();
+        buffer.insert("amp");
+        app.workspace.add_buffer(buffer);
+
+        commands::application::switch_to_select_mode(&mut app).unwrap();
+        commands::selection::swap_case(&mut app).unwrap();
+
+        assert_eq!(
+            app.workspace.current_buffer().unwrap().data(),
+            String::from("amp")
+        );
+    }
+
+    #[test]
+    fn join_lines_collapses_the_selected_lines_and_trims_their_indentation() {
+        let mut app = Application::new(&Vec::new()).unwrap();
+        let mut buffer = Buffer::new();
+        buffer.insert("amp\n  editor\n    buffer\nsyntax\n");
+        app.workspace.add_buffer(buffer);
+
+        // Select the first three lines and join them.
+        commands::application::switch_to_select_line_mode(&mut app).unwrap();
+        app.workspace.current_buffer().unwrap().cursor.move_to(Position {
+            line: 2,
+            offset: 0,
+        });
+        commands::selection::join_lines(&mut app).unwrap();
+
+        assert_eq!(
+            app.workspace.current_buffer().unwrap().data(),
+            String::from("amp editor buffer\nsyntax\n")
+        );
+        assert_eq!(
+            *app.workspace.current_buffer().unwrap().cursor,
+            Position { line: 0, offset: 10 }
+        );
+        assert!(match app.mode {
+            Mode::Normal => true,
+            _ => false,
+        });
+    }
+
+    #[test]
+    fn hard_wrap_reflows_paragraphs_to_the_configured_column() {
+        let mut app = Application::new(&Vec::new()).unwrap();
+        let data = YamlLoader::load_from_str("hard_wrap_column: 10").unwrap();
+        *app.preferences.borrow_mut() = Preferences::new(data.into_iter().nth(0));
+        let mut buffer = Buffer::new();
+        buffer.insert("  amp is a text\neditor\n\nscribe-powered buffers\n");
+        app.workspace.add_buffer(buffer);
+
+        commands::application::switch_to_select_line_mode(&mut app).unwrap();
+        app.workspace.current_buffer().unwrap().cursor.move_to(Position {
+            line: 3,
+            offset: 0,
+        });
+        commands::selection::hard_wrap(&mut app).unwrap();
+
+        assert_eq!(
+            app.workspace.current_buffer().unwrap().data(),
+            String::from("  amp is a\n  text\n  editor\n\nscribe-powered\nbuffers\n")
+        );
+    }
+
+    #[test]
+    fn hard_wrap_can_be_undone_in_a_single_step() {
+        let mut app = Application::new(&Vec::new()).unwrap();
+        let data = YamlLoader::load_from_str("hard_wrap_column: 5").unwrap();
+        *app.preferences.borrow_mut() = Preferences::new(data.into_iter().nth(0));
+        let mut buffer = Buffer::new();
+        buffer.insert("amp text editor");
+        app.workspace.add_buffer(buffer);
+
+        commands::application::switch_to_select_line_mode(&mut app).unwrap();
+        commands::selection::hard_wrap(&mut app).unwrap();
+        assert_eq!(
+            app.workspace.current_buffer().unwrap().data(),
+            String::from("amp\ntext\neditor")
+        );
+
+        app.workspace.current_buffer().unwrap().undo();
+        assert_eq!(
+            app.workspace.current_buffer().unwrap().data(),
+            String::from("amp text editor")
+        );
+    }
+
+    #[test]
+    fn surround_wraps_the_selection_with_a_matching_pair() {
+        let mut app = Application::new(&Vec::new()).unwrap();
+        let mut buffer = Buffer::new();
+        buffer.insert("amp editor");
+        app.workspace.add_buffer(buffer);
+
+        commands::application::switch_to_select_mode(&mut app).unwrap();
+        app.workspace.current_buffer().unwrap().cursor.move_to(Position {
+            line: 0,
+            offset: 3,
+        });
+        commands::application::switch_to_surround_mode(&mut app).unwrap();
+        app.view.last_key = Some(Key::Char(')'));
+        commands::selection::surround(&mut app).unwrap();
+
+        assert_eq!(app.workspace.current_buffer().unwrap().data(), "(amp) editor");
+        assert!(match app.mode {
+            Mode::Normal => true,
+            _ => false,
+        });
+
+        // The surrounding should be undone in a single step.
+        app.workspace.current_buffer().unwrap().undo();
+        assert_eq!(app.workspace.current_buffer().unwrap().data(), "amp editor");
+    }
+
+    #[test]
+    fn surround_uses_other_characters_on_both_sides() {
+        let mut app = Application::new(&Vec::new()).unwrap();
+        let mut buffer = Buffer::new();
+        buffer.insert("amp editor");
+        buffer.cursor.move_to(Position { line: 0, offset: 4 });
+        app.workspace.add_buffer(buffer);
+
+        commands::application::switch_to_select_mode(&mut app).unwrap();
+        app.workspace.current_buffer().unwrap().cursor.move_to(Position {
+            line: 0,
+            offset: 10,
+        });
+        commands::application::switch_to_surround_mode(&mut app).unwrap();
+        app.view.last_key = Some(Key::Char('"'));
+        commands::selection::surround(&mut app).unwrap();
+
+        assert_eq!(app.workspace.current_buffer().unwrap().data(), "amp \"editor\"");
+    }
+
+    #[test]
+    fn join_lines_joins_a_single_selected_line_with_the_next() {
+        let mut app = Application::new(&Vec::new()).unwrap();
+        let mut buffer = Buffer::new();
+        buffer.insert("amp\n  editor\nbuffer");
+        app.workspace.add_buffer(buffer);
+
+        commands::application::switch_to_select_line_mode(&mut app).unwrap();
+        commands::selection::join_lines(&mut app).unwrap();
+
+        assert_eq!(
+            app.workspace.current_buffer().unwrap().data(),
+            String::from("amp editor\nbuffer")
+        );
+        assert_eq!(
+            *app.workspace.current_buffer().unwrap().cursor,
+            Position { line: 0, offset: 3 }
+        );
+    }
+
+    #[test]
+    fn join_lines_can_be_undone_in_a_single_step() {
+        let mut app = Application::new(&Vec::new()).unwrap();
+        let mut buffer = Buffer::new();
+        buffer.insert("amp\neditor\nbuffer");
+        app.workspace.add_buffer(buffer);
+
+        commands::application::switch_to_select_line_mode(&mut app).unwrap();
+        app.workspace.current_buffer().unwrap().cursor.move_to(Position {
+            line: 2,
+            offset: 0,
+        });
+        commands::selection::join_lines(&mut app).unwrap();
+        commands::buffer::undo(&mut app).unwrap();
+
+        assert_eq!(
+            app.workspace.current_buffer().unwrap().data(),
+            String::from("amp\neditor\nbuffer")
+        );
+    }
+
+    #[test]
+    fn sort_lines_is_undone_in_a_single_step() {
+        let mut app = Application::new(&Vec::new()).unwrap();
+        let mut buffer = Buffer::new();
+        buffer.insert("c\na\nb\n");
+        app.workspace.add_buffer(buffer);
+
+        commands::application::switch_to_select_line_mode(&mut app).unwrap();
+        app.workspace.current_buffer().unwrap().cursor.move_to(Position {
+            line: 2,
+            offset: 0,
+        });
+        commands::selection::sort_lines(&mut app).unwrap();
+        assert_eq!(app.workspace.current_buffer().unwrap().data(), "a\nb\nc\n");
+
+        commands::buffer::undo(&mut app).unwrap();
+        assert_eq!(app.workspace.current_buffer().unwrap().data(), "c\na\nb\n");
+    }
+
+    #[test]
+    fn sort_lines_descending_reverses_the_selected_lines() {
+        let mut app = Application::new(&Vec::new()).unwrap();
+        let mut buffer = Buffer::new();
+        buffer.insert("2019-01-01\n2019-03-01\n2019-02-01\n");
+        app.workspace.add_buffer(buffer);
+
+        commands::application::switch_to_select_line_mode(&mut app).unwrap();
+        app.workspace.current_buffer().unwrap().cursor.move_to(Position {
+            line: 2,
+            offset: 0,
+        });
+        commands::selection::sort_lines_descending(&mut app).unwrap();
+
+        assert_eq!(
+            app.workspace.current_buffer().unwrap().data(),
+            String::from("2019-03-01\n2019-02-01\n2019-01-01\n")
+        );
+        assert!(match app.mode {
+            Mode::Normal => true,
+            _ => false,
+        });
+    }
+
+    #[test]
+    fn sort_lines_case_insensitive_ignores_case_when_ordering_lines() {
+        let mut app = Application::new(&Vec::new()).unwrap();
+        let mut buffer = Buffer::new();
+        buffer.insert("banana\nCherry\nApple\n");
+        app.workspace.add_buffer(buffer);
+
+        commands::application::switch_to_select_line_mode(&mut app).unwrap();
+        app.workspace.current_buffer().unwrap().cursor.move_to(Position {
+            line: 2,
+            offset: 0,
+        });
+        commands::selection::sort_lines_case_insensitive(&mut app).unwrap();
+
+        assert_eq!(
+            app.workspace.current_buffer().unwrap().data(),
+            String::from("Apple\nbanana\nCherry\n")
+        );
+    }
+
+    #[test]
+    fn deduplicate_lines_removes_only_adjacent_duplicates() {
+        let mut app = Application::new(&Vec::new()).unwrap();
+        let mut buffer = Buffer::new();
+        buffer.insert("amp\namp\neditor\namp\n");
+        app.workspace.add_buffer(buffer);
+
+        commands::application::switch_to_select_line_mode(&mut app).unwrap();
+        app.workspace.current_buffer().unwrap().cursor.move_to(Position {
+            line: 3,
+            offset: 0,
+        });
+        commands::selection::deduplicate_lines(&mut app).unwrap();
+
+        assert_eq!(
+            app.workspace.current_buffer().unwrap().data(),
+            String::from("amp\neditor\namp\n")
+        );
+        assert!(match app.mode {
+            Mode::Normal => true,
+            _ => false,
+        });
+    }
+
+    #[test]
+    fn deduplicate_all_lines_removes_duplicates_preserving_first_occurrences() {
+        let mut app = Application::new(&Vec::new()).unwrap();
+        let mut buffer = Buffer::new();
+        buffer.insert("editor\namp\neditor\namp\nbuffer\n");
+        app.workspace.add_buffer(buffer);
+
+        commands::application::switch_to_select_line_mode(&mut app).unwrap();
+        app.workspace.current_buffer().unwrap().cursor.move_to(Position {
+            line: 4,
+            offset: 0,
+        });
+        commands::selection::deduplicate_all_lines(&mut app).unwrap();
+
+        assert_eq!(
+            app.workspace.current_buffer().unwrap().data(),
+            String::from("editor\namp\nbuffer\n")
+        );
+    }
+
+    #[test]
+    fn indent_indents_every_selected_line_and_keeps_the_selection() {
+        let mut app = Application::new(&Vec::new()).unwrap();
+        let mut buffer = Buffer::new();
+        buffer.insert("amp\neditor\nbuffer\n");
+        app.workspace.add_buffer(buffer);
+
+        commands::application::switch_to_select_line_mode(&mut app).unwrap();
+        app.workspace.current_buffer().unwrap().cursor.move_to(Position {
+            line: 1,
+            offset: 0,
+        });
+        commands::selection::indent(&mut app).unwrap();
+        commands::selection::indent(&mut app).unwrap();
+
+        assert_eq!(
+            app.workspace.current_buffer().unwrap().data(),
+            String::from("    amp\n    editor\nbuffer\n")
+        );
+        assert!(match app.mode {
+            Mode::SelectLine(_) => true,
+            _ => false,
+        });
+    }
+
+    #[test]
+    fn outdent_leaves_unindented_lines_untouched() {
+        let mut app = Application::new(&Vec::new()).unwrap();
+        let mut buffer = Buffer::new();
+        buffer.insert("  amp\neditor\n buffer\n");
+        app.workspace.add_buffer(buffer);
+
+        commands::application::switch_to_select_line_mode(&mut app).unwrap();
+        app.workspace.current_buffer().unwrap().cursor.move_to(Position {
+            line: 2,
+            offset: 0,
+        });
+        commands::selection::outdent(&mut app).unwrap();
+
+        assert_eq!(
+            app.workspace.current_buffer().unwrap().data(),
+            String::from("amp\neditor\nbuffer\n")
+        );
+        assert!(match app.mode {
+            Mode::SelectLine(_) => true,
+            _ => false,
+        });
+    }
+
+    #[test]
+    fn toggle_comment_comments_selected_lines_at_their_shared_indentation() {
+        let mut app = Application::new(&Vec::new()).unwrap();
+        let mut buffer = Buffer::new();
+        buffer.insert("  amp\n    editor\nbuffer\n");
+        buffer.path = Some("test.rs".into());
+        app.workspace.add_buffer(buffer);
+
+        commands::application::switch_to_select_line_mode(&mut app).unwrap();
+        app.workspace.current_buffer().unwrap().cursor.move_to(Position {
+            line: 1,
+            offset: 0,
+        });
+        commands::selection::toggle_comment(&mut app).unwrap();
+
+        assert_eq!(
+            app.workspace.current_buffer().unwrap().data(),
+            String::from("  // amp\n  //   editor\nbuffer\n")
+        );
+
+        // Toggling again should uncomment them, now that they're all commented.
+        commands::selection::toggle_comment(&mut app).unwrap();
+
+        assert_eq!(
+            app.workspace.current_buffer().unwrap().data(),
+            String::from("  amp\n    editor\nbuffer\n")
+        );
+    }
+
+    #[test]
+    fn delete_removes_the_rectangular_region_in_select_block_mode() {
+        let mut app = Application::new(&Vec::new()).unwrap();
+        let mut buffer = Buffer::new();
+        buffer.insert("amp editor\na\nbuffer\n");
+        buffer.cursor.move_to(Position { line: 0, offset: 1 });
+        app.workspace.add_buffer(buffer);
+
+        commands::application::switch_to_select_block_mode(&mut app).unwrap();
+        app.workspace.current_buffer().unwrap().cursor.move_to(Position {
+            line: 2,
+            offset: 3,
+        });
+        commands::selection::delete(&mut app).unwrap();
+
+        assert_eq!(
+            app.workspace.current_buffer().unwrap().data(),
+            String::from("a editor\na\nbfer\n")
+        );
+        assert_eq!(
+            *app.workspace.current_buffer().unwrap().cursor,
+            Position { line: 0, offset: 1 }
+        );
+    }
+
+    #[test]
+    fn copy_stores_one_segment_per_row_in_select_block_mode() {
+        let mut app = Application::new(&Vec::new()).unwrap();
+        let mut buffer = Buffer::new();
+        buffer.insert("amp editor\na\nbuffer\n");
+        buffer.cursor.move_to(Position { line: 0, offset: 1 });
+        app.workspace.add_buffer(buffer);
+
+        commands::application::switch_to_select_block_mode(&mut app).unwrap();
+        app.workspace.current_buffer().unwrap().cursor.move_to(Position {
+            line: 2,
+            offset: 3,
+        });
+        commands::selection::copy(&mut app).unwrap();
+
+        assert_eq!(
+            *app.clipboard.get_content(),
+            ClipboardContent::Rectangular(vec![
+                String::from("mp"),
+                String::new(),
+                String::from("uf"),
+            ])
+        );
+    }
+
+    #[test]
+    fn paste_inserts_rectangular_content_at_the_cursor_column() {
+        let mut app = Application::new(&Vec::new()).unwrap();
+        let mut buffer = Buffer::new();
+        buffer.insert("amp\neditor");
+        buffer.cursor.move_to(Position { line: 0, offset: 1 });
+        app.workspace.add_buffer(buffer);
+        app.clipboard.set_content(ClipboardContent::Rectangular(vec![
+            String::from("12"),
+            String::from("34"),
+            String::from("56"),
+        ])).unwrap();
+
+        commands::buffer::paste(&mut app).unwrap();
+
+        assert_eq!(
+            app.workspace.current_buffer().unwrap().data(),
+            String::from("a12mp\ne34ditor\n 56")
+        );
+    }
+
+    #[test]
+    fn paste_pads_short_lines_to_keep_rectangular_content_aligned() {
+        let mut app = Application::new(&Vec::new()).unwrap();
+        let mut buffer = Buffer::new();
+        buffer.insert("editor\na\namp");
+        buffer.cursor.move_to(Position { line: 0, offset: 3 });
+        app.workspace.add_buffer(buffer);
+        app.clipboard.set_content(ClipboardContent::Rectangular(vec![
+            String::from("12"),
+            String::from("34"),
+            String::from("56"),
+        ])).unwrap();
+
+        commands::buffer::paste(&mut app).unwrap();
+        assert_eq!(
+            app.workspace.current_buffer().unwrap().data(),
+            String::from("edi12tor\na  34\namp56")
+        );
+        assert_eq!(
+            *app.workspace.current_buffer().unwrap().cursor,
+            Position { line: 0, offset: 3 }
+        );
+
+        // The paste should be undone in a single step.
+        app.workspace.current_buffer().unwrap().undo();
+        assert_eq!(
+            app.workspace.current_buffer().unwrap().data(),
+            String::from("editor\na\namp")
+        );
+    }
+
+    #[test]
+    fn strip_whitespace_trims_each_selected_line() {
+        let mut app = Application::new(&Vec::new()).unwrap();
+        let mut buffer = Buffer::new();
+        buffer.insert("  amp \n\teditor  \nbuffer\t\n");
+        app.workspace.add_buffer(buffer);
+
+        commands::application::switch_to_select_line_mode(&mut app).unwrap();
+        app.workspace.current_buffer().unwrap().cursor.move_to(Position {
+            line: 1,
+            offset: 0,
+        });
+        commands::selection::strip_whitespace(&mut app).unwrap();
+
+        assert_eq!(
+            app.workspace.current_buffer().unwrap().data(),
+            String::from("amp\neditor\nbuffer\t\n")
+        );
+        assert!(match app.mode {
+            Mode::Normal => true,
+            _ => false,
+        });
+    }
+
+    #[test]
+    fn collapse_whitespace_preserves_indentation() {
+        let mut app = Application::new(&Vec::new()).unwrap();
+        let mut buffer = Buffer::new();
+        buffer.insert("    amp   text\t editor\n");
+        app.workspace.add_buffer(buffer);
+
+        commands::application::switch_to_select_line_mode(&mut app).unwrap();
+        commands::selection::collapse_whitespace(&mut app).unwrap();
+
+        assert_eq!(
+            app.workspace.current_buffer().unwrap().data(),
+            String::from("    amp text editor\n")
+        );
+    }
+
+    #[test]
+    fn number_lines_prefixes_the_selected_lines_using_the_preferred_format() {
+        let mut app = Application::new(&Vec::new()).unwrap();
+        let mut buffer = Buffer::new();
+        buffer.insert("amp\neditor\nbuffer\n");
+        app.workspace.add_buffer(buffer);
+        let data = YamlLoader::load_from_str("line_number_format: \" 9) \"").unwrap();
+        *app.preferences.borrow_mut() = Preferences::new(data.into_iter().nth(0));
+
+        commands::application::switch_to_select_line_mode(&mut app).unwrap();
+        app.workspace.current_buffer().unwrap().cursor.move_to(Position {
+            line: 2,
+            offset: 0,
+        });
+        commands::selection::number_lines(&mut app).unwrap();
+
+        assert_eq!(
+            app.workspace.current_buffer().unwrap().data(),
+            String::from(" 9) amp\n10) editor\n11) buffer\n")
+        );
+        assert_eq!(
+            *app.workspace.current_buffer().unwrap().cursor,
+            Position { line: 2, offset: 0 }
+        );
+    }
+
+    #[test]
+    fn number_lines_renumbers_already_numbered_lines() {
+        let mut app = Application::new(&Vec::new()).unwrap();
+        let mut buffer = Buffer::new();
+        buffer.insert("3. amp\n7. editor\n");
+        app.workspace.add_buffer(buffer);
+
+        commands::application::switch_to_select_line_mode(&mut app).unwrap();
+        app.workspace.current_buffer().unwrap().cursor.move_to(Position {
+            line: 1,
+            offset: 0,
+        });
+        commands::selection::number_lines(&mut app).unwrap();
+
+        assert_eq!(
+            app.workspace.current_buffer().unwrap().data(),
+            String::from("1. amp\n2. editor\n")
+        );
+    }
+
+    #[test]
+    fn align_on_lines_up_the_first_occurrence_of_the_delimiter() {
+        let mut app = Application::new(&Vec::new()).unwrap();
+        let mut buffer = Buffer::new();
+        buffer.insert("a = 1\nlonger = 2 = 3\nnone\n");
+        app.workspace.add_buffer(buffer);
+
+        commands::application::switch_to_select_line_mode(&mut app).unwrap();
+        app.workspace.current_buffer().unwrap().cursor.move_to(Position {
+            line: 2,
+            offset: 0,
+        });
+        commands::application::switch_to_align_mode(&mut app).unwrap();
+        app.view.last_key = Some(Key::Char('='));
+        commands::selection::align_on(&mut app).unwrap();
+
+        assert_eq!(
+            app.workspace.current_buffer().unwrap().data(),
+            String::from("a      = 1\nlonger = 2 = 3\nnone\n")
+        );
+        assert!(match app.mode {
+            Mode::Normal => true,
+            _ => false,
+        });
+    }
+
+    #[test]
+    fn encode_base64_replaces_the_selection_with_its_encoding() {
+        let mut app = Application::new(&Vec::new()).unwrap();
+        let mut buffer = Buffer::new();
+        buffer.insert("amp editor");
+        app.workspace.add_buffer(buffer);
+
+        commands::application::switch_to_select_mode(&mut app).unwrap();
+        app.workspace.current_buffer().unwrap().cursor.move_to(Position {
+            line: 0,
+            offset: 3,
+        });
+        commands::selection::encode_base64(&mut app).unwrap();
+
+        assert_eq!(
+            app.workspace.current_buffer().unwrap().data(),
+            String::from("YW1w editor")
+        );
+    }
+
+    #[test]
+    fn decode_hex_leaves_the_buffer_untouched_when_content_is_invalid() {
+        let mut app = Application::new(&Vec::new()).unwrap();
+        let mut buffer = Buffer::new();
+        buffer.insert("616d7g");
+        app.workspace.add_buffer(buffer);
+
+        commands::application::switch_to_select_line_mode(&mut app).unwrap();
+        assert!(commands::selection::decode_hex(&mut app).is_err());
+
+        assert_eq!(
+            app.workspace.current_buffer().unwrap().data(),
+            String::from("616d7g")
+        );
+    }
+
+    #[test]
+    fn filter_through_command_replaces_the_selection_with_the_command_output() {
+        let mut app = Application::new(&Vec::new()).unwrap();
+        let mut buffer = Buffer::new();
+        buffer.insert("b\na\nc\n");
+        app.workspace.add_buffer(buffer);
+
+        commands::application::switch_to_select_line_mode(&mut app).unwrap();
+        app.workspace.current_buffer().unwrap().cursor.move_to(Position {
+            line: 1,
+            offset: 0,
+        });
+        commands::application::switch_to_filter_mode(&mut app).unwrap();
+        if let Mode::Filter(ref mut mode) = app.mode {
+            mode.input = String::from("sort");
+        }
+        commands::selection::filter_through_command(&mut app).unwrap();
+
+        assert_eq!(
+            app.workspace.current_buffer().unwrap().data(),
+            String::from("a\nb\nc\n")
+        );
+        assert!(match app.mode {
+            Mode::Normal => true,
+            _ => false,
+        });
+    }
+
+    #[test]
+    fn filter_through_command_leaves_the_buffer_untouched_when_the_command_fails() {
+        let mut app = Application::new(&Vec::new()).unwrap();
+        let mut buffer = Buffer::new();
+        buffer.insert("amp\n");
+        app.workspace.add_buffer(buffer);
+
+        commands::application::switch_to_select_line_mode(&mut app).unwrap();
+        commands::application::switch_to_filter_mode(&mut app).unwrap();
+        if let Mode::Filter(ref mut mode) = app.mode {
+            mode.input = String::from("exit 1");
+        }
+        assert!(commands::selection::filter_through_command(&mut app).is_err());
+
+        assert_eq!(
+            app.workspace.current_buffer().unwrap().data(),
+            String::from("amp\n")
+        );
+    }
 }
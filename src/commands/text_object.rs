@@ -0,0 +1,115 @@
+use crate::errors::*;
+use crate::input::Key;
+use crate::commands::{self, Result};
+use crate::models::application::{Application, Mode};
+use crate::models::application::modes::{SelectMode, TextObjectScope};
+use crate::util::delimiter;
+use scribe::buffer::Position;
+use unicode_segmentation::UnicodeSegmentation;
+
+/// Selects the content of the innermost pair of delimiters (named by the
+/// last key press) that encloses the cursor, including the delimiters
+/// themselves if text object mode was entered with the around scope.
+pub fn accept_char(app: &mut Application) -> Result {
+    let (opener, closer) = match *app.view.last_key() {
+        Some(Key::Char(c)) => delimiter::delimiter_pair(c),
+        _ => bail!("Last key press wasn't a character"),
+    };
+    let scope = if let Mode::TextObject(ref mode) = app.mode {
+        mode.scope
+    } else {
+        bail!("Can't accept text object delimiter outside of text object mode");
+    };
+
+    let buffer = app.workspace.current_buffer().ok_or(BUFFER_MISSING)?;
+    let data = buffer.data();
+    let lines: Vec<Vec<&str>> = data
+        .lines()
+        .map(|line| line.graphemes(true).collect())
+        .collect();
+    let (opening, closing) = delimiter::enclosing_pair(
+        &lines,
+        *buffer.cursor,
+        &opener.to_string(),
+        &closer.to_string()
+    ).ok_or_else(|| format!("No enclosing \"{}{}\" pair found", opener, closer))?;
+
+    // Selections exclude the character under the cursor,
+    // so the cursor sits on (or after) the closing delimiter.
+    let (anchor, cursor) = match scope {
+        TextObjectScope::Inside => (
+            Position { line: opening.line, offset: opening.offset + 1 },
+            closing
+        ),
+        TextObjectScope::Around => (
+            opening,
+            Position { line: closing.line, offset: closing.offset + 1 }
+        ),
+    };
+    buffer.cursor.move_to(cursor);
+    app.mode = Mode::Select(SelectMode::new(anchor));
+
+    commands::view::scroll_to_cursor(app)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::commands;
+    use crate::input::Key;
+    use crate::models::application::{Application, Mode};
+    use scribe::Buffer;
+    use scribe::buffer::Position;
+
+    #[test]
+    fn accept_char_selects_inside_the_enclosing_pair() {
+        let mut app = Application::new(&Vec::new()).unwrap();
+        let mut buffer = Buffer::new();
+        buffer.insert("amp(editor[0], x)");
+        buffer.cursor.move_to(Position { line: 0, offset: 15 });
+        app.workspace.add_buffer(buffer);
+
+        commands::application::switch_to_select_inside_mode(&mut app).unwrap();
+        app.view.last_key = Some(Key::Char(')'));
+        commands::text_object::accept_char(&mut app).unwrap();
+
+        match app.mode {
+            Mode::Select(ref mode) => assert_eq!(mode.anchor, Position { line: 0, offset: 4 }),
+            _ => panic!("Not in select mode"),
+        }
+        assert_eq!(*app.workspace.current_buffer().unwrap().cursor,
+                   Position { line: 0, offset: 16 });
+
+        // The selection can be deleted as usual.
+        commands::selection::delete(&mut app).unwrap();
+        assert_eq!(app.workspace.current_buffer().unwrap().data(), "amp()");
+    }
+
+    #[test]
+    fn accept_char_selects_around_the_enclosing_pair() {
+        let mut app = Application::new(&Vec::new()).unwrap();
+        let mut buffer = Buffer::new();
+        buffer.insert("amp \"editor\"");
+        buffer.cursor.move_to(Position { line: 0, offset: 7 });
+        app.workspace.add_buffer(buffer);
+
+        commands::application::switch_to_select_around_mode(&mut app).unwrap();
+        app.view.last_key = Some(Key::Char('"'));
+        commands::text_object::accept_char(&mut app).unwrap();
+        commands::selection::delete(&mut app).unwrap();
+
+        assert_eq!(app.workspace.current_buffer().unwrap().data(), "amp ");
+    }
+
+    #[test]
+    fn accept_char_fails_without_an_enclosing_pair() {
+        let mut app = Application::new(&Vec::new()).unwrap();
+        let mut buffer = Buffer::new();
+        buffer.insert("amp editor");
+        app.workspace.add_buffer(buffer);
+
+        commands::application::switch_to_select_inside_mode(&mut app).unwrap();
+        app.view.last_key = Some(Key::Char('('));
+
+        assert!(commands::text_object::accept_char(&mut app).is_err());
+    }
+}
@@ -1,6 +1,10 @@
 use crate::errors::*;
 use crate::commands::Result;
 use crate::models::application::Application;
+use crate::util;
+use crate::util::token::Direction;
+use crate::view::SplitOrientation;
+use scribe::buffer::Position;
 
 pub fn scroll_up(app: &mut Application) -> Result {
     let buffer = app.workspace.current_buffer().ok_or(BUFFER_MISSING)?;
@@ -14,6 +18,48 @@ pub fn scroll_down(app: &mut Application) -> Result {
     Ok(())
 }
 
+pub fn scroll_half_page_up(app: &mut Application) -> Result {
+    let lines = (app.view.buffer_content_height() / 2).max(1);
+    scroll_with_cursor(app, Direction::Backward, lines)
+}
+
+pub fn scroll_half_page_down(app: &mut Application) -> Result {
+    let lines = (app.view.buffer_content_height() / 2).max(1);
+    scroll_with_cursor(app, Direction::Forward, lines)
+}
+
+pub fn scroll_page_up(app: &mut Application) -> Result {
+    let lines = app.view.buffer_content_height().max(1);
+    scroll_with_cursor(app, Direction::Backward, lines)
+}
+
+pub fn scroll_page_down(app: &mut Application) -> Result {
+    let lines = app.view.buffer_content_height().max(1);
+    scroll_with_cursor(app, Direction::Forward, lines)
+}
+
+/// Scrolls the view and moves the cursor by the same number of lines, so
+/// that the cursor keeps its place on screen. Near the start or end of the
+/// buffer, the cursor moves as far as it can and is kept in view.
+fn scroll_with_cursor(app: &mut Application, direction: Direction, lines: usize) -> Result {
+    let buffer = app.workspace.current_buffer().ok_or(BUFFER_MISSING)?;
+    let line = match direction {
+        Direction::Forward => {
+            app.view.scroll_down(buffer, lines)?;
+            (buffer.cursor.line + lines).min(buffer.line_count().saturating_sub(1))
+        }
+        Direction::Backward => {
+            app.view.scroll_up(buffer, lines)?;
+            buffer.cursor.line.saturating_sub(lines)
+        }
+    };
+    let offset = buffer.cursor.offset;
+    util::move_cursor_to_nearest(buffer, Position { line, offset });
+    app.view.scroll_to_cursor(buffer)?;
+
+    Ok(())
+}
+
 pub fn scroll_to_cursor(app: &mut Application) -> Result {
     let buffer = app.workspace.current_buffer().ok_or(BUFFER_MISSING)?;
     app.view.scroll_to_cursor(buffer)?;
@@ -25,3 +71,196 @@ pub fn scroll_cursor_to_center(app: &mut Application) -> Result {
     app.view.scroll_to_center(buffer)?;
     Ok(())
 }
+
+pub fn scroll_cursor_to_top(app: &mut Application) -> Result {
+    let buffer = app.workspace.current_buffer().ok_or(BUFFER_MISSING)?;
+    app.view.scroll_to_top(buffer)?;
+    Ok(())
+}
+
+pub fn scroll_cursor_to_bottom(app: &mut Application) -> Result {
+    let buffer = app.workspace.current_buffer().ok_or(BUFFER_MISSING)?;
+    app.view.scroll_to_bottom(buffer)?;
+    Ok(())
+}
+
+pub fn toggle_line_wrapping(app: &mut Application) -> Result {
+    let wrapping = !app.preferences.borrow().line_wrapping();
+    app.preferences.borrow_mut().set_line_wrapping(wrapping);
+    app.message = Some(if wrapping {
+        "Line wrapping enabled".to_string()
+    } else {
+        "Line wrapping disabled".to_string()
+    });
+
+    if app.workspace.current_buffer().is_some() {
+        scroll_to_cursor(app)?;
+    }
+    Ok(())
+}
+
+/// Shows or hides whitespace characters for the rest of the session.
+pub fn toggle_whitespace(app: &mut Application) -> Result {
+    let show_whitespace = !app.preferences.borrow().show_whitespace();
+    app.preferences.borrow_mut().set_show_whitespace(show_whitespace);
+    app.message = Some(if show_whitespace {
+        "Showing whitespace".to_string()
+    } else {
+        "Hiding whitespace".to_string()
+    });
+
+    Ok(())
+}
+
+/// Switches to the next available theme (in alphabetical order) for the
+/// rest of the session, so that they can be previewed one after another.
+pub fn cycle_theme(app: &mut Application) -> Result {
+    let current_theme = app.preferences.borrow().theme().to_string();
+    let theme = {
+        let themes: Vec<&String> = app.view.theme_set.themes.keys().collect();
+        let index = themes
+            .iter()
+            .position(|theme| **theme == current_theme)
+            .map(|index| (index + 1) % themes.len())
+            .unwrap_or(0);
+
+        themes.get(index).map(|theme| theme.to_string()).ok_or("No themes available")?
+    };
+    app.preferences.borrow_mut().set_theme(theme.as_str());
+    app.message = Some(format!("Theme: {}", theme));
+
+    Ok(())
+}
+
+pub fn split_horizontally(app: &mut Application) -> Result {
+    let buffer = app.workspace.current_buffer().ok_or(BUFFER_MISSING)?;
+    app.view.split(buffer, SplitOrientation::Horizontal)?;
+    Ok(())
+}
+
+pub fn split_vertically(app: &mut Application) -> Result {
+    let buffer = app.workspace.current_buffer().ok_or(BUFFER_MISSING)?;
+    app.view.split(buffer, SplitOrientation::Vertical)?;
+    Ok(())
+}
+
+pub fn close_split(app: &mut Application) -> Result {
+    app.view.close_split()?;
+    Ok(())
+}
+
+/// Moves focus to the other pane of a split view, selecting its buffer
+/// and restoring its cursor position and scroll offset.
+pub fn focus_next_pane(app: &mut Application) -> Result {
+    let buffer = app.workspace.current_buffer().ok_or(BUFFER_MISSING)?;
+    let pane = app.view.swap_panes(buffer)?;
+    if !util::select_buffer(&mut app.workspace, pane.buffer_id) {
+        app.view.close_split()?;
+        bail!("The other pane's buffer is no longer open");
+    }
+
+    let buffer = app.workspace.current_buffer().ok_or(BUFFER_MISSING)?;
+    buffer.cursor.move_to(pane.cursor);
+    app.view.set_line_offset(buffer, pane.line_offset)?;
+    app.view.scroll_to_cursor(buffer)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::commands;
+    use crate::models::Application;
+    use scribe::Buffer;
+    use scribe::buffer::Position;
+
+    #[test]
+    fn scroll_page_commands_move_the_cursor_by_the_viewport_height_and_clamp() {
+        let mut app = Application::new(&Vec::new()).unwrap();
+        let mut buffer = Buffer::new();
+        buffer.insert("amp\n".repeat(99) + "amp");
+        app.workspace.add_buffer(buffer);
+        let height = app.view.buffer_content_height();
+
+        super::scroll_page_down(&mut app).unwrap();
+        assert_eq!(app.workspace.current_buffer().unwrap().cursor.line, height);
+        super::scroll_half_page_up(&mut app).unwrap();
+        assert_eq!(app.workspace.current_buffer().unwrap().cursor.line, height - height / 2);
+
+        for _ in 0..100 {
+            super::scroll_half_page_down(&mut app).unwrap();
+        }
+        assert_eq!(
+            *app.workspace.current_buffer().unwrap().cursor,
+            Position{ line: 99, offset: 0 }
+        );
+
+        for _ in 0..100 {
+            super::scroll_page_up(&mut app).unwrap();
+        }
+        assert_eq!(app.workspace.current_buffer().unwrap().cursor.line, 0);
+    }
+
+    #[test]
+    fn toggle_whitespace_flips_the_in_memory_preference() {
+        let mut app = Application::new(&Vec::new()).unwrap();
+        super::toggle_whitespace(&mut app).unwrap();
+        assert!(app.preferences.borrow().show_whitespace());
+        assert_eq!(app.message, Some(String::from("Showing whitespace")));
+
+        super::toggle_whitespace(&mut app).unwrap();
+        assert!(!app.preferences.borrow().show_whitespace());
+    }
+
+    #[test]
+    fn cycle_theme_steps_through_every_theme_and_wraps_around() {
+        let mut app = Application::new(&Vec::new()).unwrap();
+        app.preferences.borrow_mut().set_theme("solarized_dark");
+        super::cycle_theme(&mut app).unwrap();
+        assert_eq!(app.preferences.borrow().theme(), "solarized_light");
+        assert_eq!(app.message, Some(String::from("Theme: solarized_light")));
+
+        for _ in 1..app.view.theme_set.themes.len() {
+            super::cycle_theme(&mut app).unwrap();
+        }
+        assert_eq!(app.preferences.borrow().theme(), "solarized_dark");
+    }
+
+    #[test]
+    fn focus_next_pane_switches_between_regions_of_the_same_buffer() {
+        let mut app = Application::new(&Vec::new()).unwrap();
+        let mut buffer = Buffer::new();
+        buffer.insert("amp\neditor\namp");
+        app.workspace.add_buffer(buffer);
+
+        super::split_vertically(&mut app).unwrap();
+        app.workspace.current_buffer().unwrap().cursor.move_to(Position{ line: 2, offset: 1 });
+        super::focus_next_pane(&mut app).unwrap();
+        assert_eq!(*app.workspace.current_buffer().unwrap().cursor, Position{ line: 0, offset: 0 });
+
+        super::focus_next_pane(&mut app).unwrap();
+        assert_eq!(*app.workspace.current_buffer().unwrap().cursor, Position{ line: 2, offset: 1 });
+    }
+
+    #[test]
+    fn focus_next_pane_selects_the_other_panes_buffer() {
+        let mut app = Application::new(&Vec::new()).unwrap();
+        let mut buffer = Buffer::new();
+        buffer.insert("amp");
+        app.workspace.add_buffer(buffer);
+        super::split_horizontally(&mut app).unwrap();
+
+        let mut other_buffer = Buffer::new();
+        other_buffer.insert("editor");
+        app.workspace.add_buffer(other_buffer);
+        super::focus_next_pane(&mut app).unwrap();
+        assert_eq!(app.workspace.current_buffer().unwrap().data(), "amp");
+
+        super::focus_next_pane(&mut app).unwrap();
+        assert_eq!(app.workspace.current_buffer().unwrap().data(), "editor");
+
+        // Closing the split keeps the focused pane.
+        super::close_split(&mut app).unwrap();
+        assert!(super::focus_next_pane(&mut app).is_err());
+        assert!(commands::view::scroll_to_cursor(&mut app).is_ok());
+    }
+}
@@ -1,3 +1,4 @@
+use crate::errors::*;
 use crate::commands::Result;
 use scribe::Buffer;
 use crate::models::application::Application;
@@ -9,6 +10,17 @@ pub fn next_buffer(app: &mut Application) -> Result {
     Ok(())
 }
 
+/// Toggles between the current buffer and the one used before it.
+pub fn switch_to_previous_buffer(app: &mut Application) -> Result {
+    let id = app.workspace.current_buffer().and_then(|buffer| buffer.id).ok_or(BUFFER_MISSING)?;
+    let previous_id = app.buffer_history.previous(id).ok_or("No previous buffer to switch to")?;
+    if !util::select_buffer(&mut app.workspace, previous_id) {
+        bail!("Couldn't find the previous buffer");
+    }
+
+    Ok(())
+}
+
 pub fn new_buffer(app: &mut Application) -> Result {
     util::add_buffer(Buffer::new(), app)
 }
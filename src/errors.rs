@@ -7,7 +7,11 @@ error_chain! {
 
 pub static SEARCH_QUERY_MISSING: &str = "No search query";
 pub static NO_SEARCH_RESULTS: &str = "No search results available";
+pub static REPLACEMENT_MISSING: &str = "No replacement";
 pub static BUFFER_MISSING: &str = "No buffer available";
+pub static BUFFER_ID_MISSING: &str = "No ID found for the current buffer";
+pub static BUFFER_READ_ONLY: &str = "The current buffer is read-only";
 pub static BUFFER_PATH_MISSING: &str = "No path found for the current buffer";
 pub static CURRENT_LINE_MISSING: &str = "The current line couldn't be found in the buffer";
 pub static SCROLL_TO_CURSOR_FAILED: &str = "Failed to scroll to cursor position";
+pub static VIEW_NOT_SPLIT: &str = "The view isn't split";
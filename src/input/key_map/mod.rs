@@ -7,8 +7,12 @@ use std::ops::{Deref, DerefMut};
 use std::convert::Into;
 use crate::yaml::yaml::{Hash, Yaml, YamlLoader};
 
+type KeyBindings = HashMap<Key, SmallVec<[Command; 4]>>;
+type KeySequences = HashMap<Vec<Key>, SmallVec<[Command; 4]>>;
+
 /// Nested HashMap newtype that provides a more ergonomic interface.
-pub struct KeyMap(HashMap<String, HashMap<Key, SmallVec<[Command; 4]>>>);
+/// Multi-key bindings (e.g. `g g`) are tracked separately, by mode.
+pub struct KeyMap(HashMap<String, KeyBindings>, HashMap<String, KeySequences>);
 
 impl KeyMap {
     /// Parses a Yaml tree of modes and their keybindings into a complete keymap.
@@ -24,19 +28,61 @@ impl KeyMap {
     ///
     pub fn from(keymap_data: &Hash) -> Result<KeyMap> {
         let mut keymap = HashMap::new();
+        let mut sequences = HashMap::new();
         let commands = commands::hash_map();
 
         for (yaml_mode, yaml_key_bindings) in keymap_data {
             let mode = yaml_mode.as_str().ok_or_else(||
                 "A mode key couldn't be parsed as a string".to_string()
             )?;
-            let key_bindings = parse_mode_key_bindings(yaml_key_bindings, &commands).
+            let (key_bindings, key_sequences) =
+                parse_mode_key_bindings(yaml_key_bindings, &commands, None).
                 chain_err(|| format!("Failed to parse keymaps for \"{}\" mode", mode))?;
 
             keymap.insert(mode.to_string(), key_bindings);
+            sequences.insert(mode.to_string(), key_sequences);
+        }
+
+        Ok(KeyMap(keymap, sequences))
+    }
+
+    /// Parses user-defined key bindings, skipping (rather than failing on)
+    /// invalid entries, such as those referencing unknown commands or modes.
+    /// A description of each skipped entry is returned alongside the keymap.
+    pub fn from_overrides(keymap_data: &Hash) -> (KeyMap, Vec<String>) {
+        let mut keymap = HashMap::new();
+        let mut sequences = HashMap::new();
+        let mut warnings = Vec::new();
+        let commands = commands::hash_map();
+        let default_keymap = KeyMap::default().ok();
+
+        for (yaml_mode, yaml_key_bindings) in keymap_data {
+            let mode = match yaml_mode.as_str() {
+                Some(mode) => mode,
+                None => {
+                    warnings.push("A mode key couldn't be parsed as a string".to_string());
+                    continue;
+                }
+            };
+            if default_keymap.as_ref().map_or(false, |keymap| !keymap.contains_key(mode)) {
+                warnings.push(format!("Keymap mode \"{}\" doesn't exist", mode));
+                continue;
+            }
+
+            let mut mode_warnings = Vec::new();
+            match parse_mode_key_bindings(yaml_key_bindings, &commands, Some(&mut mode_warnings)) {
+                Ok((key_bindings, key_sequences)) => {
+                    keymap.insert(mode.to_string(), key_bindings);
+                    sequences.insert(mode.to_string(), key_sequences);
+                }
+                Err(error) => mode_warnings.push(error.to_string()),
+            }
+            for warning in mode_warnings {
+                warnings.push(format!("{} (in \"{}\" mode)", warning, mode));
+            }
         }
 
-        Ok(KeyMap(keymap))
+        (KeyMap(keymap, sequences), warnings)
     }
 
     /// Searches the keymap for the specified key.
@@ -55,6 +101,27 @@ impl KeyMap {
         }).map(|commands| (*commands).clone())
     }
 
+    /// Searches the keymap for a multi-key binding matching the keys exactly.
+    pub fn commands_for_sequence(
+        &self,
+        mode: &str,
+        keys: &[Key],
+    ) -> Option<SmallVec<[Command; 4]>> {
+        self.1.get(mode)
+            .and_then(|sequences| sequences.get(keys))
+            .cloned()
+    }
+
+    /// Whether the keys are the start of a longer multi-key binding,
+    /// in which case the rest of the sequence should be waited for.
+    pub fn starts_sequence(&self, mode: &str, keys: &[Key]) -> bool {
+        self.1.get(mode).map_or(false, |sequences| {
+            sequences.keys().any(|sequence| {
+                sequence.len() > keys.len() && sequence.starts_with(keys)
+            })
+        })
+    }
+
     /// Loads the default keymap from a static
     /// YAML document injected during the build.
     pub fn default() -> Result<KeyMap> {
@@ -104,6 +171,13 @@ impl KeyMap {
                 }
             }
         }
+
+        // Multi-key bindings are merged the same way.
+        for (mode, other_key_sequences) in key_map.1.drain() {
+            if self.0.contains_key(&mode) {
+                self.1.entry(mode).or_insert_with(HashMap::new).extend(other_key_sequences);
+            }
+        }
     }
 }
 
@@ -117,53 +191,87 @@ impl KeyMap {
 ///
 ///   Key::Char('k') => [commands::cursor::move_up]
 ///
-fn parse_mode_key_bindings(mode: &Yaml, commands: &HashMap<&str, Command>) -> Result<HashMap<Key, SmallVec<[Command; 4]>>> {
+/// If a warnings collection is provided, invalid bindings are
+/// described there and skipped, rather than failing the whole mode.
+///
+fn parse_mode_key_bindings(
+    mode: &Yaml,
+    commands: &HashMap<&str, Command>,
+    mut warnings: Option<&mut Vec<String>>,
+) -> Result<(KeyBindings, KeySequences)> {
     let mode_key_bindings = mode.as_hash().ok_or(
         "Keymap mode config didn't return a hash of key bindings",
     )?;
 
     let mut key_bindings = HashMap::new();
+    let mut key_sequences = HashMap::new();
     for (yaml_key, yaml_command) in mode_key_bindings {
-        // Parse modifier/character from key component.
-        let key = parse_key(yaml_key.as_str().ok_or_else(||
-            "A keymap key couldn't be parsed as a string".to_string()
-        )?)?;
+        match parse_key_binding(yaml_key, yaml_command, commands) {
+            Ok((mut keys, key_commands)) => {
+                if keys.len() == 1 {
+                    key_bindings.insert(keys.remove(0), key_commands);
+                } else {
+                    key_sequences.insert(keys, key_commands);
+                }
+            }
+            Err(error) => match warnings {
+                Some(ref mut warnings) => warnings.push(error.to_string()),
+                None => return Err(error),
+            }
+        }
+    }
 
-        let mut key_commands = SmallVec::new();
+    Ok((key_bindings, key_sequences))
+}
 
-        // Parse and find command reference from command component.
-        match *yaml_command {
-            Yaml::String(ref command) => {
-                let command_string = command.as_str();
+/// Parses a single key binding into its keys and command sequence.
+/// Multi-key bindings separate their keys with spaces (e.g. `g g`).
+fn parse_key_binding(
+    yaml_key: &Yaml,
+    yaml_command: &Yaml,
+    commands: &HashMap<&str, Command>,
+) -> Result<(Vec<Key>, SmallVec<[Command; 4]>)> {
+    // Parse modifier/character from each key component.
+    let key_data = yaml_key.as_str().ok_or_else(||
+        "A keymap key couldn't be parsed as a string".to_string()
+    )?;
+    let keys = if key_data.trim().is_empty() {
+        vec![parse_key(key_data)?]
+    } else {
+        key_data.split_whitespace().map(parse_key).collect::<Result<Vec<Key>>>()?
+    };
+
+    let mut key_commands = SmallVec::new();
+
+    // Parse and find command reference from command component.
+    match *yaml_command {
+        Yaml::String(ref command) => {
+            let command_string = command.as_str();
+
+            key_commands.push(
+                *commands.get(&command_string).ok_or_else(|| format!(
+                    "Keymap command \"{}\" doesn't exist",
+                    command_string
+                ))?
+            );
+        },
+        Yaml::Array(ref command_array) => {
+            for command in command_array {
+                let command_string = command.as_str().ok_or_else(||
+                    format!("Keymap command \"{:?}\" couldn't be parsed as a string", command)
+                )?;
 
                 key_commands.push(
-                    *commands.get(&command_string).ok_or_else(|| format!(
-                        "Keymap command \"{}\" doesn't exist",
-                        command_string
-                    ))?
+                    *commands.get(command_string).ok_or_else(||
+                        format!("Keymap command \"{}\" doesn't exist", command_string)
+                    )?
                 );
-            },
-            Yaml::Array(ref command_array) => {
-                for command in command_array {
-                    let command_string = command.as_str().ok_or_else(||
-                        format!("Keymap command \"{:?}\" couldn't be parsed as a string", command)
-                    )?;
-
-                    key_commands.push(
-                        *commands.get(command_string).ok_or_else(||
-                            format!("Keymap command \"{}\" doesn't exist", command_string)
-                        )?
-                    );
-                }
-            },
-            _ => bail!(format!("Keymap command \"{:?}\" couldn't be parsed", yaml_command))
-        }
-
-        // Add a key/command entry to the mapping.
-        key_bindings.insert(key, key_commands);
+            }
+        },
+        _ => bail!(format!("Keymap command \"{:?}\" couldn't be parsed", yaml_command))
     }
 
-    Ok(key_bindings)
+    Ok((keys, key_commands))
 }
 
 /// Parses a str-based key into its Key equivalent.
@@ -207,6 +315,7 @@ fn parse_key(data: &str) -> Result<Key> {
             "insert"        => Key::Insert,
             "escape"        => Key::Esc,
             "tab"           => Key::Tab,
+            "backtab"       => Key::BackTab,
             "enter"         => Key::Enter,
             "scroll_up"     => Key::ScrollUp,
             "scroll_down"   => Key::ScrollDown,
@@ -248,6 +357,25 @@ mod tests {
     use crate::commands;
     use crate::input::Key;
 
+    #[test]
+    fn keymap_correctly_parses_yaml_multi_key_keybindings() {
+        let yaml_data = "normal:\n  g g: cursor::move_up\n  g: cursor::move_down";
+        let yaml = YamlLoader::load_from_str(yaml_data).unwrap();
+        let keymap = KeyMap::from(&yaml[0].as_hash().unwrap()).unwrap();
+
+        let keys = [Key::Char('g'), Key::Char('g')];
+        let command = keymap.commands_for_sequence("normal", &keys).expect(
+            "Keymap doesn't contain command",
+        );
+        assert_eq!(
+            (command[0] as *const usize),
+            (commands::cursor::move_up as *const usize)
+        );
+        assert!(keymap.starts_sequence("normal", &keys[..1]));
+        assert!(!keymap.starts_sequence("normal", &keys));
+        assert!(keymap.commands_for("normal", &Key::Char('g')).is_some());
+    }
+
     #[test]
     fn keymap_correctly_parses_yaml_character_keybindings() {
         // Build the keymap
@@ -366,6 +494,28 @@ mod tests {
         );
     }
 
+    #[test]
+    fn keymap_overrides_skip_invalid_bindings_with_warnings() {
+        let yaml_data = "normal:\n  k: cursor::move_up\n  j: cursor::unknown\n\
+                         unknown:\n  l: cursor::move_right";
+        let yaml = YamlLoader::load_from_str(yaml_data).unwrap();
+        let (keymap, warnings) = KeyMap::from_overrides(&yaml[0].as_hash().unwrap());
+
+        let command = keymap.commands_for("normal", &Key::Char('k')).expect(
+            "Keymap doesn't contain valid command",
+        );
+        assert_eq!(
+            (command[0] as *const usize),
+            (commands::cursor::move_up as *const usize)
+        );
+        assert!(keymap.commands_for("normal", &Key::Char('j')).is_none());
+        assert!(keymap.get("unknown").is_none());
+        assert_eq!(warnings, vec![
+            String::from("Keymap command \"cursor::unknown\" doesn't exist (in \"normal\" mode)"),
+            String::from("Keymap mode \"unknown\" doesn't exist"),
+        ]);
+    }
+
     #[test]
     fn keymap_correctly_merges_keybindings() {
         let yaml_data = "normal:\n  k: cursor::move_up\n  j: cursor::move_down";
@@ -1,5 +1,7 @@
 pub use self::key_map::KeyMap;
 
+use std::fmt;
+
 mod key_map;
 
 #[derive(Clone, Debug, Eq, Hash, PartialEq)]
@@ -18,6 +20,7 @@ pub enum Key {
     Insert,
     Esc,
     Tab,
+    BackTab,
     Enter,
     AnyChar,
     Char(char),
@@ -26,3 +29,32 @@ pub enum Key {
     ScrollUp,
     ScrollDown,
 }
+
+impl fmt::Display for Key {
+    /// Writes the key as it's named in keymaps (e.g. `ctrl-r` or `space`).
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Key::Backspace => write!(f, "backspace"),
+            Key::Left => write!(f, "left"),
+            Key::Right => write!(f, "right"),
+            Key::Up => write!(f, "up"),
+            Key::Down => write!(f, "down"),
+            Key::Home => write!(f, "home"),
+            Key::End => write!(f, "end"),
+            Key::PageUp => write!(f, "page_up"),
+            Key::PageDown => write!(f, "page_down"),
+            Key::Delete => write!(f, "delete"),
+            Key::Insert => write!(f, "insert"),
+            Key::Esc => write!(f, "escape"),
+            Key::Tab => write!(f, "tab"),
+            Key::BackTab => write!(f, "backtab"),
+            Key::Enter => write!(f, "enter"),
+            Key::AnyChar => write!(f, "_"),
+            Key::Char(' ') => write!(f, "space"),
+            Key::Char(c) => write!(f, "{}", c),
+            Key::Ctrl(c) => write!(f, "ctrl-{}", c),
+            Key::ScrollUp => write!(f, "scroll_up"),
+            Key::ScrollDown => write!(f, "scroll_down"),
+        }
+    }
+}
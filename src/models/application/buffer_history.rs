@@ -0,0 +1,76 @@
+/// The order in which buffers were last focused, used to list them from
+/// most to least recently used. Buffers are identified using their
+/// workspace-assigned IDs.
+#[derive(Default)]
+pub struct BufferHistory {
+    ids: Vec<usize>,
+}
+
+impl BufferHistory {
+    pub fn new() -> BufferHistory {
+        BufferHistory::default()
+    }
+
+    /// Marks the buffer as the most recently used.
+    pub fn focus(&mut self, buffer_id: usize) {
+        if self.ids.last() != Some(&buffer_id) {
+            self.ids.retain(|&id| id != buffer_id);
+            self.ids.push(buffer_id);
+        }
+    }
+
+    /// The position of the buffer in the history, where zero is the most
+    /// recently used. Buffers that have never been focused come last.
+    pub fn rank(&self, buffer_id: usize) -> usize {
+        self.ids
+            .iter()
+            .rev()
+            .position(|&id| id == buffer_id)
+            .unwrap_or(self.ids.len())
+    }
+
+    /// The most recently used buffer, other than the specified one.
+    pub fn previous(&self, buffer_id: usize) -> Option<usize> {
+        self.ids.iter().rev().find(|&&id| id != buffer_id).cloned()
+    }
+
+    /// Discards the specified buffer from the history.
+    /// This method should be called whenever a buffer is closed.
+    pub fn forget_buffer(&mut self, buffer_id: usize) {
+        self.ids.retain(|&id| id != buffer_id);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::BufferHistory;
+
+    #[test]
+    fn rank_orders_buffers_from_most_to_least_recently_focused() {
+        let mut history = BufferHistory::new();
+        history.focus(0);
+        history.focus(1);
+        history.focus(2);
+        history.focus(0);
+
+        assert_eq!(history.rank(0), 0);
+        assert_eq!(history.rank(2), 1);
+        assert_eq!(history.rank(1), 2);
+        assert_eq!(history.rank(3), 3);
+    }
+
+    #[test]
+    fn previous_skips_the_specified_and_forgotten_buffers() {
+        let mut history = BufferHistory::new();
+        history.focus(0);
+        history.focus(1);
+        history.focus(2);
+        history.forget_buffer(1);
+
+        assert_eq!(history.previous(2), Some(0));
+        assert_eq!(history.previous(0), Some(2));
+
+        history.forget_buffer(0);
+        assert_eq!(history.previous(2), None);
+    }
+}
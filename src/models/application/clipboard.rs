@@ -1,42 +1,72 @@
 use crate::errors::*;
 use clipboard::{ClipboardContext, ClipboardProvider};
+use scribe::buffer::{Position, Range};
+use std::collections::VecDeque;
+use std::mem;
 
-/// In-app content can be captured in both regular and full-line selection
+/// In-app content can be captured in regular, full-line, and block selection
 /// modes. This type describes the structure of said content, based on the
-/// context in which it was captured. When OS-level clipboard contents are
-/// used, they are always represented as inline, as we cannot infer block
-/// style without the copy context.
+/// context in which it was captured; rectangular content holds one segment
+/// per selected row. When OS-level clipboard contents are used, they are
+/// always represented as inline, as we cannot infer block style without the
+/// copy context.
 #[derive(Debug, PartialEq)]
 pub enum ClipboardContent {
     Inline(String),
     Block(String),
+    Rectangular(Vec<String>),
     None,
 }
 
+/// Where the current content was last pasted, which is needed to cycle it with
+/// a previous entry. Content pasted over a selection replaced the listed ranges
+/// (in the order they were deleted), which were whole lines if `replaced_lines`
+/// is set; cycling replays that replacement with the previous entry.
+#[derive(Clone)]
+pub struct Paste {
+    pub buffer_id: usize,
+    pub position: Position,
+    pub replaced_ranges: Vec<Range>,
+    pub replaced_lines: bool,
+}
+
 /// Qualifies in-app copy/paste content with structural information, and
 /// synchronizes said content with the OS-level clipboard (preferring it
-/// in scenarios where it differs from the in-app equivalent).
+/// in scenarios where it differs from the in-app equivalent). Content that
+/// has been replaced is retained in a bounded history, which can be cycled.
 pub struct Clipboard {
     content: ClipboardContent,
+    history: VecDeque<ClipboardContent>,
+    history_size: usize,
+    paste: Option<Paste>,
+    paste_retained: bool,
     system_clipboard: Option<ClipboardContext>,
 }
 
 impl Default for Clipboard {
     fn default() -> Self {
-        Self::new()
+        Self::new(true, 0)
     }
 }
 
 impl Clipboard {
-    pub fn new() -> Clipboard {
+    /// Creates a clipboard, synchronized with the system clipboard when
+    /// requested. If the system clipboard is unavailable (e.g. there's no
+    /// display server), the in-app clipboard is used exclusively.
+    pub fn new(use_system_clipboard: bool, history_size: usize) -> Clipboard {
         // Initialize and keep a reference to the system clipboard.
-        let system_clipboard = match ClipboardProvider::new() {
-            Ok(clipboard) => Some(clipboard),
-            Err(_) => None,
+        let system_clipboard = if use_system_clipboard {
+            ClipboardProvider::new().ok()
+        } else {
+            None
         };
 
         Clipboard {
             content: ClipboardContent::None,
+            history: VecDeque::new(),
+            history_size,
+            paste: None,
+            paste_retained: false,
             system_clipboard,
         }
     }
@@ -65,6 +95,14 @@ impl Clipboard {
                                         None
                                     }
                                 }
+                                ClipboardContent::Rectangular(ref rows) => {
+                                    // Rows are synchronized as newline-separated text.
+                                    if content != rows.join("\n") {
+                                        Some(ClipboardContent::Inline(content))
+                                    } else {
+                                        None
+                                    }
+                                }
                                 // We have no in-app clipboard content. Use the system's.
                                 _ => Some(ClipboardContent::Inline(content)),
                             }
@@ -77,8 +115,8 @@ impl Clipboard {
         };
 
         // Update the in-app clipboard if we've found newer content.
-        if new_content.is_some() {
-            self.content = new_content.unwrap();
+        if let Some(content) = new_content {
+            self.replace_content(content);
         }
 
         &self.content
@@ -87,21 +125,175 @@ impl Clipboard {
     // Updates the in-app and system clipboards with the specified content.
     pub fn set_content(&mut self, content: ClipboardContent) -> Result<()> {
         // Update the in-app clipboard.
-        self.content = content;
+        self.replace_content(content);
 
         // Update the system clipboard.
-        match self.content {
+        self.sync_system_clipboard();
+
+        Ok(())
+    }
+
+    /// Replaces the current content with the most recent history entry,
+    /// moving the current content to the back of the history so that
+    /// repeated calls cycle through every entry. Returns false if there
+    /// is no history to cycle through.
+    pub fn cycle_content(&mut self) -> bool {
+        match self.history.pop_front() {
+            Some(previous_content) => {
+                let current_content = mem::replace(&mut self.content, previous_content);
+                self.history.push_back(current_content);
+                self.sync_system_clipboard();
+
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Tracks where the current content was last pasted.
+    pub fn set_paste(&mut self, paste: Paste) {
+        self.paste = Some(paste);
+        self.paste_retained = true;
+    }
+
+    /// The last paste, if it was made into the specified buffer and hasn't
+    /// since expired.
+    pub fn paste(&self, buffer_id: usize) -> Option<&Paste> {
+        self.paste.as_ref().filter(|paste| paste.buffer_id == buffer_id)
+    }
+
+    /// Forgets the last paste, unless it's been tracked since this was last
+    /// called, so that pasted content can only be cycled by the command that
+    /// immediately follows the paste (or a previous cycle). This method
+    /// should be called after every command.
+    pub fn expire_paste(&mut self) {
+        if !self.paste_retained {
+            self.paste = None;
+        }
+        self.paste_retained = false;
+    }
+
+    // Moves the current content into the history, discarding the oldest
+    // entries beyond the history size, and replaces it with new content.
+    fn replace_content(&mut self, content: ClipboardContent) {
+        let previous_content = mem::replace(&mut self.content, content);
+        if previous_content != ClipboardContent::None && self.history_size > 0 {
+            self.history.push_front(previous_content);
+            self.history.truncate(self.history_size);
+        }
+
+        // Pasted content has changed; it can no longer be cycled.
+        self.paste = None;
+    }
+
+    fn sync_system_clipboard(&mut self) {
+        let system_content = match self.content {
             ClipboardContent::Inline(ref app_content) |
-            ClipboardContent::Block(ref app_content) => {
-                if let Some(ref mut clipboard) = self.system_clipboard {
-                    return clipboard
-                        .set_contents(app_content.clone())
-                        .map_err(|_| Error::from("Failed to update system clipboard"));
-                }
+            ClipboardContent::Block(ref app_content) => Some(app_content.clone()),
+            ClipboardContent::Rectangular(ref rows) => Some(rows.join("\n")),
+            ClipboardContent::None => None,
+        };
+
+        if let Some(system_content) = system_content {
+            let updated = match self.system_clipboard {
+                Some(ref mut clipboard) => clipboard.set_contents(system_content).is_ok(),
+                None => true,
+            };
+
+            // The system clipboard has become unavailable; rather than
+            // failing, stop syncing and rely on the in-app content.
+            if !updated {
+                self.system_clipboard = None;
             }
-            _ => (),
         }
+    }
+}
 
-        Ok(())
+#[cfg(test)]
+mod tests {
+    use super::{Clipboard, ClipboardContent, Paste};
+    use scribe::buffer::Position;
+
+    #[test]
+    fn set_content_uses_in_app_content_when_system_clipboard_is_disabled() {
+        let mut clipboard = Clipboard::new(false, 10);
+        clipboard.set_content(ClipboardContent::Block("amp\n".to_string())).unwrap();
+
+        assert_eq!(
+            *clipboard.get_content(),
+            ClipboardContent::Block("amp\n".to_string())
+        );
+    }
+
+    #[test]
+    fn cycle_content_rotates_through_previous_content() {
+        let mut clipboard = Clipboard::new(false, 2);
+        clipboard.set_content(ClipboardContent::Inline("amp".to_string())).unwrap();
+        clipboard.set_content(ClipboardContent::Block("editor\n".to_string())).unwrap();
+        clipboard.set_content(ClipboardContent::Inline("buffer".to_string())).unwrap();
+
+        assert!(clipboard.cycle_content());
+        assert_eq!(
+            *clipboard.get_content(),
+            ClipboardContent::Block("editor\n".to_string())
+        );
+        assert!(clipboard.cycle_content());
+        assert_eq!(
+            *clipboard.get_content(),
+            ClipboardContent::Inline("amp".to_string())
+        );
+
+        // Cycling wraps back around to the original content.
+        assert!(clipboard.cycle_content());
+        assert_eq!(
+            *clipboard.get_content(),
+            ClipboardContent::Inline("buffer".to_string())
+        );
+    }
+
+    #[test]
+    fn history_is_limited_to_its_configured_size() {
+        let mut clipboard = Clipboard::new(false, 1);
+        clipboard.set_content(ClipboardContent::Inline("amp".to_string())).unwrap();
+        clipboard.set_content(ClipboardContent::Inline("editor".to_string())).unwrap();
+        clipboard.set_content(ClipboardContent::Inline("buffer".to_string())).unwrap();
+
+        assert!(clipboard.cycle_content());
+        assert_eq!(
+            *clipboard.get_content(),
+            ClipboardContent::Inline("editor".to_string())
+        );
+        assert!(clipboard.cycle_content());
+        assert_eq!(
+            *clipboard.get_content(),
+            ClipboardContent::Inline("buffer".to_string())
+        );
+    }
+
+    #[test]
+    fn paste_expires_unless_tracked_since_the_last_command() {
+        let mut clipboard = Clipboard::new(false, 10);
+        let position = Position { line: 1, offset: 2 };
+        clipboard.set_paste(Paste {
+            buffer_id: 0,
+            position,
+            replaced_ranges: Vec::new(),
+            replaced_lines: false,
+        });
+        clipboard.expire_paste();
+
+        assert_eq!(clipboard.paste(0).map(|paste| paste.position), Some(position));
+        assert!(clipboard.paste(1).is_none());
+
+        clipboard.expire_paste();
+        assert!(clipboard.paste(0).is_none());
+    }
+
+    #[test]
+    fn cycle_content_returns_false_without_history() {
+        let mut clipboard = Clipboard::new(false, 10);
+        clipboard.set_content(ClipboardContent::Inline("amp".to_string())).unwrap();
+
+        assert!(!clipboard.cycle_content());
     }
 }
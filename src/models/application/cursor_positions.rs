@@ -0,0 +1,157 @@
+use crate::errors::*;
+use crate::models::application::Preferences;
+use crate::util;
+use scribe::Buffer;
+use scribe::buffer::Position;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+const FILE_NAME: &str = "cursor_positions";
+const MAX_ENTRIES: usize = 1000;
+
+/// The last known cursor position for each file that's been worked on,
+/// ordered from least to most recently recorded, and persisted (one
+/// tab-delimited `line offset path` entry per line) to a file when a
+/// path is provided.
+pub struct CursorPositions {
+    entries: Vec<(PathBuf, Position)>,
+    path: Option<PathBuf>,
+}
+
+impl CursorPositions {
+    /// Builds a collection backed by the specified file, loading any existing
+    /// entries. A missing or unreadable file (or malformed entry) is ignored.
+    pub fn new(path: Option<PathBuf>) -> CursorPositions {
+        let entries = path
+            .as_ref()
+            .and_then(|path| fs::read_to_string(path).ok())
+            .map(|data| data.lines().filter_map(parse_entry).collect())
+            .unwrap_or_default();
+
+        CursorPositions { entries, path }
+    }
+
+    /// Loads the positions from the user preferences directory.
+    pub fn load() -> CursorPositions {
+        CursorPositions::new(Preferences::state_path(FILE_NAME))
+    }
+
+    /// Records the buffer's cursor position against its path, discarding
+    /// the oldest entries beyond the size limit, and persists the result.
+    /// Buffers without paths are ignored.
+    pub fn record(&mut self, buffer: &Buffer) -> Result<()> {
+        let path = match buffer.path {
+            Some(ref path) => normalize(path),
+            None => return Ok(()),
+        };
+
+        self.entries.retain(|(entry_path, _)| *entry_path != path);
+        self.entries.push((path, *buffer.cursor));
+        if self.entries.len() > MAX_ENTRIES {
+            let excess = self.entries.len() - MAX_ENTRIES;
+            self.entries.drain(..excess);
+        }
+
+        self.save()
+    }
+
+    /// Moves the buffer's cursor to the position last recorded for its path,
+    /// clamped to the buffer's current content.
+    pub fn restore(&self, buffer: &mut Buffer) {
+        if let Some(position) = buffer.path.as_ref().and_then(|path| self.get(path)) {
+            util::move_cursor_to_nearest(buffer, position);
+        }
+    }
+
+    pub fn get(&self, path: &Path) -> Option<Position> {
+        let path = normalize(path);
+
+        self.entries
+            .iter()
+            .find(|(entry_path, _)| *entry_path == path)
+            .map(|&(_, position)| position)
+    }
+
+    fn save(&self) -> Result<()> {
+        if let Some(ref path) = self.path {
+            let data: String = self.entries
+                .iter()
+                .map(|(path, position)| {
+                    format!("{}\t{}\t{}\n", position.line, position.offset, path.to_string_lossy())
+                })
+                .collect();
+
+            fs::write(path, data).chain_err(|| "Couldn't write cursor positions file")?;
+        }
+
+        Ok(())
+    }
+}
+
+fn parse_entry(entry: &str) -> Option<(PathBuf, Position)> {
+    let mut fields = entry.splitn(3, '\t');
+    let line = fields.next()?.parse().ok()?;
+    let offset = fields.next()?.parse().ok()?;
+    let path = PathBuf::from(fields.next()?);
+
+    Some((path, Position { line, offset }))
+}
+
+// Buffers opened through different routes can refer to the same
+// file using relative and absolute paths; resolve them when possible.
+fn normalize(path: &Path) -> PathBuf {
+    fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf())
+}
+
+#[cfg(test)]
+mod tests {
+    use scribe::Buffer;
+    use scribe::buffer::Position;
+    use std::env;
+    use std::fs;
+    use std::path::PathBuf;
+    use super::CursorPositions;
+
+    #[test]
+    fn positions_persist_to_their_file() {
+        let path = env::temp_dir().join("amp_cursor_positions_test");
+        let _ = fs::remove_file(&path);
+
+        let mut buffer = Buffer::new();
+        buffer.insert("amp\neditor");
+        buffer.path = Some(PathBuf::from("/amp/editor.rs"));
+        buffer.cursor.move_to(Position { line: 1, offset: 2 });
+
+        let mut positions = CursorPositions::new(Some(path.clone()));
+        positions.record(&buffer).unwrap();
+        buffer.cursor.move_to(Position { line: 1, offset: 3 });
+        positions.record(&buffer).unwrap();
+
+        let reloaded_positions = CursorPositions::new(Some(path.clone()));
+        assert_eq!(
+            reloaded_positions.get(&PathBuf::from("/amp/editor.rs")),
+            Some(Position { line: 1, offset: 3 })
+        );
+        assert_eq!(fs::read_to_string(&path).unwrap().lines().count(), 1);
+
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn restore_clamps_positions_beyond_the_buffer_content() {
+        let mut buffer = Buffer::new();
+        buffer.insert("amp\neditor\nbuffer");
+        buffer.path = Some(PathBuf::from("/amp/editor.rs"));
+        buffer.cursor.move_to(Position { line: 2, offset: 6 });
+
+        let mut positions = CursorPositions::new(None);
+        positions.record(&buffer).unwrap();
+
+        let mut shorter_buffer = Buffer::new();
+        shorter_buffer.insert("amp\nedit");
+        shorter_buffer.path = Some(PathBuf::from("/amp/editor.rs"));
+        positions.restore(&mut shorter_buffer);
+
+        assert_eq!(*shorter_buffer.cursor, Position { line: 1, offset: 4 });
+    }
+}
@@ -0,0 +1,102 @@
+use crate::input::Key;
+
+/// Records the key presses that made up the most recent change, so that it
+/// can be repeated. A change is a sequence of key presses that starts and
+/// ends in normal mode (e.g. entering insert mode, typing, and leaving it),
+/// during which a command modified the buffer. Sequences that don't modify
+/// the buffer (navigation, searching, etc.) are discarded.
+#[derive(Default)]
+pub struct LastChange {
+    keys: Vec<Key>,
+    pending_keys: Vec<Key>,
+    pending_change: bool,
+    replaying: bool,
+}
+
+impl LastChange {
+    pub fn new() -> LastChange {
+        LastChange::default()
+    }
+
+    /// The key presses that made up the last completed change.
+    pub fn keys(&self) -> &[Key] {
+        &self.keys
+    }
+
+    /// Adds a key press to the current sequence. Key presses
+    /// made in normal mode start a new sequence.
+    pub fn record_key(&mut self, key: Key, normal_mode: bool) {
+        if self.replaying {
+            return;
+        }
+        if normal_mode {
+            self.pending_keys.clear();
+            self.pending_change = false;
+        }
+
+        self.pending_keys.push(key);
+    }
+
+    /// Flags the current sequence as having modified the buffer.
+    pub fn record_change(&mut self) {
+        if !self.replaying {
+            self.pending_change = true;
+        }
+    }
+
+    /// Ends the current sequence, retaining it as the last
+    /// change if it modified the buffer. This method should be
+    /// called when the application returns to normal mode.
+    pub fn finish_sequence(&mut self) {
+        if self.pending_change {
+            self.keys = self.pending_keys.clone();
+        }
+
+        self.pending_keys.clear();
+        self.pending_change = false;
+    }
+
+    /// Suspends recording while the last change is being replayed, so that
+    /// the replayed key presses don't replace (or add to) the recording.
+    pub fn set_replaying(&mut self, replaying: bool) {
+        self.replaying = replaying;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::LastChange;
+    use crate::input::Key;
+
+    #[test]
+    fn finish_sequence_retains_sequences_that_modified_the_buffer() {
+        let mut last_change = LastChange::new();
+        last_change.record_key(Key::Char('i'), true);
+        last_change.record_change();
+        last_change.record_key(Key::Char('a'), false);
+        last_change.record_key(Key::Esc, false);
+        last_change.finish_sequence();
+
+        // Navigation shouldn't replace the recorded change.
+        last_change.record_key(Key::Char('j'), true);
+        last_change.finish_sequence();
+
+        assert_eq!(last_change.keys(), &[Key::Char('i'), Key::Char('a'), Key::Esc]);
+    }
+
+    #[test]
+    fn recording_is_suspended_while_replaying() {
+        let mut last_change = LastChange::new();
+        last_change.record_key(Key::Char('x'), true);
+        last_change.record_change();
+        last_change.finish_sequence();
+
+        last_change.set_replaying(true);
+        last_change.record_key(Key::Char('d'), true);
+        last_change.record_change();
+        last_change.set_replaying(false);
+        last_change.finish_sequence();
+
+        assert_eq!(last_change.keys(), &[Key::Char('x')]);
+    }
+}
@@ -0,0 +1,172 @@
+use crate::errors::*;
+use std::fmt::Display;
+use std::fs::{self, File, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use crate::util;
+use std::time::SystemTime;
+
+#[cfg(not(test))]
+use crate::models::application::Preferences;
+
+const FILE_NAME: &str = "log";
+
+/// Log entry severities, from most to least severe.
+#[derive(Clone, Copy, Debug, PartialEq, PartialOrd)]
+pub enum LogLevel {
+    Error,
+    Warn,
+    Info,
+    Debug,
+}
+
+impl LogLevel {
+    fn label(self) -> &'static str {
+        match self {
+            LogLevel::Error => "ERROR",
+            LogLevel::Warn => "WARN",
+            LogLevel::Info => "INFO",
+            LogLevel::Debug => "DEBUG",
+        }
+    }
+}
+
+/// An append-only application log file. Once the file grows beyond its size
+/// limit, it's rotated (to `log.1`, replacing any previous rotation) the next
+/// time it's opened, keeping recent history around without unbounded growth.
+///
+/// Entries are written one per line, prefixed with a UTC timestamp and their
+/// level. Entries less severe than the log's level are discarded without
+/// being formatted.
+pub struct Log {
+    file: File,
+    level: LogLevel,
+}
+
+impl Log {
+    pub fn new(path: &Path, size_limit: u64, level: LogLevel) -> Result<Log> {
+        let size = fs::metadata(path).map(|metadata| metadata.len()).unwrap_or(0);
+        if size > size_limit {
+            fs::rename(path, rotated_path(path)).chain_err(|| "Failed to rotate log file")?;
+        }
+
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .chain_err(|| "Failed to open log file")?;
+
+        Ok(Log { file, level })
+    }
+
+    /// Opens the log in the user preferences directory.
+    #[cfg(not(test))]
+    pub fn load(preferences: &Preferences) -> Result<Log> {
+        Log::new(
+            &Preferences::directory()?.join(FILE_NAME),
+            preferences.log_size_limit(),
+            preferences.log_level()
+        )
+    }
+
+    /// Don't write to the user's log during tests.
+    #[cfg(test)]
+    pub fn load(_: &crate::models::application::Preferences) -> Result<Log> {
+        bail!("Logging is disabled during tests")
+    }
+
+    pub fn error<T: Display>(&mut self, message: T) {
+        self.write(LogLevel::Error, message)
+    }
+
+    pub fn warn<T: Display>(&mut self, message: T) {
+        self.write(LogLevel::Warn, message)
+    }
+
+    pub fn info<T: Display>(&mut self, message: T) {
+        self.write(LogLevel::Info, message)
+    }
+
+    pub fn debug<T: Display>(&mut self, message: T) {
+        self.write(LogLevel::Debug, message)
+    }
+
+    // Logging is best-effort; failing to write an entry
+    // shouldn't interrupt whatever is being logged.
+    fn write<T: Display>(&mut self, level: LogLevel, message: T) {
+        if level > self.level {
+            return;
+        }
+
+        let _ = writeln!(self.file, "{} {} {}", timestamp(SystemTime::now()), level.label(), message);
+    }
+}
+
+fn rotated_path(path: &Path) -> PathBuf {
+    let mut file_name = path
+        .file_name()
+        .map(|name| name.to_os_string())
+        .unwrap_or_else(|| FILE_NAME.into());
+    file_name.push(".1");
+
+    path.with_file_name(file_name)
+}
+
+// Formats the time as an ISO 8601 UTC timestamp (e.g. 2020-01-01T00:00:00Z).
+fn timestamp(time: SystemTime) -> String {
+    util::time::format(time, util::time::ISO_8601)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Log, LogLevel};
+    use std::env;
+    use std::fs;
+    use std::time::{Duration, UNIX_EPOCH};
+
+    #[test]
+    fn new_appends_to_existing_logs() {
+        let path = env::temp_dir().join("amp_log_append_test");
+        fs::write(&path, "amp\n").unwrap();
+        Log::new(&path, 1024, LogLevel::Info).unwrap().info("editor");
+
+        let data = fs::read_to_string(&path).unwrap();
+        assert!(data.starts_with("amp\n"));
+        assert!(data.ends_with(" INFO editor\n"));
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn new_rotates_logs_exceeding_the_size_limit() {
+        let path = env::temp_dir().join("amp_log_rotation_test");
+        let rotated_path = env::temp_dir().join("amp_log_rotation_test.1");
+        fs::write(&path, "amp\n").unwrap();
+        Log::new(&path, 2, LogLevel::Info).unwrap().error("editor");
+
+        assert_eq!(fs::read_to_string(&rotated_path).unwrap(), "amp\n");
+        assert!(fs::read_to_string(&path).unwrap().ends_with(" ERROR editor\n"));
+        fs::remove_file(&path).unwrap();
+        fs::remove_file(&rotated_path).unwrap();
+    }
+
+    #[test]
+    fn entries_below_the_log_level_are_skipped() {
+        let path = env::temp_dir().join("amp_log_level_test");
+        let mut log = Log::new(&path, 1024, LogLevel::Warn).unwrap();
+        log.info("amp");
+        log.debug("amp");
+        log.warn("editor");
+
+        let data = fs::read_to_string(&path).unwrap();
+        assert_eq!(data.lines().count(), 1);
+        assert!(data.ends_with(" WARN editor\n"));
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn timestamp_formats_utc_dates() {
+        let time = UNIX_EPOCH + Duration::from_secs(951_827_696);
+
+        assert_eq!(super::timestamp(time), "2000-02-29T12:34:56Z");
+    }
+}
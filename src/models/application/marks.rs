@@ -0,0 +1,296 @@
+use scribe::buffer::Position;
+use std::collections::HashMap;
+
+// The number of positions retained in each buffer's jump and change lists.
+const JUMP_LIMIT: usize = 100;
+const CHANGE_LIMIT: usize = 100;
+
+/// Named cursor positions, jump lists, and change lists, tracked per buffer.
+/// Buffers are identified using their workspace-assigned IDs.
+#[derive(Default)]
+pub struct Marks {
+    buffers: HashMap<usize, BufferMarks>,
+}
+
+#[derive(Default)]
+struct BufferMarks {
+    named: HashMap<char, Position>,
+    previous_jumps: Vec<Position>,
+    next_jumps: Vec<Position>,
+    changes: Vec<Position>,
+    change_index: Option<usize>,
+}
+
+impl Marks {
+    pub fn new() -> Marks {
+        Marks::default()
+    }
+
+    pub fn set(&mut self, buffer_id: usize, name: char, position: Position) {
+        self.buffers
+            .entry(buffer_id)
+            .or_default()
+            .named
+            .insert(name, position);
+    }
+
+    pub fn get(&self, buffer_id: usize, name: char) -> Option<Position> {
+        self.buffers
+            .get(&buffer_id)
+            .and_then(|marks| marks.named.get(&name))
+            .cloned()
+    }
+
+    /// Records the position the cursor was at prior to jumping elsewhere,
+    /// discarding any positions that had been stepped back through, as well
+    /// as the oldest positions beyond the jump list's limit.
+    pub fn record_jump(&mut self, buffer_id: usize, position: Position) {
+        let marks = self.buffers.entry(buffer_id).or_default();
+        marks.next_jumps.clear();
+        if marks.previous_jumps.last() != Some(&position) {
+            marks.previous_jumps.push(position);
+        }
+        if marks.previous_jumps.len() > JUMP_LIMIT {
+            let excess = marks.previous_jumps.len() - JUMP_LIMIT;
+            marks.previous_jumps.drain(..excess);
+        }
+    }
+
+    /// Steps back through the jump list, returning the position to move to.
+    /// The current position is retained, so that it can be stepped forward to.
+    pub fn previous_jump(&mut self, buffer_id: usize, current: Position) -> Option<Position> {
+        let marks = self.buffers.get_mut(&buffer_id)?;
+        let position = marks.previous_jumps.pop()?;
+        marks.next_jumps.push(current);
+
+        Some(position)
+    }
+
+    /// Steps forward through positions previously stepped back through.
+    pub fn next_jump(&mut self, buffer_id: usize, current: Position) -> Option<Position> {
+        let marks = self.buffers.get_mut(&buffer_id)?;
+        let position = marks.next_jumps.pop()?;
+        marks.previous_jumps.push(current);
+
+        Some(position)
+    }
+
+    /// Discards positions that had been stepped back through. This method
+    /// should be called whenever the buffer is modified, so that editing
+    /// somewhere starts a new branch of jump history.
+    pub fn truncate_jumps(&mut self, buffer_id: usize) {
+        if let Some(marks) = self.buffers.get_mut(&buffer_id) {
+            marks.next_jumps.clear();
+        }
+    }
+
+    /// Records the position of an edit, replacing the last recorded position
+    /// if it's on the same line (so that typing a word adds a single entry),
+    /// and discarding the oldest positions beyond the change list's limit.
+    /// Cycling through the list starts over from the newest change.
+    pub fn record_change(&mut self, buffer_id: usize, position: Position) {
+        let marks = self.buffers.entry(buffer_id).or_default();
+        marks.change_index = None;
+        if marks.changes.last().map(|change| change.line) == Some(position.line) {
+            marks.changes.pop();
+        }
+        marks.changes.push(position);
+        if marks.changes.len() > CHANGE_LIMIT {
+            let excess = marks.changes.len() - CHANGE_LIMIT;
+            marks.changes.drain(..excess);
+        }
+    }
+
+    /// Steps to the next oldest change, starting with the newest.
+    pub fn previous_change(&mut self, buffer_id: usize) -> Option<Position> {
+        let marks = self.buffers.get_mut(&buffer_id)?;
+        let index = marks.change_index.unwrap_or(marks.changes.len()).checked_sub(1)?;
+        marks.change_index = Some(index);
+
+        marks.changes.get(index).cloned()
+    }
+
+    /// Steps back towards the newest change, after stepping through older ones.
+    pub fn next_change(&mut self, buffer_id: usize) -> Option<Position> {
+        let marks = self.buffers.get_mut(&buffer_id)?;
+        let index = marks.change_index? + 1;
+        let position = marks.changes.get(index).cloned()?;
+        marks.change_index = Some(index);
+
+        Some(position)
+    }
+
+    /// Shifts marks to account for lines having been inserted (positive delta)
+    /// or removed (negative delta) immediately below the specified line.
+    /// Marks on removed lines no longer point to anything, and are discarded.
+    pub fn adjust(&mut self, buffer_id: usize, line: usize, delta: isize) {
+        let marks = match self.buffers.get_mut(&buffer_id) {
+            Some(marks) => marks,
+            None => return,
+        };
+
+        marks.named.retain(|_, position| adjust_position(position, line, delta));
+        adjust_positions(&mut marks.previous_jumps, line, delta);
+        adjust_positions(&mut marks.next_jumps, line, delta);
+        adjust_positions(&mut marks.changes, line, delta);
+        marks.change_index = marks.change_index.map(|index| index.min(marks.changes.len()));
+    }
+
+    /// Discards marks for the specified buffer. This method
+    /// should be called whenever a buffer is closed.
+    pub fn forget_buffer(&mut self, buffer_id: usize) {
+        self.buffers.remove(&buffer_id);
+    }
+}
+
+// Adjusts each of the positions, discarding those on removed lines.
+fn adjust_positions(positions: &mut Vec<Position>, line: usize, delta: isize) {
+    *positions = positions
+        .drain(..)
+        .filter_map(|mut position| if adjust_position(&mut position, line, delta) {
+            Some(position)
+        } else {
+            None
+        })
+        .collect();
+}
+
+// Moves the position to reflect a change in line count below the specified
+// line, returning false if the line on which it resides was removed.
+fn adjust_position(position: &mut Position, line: usize, delta: isize) -> bool {
+    if position.line <= line {
+        return true;
+    }
+
+    if delta >= 0 {
+        position.line += delta as usize;
+    } else {
+        let removed = delta.abs() as usize;
+        if position.line <= line + removed {
+            return false;
+        }
+        position.line -= removed;
+    }
+
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Marks;
+    use scribe::buffer::Position;
+
+    #[test]
+    fn adjust_shifts_marks_below_inserted_lines() {
+        let mut marks = Marks::new();
+        marks.set(0, 'a', Position{ line: 1, offset: 2 });
+        marks.set(0, 'b', Position{ line: 3, offset: 2 });
+        marks.record_jump(0, Position{ line: 4, offset: 0 });
+        marks.adjust(0, 1, 2);
+
+        assert_eq!(marks.get(0, 'a'), Some(Position{ line: 1, offset: 2 }));
+        assert_eq!(marks.get(0, 'b'), Some(Position{ line: 5, offset: 2 }));
+        assert_eq!(
+            marks.previous_jump(0, Position{ line: 0, offset: 0 }),
+            Some(Position{ line: 6, offset: 0 })
+        );
+    }
+
+    #[test]
+    fn adjust_shifts_marks_below_removed_lines_and_discards_removed_marks() {
+        let mut marks = Marks::new();
+        marks.set(0, 'a', Position{ line: 2, offset: 0 });
+        marks.set(0, 'b', Position{ line: 5, offset: 1 });
+        marks.record_jump(0, Position{ line: 3, offset: 0 });
+        marks.adjust(0, 1, -2);
+
+        assert_eq!(marks.get(0, 'a'), None);
+        assert_eq!(marks.get(0, 'b'), Some(Position{ line: 3, offset: 1 }));
+        assert_eq!(marks.previous_jump(0, Position{ line: 0, offset: 0 }), None);
+    }
+
+    #[test]
+    fn jumps_can_be_stepped_through_in_both_directions() {
+        let mut marks = Marks::new();
+        let positions: Vec<_> = (0..3).map(|line| Position{ line, offset: 0 }).collect();
+        marks.record_jump(0, positions[0]);
+        marks.record_jump(0, positions[1]);
+
+        assert_eq!(marks.previous_jump(0, positions[2]), Some(positions[1]));
+        assert_eq!(marks.previous_jump(0, positions[1]), Some(positions[0]));
+        assert_eq!(marks.previous_jump(0, positions[0]), None);
+        assert_eq!(marks.next_jump(0, positions[0]), Some(positions[1]));
+        assert_eq!(marks.next_jump(0, positions[1]), Some(positions[2]));
+        assert_eq!(marks.next_jump(0, positions[2]), None);
+    }
+
+    #[test]
+    fn new_jumps_and_edits_truncate_forward_history() {
+        let mut marks = Marks::new();
+        let positions: Vec<_> = (0..3).map(|line| Position{ line, offset: 0 }).collect();
+        marks.record_jump(0, positions[0]);
+        marks.previous_jump(0, positions[1]);
+        marks.truncate_jumps(0);
+        assert_eq!(marks.next_jump(0, positions[0]), None);
+
+        marks.record_jump(0, positions[1]);
+        marks.previous_jump(0, positions[2]);
+        marks.record_jump(0, positions[0]);
+        assert_eq!(marks.next_jump(0, positions[0]), None);
+    }
+
+    #[test]
+    fn record_jump_discards_the_oldest_positions_beyond_the_limit() {
+        let mut marks = Marks::new();
+        for line in 0..super::JUMP_LIMIT + 1 {
+            marks.record_jump(0, Position{ line, offset: 0 });
+        }
+
+        let mut count = 0;
+        while marks.previous_jump(0, Position{ line: 0, offset: 0 }).is_some() {
+            count += 1;
+        }
+        assert_eq!(count, super::JUMP_LIMIT);
+    }
+
+    #[test]
+    fn changes_can_be_cycled_through_in_both_directions() {
+        let mut marks = Marks::new();
+        marks.record_change(0, Position{ line: 0, offset: 1 });
+        marks.record_change(0, Position{ line: 2, offset: 1 });
+        marks.record_change(0, Position{ line: 2, offset: 3 });
+        marks.record_change(0, Position{ line: 4, offset: 0 });
+
+        assert_eq!(marks.next_change(0), None);
+        assert_eq!(marks.previous_change(0), Some(Position{ line: 4, offset: 0 }));
+        assert_eq!(marks.previous_change(0), Some(Position{ line: 2, offset: 3 }));
+        assert_eq!(marks.previous_change(0), Some(Position{ line: 0, offset: 1 }));
+        assert_eq!(marks.previous_change(0), None);
+        assert_eq!(marks.next_change(0), Some(Position{ line: 2, offset: 3 }));
+
+        // Recording a change starts over from the newest.
+        marks.record_change(0, Position{ line: 1, offset: 0 });
+        assert_eq!(marks.previous_change(0), Some(Position{ line: 1, offset: 0 }));
+    }
+
+    #[test]
+    fn adjust_shifts_and_discards_changes() {
+        let mut marks = Marks::new();
+        marks.record_change(0, Position{ line: 2, offset: 0 });
+        marks.record_change(0, Position{ line: 5, offset: 1 });
+        marks.adjust(0, 1, -2);
+
+        assert_eq!(marks.previous_change(0), Some(Position{ line: 3, offset: 1 }));
+        assert_eq!(marks.previous_change(0), None);
+    }
+
+    #[test]
+    fn marks_are_tracked_per_buffer() {
+        let mut marks = Marks::new();
+        marks.set(0, 'a', Position{ line: 2, offset: 0 });
+        marks.adjust(1, 0, 5);
+
+        assert_eq!(marks.get(0, 'a'), Some(Position{ line: 2, offset: 0 }));
+        assert_eq!(marks.get(1, 'a'), None);
+    }
+}
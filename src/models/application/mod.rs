@@ -1,43 +1,83 @@
+mod buffer_history;
 mod clipboard;
+mod cursor_positions;
 mod editorconfig;
 mod event;
+mod last_change;
+mod log;
+mod marks;
+mod modification_times;
 pub mod modes;
+mod pending_keys;
 mod preferences;
+mod preferences_watcher;
+mod read_only_buffers;
+mod recent_files;
+mod search_history;
+mod secondary_cursors;
 
 // Published API
-pub use self::clipboard::ClipboardContent;
+pub use self::clipboard::{ClipboardContent, Paste};
 pub use self::event::Event;
+pub use self::log::LogLevel;
 pub use self::preferences::Preferences;
+pub use self::secondary_cursors::SecondaryCursors;
 
+use self::buffer_history::BufferHistory;
 use self::clipboard::Clipboard;
+use self::cursor_positions::CursorPositions;
+use self::last_change::LastChange;
+use self::log::Log;
+use self::marks::Marks;
+use self::modification_times::ModificationTimes;
+use self::pending_keys::PendingKeys;
+use self::preferences_watcher::PreferencesWatcher;
+use self::read_only_buffers::ReadOnlyBuffers;
+use self::recent_files::RecentFiles;
+use self::search_history::SearchHistory;
 use self::modes::*;
 use crate::commands;
 use crate::errors::*;
 use git2::Repository;
 use crate::presenters;
+use crate::util;
 use scribe::{Buffer, Workspace};
+use scribe::buffer::Position;
 use std::cell::RefCell;
+use std::cmp;
 use std::env;
 use std::path::Path;
 use std::rc::Rc;
-use std::sync::mpsc::{self, Receiver, Sender};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError, Sender};
+use std::time::{Duration, Instant};
 use crate::view::View;
 
 pub enum Mode {
+    Align(AlignMode),
+    BufferSwitcher(BufferSwitcherMode),
     Confirm(ConfirmMode),
     Command(CommandMode),
     Exit,
+    Filter(FilterMode),
     Insert,
     Jump(JumpMode),
     LineJump(LineJumpMode),
+    Mark(MarkMode),
     Path(PathMode),
     Normal,
     Open(OpenMode),
+    Recent(RecentMode),
+    Replace(ReplaceMode),
+    ReplaceCharacter,
     Select(SelectMode),
+    SelectBlock(SelectBlockMode),
     SelectLine(SelectLineMode),
+    Spelling(SpellingMode),
+    Surround(SurroundMode),
     Search(SearchMode),
     SymbolJump(SymbolJumpMode),
     Syntax(SyntaxMode),
+    TextObject(TextObjectMode),
     Theme(ThemeMode),
 }
 
@@ -45,38 +85,121 @@ pub struct Application {
     pub mode: Mode,
     pub workspace: Workspace,
     pub search_query: Option<String>,
+    pub search_history: SearchHistory,
+    pub cursor_positions: CursorPositions,
+    pub recent_files: RecentFiles,
+    pub replacement: Option<String>,
     pub view: View,
     pub clipboard: Clipboard,
+    pub last_change: LastChange,
+    pub pending_keys: PendingKeys,
+    pub count: Option<usize>,
+    pub marks: Marks,
+    pub modification_times: ModificationTimes,
+    pub read_only_buffers: ReadOnlyBuffers,
+    pub secondary_cursors: SecondaryCursors,
+    pub buffer_history: BufferHistory,
     pub repository: Option<Repository>,
     pub error: Option<Error>,
+    pub message: Option<String>,
     pub preferences: Rc<RefCell<Preferences>>,
+    preferences_watcher: PreferencesWatcher,
     pub event_channel: Sender<Event>,
     events: Receiver<Event>,
+    last_edit: Option<Instant>,
+    log: Option<Log>,
 }
 
 impl Application {
     pub fn new(args: &Vec<String>) -> Result<Application> {
         let preferences = initialize_preferences();
+        let log = Log::load(&preferences.borrow()).ok();
+
+        // Read piped input before the view claims the terminal.
+        let piped_input = read_piped_input()?;
 
         let (event_channel, events) = mpsc::channel();
         let mut view = View::new(preferences.clone(), event_channel.clone())?;
-        let clipboard = Clipboard::new();
+        let clipboard = Clipboard::new(
+            preferences.borrow().system_clipboard(),
+            preferences.borrow().clipboard_history_size()
+        );
 
         // Set up a workspace in the current directory.
-        let workspace = create_workspace(&mut view, &preferences.borrow(), args)?;
+        let directory_argument = args.iter().skip(1).any(|arg| Path::new(arg).is_dir());
+        let (workspace, argument_positions) =
+            create_workspace(&mut view, &preferences.borrow(), args)?;
+
+        // Project preferences are found in the workspace directory,
+        // which may have been changed by a directory argument.
+        if directory_argument {
+            let _ = preferences.borrow_mut().reload();
+        }
 
-        Ok(Application {
+        let mut app = Application {
             mode: Mode::Normal,
             workspace,
             search_query: None,
+            search_history: SearchHistory::load(),
+            cursor_positions: CursorPositions::load(),
+            recent_files: RecentFiles::load(preferences.borrow().recent_files_limit()),
+            replacement: None,
             view,
             clipboard,
+            last_change: LastChange::new(),
+            pending_keys: PendingKeys::new(),
+            count: None,
+            marks: Marks::new(),
+            modification_times: ModificationTimes::new(),
+            read_only_buffers: ReadOnlyBuffers::new(),
+            secondary_cursors: SecondaryCursors::new(),
+            buffer_history: BufferHistory::new(),
             repository: Repository::discover(&env::current_dir()?).ok(),
             error: None,
+            message: None,
             preferences,
+            preferences_watcher: PreferencesWatcher::new(Preferences::paths()),
             event_channel,
             events,
-        })
+            last_edit: None,
+            log,
+        };
+
+        // Return to where we left off in files opened via arguments,
+        // unless they specified a position (e.g. `src/main.rs:120`).
+        util::for_each_buffer(&mut app, |app| {
+            let buffer = app.workspace.current_buffer().ok_or(BUFFER_MISSING)?;
+            let position = argument_positions
+                .iter()
+                .find(|(id, _)| buffer.id == Some(*id))
+                .map(|&(_, position)| position);
+            if let Some(position) = position {
+                util::move_cursor_to_nearest(buffer, position);
+                app.view.scroll_to_center(buffer)?;
+            } else {
+                util::restore_cursor_position(app)?;
+            }
+            util::record_recent_file(app)
+        })?;
+
+        // Open piped input (e.g. `cargo build 2>&1 | amp`) in a scratch buffer.
+        // It has no path, so saving it prompts for one.
+        if let Some(data) = piped_input {
+            let mut buffer = Buffer::new();
+            buffer.insert(data);
+            util::add_buffer(buffer, &mut app)?;
+        }
+
+        if let Some(warnings) = app.preference_warnings() {
+            app.message = Some(warnings);
+        }
+
+        // Present the file finder when opening a directory on its own.
+        if directory_argument && app.workspace.current_buffer().is_none() {
+            commands::application::switch_to_open_mode(&mut app)?;
+        }
+
+        Ok(app)
     }
 
     pub fn run(&mut self) -> Result<()> {
@@ -98,10 +221,24 @@ impl Application {
         } else if let Some(ref error) = self.error {
             // Display an error from previous command invocation, if one exists.
             presenters::error::display(&mut self.workspace, &mut self.view, error);
+        } else if let Some(ref message) = self.message {
+            // Display a non-fatal message from the previous command invocation.
+            presenters::message::display(&mut self.workspace, &mut self.view, message);
+        } else if self.key_sequence_in_progress() {
+            // Let the user know that we're waiting on the rest of a key sequence.
+            let mut prefix: Vec<String> =
+                self.count.iter().map(|count| count.to_string()).collect();
+            if !self.pending_keys.is_empty() {
+                prefix.push(self.pending_keys.description());
+            }
+            let message = format!("{} \u{2026}", prefix.join(" "));
+            presenters::message::display(&mut self.workspace, &mut self.view, &message);
         }
     }
 
     fn present(&mut self) -> Result<()> {
+        self.view.render_unfocused_pane(&mut self.workspace)?;
+
         match self.mode {
             Mode::Confirm(_) => {
                 presenters::modes::confirm::display(&mut self.workspace, &mut self.view)
@@ -109,7 +246,11 @@ impl Application {
             Mode::Command(ref mut mode) => {
                 presenters::modes::search_select::display(&mut self.workspace, mode, &mut self.view)
             }
-            Mode::Insert => presenters::modes::insert::display(&mut self.workspace, &mut self.view),
+            Mode::Insert => presenters::modes::insert::display(
+                &mut self.workspace,
+                &mut self.view,
+                &self.secondary_cursors,
+            ),
             Mode::Open(ref mut mode) => {
                 presenters::modes::search_select::display(&mut self.workspace, mode, &mut self.view)
             }
@@ -122,25 +263,59 @@ impl Application {
             Mode::LineJump(ref mode) => {
                 presenters::modes::line_jump::display(&mut self.workspace, mode, &mut self.view)
             }
+            Mode::Mark(ref mode) => {
+                presenters::modes::mark::display(&mut self.workspace, mode, &mut self.view)
+            }
             Mode::Path(ref mode) => {
                 presenters::modes::path::display(&mut self.workspace, mode, &mut self.view)
             }
+            Mode::Replace(ref mode) => {
+                presenters::modes::replace::display(&mut self.workspace, mode, &mut self.view)
+            }
+            Mode::ReplaceCharacter => {
+                presenters::modes::replace_character::display(&mut self.workspace, &mut self.view)
+            }
             Mode::SymbolJump(ref mut mode) => {
                 presenters::modes::search_select::display(&mut self.workspace, mode, &mut self.view)
             }
+            Mode::BufferSwitcher(ref mut mode) => {
+                presenters::modes::search_select::display(&mut self.workspace, mode, &mut self.view)
+            }
+            Mode::Recent(ref mut mode) => {
+                presenters::modes::search_select::display(&mut self.workspace, mode, &mut self.view)
+            }
             Mode::Syntax(ref mut mode) => {
                 presenters::modes::search_select::display(&mut self.workspace, mode, &mut self.view)
             }
+            Mode::Spelling(ref mut mode) => {
+                presenters::modes::search_select::display(&mut self.workspace, mode, &mut self.view)
+            }
             Mode::Select(ref mode) => {
                 presenters::modes::select::display(&mut self.workspace, mode, &mut self.view)
             }
+            Mode::SelectBlock(ref mode) => {
+                presenters::modes::select_block::display(&mut self.workspace, mode, &mut self.view)
+            }
             Mode::SelectLine(ref mode) => {
                 presenters::modes::select_line::display(&mut self.workspace, mode, &mut self.view)
             }
+            Mode::Filter(ref mode) => {
+                presenters::modes::filter::display(&mut self.workspace, mode, &mut self.view)
+            }
+            Mode::Align(ref mode) => {
+                presenters::modes::align::display(&mut self.workspace, mode, &mut self.view)
+            }
+            Mode::Surround(ref mode) => {
+                presenters::modes::surround::display(&mut self.workspace, mode, &mut self.view)
+            }
+            Mode::TextObject(ref mode) => {
+                presenters::modes::text_object::display(&mut self.workspace, mode, &mut self.view)
+            }
             Mode::Normal => presenters::modes::normal::display(
                 &mut self.workspace,
                 &mut self.view,
                 &self.repository,
+                &self.secondary_cursors,
             ),
             Mode::Theme(ref mut mode) => {
                 presenters::modes::search_select::display(&mut self.workspace, mode, &mut self.view)
@@ -150,14 +325,77 @@ impl Application {
     }
 
     fn wait_for_event(&mut self) -> Result<()> {
-        let event = self
-            .events
-            .recv()
-            .chain_err(|| "Error receiving application event")?;
+        let key_sequence_timeout = self.preferences.borrow().key_sequence_timeout();
+        let pending_keys_timeout = self.pending_keys.timeout(key_sequence_timeout);
+        let scroll_animation_timeout = self.view.scroll_animation_timeout();
+        let timeout = [self.autosave_timeout(), pending_keys_timeout, scroll_animation_timeout]
+            .iter()
+            .flatten()
+            .min()
+            .cloned();
+        let event = match timeout {
+            Some(timeout) => match self.events.recv_timeout(timeout) {
+                Ok(event) => event,
+                Err(RecvTimeoutError::Timeout) => {
+                    if pending_keys_timeout == Some(timeout) {
+                        // Give up on the rest of the key sequence.
+                        self.message = None;
+                        self.error = commands::application::handle_pending_keys(self).err();
+                        if self.in_normal_mode() {
+                            self.last_change.finish_sequence();
+                        }
+                    } else if scroll_animation_timeout == Some(timeout) {
+                        self.view.step_scroll_animations();
+                    } else {
+                        self.autosave();
+                    }
+                    return Ok(());
+                }
+                Err(RecvTimeoutError::Disconnected) => bail!("Error receiving application event"),
+            },
+            None => self
+                .events
+                .recv()
+                .chain_err(|| "Error receiving application event")?,
+        };
         match event {
             Event::Key(key) => {
+                self.view.finish_scroll_animations();
+
+                // Keys continuing a sequence belong to the change it started.
+                let starts_change = self.in_normal_mode() && !self.key_sequence_in_progress();
+                self.last_change.record_key(key.clone(), starts_change);
                 self.view.last_key = Some(key);
+                let buffer_state = self.buffer_state();
+                self.message = None;
                 self.error = commands::application::handle_input(self).err();
+                if self.in_normal_mode() && !self.key_sequence_in_progress() {
+                    self.last_change.finish_sequence();
+                }
+                let cursor_state = self.workspace.current_buffer()
+                    .map(|buffer| (buffer.id, *buffer.cursor));
+                self.secondary_cursors.clear_if_stale(
+                    cursor_state.and_then(|(id, _)| id),
+                    cursor_state.map(|(_, cursor)| cursor)
+                );
+                if let Some((Some(id), _)) = cursor_state {
+                    self.buffer_history.focus(id);
+                }
+                if let (Some(log), Some(error)) = (self.log.as_mut(), self.error.as_ref()) {
+                    log.error(error);
+                }
+                self.adjust_marks(buffer_state);
+
+                self.check_for_external_changes();
+                self.reload_changed_preferences();
+
+                // Restart the autosave timer if there are unsaved changes.
+                let modified = self.workspace.current_buffer().map(|b| b.modified());
+                self.last_edit = if modified == Some(true) {
+                    Some(Instant::now())
+                } else {
+                    None
+                };
             }
             Event::Resize => {}
             Event::OpenModeIndexComplete(index) => {
@@ -174,6 +412,166 @@ impl Application {
         Ok(())
     }
 
+    // The time remaining until the current buffer should be autosaved,
+    // if autosaving is enabled and the buffer has been modified.
+    fn autosave_timeout(&self) -> Option<Duration> {
+        let interval = self.preferences.borrow().autosave_interval()?;
+        let last_edit = self.last_edit?;
+
+        Some(interval.checked_sub(last_edit.elapsed()).unwrap_or_default())
+    }
+
+    // Writes the current buffer if it has a path and unsaved changes. Insert
+    // mode holds an open operation group, so we hold off until it's closed
+    // (the timer restarts with the key press that leaves insert mode).
+    fn autosave(&mut self) {
+        self.last_edit = None;
+        if let Mode::Insert = self.mode {
+            return;
+        }
+
+        let path = match self.workspace.current_buffer() {
+            Some(ref buffer) if buffer.modified() &&
+                !self.read_only_buffers.is_read_only(buffer) => buffer.path.clone(),
+            _ => None,
+        };
+
+        if let Some(path) = path {
+            match commands::buffer::write_current_buffer(self) {
+                Ok(_) => {
+                    let message = format!("Autosaved {}", path.to_string_lossy());
+                    if let Some(ref mut log) = self.log {
+                        log.info(&message);
+                    }
+                    self.message = Some(message);
+                }
+                Err(error) => self.error = Some(error),
+            }
+        }
+    }
+
+    // Lets the user know if the current buffer's file has been
+    // modified by another program since it was last read or written.
+    fn check_for_external_changes(&mut self) {
+        if let Some(buffer) = self.workspace.current_buffer() {
+            if self.modification_times.changed_externally(buffer) && self.error.is_none() {
+                self.message = Some(String::from(
+                    "File changed on disk; use buffer::reload (ctrl-r) to load the changes"
+                ));
+            }
+        }
+    }
+
+    // Captures the current buffer's ID, cursor line, and line count.
+    fn buffer_state(&mut self) -> Option<(usize, usize, usize)> {
+        self.workspace.current_buffer().and_then(|buffer| {
+            buffer.id.map(|id| (id, buffer.cursor.line, buffer.line_count()))
+        })
+    }
+
+    // Shifts the current buffer's marks if the last command changed its line
+    // count. Edits happen at the cursor, so we treat the earlier of its lines
+    // (before and after the command) as the location of the change.
+    fn adjust_marks(&mut self, previous_state: Option<(usize, usize, usize)>) {
+        if let (Some((id, line, line_count)), Some((current_id, current_line, current_line_count))) =
+            (previous_state, self.buffer_state()) {
+            if id == current_id && line_count != current_line_count {
+                self.marks.adjust(
+                    id,
+                    cmp::min(line, current_line),
+                    current_line_count as isize - line_count as isize
+                );
+            }
+        }
+    }
+
+    /// Reloads preferences from disk, re-applying those that are held outside
+    /// of them (i.e. themes and logging). Clipboard settings, the recent files
+    /// limit, and user syntax definitions only take effect after a restart.
+    pub fn reload_preferences(&mut self) -> Result<()> {
+        self.preferences.borrow_mut().reload()?;
+        self.preferences_watcher = PreferencesWatcher::new(Preferences::paths());
+        self.view.reload_themes()?;
+        self.view.reload_spell_checker();
+        self.log = Log::load(&self.preferences.borrow()).ok();
+
+        self.message = Some(
+            self.preference_warnings().unwrap_or_else(|| String::from("Reloaded preferences"))
+        );
+
+        Ok(())
+    }
+
+    // Reloads preferences if any of the files they're loaded from have changed.
+    fn reload_changed_preferences(&mut self) {
+        if !self.preferences.borrow().watch_preferences() || !self.preferences_watcher.changed() {
+            return;
+        }
+
+        if let Err(error) = self.reload_preferences() {
+            if let Some(ref mut log) = self.log {
+                log.error(&error);
+            }
+            self.error = Some(error);
+        }
+    }
+
+    // Logs and describes user-defined key bindings, project preferences, and
+    // themes that couldn't be loaded; they're skipped in favour of the defaults.
+    fn preference_warnings(&mut self) -> Option<String> {
+        let keymap_warnings = self.preferences.borrow().keymap_warnings().to_vec();
+        let project_warning = self.preferences.borrow().project_warning().map(String::from);
+        if let Some(ref mut log) = self.log {
+            for warning in keymap_warnings.iter().chain(project_warning.iter()) {
+                log.warn(warning);
+            }
+        }
+
+        let mut warnings = Vec::new();
+        if !keymap_warnings.is_empty() {
+            warnings.push(format!("Skipped invalid key bindings: {}", keymap_warnings.join("; ")));
+        }
+        warnings.extend(project_warning);
+
+        let theme = self.preferences.borrow().theme().to_string();
+        if !self.view.theme_set.themes.contains_key(&theme) {
+            let warning = format!(
+                "Couldn't find \"{}\" theme; using {}",
+                theme,
+                self.preferences.borrow().default_theme()
+            );
+            if let Some(ref mut log) = self.log {
+                log.warn(&warning);
+            }
+            warnings.push(warning);
+        }
+
+        if warnings.is_empty() {
+            None
+        } else {
+            Some(warnings.join(" | "))
+        }
+    }
+
+    /// Consumes the count typed ahead of the current command (e.g. the 5
+    /// in 5j), for commands that can be repeated. Defaults to one.
+    pub fn take_count(&mut self) -> usize {
+        self.count.take().unwrap_or(1)
+    }
+
+    // Whether a count or the leading keys of a multi-key
+    // binding are waiting on the rest of the command.
+    fn key_sequence_in_progress(&self) -> bool {
+        self.count.is_some() || !self.pending_keys.is_empty()
+    }
+
+    fn in_normal_mode(&self) -> bool {
+        match self.mode {
+            Mode::Normal => true,
+            _ => false,
+        }
+    }
+
     pub fn mode_str(&self) -> Option<&'static str> {
         match self.mode {
             Mode::Command(ref mode) => if mode.insert_mode() {
@@ -196,6 +594,21 @@ impl Application {
             } else {
                 Some("search_select")
             },
+            Mode::Spelling(ref mode) => if mode.insert_mode() {
+                Some("search_select_insert")
+            } else {
+                Some("search_select")
+            },
+            Mode::BufferSwitcher(ref mode) => if mode.insert_mode() {
+                Some("search_select_insert")
+            } else {
+                Some("search_select")
+            },
+            Mode::Recent(ref mode) => if mode.insert_mode() {
+                Some("search_select_insert")
+            } else {
+                Some("search_select")
+            },
             Mode::Syntax(ref mode) => if mode.insert_mode() {
                 Some("search_select_insert")
             } else {
@@ -203,12 +616,20 @@ impl Application {
             },
             Mode::Normal => Some("normal"),
             Mode::Path(_) => Some("path"),
+            Mode::Replace(_) => Some("replace"),
+            Mode::ReplaceCharacter => Some("replace_character"),
             Mode::Confirm(_) => Some("confirm"),
             Mode::Insert => Some("insert"),
             Mode::Jump(_) => Some("jump"),
             Mode::LineJump(_) => Some("line_jump"),
+            Mode::Mark(_) => Some("mark"),
             Mode::Select(_) => Some("select"),
+            Mode::SelectBlock(_) => Some("select_block"),
             Mode::SelectLine(_) => Some("select_line"),
+            Mode::Surround(_) => Some("surround"),
+            Mode::Align(_) => Some("align"),
+            Mode::Filter(_) => Some("filter"),
+            Mode::TextObject(_) => Some("text_object"),
             Mode::Search(ref mode) => if mode.insert_mode() {
                 Some("search_insert")
             } else {
@@ -225,18 +646,54 @@ fn initialize_preferences() -> Rc<RefCell<Preferences>> {
     ))
 }
 
-fn create_workspace(view: &mut View, preferences: &Preferences, args: &Vec<String>) -> Result<Workspace> {
+/// Reads stdin to completion if it's been redirected (i.e. isn't a terminal),
+/// and then reopens the terminal in its place so that it can still be
+/// used for keyboard input.
+#[cfg(not(test))]
+fn read_piped_input() -> Result<Option<String>> {
+    use std::fs::File;
+    use std::io::{self, Read};
+    use std::os::unix::io::AsRawFd;
+
+    if unsafe { libc::isatty(libc::STDIN_FILENO) } == 1 {
+        return Ok(None);
+    }
+
+    let mut data = Vec::new();
+    io::stdin().read_to_end(&mut data).chain_err(|| "Couldn't read piped input")?;
+
+    let terminal = File::open("/dev/tty").chain_err(|| "Couldn't open the terminal for input")?;
+    if unsafe { libc::dup2(terminal.as_raw_fd(), libc::STDIN_FILENO) } == -1 {
+        bail!("Couldn't redirect terminal input to stdin");
+    }
+
+    Ok(Some(String::from_utf8_lossy(&data).into_owned()))
+}
+
+/// Leave the test harness' stdin alone, which is rarely a terminal.
+#[cfg(test)]
+fn read_piped_input() -> Result<Option<String>> {
+    Ok(None)
+}
+
+/// Builds a workspace for the specified arguments, along with the cursor
+/// positions requested for the buffers they opened, keyed by buffer ID.
+/// The first directory argument becomes the workspace root, and every file
+/// argument (resolved relative to the original working directory, so that
+/// it can be mixed with a directory argument) is opened in its own buffer,
+/// with the first one focused.
+fn create_workspace(
+    view: &mut View,
+    preferences: &Preferences,
+    args: &Vec<String>,
+) -> Result<(Workspace, Vec<(usize, Position)>)> {
     // Discard the executable portion of the argument list.
-    let mut path_args = args.iter().skip(1).peekable();
+    let path_args: Vec<&String> = args.iter().skip(1).collect();
 
     // Move into an argument-specified directory, if present.
     let initial_dir = env::current_dir()?;
-    if let Some(arg) = path_args.peek() {
-        let path = Path::new(&arg);
-
-        if path.is_dir() {
-            env::set_current_dir(path.canonicalize()?)?;
-        }
+    if let Some(arg) = path_args.iter().find(|arg| initial_dir.join(arg).is_dir()) {
+        env::set_current_dir(initial_dir.join(arg).canonicalize()?)?;
     }
 
     let workspace_dir = env::current_dir()?;
@@ -255,14 +712,20 @@ fn create_workspace(view: &mut View, preferences: &Preferences, args: &Vec<Strin
 
     workspace.syntax_set = Rc::new(syntax_set_builder.build());
 
-    // If the first argument was a directory, we've navigated into
-    // it; skip it before evaluating file args, lest we interpret
-    // it again as a non-existent file and create a buffer for it.
-    if workspace_dir != initial_dir { path_args.next(); }
-
-    // Try to open specified files.
+    // Try to open specified files, jumping to any line (and column)
+    // suffix, unless the suffix is actually part of the file name.
+    // Directories are skipped, lest we interpret them as non-existent
+    // files and create buffers for them; only the first is used.
+    let mut positions = Vec::new();
+    let mut first_buffer_id = None;
     for path_arg in path_args {
-        let path = Path::new(&path_arg);
+        let (path, position) = if initial_dir.join(path_arg).exists() {
+            (initial_dir.join(path_arg), None)
+        } else {
+            let (path_arg, position) = util::split_path_position(path_arg);
+            (initial_dir.join(path_arg), position)
+        };
+        let path = path.as_path();
 
         if path.is_dir() { continue; }
 
@@ -286,21 +749,26 @@ fn create_workspace(view: &mut View, preferences: &Preferences, args: &Vec<Strin
             buffer.syntax_set = Some(workspace.syntax_set.clone());
             buffer.syntax_reference = syntax_reference;
 
-            // Point the buffer to the path, ensuring that it's absolute.
-            if path.is_absolute() {
-                buffer.path = Some(path.to_path_buf());
-            } else {
-                buffer.path = Some(workspace.path.join(path));
-            }
+            // The path has been resolved, so it's already absolute.
+            buffer.path = Some(path.to_path_buf());
 
             buffer
         };
 
         workspace.add_buffer(argument_buffer);
-        view.initialize_buffer(workspace.current_buffer().unwrap())?;
+        let buffer = workspace.current_buffer().unwrap();
+        view.initialize_buffer(buffer)?;
+        if let (Some(id), Some(position)) = (buffer.id, position) {
+            positions.push((id, position));
+        }
+        first_buffer_id = first_buffer_id.or(buffer.id);
     }
 
-    Ok(workspace)
+    if let Some(id) = first_buffer_id {
+        util::select_buffer(&mut workspace, id);
+    }
+
+    Ok((workspace, positions))
 }
 
 #[cfg(test)]
@@ -311,8 +779,10 @@ mod tests {
 
     use yaml::YamlLoader;
     use scribe::Buffer;
+    use scribe::buffer::Position;
     use std::cell::RefCell;
     use std::env;
+    use std::fs;
     use std::path::Path;
     use std::rc::Rc;
     use std::sync::mpsc;
@@ -333,6 +803,39 @@ mod tests {
         );
     }
 
+    #[test]
+    fn application_moves_the_cursor_to_line_and_column_suffixes_of_file_arguments() {
+        let mut application =
+            Application::new(&vec![String::new(), String::from("Cargo.toml:2:500")]).unwrap();
+        let buffer = application.workspace.current_buffer().unwrap();
+        let line_length = buffer.data().lines().nth(1).unwrap().len();
+
+        assert_eq!(buffer.path, Buffer::from_file(Path::new("Cargo.toml")).unwrap().path);
+        assert_eq!(*buffer.cursor, Position { line: 1, offset: line_length });
+    }
+
+    #[test]
+    fn application_opens_each_file_argument_and_focuses_the_first() {
+        let mut application = Application::new(&vec![
+            String::new(),
+            String::from("Cargo.toml"),
+            String::from("Cargo.lock"),
+            String::from("non_existent_file"),
+        ]).unwrap();
+
+        let mut paths = Vec::new();
+        crate::util::for_each_buffer(&mut application, |app| {
+            paths.push(app.workspace.current_buffer().unwrap().path.clone());
+            Ok(())
+        }).unwrap();
+
+        assert_eq!(paths, vec![
+            Buffer::from_file(Path::new("Cargo.toml")).unwrap().path,
+            Buffer::from_file(Path::new("Cargo.lock")).unwrap().path,
+            Some(env::current_dir().unwrap().join("non_existent_file")),
+        ]);
+    }
+
     #[test]
     fn application_uses_file_arguments_to_create_new_buffers_when_files_do_not_exist() {
         let mut application =
@@ -353,11 +856,67 @@ mod tests {
         let mut view = View::new(preferences.clone(), event_channel.clone()).unwrap();
 
         let args = vec![String::new(), String::from("src/test.xyz")];
-        let mut workspace = super::create_workspace(&mut view, &preferences.borrow(), &args).unwrap();
+        let (mut workspace, _) =
+            super::create_workspace(&mut view, &preferences.borrow(), &args).unwrap();
 
         assert_eq!(
             workspace.current_buffer().unwrap().syntax_reference.as_ref().unwrap().name,
             "Rust"
         );
     }
+
+    #[test]
+    fn reload_preferences_discards_in_memory_overrides() {
+        let mut application = Application::new(&Vec::new()).unwrap();
+        application.preferences.borrow_mut().set_theme("amp_reload_test_theme");
+        application.reload_preferences().unwrap();
+
+        assert_ne!(application.preferences.borrow().theme(), "amp_reload_test_theme");
+        assert!(application.message.is_some());
+    }
+
+    #[test]
+    fn autosave_writes_modified_buffers_with_paths() {
+        let path = env::temp_dir().join("amp_autosave_test");
+        let mut application = Application::new(&Vec::new()).unwrap();
+        let mut buffer = Buffer::new();
+        buffer.path = Some(path.clone());
+        buffer.insert("amp");
+        application.workspace.add_buffer(buffer);
+        application.autosave();
+
+        // Autosaving should clean up content, just like saving does.
+        assert_eq!(fs::read_to_string(&path).unwrap(), "amp\n");
+        assert!(!application.workspace.current_buffer().unwrap().modified());
+        assert!(application.message.is_some());
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn autosave_skips_buffers_without_paths() {
+        let mut application = Application::new(&Vec::new()).unwrap();
+        let mut buffer = Buffer::new();
+        buffer.insert("amp");
+        application.workspace.add_buffer(buffer);
+        application.autosave();
+
+        assert!(application.workspace.current_buffer().unwrap().modified());
+        assert!(application.message.is_none());
+        assert!(application.error.is_none());
+    }
+
+    #[test]
+    fn autosave_waits_for_insert_mode_to_finish() {
+        let path = env::temp_dir().join("amp_autosave_insert_mode_test");
+        let mut application = Application::new(&Vec::new()).unwrap();
+        let mut buffer = Buffer::new();
+        buffer.path = Some(path.clone());
+        buffer.insert("amp");
+        application.workspace.add_buffer(buffer);
+        application.mode = super::Mode::Insert;
+        application.autosave();
+
+        assert!(application.workspace.current_buffer().unwrap().modified());
+        assert!(!path.exists());
+    }
 }
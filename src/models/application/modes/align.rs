@@ -0,0 +1,13 @@
+use scribe::buffer::Range;
+
+/// Holds the selected lines to be aligned on
+/// the delimiter named by the next key press.
+pub struct AlignMode {
+    pub range: Range,
+}
+
+impl AlignMode {
+    pub fn new(range: Range) -> AlignMode {
+        AlignMode { range }
+    }
+}
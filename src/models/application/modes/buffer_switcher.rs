@@ -0,0 +1,135 @@
+use fragment;
+use fragment::matching::AsStr;
+use crate::util::SelectableVec;
+use std::fmt;
+use std::slice::Iter;
+use crate::models::application::modes::{SearchSelectMode, SearchSelectConfig};
+
+/// An open buffer, identified by its workspace-assigned ID.
+#[derive(Clone, Debug, PartialEq)]
+pub struct BufferEntry {
+    pub id: usize,
+    pub label: String,
+}
+
+impl fmt::Display for BufferEntry {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.label)
+    }
+}
+
+impl AsStr for BufferEntry {
+    fn as_str(&self) -> &str {
+        &self.label
+    }
+}
+
+pub struct BufferSwitcherMode {
+    insert: bool,
+    input: String,
+    buffers: Vec<BufferEntry>,
+    results: SelectableVec<BufferEntry>,
+    config: SearchSelectConfig,
+}
+
+impl BufferSwitcherMode {
+    /// Builds a switcher for buffers ordered from most to least recently used,
+    /// the first of which is expected to be the current buffer.
+    pub fn new(buffers: Vec<BufferEntry>, config: SearchSelectConfig) -> BufferSwitcherMode {
+        BufferSwitcherMode {
+            insert: true,
+            input: String::new(),
+            buffers,
+            results: SelectableVec::new(Vec::new()),
+            config,
+        }
+    }
+}
+
+impl fmt::Display for BufferSwitcherMode {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "BUFFERS")
+    }
+}
+
+impl SearchSelectMode<BufferEntry> for BufferSwitcherMode {
+    fn search(&mut self) {
+        if self.input.is_empty() {
+            // List every buffer by recency, selecting the previously used
+            // one (rather than the current buffer) so that it's a single
+            // keystroke away.
+            let results = self.buffers.iter().take(self.config.max_results).cloned().collect();
+            self.results = SelectableVec::new(results);
+            if self.results.len() > 1 {
+                self.results.select_next();
+            }
+        } else {
+            let results = fragment::matching::find(&self.input, &self.buffers, self.config.max_results);
+            self.results = SelectableVec::new(results.into_iter().map(|r| r.clone()).collect());
+        }
+    }
+
+    fn query(&mut self) -> &mut String {
+        &mut self.input
+    }
+
+    fn insert_mode(&self) -> bool {
+        self.insert
+    }
+
+    fn set_insert_mode(&mut self, insert_mode: bool) {
+        self.insert = insert_mode;
+    }
+
+    fn results(&self) -> Iter<BufferEntry> {
+        self.results.iter()
+    }
+
+    fn selection(&self) -> Option<&BufferEntry> {
+        self.results.selection()
+    }
+
+    fn selected_index(&self) -> usize {
+        self.results.selected_index()
+    }
+
+    fn select_previous(&mut self) {
+        self.results.select_previous();
+    }
+
+    fn select_next(&mut self) {
+        self.results.select_next();
+    }
+
+    fn config(&self) -> &SearchSelectConfig {
+        &self.config
+    }
+
+    fn message(&mut self) -> Option<String> {
+        if self.results().count() == 0 {
+            Some(String::from("No matching buffers found."))
+        } else {
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::models::application::modes::{SearchSelectMode, SearchSelectConfig};
+    use super::{BufferEntry, BufferSwitcherMode};
+
+    #[test]
+    fn search_selects_the_previously_used_buffer_without_a_query() {
+        let buffers = vec![
+            BufferEntry { id: 2, label: String::from("src/main.rs") },
+            BufferEntry { id: 0, label: String::from("Cargo.toml") },
+            BufferEntry { id: 1, label: String::from("README.md") },
+        ];
+        let mut mode = BufferSwitcherMode::new(buffers, SearchSelectConfig::default());
+        mode.search();
+
+        assert_eq!(mode.results().count(), 3);
+        assert_eq!(mode.selection().map(|entry| entry.id), Some(0));
+    }
+}
@@ -37,14 +37,20 @@ impl fmt::Display for CommandMode {
 
 impl SearchSelectMode<DisplayableCommand> for CommandMode {
     fn search(&mut self) {
-        let commands: Vec<&'static str> = self.commands.keys().map(|k| *k).collect();
-
-        // Find the commands we're looking for using the query.
-        let results = fragment::matching::find(
-            &self.input,
-            &commands,
-            self.config.max_results
-        );
+        let mut commands: Vec<&'static str> = self.commands.keys().map(|k| *k).collect();
+
+        // Find the commands we're looking for using the query, listing
+        // all of them (alphabetically) when there isn't one, to browse.
+        let results: Vec<&'static str> = if self.input.is_empty() {
+            commands.sort_unstable();
+            commands
+        } else {
+            fragment::matching::find(
+                &self.input,
+                &commands,
+                self.config.max_results
+            ).into_iter().map(|result| *result).collect()
+        };
 
         // We don't care about the result objects; we just want
         // the underlying commands. Map the collection to get these.
@@ -52,9 +58,9 @@ impl SearchSelectMode<DisplayableCommand> for CommandMode {
             results
             .into_iter()
             .filter_map(|result| {
-                self.commands.get(*result).map(|command| {
+                self.commands.get(result).map(|command| {
                     DisplayableCommand{
-                      description: *result,
+                      description: result,
                       command: *command
                     }
                 })
@@ -98,4 +104,37 @@ impl SearchSelectMode<DisplayableCommand> for CommandMode {
     fn config(&self) -> &SearchSelectConfig {
         &self.config
     }
+
+    fn message(&mut self) -> Option<String> {
+        if self.results().count() == 0 {
+            Some(String::from("No matching commands found."))
+        } else {
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::models::application::modes::{SearchSelectMode, SearchSelectConfig};
+    use super::CommandMode;
+
+    #[test]
+    fn search_lists_every_command_alphabetically_without_a_query() {
+        let mut mode = CommandMode::new(SearchSelectConfig::default());
+        mode.search();
+
+        let descriptions: Vec<&str> = mode.results().map(|result| result.description).collect();
+        let mut sorted_descriptions = descriptions.clone();
+        sorted_descriptions.sort_unstable();
+        assert_eq!(descriptions.len(), mode.commands.len());
+        assert_eq!(descriptions, sorted_descriptions);
+
+        mode.push_search_char('s');
+        mode.push_search_char('o');
+        mode.push_search_char('r');
+        mode.push_search_char('t');
+        mode.search();
+        assert!(mode.results().any(|result| result.description == "selection::sort_lines"));
+    }
 }
@@ -0,0 +1,17 @@
+use scribe::buffer::Range;
+
+/// Holds the selection to be piped through
+/// the shell command that's being entered.
+pub struct FilterMode {
+    pub input: String,
+    pub range: Range,
+}
+
+impl FilterMode {
+    pub fn new(range: Range) -> FilterMode {
+        FilterMode {
+            input: String::new(),
+            range,
+        }
+    }
+}
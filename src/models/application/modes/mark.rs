@@ -0,0 +1,16 @@
+/// What to do with the mark named by the next key press.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum MarkAction {
+    Set,
+    Jump,
+}
+
+pub struct MarkMode {
+    pub action: MarkAction,
+}
+
+impl MarkMode {
+    pub fn new(action: MarkAction) -> MarkMode {
+        MarkMode { action }
+    }
+}
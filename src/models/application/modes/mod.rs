@@ -1,27 +1,47 @@
+mod align;
+mod buffer_switcher;
 mod confirm;
 mod command;
+mod filter;
 pub mod jump;
 mod line_jump;
+mod mark;
 pub mod open;
 mod path;
+mod recent;
+mod replace;
 mod search;
 mod search_select;
 mod select;
+mod select_block;
 mod select_line;
+mod spelling;
+mod surround;
 mod symbol_jump;
 mod syntax;
+mod text_object;
 mod theme;
 
+pub use self::align::AlignMode;
+pub use self::buffer_switcher::{BufferEntry, BufferSwitcherMode};
 pub use self::confirm::ConfirmMode;
 pub use self::command::CommandMode;
+pub use self::filter::FilterMode;
 pub use self::jump::JumpMode;
 pub use self::line_jump::LineJumpMode;
+pub use self::mark::{MarkAction, MarkMode};
 pub use self::path::PathMode;
 pub use self::open::OpenMode;
-pub use self::search::SearchMode;
+pub use self::recent::RecentMode;
+pub use self::replace::ReplaceMode;
+pub use self::search::{CaseSensitivity, SearchMode};
 pub use self::search_select::{SearchSelectMode, SearchSelectConfig};
 pub use self::select::SelectMode;
+pub use self::select_block::SelectBlockMode;
 pub use self::select_line::SelectLineMode;
+pub use self::spelling::SpellingMode;
+pub use self::surround::SurroundMode;
 pub use self::symbol_jump::SymbolJumpMode;
 pub use self::syntax::SyntaxMode;
+pub use self::text_object::{TextObjectMode, TextObjectScope};
 pub use self::theme::ThemeMode;
@@ -1,7 +1,11 @@
 use crate::errors::*;
 use bloodhound::ExclusionPattern;
+use std::fs;
+use std::path::Path;
 use crate::yaml::Yaml;
 
+const VCS_DIRECTORIES: [&str; 3] = [".git", ".hg", ".svn"];
+
 pub fn parse(exclusion_data: &[Yaml]) -> Result<Vec<ExclusionPattern>> {
     let mut mapped_exclusions = Vec::new();
 
@@ -20,9 +24,39 @@ pub fn parse(exclusion_data: &[Yaml]) -> Result<Vec<ExclusionPattern>> {
     Ok(mapped_exclusions)
 }
 
+/// Builds patterns excluding common VCS directories and the paths ignored by
+/// the workspace root's `.gitignore` file. Negated patterns (which re-include
+/// paths) aren't supported, and are skipped along with any that can't be parsed.
+pub fn ignored(workspace_path: &Path) -> Vec<ExclusionPattern> {
+    let gitignore = fs::read_to_string(workspace_path.join(".gitignore")).unwrap_or_default();
+    let gitignore_patterns = gitignore
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#') && !line.starts_with('!'))
+        .map(|line| {
+            // Patterns containing a slash (other than a trailing one,
+            // which restricts them to directories) are anchored to
+            // the workspace root; others can match at any depth.
+            let line = line.trim_end_matches('/');
+            if line.contains('/') {
+                format!("{}/{}", workspace_path.to_string_lossy(), line.trim_start_matches('/'))
+            } else {
+                format!("**/{}", line)
+            }
+        });
+
+    VCS_DIRECTORIES
+        .iter()
+        .map(|directory| format!("**/{}", directory))
+        .chain(gitignore_patterns)
+        .filter_map(|pattern| ExclusionPattern::new(&pattern).ok())
+        .collect()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use std::env;
 
     #[test]
     fn parse_converts_yaml_strings_into_glob_patterns() {
@@ -46,4 +80,36 @@ mod tests {
 
         assert!(parse(&exclusion_data).is_err());
     }
+
+    #[test]
+    fn ignored_converts_gitignore_entries_into_glob_patterns() {
+        let workspace_path = env::temp_dir().join("amp_gitignore_test");
+        fs::create_dir_all(&workspace_path).unwrap();
+        fs::write(
+            workspace_path.join(".gitignore"),
+            "# Build output\ntarget/\n\n*.log\n/docs/build\n!keep.log\n"
+        ).unwrap();
+
+        let root = workspace_path.to_string_lossy();
+        assert_eq!(
+            ignored(&workspace_path),
+            vec![
+                ExclusionPattern::new("**/.git").unwrap(),
+                ExclusionPattern::new("**/.hg").unwrap(),
+                ExclusionPattern::new("**/.svn").unwrap(),
+                ExclusionPattern::new("**/target").unwrap(),
+                ExclusionPattern::new("**/*.log").unwrap(),
+                ExclusionPattern::new(&format!("{}/docs/build", root)).unwrap(),
+            ]
+        );
+
+        fs::remove_dir_all(&workspace_path).unwrap();
+    }
+
+    #[test]
+    fn ignored_excludes_vcs_directories_without_a_gitignore_file() {
+        let workspace_path = env::temp_dir().join("amp_missing_gitignore_test");
+
+        assert_eq!(ignored(&workspace_path).len(), 3);
+    }
 }
@@ -1,27 +1,51 @@
+use scribe::buffer::Range;
 use std::fmt;
 
 pub struct PathMode {
     pub input: String,
     pub save_on_accept: bool,
+
+    /// When set, the contents of the entered path are inserted
+    /// at the cursor, rather than assigning the path to the buffer.
+    pub insert_on_accept: bool,
+
+    /// When set, the selected range is written to the entered
+    /// path, rather than assigning the path to the buffer.
+    pub write_range: Option<Range>,
+
+    /// Whether the user has been warned that the entered path exists;
+    /// cleared when the path is changed.
+    pub overwrite_warned: bool,
 }
 
 impl PathMode {
     pub fn new(initial_path: String) -> PathMode {
         PathMode {
             input: initial_path,
-            save_on_accept: false
+            save_on_accept: false,
+            insert_on_accept: false,
+            write_range: None,
+            overwrite_warned: false,
         }
     }
     pub fn push_char(&mut self, c: char) {
         self.input.push(c);
+        self.overwrite_warned = false;
     }
     pub fn pop_char(&mut self) {
         self.input.pop();
+        self.overwrite_warned = false;
     }
 }
 
 impl fmt::Display for PathMode {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "PATH")
+        if self.write_range.is_some() {
+            write!(f, "WRITE SELECTION")
+        } else if self.insert_on_accept {
+            write!(f, "INSERT FILE")
+        } else {
+            write!(f, "PATH")
+        }
     }
 }
@@ -0,0 +1,115 @@
+use fragment;
+use crate::util::SelectableVec;
+use std::fmt;
+use std::path::PathBuf;
+use std::slice::Iter;
+use crate::models::application::modes::{SearchSelectMode, SearchSelectConfig};
+
+pub struct RecentMode {
+    insert: bool,
+    input: String,
+    paths: Vec<String>,
+    results: SelectableVec<String>,
+    config: SearchSelectConfig,
+}
+
+impl RecentMode {
+    pub fn new(paths: Vec<PathBuf>, config: SearchSelectConfig) -> RecentMode {
+        RecentMode {
+            insert: true,
+            input: String::new(),
+            paths: paths.iter().map(|path| path.to_string_lossy().into_owned()).collect(),
+            results: SelectableVec::new(Vec::new()),
+            config,
+        }
+    }
+}
+
+impl fmt::Display for RecentMode {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "RECENT")
+    }
+}
+
+impl SearchSelectMode<String> for RecentMode {
+    fn search(&mut self) {
+        // Without a query, list the most recent files first.
+        let results = if self.input.is_empty() {
+            self.paths.iter().take(self.config.max_results).cloned().collect()
+        } else {
+            fragment::matching::find(&self.input, &self.paths, self.config.max_results)
+                .into_iter()
+                .map(|r| r.clone())
+                .collect()
+        };
+
+        self.results = SelectableVec::new(results);
+    }
+
+    fn query(&mut self) -> &mut String {
+        &mut self.input
+    }
+
+    fn insert_mode(&self) -> bool {
+        self.insert
+    }
+
+    fn set_insert_mode(&mut self, insert_mode: bool) {
+        self.insert = insert_mode;
+    }
+
+    fn results(&self) -> Iter<String> {
+        self.results.iter()
+    }
+
+    fn selection(&self) -> Option<&String> {
+        self.results.selection()
+    }
+
+    fn selected_index(&self) -> usize {
+        self.results.selected_index()
+    }
+
+    fn select_previous(&mut self) {
+        self.results.select_previous();
+    }
+
+    fn select_next(&mut self) {
+        self.results.select_next();
+    }
+
+    fn config(&self) -> &SearchSelectConfig {
+        &self.config
+    }
+
+    fn message(&mut self) -> Option<String> {
+        if self.paths.is_empty() {
+            Some(String::from("No recent files."))
+        } else if self.results().count() == 0 {
+            Some(String::from("No matching entries found."))
+        } else {
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::models::application::modes::{SearchSelectMode, SearchSelectConfig};
+    use std::path::PathBuf;
+    use super::RecentMode;
+
+    #[test]
+    fn search_lists_every_path_in_order_without_a_query() {
+        let paths = vec![PathBuf::from("/amp/src/main.rs"), PathBuf::from("/amp/Cargo.toml")];
+        let mut mode = RecentMode::new(paths, SearchSelectConfig::default());
+        mode.search();
+        assert_eq!(mode.results().count(), 2);
+        assert_eq!(mode.selection(), Some(&String::from("/amp/src/main.rs")));
+
+        mode.push_search_char('t');
+        mode.push_search_char('o');
+        mode.search();
+        assert_eq!(mode.results().collect::<Vec<_>>(), vec!["/amp/Cargo.toml"]);
+    }
+}
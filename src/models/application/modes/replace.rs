@@ -0,0 +1,12 @@
+#[derive(Default)]
+pub struct ReplaceMode {
+    pub input: String,
+}
+
+impl ReplaceMode {
+    pub fn new(replacement: Option<String>) -> ReplaceMode {
+        ReplaceMode {
+            input: replacement.unwrap_or_default(),
+        }
+    }
+}
@@ -1,12 +1,52 @@
 use crate::errors::*;
 use crate::util::SelectableVec;
+use regex::{self, Regex, RegexBuilder};
 use std::fmt;
-use scribe::buffer::{Buffer, Distance, Range};
+use std::slice;
+use scribe::buffer::{Buffer, Distance, Position, Range};
+use unicode_segmentation::UnicodeSegmentation;
+
+/// Determines whether queries are matched regardless of case. Smart case
+/// ignores case unless the query contains an uppercase character.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum CaseSensitivity {
+    Sensitive,
+    Insensitive,
+    Smart,
+}
+
+impl CaseSensitivity {
+    pub fn ignores_case(&self, query: &str) -> bool {
+        match *self {
+            CaseSensitivity::Sensitive => false,
+            CaseSensitivity::Insensitive => true,
+            CaseSensitivity::Smart => !query.chars().any(char::is_uppercase),
+        }
+    }
+
+    /// Returns the next setting, cycling back to the first.
+    pub fn next(&self) -> CaseSensitivity {
+        match *self {
+            CaseSensitivity::Smart => CaseSensitivity::Insensitive,
+            CaseSensitivity::Insensitive => CaseSensitivity::Sensitive,
+            CaseSensitivity::Sensitive => CaseSensitivity::Smart,
+        }
+    }
+}
 
 pub struct SearchMode {
     pub insert: bool,
     pub input: Option<String>,
+    pub regex: bool,
+    pub case_sensitivity: CaseSensitivity,
+    pub whole_word: bool,
+    pub history_index: Option<usize>,
+    pub original_position: Option<Position>,
     pub results: Option<SelectableVec<Range>>,
+
+    // Results grouped together to be copied or deleted
+    // at once. These are cleared whenever the search is run.
+    pub selected_results: Vec<Range>,
 }
 
 impl SearchMode {
@@ -14,7 +54,13 @@ impl SearchMode {
         SearchMode {
             insert: true,
             input: query,
+            regex: false,
+            case_sensitivity: CaseSensitivity::Sensitive,
+            whole_word: false,
+            history_index: None,
+            original_position: None,
             results: None,
+            selected_results: Vec::new(),
         }
     }
 
@@ -22,38 +68,189 @@ impl SearchMode {
         self.insert
     }
 
+    /// Adds the current result to the group of selected results.
+    pub fn select_current_result(&mut self) {
+        if let Some(result) = self.results.as_ref().and_then(|r| r.selection()) {
+            if !self.selected_results.contains(result) {
+                self.selected_results.push(result.clone());
+            }
+        }
+    }
+
     // Searches the specified buffer for the input string
     // and stores the result as a collection of ranges.
     pub fn search(&mut self, buffer: &Buffer) -> Result<()> {
         let query = self.input.as_ref().ok_or(SEARCH_QUERY_MISSING)?;
-        let distance = Distance::of_str(&query);
 
-        // Buffer search returns match starting positions, but we'd like ranges.
-        // This maps the positions to ranges using the search query distance
-        // before storing them.
-        self.results = Some(
-            SelectableVec::new(
+        let data = buffer.data();
+
+        let mut results = match self.matcher()? {
+            Some(regex) => regex_search(&regex, &data),
+            None => {
+                let distance = Distance::of_str(&query);
+
+                // Buffer search returns match starting positions, but we'd like ranges.
+                // This maps the positions to ranges using the search query distance
+                // before storing them.
                 buffer.search(&query)
                     .into_iter()
                     .map(|start| Range::new(start, start + distance))
                     .collect()
-            )
-        );
+            }
+        };
+        if self.whole_word {
+            let lines: Vec<&str> = data.lines().collect();
+            results.retain(|result| is_whole_word(result, &lines));
+        }
+        self.results = Some(SelectableVec::new(results));
+        self.selected_results.clear();
 
         Ok(())
     }
+
+    /// Builds the text used to replace a result found in the data.
+    pub fn replacement_for(&self, data: &str, result: &Range, replacement: &str) -> Result<String> {
+        let mut replacements = self.replacements_for(data, slice::from_ref(result), replacement)?;
+
+        Ok(replacements.remove(0))
+    }
+
+    /// Builds the text used to replace each of the results, which are
+    /// expected in the order they're found in the data. In regex mode, the
+    /// replacement can reference the query's capture groups (e.g. `$1`).
+    /// Groups are captured from the full data rather than the matched text
+    /// alone, so that anchors and word boundaries match as they did during
+    /// the search.
+    pub fn replacements_for(
+        &self,
+        data: &str,
+        results: &[Range],
+        replacement: &str,
+    ) -> Result<Vec<String>> {
+        let regex = match (self.regex, self.matcher()?) {
+            (true, Some(regex)) => regex,
+            _ => return Ok(vec![replacement.to_string(); results.len()]),
+        };
+        let position_at = position_finder(data);
+        let mut captures = regex.captures_iter(data);
+
+        results.iter().map(|result| -> Result<String> {
+            let caps = captures
+                .find(|caps| {
+                    caps.get(0).map_or(false, |m| position_at(m.start()) == result.start())
+                })
+                .ok_or("Couldn't find search result in buffer")?;
+            let mut content = String::new();
+            caps.expand(replacement, &mut content);
+
+            Ok(content)
+        }).collect()
+    }
+
+    // Compiles the query into a regex if it can't be matched by a
+    // literal, case-sensitive buffer search, which is preferred.
+    fn matcher(&self) -> Result<Option<Regex>> {
+        let query = self.input.as_ref().ok_or(SEARCH_QUERY_MISSING)?;
+        let ignore_case = self.case_sensitivity.ignores_case(query);
+        if !self.regex && !ignore_case {
+            return Ok(None);
+        }
+
+        let pattern = if self.regex {
+            query.clone()
+        } else {
+            regex::escape(query)
+        };
+
+        RegexBuilder::new(&pattern)
+            .case_insensitive(ignore_case)
+            .build()
+            .map(Some)
+            .chain_err(|| format!("Invalid regular expression: \"{}\"", query))
+    }
+}
+
+// Checks that a result isn't adjacent to word characters,
+// treating line and buffer edges as word boundaries.
+fn is_whole_word(result: &Range, lines: &[&str]) -> bool {
+    let is_word = |grapheme: Option<&str>| {
+        grapheme
+            .and_then(|g| g.chars().next())
+            .map(|c| c.is_alphanumeric() || c == '_')
+            .unwrap_or(false)
+    };
+    let grapheme_at = |line: usize, offset: usize| {
+        lines.get(line).and_then(|content| content.graphemes(true).nth(offset))
+    };
+
+    let start = result.start();
+    let end = result.end();
+    let preceded_by_word = start.offset > 0 && is_word(grapheme_at(start.line, start.offset - 1));
+    let followed_by_word = is_word(grapheme_at(end.line, end.offset));
+
+    !preceded_by_word && !followed_by_word
+}
+
+// Maps regex matches to buffer ranges, translating their byte indices into
+// line and grapheme offsets. Empty matches are skipped, as there's nothing
+// to highlight or select.
+fn regex_search(regex: &Regex, data: &str) -> Vec<Range> {
+    let position_at = position_finder(data);
+
+    regex.find_iter(data)
+        .filter(|m| m.start() != m.end())
+        .map(|m| Range::new(position_at(m.start()), position_at(m.end())))
+        .collect()
+}
+
+// Builds a function mapping byte offsets in the data to buffer positions.
+fn position_finder(data: &str) -> impl Fn(usize) -> Position + '_ {
+    let line_starts: Vec<usize> = std::iter::once(0)
+        .chain(data.match_indices('\n').map(|(index, _)| index + 1))
+        .collect();
+
+    move |index: usize| {
+        let line = match line_starts.binary_search(&index) {
+            Ok(line) => line,
+            Err(line) => line - 1,
+        };
+
+        Position {
+            line,
+            offset: data[line_starts[line]..index].graphemes(true).count(),
+        }
+    }
 }
 
 impl fmt::Display for SearchMode {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "SEARCH")
+        // Qualify the mode with any non-default matching options.
+        let mut options = Vec::new();
+        if self.regex {
+            options.push("regex");
+        }
+        match self.case_sensitivity {
+            CaseSensitivity::Sensitive => options.push("case"),
+            CaseSensitivity::Insensitive => options.push("nocase"),
+            CaseSensitivity::Smart => (),
+        }
+
+        if self.whole_word {
+            options.push("word");
+        }
+
+        if options.is_empty() {
+            write!(f, "SEARCH")
+        } else {
+            write!(f, "SEARCH [{}]", options.join(", "))
+        }
     }
 }
 
 #[cfg(test)]
 mod tests {
     use scribe::buffer::{Buffer, Position, Range};
-    use super::SearchMode;
+    use super::{CaseSensitivity, SearchMode};
 
     #[test]
     fn search_populates_results_with_correct_ranges() {
@@ -77,4 +274,164 @@ mod tests {
             ]
         );
     }
+
+    #[test]
+    fn search_populates_results_with_regex_matches() {
+        let mut buffer = Buffer::new();
+        buffer.insert("amp 1\nédit 23");
+
+        let mut mode = SearchMode::new(Some(String::from(r"\d+")));
+        mode.regex = true;
+        mode.search(&buffer).unwrap();
+
+        assert_eq!(
+            *mode.results.unwrap(),
+            vec![
+                Range::new(
+                    Position{ line: 0, offset: 4 },
+                    Position{ line: 0, offset: 5 },
+                ),
+                Range::new(
+                    Position{ line: 1, offset: 5 },
+                    Position{ line: 1, offset: 7 },
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn search_returns_an_error_for_invalid_regex_queries() {
+        let mut buffer = Buffer::new();
+        buffer.insert("amp");
+
+        let mut mode = SearchMode::new(Some(String::from("(amp")));
+        mode.regex = true;
+
+        assert!(mode.search(&buffer).is_err());
+    }
+
+    #[test]
+    fn replacement_for_expands_capture_groups_in_regex_mode() {
+        let result = Range::new(
+            Position { line: 0, offset: 0 },
+            Position { line: 0, offset: 10 },
+        );
+        let mut mode = SearchMode::new(Some(String::from(r"(\w+)@(\w+)")));
+        assert_eq!(mode.replacement_for("amp@editor", &result, "$2@$1").unwrap(), "$2@$1");
+
+        mode.regex = true;
+        assert_eq!(mode.replacement_for("amp@editor", &result, "$2@$1").unwrap(), "editor@amp");
+    }
+
+    #[test]
+    fn replacement_for_matches_assertions_in_the_context_of_the_data() {
+        let data = "amp\nmp";
+        let result = Range::new(
+            Position { line: 0, offset: 1 },
+            Position { line: 0, offset: 3 },
+        );
+        let mut mode = SearchMode::new(Some(String::from(r"\B(m)p")));
+        mode.regex = true;
+
+        // The matched text alone starts at a word boundary.
+        assert_eq!(mode.replacement_for(data, &result, "${1}x").unwrap(), "mx");
+    }
+
+    #[test]
+    fn search_ignores_case_when_case_insensitive() {
+        let mut buffer = Buffer::new();
+        buffer.insert("Amp\namp\nAMP");
+
+        let mut mode = SearchMode::new(Some(String::from("amp")));
+        mode.case_sensitivity = CaseSensitivity::Insensitive;
+        mode.search(&buffer).unwrap();
+
+        assert_eq!(mode.results.unwrap().len(), 3);
+    }
+
+    #[test]
+    fn search_uses_smart_case_based_on_query() {
+        let mut buffer = Buffer::new();
+        buffer.insert("Amp\namp\nAMP");
+
+        let mut mode = SearchMode::new(Some(String::from("amp")));
+        mode.case_sensitivity = CaseSensitivity::Smart;
+        mode.search(&buffer).unwrap();
+        assert_eq!(mode.results.as_ref().unwrap().len(), 3);
+
+        // An uppercase character makes the search case-sensitive.
+        mode.input = Some(String::from("Amp"));
+        mode.search(&buffer).unwrap();
+        assert_eq!(
+            *mode.results.unwrap(),
+            vec![
+                Range::new(
+                    Position{ line: 0, offset: 0 },
+                    Position{ line: 0, offset: 3 },
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn search_treats_case_insensitive_literal_queries_literally() {
+        let mut buffer = Buffer::new();
+        buffer.insert("a.b\naxb");
+
+        let mut mode = SearchMode::new(Some(String::from("A.B")));
+        mode.case_sensitivity = CaseSensitivity::Insensitive;
+        mode.search(&buffer).unwrap();
+
+        assert_eq!(mode.results.unwrap().len(), 1);
+    }
+
+    #[test]
+    fn search_only_matches_whole_words_when_enabled() {
+        let mut buffer = Buffer::new();
+        buffer.insert("log login\nblog log_in\n(log) log");
+
+        let mut mode = SearchMode::new(Some(String::from("log")));
+        mode.whole_word = true;
+        mode.search(&buffer).unwrap();
+
+        // Matches at the very start and end of the buffer are included.
+        assert_eq!(
+            *mode.results.unwrap(),
+            vec![
+                Range::new(
+                    Position{ line: 0, offset: 0 },
+                    Position{ line: 0, offset: 3 },
+                ),
+                Range::new(
+                    Position{ line: 2, offset: 1 },
+                    Position{ line: 2, offset: 4 },
+                ),
+                Range::new(
+                    Position{ line: 2, offset: 6 },
+                    Position{ line: 2, offset: 9 },
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn search_composes_whole_word_matching_with_case_insensitivity() {
+        let mut buffer = Buffer::new();
+        buffer.insert("Log\nLOGIN");
+
+        let mut mode = SearchMode::new(Some(String::from("log")));
+        mode.whole_word = true;
+        mode.case_sensitivity = CaseSensitivity::Insensitive;
+        mode.search(&buffer).unwrap();
+
+        assert_eq!(
+            *mode.results.unwrap(),
+            vec![
+                Range::new(
+                    Position{ line: 0, offset: 0 },
+                    Position{ line: 0, offset: 3 },
+                ),
+            ]
+        );
+    }
 }
@@ -0,0 +1,55 @@
+use scribe::buffer::{Position, Range};
+use unicode_segmentation::UnicodeSegmentation;
+
+pub struct SelectBlockMode {
+    pub anchor: Position,
+}
+
+impl SelectBlockMode {
+    pub fn new(anchor: Position) -> SelectBlockMode {
+        SelectBlockMode { anchor }
+    }
+
+    /// Produces one range per line in the rectangular region between the
+    /// anchor and cursor. Lines that end before the region's right edge
+    /// yield shorter (possibly empty) ranges, rather than being skipped.
+    pub fn to_ranges(&self, cursor: &Position, data: &str) -> Vec<Range> {
+        let first_line = self.anchor.line.min(cursor.line);
+        let last_line = self.anchor.line.max(cursor.line);
+        let left = self.anchor.offset.min(cursor.offset);
+        let right = self.anchor.offset.max(cursor.offset);
+
+        let mut lines = data.lines().skip(first_line);
+        (first_line..=last_line).map(|line| {
+            let length = lines.next().map(|l| l.graphemes(true).count()).unwrap_or(0);
+
+            Range::new(
+                Position { line, offset: left.min(length) },
+                Position { line, offset: right.min(length) }
+            )
+        }).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use scribe::buffer::Position;
+    use super::SelectBlockMode;
+
+    #[test]
+    fn to_ranges_clamps_rows_to_the_length_of_short_lines() {
+        let mode = SelectBlockMode::new(Position { line: 0, offset: 1 });
+        let ranges = mode.to_ranges(&Position { line: 2, offset: 4 }, "amp editor\na\nbuffer");
+
+        let bounds: Vec<(Position, Position)> = ranges
+            .iter()
+            .map(|range| (range.start(), range.end()))
+            .collect();
+
+        assert_eq!(bounds, vec![
+            (Position { line: 0, offset: 1 }, Position { line: 0, offset: 4 }),
+            (Position { line: 1, offset: 1 }, Position { line: 1, offset: 1 }),
+            (Position { line: 2, offset: 1 }, Position { line: 2, offset: 4 }),
+        ]);
+    }
+}
@@ -0,0 +1,118 @@
+use fragment;
+use crate::util::SelectableVec;
+use scribe::buffer::Range;
+use std::fmt;
+use std::slice::Iter;
+use crate::models::application::modes::{SearchSelectMode, SearchSelectConfig};
+
+/// Suggested replacements for a misspelled word,
+/// which is located in the current buffer by its range.
+pub struct SpellingMode {
+    insert: bool,
+    input: String,
+    pub range: Range,
+    suggestions: Vec<String>,
+    results: SelectableVec<String>,
+    config: SearchSelectConfig,
+}
+
+impl SpellingMode {
+    pub fn new(range: Range, suggestions: Vec<String>, config: SearchSelectConfig) -> SpellingMode {
+        SpellingMode {
+            insert: false,
+            input: String::new(),
+            range,
+            suggestions,
+            results: SelectableVec::new(Vec::new()),
+            config,
+        }
+    }
+}
+
+impl fmt::Display for SpellingMode {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "SPELLING")
+    }
+}
+
+impl SearchSelectMode<String> for SpellingMode {
+    fn search(&mut self) {
+        // Without a query, list the closest suggestions first.
+        let results = if self.input.is_empty() {
+            self.suggestions.iter().take(self.config.max_results).cloned().collect()
+        } else {
+            fragment::matching::find(&self.input, &self.suggestions, self.config.max_results)
+                .into_iter()
+                .map(|r| r.clone())
+                .collect()
+        };
+
+        self.results = SelectableVec::new(results);
+    }
+
+    fn query(&mut self) -> &mut String {
+        &mut self.input
+    }
+
+    fn insert_mode(&self) -> bool {
+        self.insert
+    }
+
+    fn set_insert_mode(&mut self, insert_mode: bool) {
+        self.insert = insert_mode;
+    }
+
+    fn results(&self) -> Iter<String> {
+        self.results.iter()
+    }
+
+    fn selection(&self) -> Option<&String> {
+        self.results.selection()
+    }
+
+    fn selected_index(&self) -> usize {
+        self.results.selected_index()
+    }
+
+    fn select_previous(&mut self) {
+        self.results.select_previous();
+    }
+
+    fn select_next(&mut self) {
+        self.results.select_next();
+    }
+
+    fn config(&self) -> &SearchSelectConfig {
+        &self.config
+    }
+
+    fn message(&mut self) -> Option<String> {
+        if self.suggestions.is_empty() {
+            Some(String::from("No spelling suggestions found."))
+        } else if self.results().count() == 0 {
+            Some(String::from("No matching suggestions found."))
+        } else {
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::models::application::modes::{SearchSelectMode, SearchSelectConfig};
+    use scribe::buffer::{Position, Range};
+    use super::SpellingMode;
+
+    #[test]
+    fn search_lists_suggestions_in_order_without_a_query() {
+        let range = Range::new(Position { line: 0, offset: 0 }, Position { line: 0, offset: 5 });
+        let suggestions = vec![String::from("edit"), String::from("editor")];
+        let mut mode = SpellingMode::new(range, suggestions, SearchSelectConfig::default());
+        mode.search();
+        assert_eq!(mode.results().collect::<Vec<_>>(), vec!["edit", "editor"]);
+
+        mode.push_search_char('o');
+        mode.search();
+        assert_eq!(mode.results().collect::<Vec<_>>(), vec!["editor"]);
+    }
+}
@@ -0,0 +1,13 @@
+use scribe::buffer::Range;
+
+/// Holds the selection to be surrounded with the
+/// delimiter pair named by the next key press.
+pub struct SurroundMode {
+    pub range: Range,
+}
+
+impl SurroundMode {
+    pub fn new(range: Range) -> SurroundMode {
+        SurroundMode { range }
+    }
+}
@@ -0,0 +1,17 @@
+/// Whether a text object includes its delimiters.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum TextObjectScope {
+    Inside,
+    Around,
+}
+
+/// Awaits the delimiter of the text object to be selected.
+pub struct TextObjectMode {
+    pub scope: TextObjectScope,
+}
+
+impl TextObjectMode {
+    pub fn new(scope: TextObjectScope) -> TextObjectMode {
+        TextObjectMode { scope }
+    }
+}
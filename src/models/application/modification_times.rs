@@ -0,0 +1,84 @@
+use scribe::Buffer;
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+use std::time::SystemTime;
+
+/// Tracks the modification time of each buffer's file as of when it was
+/// last read or written, so that changes made by other programs can be
+/// detected. Buffers are identified using their workspace-assigned IDs.
+#[derive(Default)]
+pub struct ModificationTimes {
+    times: HashMap<usize, SystemTime>,
+}
+
+impl ModificationTimes {
+    pub fn new() -> ModificationTimes {
+        ModificationTimes::default()
+    }
+
+    /// Records the current modification time of the buffer's file.
+    pub fn record(&mut self, buffer: &Buffer) {
+        if let (Some(id), Some(time)) = (buffer.id, buffer.path.as_ref().and_then(|p| modified_time(p))) {
+            self.times.insert(id, time);
+        }
+    }
+
+    /// Whether the buffer's file has been modified since its time was last
+    /// recorded. Buffers without a recorded time (i.e. those that haven't been
+    /// checked before) are assumed to be in sync with their file, and recorded.
+    pub fn changed_externally(&mut self, buffer: &Buffer) -> bool {
+        let id = match buffer.id {
+            Some(id) => id,
+            None => return false,
+        };
+        let current_time = match buffer.path.as_ref().and_then(|p| modified_time(p)) {
+            Some(time) => time,
+            None => return false,
+        };
+
+        match self.times.insert(id, current_time) {
+            Some(recorded_time) => current_time > recorded_time,
+            None => false,
+        }
+    }
+
+    /// Discards the recorded time for the specified buffer.
+    /// This method should be called whenever a buffer is closed.
+    pub fn forget_buffer(&mut self, buffer_id: usize) {
+        self.times.remove(&buffer_id);
+    }
+}
+
+fn modified_time(path: &Path) -> Option<SystemTime> {
+    fs::metadata(path).and_then(|metadata| metadata.modified()).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ModificationTimes;
+    use scribe::Buffer;
+    use std::env;
+    use std::fs;
+    use std::time::UNIX_EPOCH;
+
+    #[test]
+    fn changed_externally_detects_newer_modification_times() {
+        let path = env::temp_dir().join("amp_modification_times_test");
+        fs::write(&path, "amp").unwrap();
+        let mut buffer = Buffer::from_file(&path).unwrap();
+        buffer.id = Some(0);
+
+        let mut times = ModificationTimes::new();
+        assert!(!times.changed_externally(&buffer));
+        assert!(!times.changed_externally(&buffer));
+
+        // Backdate the recorded time, as though another program wrote to the file.
+        times.times.insert(0, UNIX_EPOCH);
+        assert!(times.changed_externally(&buffer));
+
+        // The change is only reported once.
+        assert!(!times.changed_externally(&buffer));
+        fs::remove_file(&path).unwrap();
+    }
+}
@@ -0,0 +1,75 @@
+use crate::input::Key;
+use std::mem;
+use std::time::{Duration, Instant};
+
+/// The leading keys of a multi-key binding (e.g. the first `g` in `g g`)
+/// that have been pressed, while waiting for the rest of the sequence.
+#[derive(Default)]
+pub struct PendingKeys {
+    keys: Vec<Key>,
+    last_press: Option<Instant>,
+}
+
+impl PendingKeys {
+    pub fn new() -> PendingKeys {
+        PendingKeys::default()
+    }
+
+    pub fn keys(&self) -> &[Key] {
+        &self.keys
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.keys.is_empty()
+    }
+
+    /// Replaces the pending keys, restarting the timeout.
+    pub fn set(&mut self, keys: Vec<Key>) {
+        self.keys = keys;
+        self.last_press = Some(Instant::now());
+    }
+
+    /// Removes and returns the pending keys.
+    pub fn take(&mut self) -> Vec<Key> {
+        self.last_press = None;
+        mem::replace(&mut self.keys, Vec::new())
+    }
+
+    /// The time remaining until the pending keys should be given up on,
+    /// if there are any.
+    pub fn timeout(&self, limit: Duration) -> Option<Duration> {
+        if self.keys.is_empty() {
+            return None;
+        }
+        let elapsed = self.last_press?.elapsed();
+
+        Some(limit.checked_sub(elapsed).unwrap_or_default())
+    }
+
+    /// The pending keys, as they'd be written in a keymap (e.g. `g`).
+    pub fn description(&self) -> String {
+        self.keys.iter().map(|key| key.to_string()).collect::<Vec<_>>().join(" ")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::input::Key;
+    use std::time::Duration;
+    use super::PendingKeys;
+
+    #[test]
+    fn timeout_is_only_tracked_while_keys_are_pending() {
+        let mut pending_keys = PendingKeys::new();
+        assert_eq!(pending_keys.timeout(Duration::from_secs(1)), None);
+
+        pending_keys.set(vec![Key::Char('g'), Key::Ctrl('w')]);
+        assert!(pending_keys.timeout(Duration::from_secs(1)).unwrap() > Duration::from_millis(0));
+        assert_eq!(pending_keys.timeout(Duration::from_secs(0)), Some(Duration::from_secs(0)));
+        assert_eq!(pending_keys.description(), "g ctrl-w");
+
+        assert_eq!(pending_keys.take(), vec![Key::Char('g'), Key::Ctrl('w')]);
+        assert!(pending_keys.is_empty());
+        assert_eq!(pending_keys.timeout(Duration::from_secs(1)), None);
+    }
+}
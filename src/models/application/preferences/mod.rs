@@ -5,34 +5,75 @@ use crate::input::KeyMap;
 use crate::models::application::modes::open;
 use scribe::Buffer;
 use std::env;
-use std::fs::OpenOptions;
+use std::fs::{self, OpenOptions};
 use std::io::Read;
 use std::path::{Path, PathBuf};
+use std::time::Duration;
 use crate::yaml::yaml::{Hash, Yaml, YamlLoader};
-use crate::models::application::modes::SearchSelectConfig;
+use crate::models::application::modes::{CaseSensitivity, SearchSelectConfig};
 use crate::models::application::editorconfig::{self, EditorConfig};
+use crate::models::application::LogLevel;
 
 const APP_INFO: AppInfo = AppInfo {
     name: "amp",
     author: "Jordan MacDonald",
 };
+const AUTO_CLOSE_PAIRS_KEY: &str = "auto_close_pairs";
+const AUTOSAVE_INTERVAL_KEY: &str = "autosave_interval";
+const BACKUP_ON_SAVE_KEY: &str = "backup_on_save";
 const FILE_NAME: &str = "config.yml";
+const KEYMAP_FILE_NAME: &str = "keymap.yml";
+const PROJECT_FILE_NAME: &str = ".amp.yml";
+const KEYMAP_KEY: &str = "keymap";
+const HARD_WRAP_COLUMN_KEY: &str = "hard_wrap_column";
+const HORIZONTAL_SCROLL_MARGIN_KEY: &str = "horizontal_scroll_margin";
+const HIGHLIGHT_TRAILING_WHITESPACE_KEY: &str = "highlight_trailing_whitespace";
+const LOG_LEVEL_KEY: &str = "log_level";
+const LOG_SIZE_LIMIT_KEY: &str = "log_size_limit";
 const LINE_COMMENT_PREFIX_KEY: &str = "line_comment_prefix";
 const LINE_LENGTH_GUIDE_KEY: &str = "line_length_guide";
+const KEY_SEQUENCE_TIMEOUT_KEY: &str = "key_sequence_timeout";
+const MINIMUM_LINE_NUMBER_WIDTH_KEY: &str = "minimum_line_number_width";
+const LINE_NUMBER_FORMAT_KEY: &str = "line_number_format";
 const LINE_WRAPPING_KEY: &str = "line_wrapping";
 const OPEN_MODE_KEY: &str = "open_mode";
 const OPEN_MODE_EXCLUSIONS_KEY: &str = "exclusions";
+const OPEN_MODE_INCLUDE_IGNORED_KEY: &str = "include_ignored";
+const RECENT_FILES_LIMIT_KEY: &str = "recent_files_limit";
+const RELATIVE_LINE_NUMBERS_KEY: &str = "relative_line_numbers";
+const RESTORE_CURSOR_POSITIONS_KEY: &str = "restore_cursor_positions";
+const SCROLL_MARGIN_KEY: &str = "scroll_margin";
+const SEARCH_CASE_SENSITIVITY_KEY: &str = "search_case_sensitivity";
+const SEARCH_REGEX_KEY: &str = "search_regex";
 const SEARCH_SELECT_KEY: &str = "search_select";
+const SEARCH_WHOLE_WORD_KEY: &str = "search_whole_word";
+const SHOW_WHITESPACE_KEY: &str = "show_whitespace";
+const SMOOTH_SCROLLING_KEY: &str = "smooth_scrolling";
 const SOFT_TABS_KEY: &str = "soft_tabs";
+const SPELL_CHECK_KEY: &str = "spell_check";
+const SPELL_CHECK_DICTIONARY_KEY: &str = "spell_check_dictionary";
+const SYSTEM_CLIPBOARD_KEY: &str = "system_clipboard";
 const SYNTAX_PATH: &str = "syntaxes";
 const TAB_WIDTH_KEY: &str = "tab_width";
 const THEME_KEY: &str = "theme";
+const TIMESTAMP_FORMAT_KEY: &str = "timestamp_format";
+const VISIBLE_TABS_KEY: &str = "visible_tabs";
+const WHITESPACE_CHARACTERS_KEY: &str = "whitespace_characters";
+const WHITESPACE_CHARACTERS_SPACE_KEY: &str = "space";
+const WHITESPACE_CHARACTERS_TAB_KEY: &str = "tab";
+const WHITESPACE_CHARACTERS_NEWLINE_KEY: &str = "newline";
+const STATUS_LINE_FORMAT_KEY: &str = "status_line_format";
 const THEME_PATH: &str = "themes";
 const TYPES_KEY: &str = "types";
 const TYPES_SYNTAX_KEY: &str = "syntax";
 const REMOVE_TRAILING_WHITESPACE_KEY: &str = "remove_trailing_whitespace";
+const CLIPBOARD_HISTORY_SIZE_KEY: &str = "clipboard_history_size";
+const COLLAPSE_TRAILING_NEWLINES_KEY: &str = "collapse_trailing_newlines";
 const ENSURE_TRAILING_NEWLINE_KEY: &str = "ensure_trailing_newline";
+const FORMAT_ON_SAVE_KEY: &str = "format_on_save";
+const FORMATTER_KEY: &str = "formatter";
 const USE_EDITORCONFIG_KEY: &str = "use_editorconfig";
+const WATCH_PREFERENCES_KEY: &str = "watch_preferences";
 
 /// Loads, creates, and provides default values for application preferences.
 /// Values are immutable once loaded, with the exception of those that provide
@@ -41,7 +82,14 @@ pub struct Preferences {
     default: Yaml,
     data: Option<Yaml>,
     keymap: KeyMap,
+    keymap_warnings: Vec<String>,
+    project_warning: Option<String>,
     theme: Option<String>,
+    line_wrapping: Option<bool>,
+    show_whitespace: Option<bool>,
+    search_regex: Option<bool>,
+    search_case_sensitivity: Option<CaseSensitivity>,
+    search_whole_word: Option<bool>,
     editorconfig: Option<EditorConfig>,
 }
 
@@ -52,17 +100,26 @@ impl Preferences {
             default: load_default_document().expect("Failed to load default preferences!"),
             data,
             keymap: KeyMap::default().expect("Failed to load default keymap!"),
+            keymap_warnings: Vec::new(),
+            project_warning: None,
             theme: None,
+            line_wrapping: None,
+            show_whitespace: None,
+            search_regex: None,
+            search_case_sensitivity: None,
+            search_whole_word: None,
             editorconfig: None,
         }
     }
 
     /// Loads preferences from disk, returning any filesystem or parse errors.
+    /// A project preferences file in the workspace directory is merged over
+    /// those of the user, unless it can't be parsed, in which case it's skipped.
     pub fn load() -> Result<Preferences> {
         let default = load_default_document()?;
-        let data = load_document()?;
-        let keymap = load_keymap(
-            data.as_ref().and_then(|data| data["keymap"].as_hash())
+        let (data, project_warning) = apply_project_document(load_document()?);
+        let (keymap, keymap_warnings) = load_keymap(
+            data.as_ref().and_then(|data| data[KEYMAP_KEY].as_hash())
         )?;
         let editorconfig = load_editorconfig().ok();
 
@@ -70,7 +127,14 @@ impl Preferences {
             default,
             data,
             keymap,
+            keymap_warnings,
+            project_warning,
             theme: None,
+            line_wrapping: None,
+            show_whitespace: None,
+            search_regex: None,
+            search_case_sensitivity: None,
+            search_whole_word: None,
             editorconfig,
         })
     }
@@ -78,16 +142,23 @@ impl Preferences {
     /// Reloads all user preferences from disk and merges them with defaults.
     pub fn reload(&mut self) -> Result<()> {
         let default = load_default_document()?;
-        let data = load_document()?;
-        let keymap = load_keymap(
-            data.as_ref().and_then(|data| data["keymap"].as_hash())
+        let (data, project_warning) = apply_project_document(load_document()?);
+        let (keymap, keymap_warnings) = load_keymap(
+            data.as_ref().and_then(|data| data[KEYMAP_KEY].as_hash())
         )?;
         let editorconfig = load_editorconfig().ok();
 
         self.default = default;
         self.data = data;
         self.keymap = keymap;
+        self.keymap_warnings = keymap_warnings;
+        self.project_warning = project_warning;
         self.theme = None;
+        self.line_wrapping = None;
+        self.show_whitespace = None;
+        self.search_regex = None;
+        self.search_case_sensitivity = None;
+        self.search_whole_word = None;
         self.editorconfig = editorconfig;
 
         Ok(())
@@ -98,12 +169,52 @@ impl Preferences {
         &self.keymap
     }
 
+    /// Descriptions of the user-defined key bindings that were
+    /// skipped when loading the keymap (e.g. for unknown commands).
+    pub fn keymap_warnings(&self) -> &[String] {
+        &self.keymap_warnings
+    }
+
+    /// A description of the problem with the project preferences
+    /// file, if it was skipped when loading preferences.
+    pub fn project_warning(&self) -> Option<&str> {
+        self.project_warning.as_ref().map(|warning| warning.as_str())
+    }
+
     /// A path pointing to the user preferences directory.
     pub fn directory() -> Result<PathBuf> {
         app_root(AppDataType::UserConfig, &APP_INFO)
             .chain_err(|| "Couldn't create preferences directory or build a path to it.")
     }
 
+    /// A path in the user preferences directory for a file used to persist
+    /// application state (e.g. recent files). Tests keep their state in
+    /// memory, to avoid reading or clobbering user data, so there's no path.
+    #[cfg(not(test))]
+    pub fn state_path(file_name: &str) -> Option<PathBuf> {
+        Preferences::directory().ok().map(|directory| directory.join(file_name))
+    }
+
+    #[cfg(test)]
+    pub fn state_path(_: &str) -> Option<PathBuf> {
+        None
+    }
+
+    /// The files that preferences are loaded from, whether or not they exist:
+    /// the user's preferences and keymap files, and the project's preferences.
+    pub fn paths() -> Vec<PathBuf> {
+        let mut paths = Vec::new();
+        if let Ok(directory) = Preferences::directory() {
+            paths.push(directory.join(FILE_NAME));
+            paths.push(directory.join(KEYMAP_FILE_NAME));
+        }
+        if let Ok(directory) = env::current_dir() {
+            paths.push(directory.join(PROJECT_FILE_NAME));
+        }
+
+        paths
+    }
+
     /// A path pointing to the user syntax definition directory.
     pub fn syntax_path() -> Result<PathBuf> {
         app_dir(AppDataType::UserConfig, &APP_INFO, SYNTAX_PATH)
@@ -143,9 +254,12 @@ impl Preferences {
                       } else {
                           None
                       })
-            .unwrap_or_else(|| {
-                self.default[THEME_KEY].as_str().expect("Couldn't find default theme name!")
-            })
+            .unwrap_or_else(|| self.default_theme())
+    }
+
+    /// The theme used when none is configured, or the configured one can't be found.
+    pub fn default_theme(&self) -> &str {
+        self.default[THEME_KEY].as_str().expect("Couldn't find default theme name!")
     }
 
     /// Returns the theme path, making sure the directory exists.
@@ -159,6 +273,75 @@ impl Preferences {
         self.theme = Some(theme.into());
     }
 
+    /// If set, returns the in-memory search regex value, falling back to the
+    /// value set via the preferences file, and then the default value.
+    pub fn search_regex(&self) -> bool {
+        if let Some(regex) = self.search_regex { return regex; }
+
+        self.data
+            .as_ref()
+            .and_then(|data| if let Yaml::Boolean(regex) = data[SEARCH_REGEX_KEY] {
+                          Some(regex)
+                      } else {
+                          None
+                      })
+            .unwrap_or_else(|| {
+                self.default[SEARCH_REGEX_KEY].as_bool()
+                    .expect("Couldn't find default search regex setting!")
+            })
+    }
+
+    /// Updates the in-memory search regex value.
+    pub fn set_search_regex(&mut self, regex: bool) {
+        self.search_regex = Some(regex);
+    }
+
+    /// If set, returns the in-memory search case sensitivity, falling back to
+    /// the value set via the preferences file, and then the default value.
+    pub fn search_case_sensitivity(&self) -> CaseSensitivity {
+        if let Some(case_sensitivity) = self.search_case_sensitivity {
+            return case_sensitivity;
+        }
+
+        self.data
+            .as_ref()
+            .and_then(|data| data[SEARCH_CASE_SENSITIVITY_KEY].as_str())
+            .and_then(parse_case_sensitivity)
+            .unwrap_or_else(|| {
+                self.default[SEARCH_CASE_SENSITIVITY_KEY].as_str()
+                    .and_then(parse_case_sensitivity)
+                    .expect("Couldn't find default search case sensitivity setting!")
+            })
+    }
+
+    /// Updates the in-memory search case sensitivity value.
+    pub fn set_search_case_sensitivity(&mut self, case_sensitivity: CaseSensitivity) {
+        self.search_case_sensitivity = Some(case_sensitivity);
+    }
+
+    /// If set, returns the in-memory whole word search value, falling back to
+    /// the value set via the preferences file, and then the default value.
+    pub fn search_whole_word(&self) -> bool {
+        if let Some(whole_word) = self.search_whole_word { return whole_word; }
+
+        self.data
+            .as_ref()
+            .and_then(|data| if let Yaml::Boolean(whole_word) = data[SEARCH_WHOLE_WORD_KEY] {
+                          Some(whole_word)
+                      } else {
+                          None
+                      })
+            .unwrap_or_else(|| {
+                self.default[SEARCH_WHOLE_WORD_KEY].as_bool()
+                    .expect("Couldn't find default whole word search setting!")
+            })
+    }
+
+    /// Updates the in-memory whole word search value.
+    pub fn set_search_whole_word(&mut self, whole_word: bool) {
+        self.search_whole_word = Some(whole_word);
+    }
+
     pub fn tab_width(&self, path: Option<&PathBuf>) -> usize {
         if let Some(value) = self.get_editorconfig(path).and_then(|p| p.indent_size) {
             match value {
@@ -256,7 +439,11 @@ impl Preferences {
         }
     }
 
+    /// If set, returns the in-memory line wrapping value, falling back to the
+    /// value set via the preferences file, and then the default value.
     pub fn line_wrapping(&self) -> bool {
+        if let Some(wrapping) = self.line_wrapping { return wrapping; }
+
         self.data
             .as_ref()
             .and_then(|data| if let Yaml::Boolean(wrapping) = data[LINE_WRAPPING_KEY] {
@@ -270,405 +457,1472 @@ impl Preferences {
             })
     }
 
-    pub fn tab_content(&self, path: Option<&PathBuf>) -> String {
-        if self.soft_tabs(path) {
-            format!("{:1$}", "", self.tab_width(path))
-        } else {
-            String::from("\t")
-        }
+    /// Updates the in-memory line wrapping value.
+    pub fn set_line_wrapping(&mut self, wrapping: bool) {
+        self.line_wrapping = Some(wrapping);
     }
 
-    pub fn open_mode_exclusions(&self) -> Result<Option<Vec<ExclusionPattern>>> {
-        let exclusion_data = self.data
+    pub fn relative_line_numbers(&self) -> bool {
+        self.data
             .as_ref()
-            .map(|data| &data[OPEN_MODE_KEY][OPEN_MODE_EXCLUSIONS_KEY]);
+            .and_then(|data| if let Yaml::Boolean(relative) = data[RELATIVE_LINE_NUMBERS_KEY] {
+                          Some(relative)
+                      } else {
+                          None
+                      })
+            .unwrap_or_else(|| {
+                self.default[RELATIVE_LINE_NUMBERS_KEY].as_bool()
+                    .expect("Couldn't find default relative line numbers setting!")
+            })
+    }
 
-        if let Some(exclusion_data) = exclusion_data {
-            match *exclusion_data {
-                Yaml::Array(ref exclusions) => {
-                    open::exclusions::parse(exclusions)
-                        .chain_err(|| "Failed to parse user-defined open mode exclusions")
-                        .map(Some)
-                },
-                Yaml::Boolean(_) => Ok(None),
-                _ => self.default_open_mode_exclusions(),
-            }
-        } else {
-            self.default_open_mode_exclusions()
-        }
+    pub fn system_clipboard(&self) -> bool {
+        self.data
+            .as_ref()
+            .and_then(|data| if let Yaml::Boolean(enabled) = data[SYSTEM_CLIPBOARD_KEY] {
+                          Some(enabled)
+                      } else {
+                          None
+                      })
+            .unwrap_or_else(|| {
+                self.default[SYSTEM_CLIPBOARD_KEY].as_bool()
+                    .expect("Couldn't find default system clipboard setting!")
+            })
     }
 
-    pub fn line_comment_prefix(&self, path: &PathBuf) -> Option<String> {
-        let extension = path_extension(Some(path))?;
+    pub fn clipboard_history_size(&self) -> usize {
+        self.data
+            .as_ref()
+            .and_then(|data| if let Yaml::Integer(size) = data[CLIPBOARD_HISTORY_SIZE_KEY] {
+                          Some(size.max(0) as usize)
+                      } else {
+                          None
+                      })
+            .unwrap_or_else(|| {
+                self.default[CLIPBOARD_HISTORY_SIZE_KEY].as_i64()
+                    .expect("Couldn't find default clipboard history size setting!") as usize
+            })
+    }
 
+    /// The number of files retained by the recent files list.
+    pub fn recent_files_limit(&self) -> usize {
         self.data
             .as_ref()
-            .and_then(|data| data[TYPES_KEY][extension][LINE_COMMENT_PREFIX_KEY].as_str())
-            .or_else(|| self.default[TYPES_KEY][extension][LINE_COMMENT_PREFIX_KEY].as_str())
-            .map(|prefix| prefix.to_owned())
+            .and_then(|data| if let Yaml::Integer(limit) = data[RECENT_FILES_LIMIT_KEY] {
+                          Some(limit.max(0) as usize)
+                      } else {
+                          None
+                      })
+            .unwrap_or_else(|| {
+                self.default[RECENT_FILES_LIMIT_KEY].as_i64()
+                    .expect("Couldn't find default recent files limit setting!") as usize
+            })
     }
 
-    pub fn syntax_definition_name(&self, path: &Path) -> Option<String> {
+    /// The column at which selected text is reflowed by `selection::hard_wrap`.
+    pub fn hard_wrap_column(&self) -> usize {
         self.data
             .as_ref()
-            .and_then(|data| {
-                // First try to match the file extension
-                if let Some(extension) = path.extension().and_then(|ext| ext.to_str()) {
-                    if let Some(syntax) = data[TYPES_KEY][extension][TYPES_SYNTAX_KEY].as_str() {
-                        return Some(syntax.to_owned());
-                    }
-                }
+            .and_then(|data| if let Yaml::Integer(column) = data[HARD_WRAP_COLUMN_KEY] {
+                          Some(column.max(0) as usize)
+                      } else {
+                          None
+                      })
+            .unwrap_or_else(|| {
+                self.default[HARD_WRAP_COLUMN_KEY].as_i64()
+                    .expect("Couldn't find default hard wrap column setting!") as usize
+            })
+    }
 
-                // If matching the file extension fails, try matching the whole filename
-                if let Some(path) = path.file_name().and_then(|name| name.to_str()) {
-                    if let Some(syntax) = data[TYPES_KEY][path][TYPES_SYNTAX_KEY].as_str() {
-                        return Some(syntax.to_owned());
-                    }
-                }
+    /// The minimum number of digits that the line number gutter is sized for.
+    pub fn minimum_line_number_width(&self) -> usize {
+        self.data
+            .as_ref()
+            .and_then(|data| if let Yaml::Integer(digits) = data[MINIMUM_LINE_NUMBER_WIDTH_KEY] {
+                          Some(digits.max(0) as usize)
+                      } else {
+                          None
+                      })
+            .unwrap_or_else(|| {
+                self.default[MINIMUM_LINE_NUMBER_WIDTH_KEY].as_i64()
+                    .expect("Couldn't find default minimum line number width setting!") as usize
+            })
+    }
 
-                None
+    /// A sample of the prefix added to the first line by `selection::number_lines`
+    /// (e.g. `1. `), from which the starting number, padding, and suffix are derived.
+    pub fn line_number_format(&self) -> &str {
+        self.data
+            .as_ref()
+            .and_then(|data| data[LINE_NUMBER_FORMAT_KEY].as_str())
+            .unwrap_or_else(|| {
+                self.default[LINE_NUMBER_FORMAT_KEY].as_str()
+                    .expect("Couldn't find default line number format setting!")
             })
     }
 
-    fn default_open_mode_exclusions(&self) -> Result<Option<Vec<ExclusionPattern>>> {
-        let exclusions = self.default[OPEN_MODE_KEY][OPEN_MODE_EXCLUSIONS_KEY]
-            .as_vec()
-            .chain_err(|| "Couldn't find default open mode exclusions settings!")?;
+    /// The format of timestamps inserted by `buffer::insert_timestamp`.
+    pub fn timestamp_format(&self) -> &str {
+        self.data
+            .as_ref()
+            .and_then(|data| data[TIMESTAMP_FORMAT_KEY].as_str())
+            .unwrap_or_else(|| {
+                self.default[TIMESTAMP_FORMAT_KEY].as_str()
+                    .expect("Couldn't find default timestamp format setting!")
+            })
+    }
 
-        open::exclusions::parse(exclusions)
-            .chain_err(|| "Failed to parse default open mode exclusions")
-            .map(Some)
+    /// The template used to describe the current buffer in the status line.
+    pub fn status_line_format(&self) -> &str {
+        self.data
+            .as_ref()
+            .and_then(|data| data[STATUS_LINE_FORMAT_KEY].as_str())
+            .unwrap_or_else(|| {
+                self.default[STATUS_LINE_FORMAT_KEY].as_str()
+                    .expect("Couldn't find default status line format setting!")
+            })
     }
 
-    pub fn remove_trailing_whitespace(&self, path: Option<&PathBuf>) -> bool {
-        if let Some(value) = self.get_editorconfig(path).and_then(|p| p.trim_trailing_whitespace) {
-            return value;
-        }
+    /// Whether typing an opening bracket or quote also inserts its closing counterpart.
+    pub fn auto_close_pairs(&self) -> bool {
+        self.data
+            .as_ref()
+            .and_then(|data| if let Yaml::Boolean(enabled) = data[AUTO_CLOSE_PAIRS_KEY] {
+                          Some(enabled)
+                      } else {
+                          None
+                      })
+            .unwrap_or_else(|| {
+                self.default[AUTO_CLOSE_PAIRS_KEY].as_bool()
+                    .expect("Couldn't find default auto close pairs setting!")
+            })
+    }
 
+    /// Whether whitespace at the end of lines is visibly marked when rendered.
+    pub fn highlight_trailing_whitespace(&self) -> bool {
         self.data
             .as_ref()
-            .and_then(|data| if let Yaml::Boolean(remove_trailing) = data[REMOVE_TRAILING_WHITESPACE_KEY] {
-                          Some(remove_trailing)
+            .and_then(|data| data[HIGHLIGHT_TRAILING_WHITESPACE_KEY].as_bool())
+            .unwrap_or_else(|| {
+                self.default[HIGHLIGHT_TRAILING_WHITESPACE_KEY].as_bool()
+                    .expect("Couldn't find default highlight trailing whitespace setting!")
+            })
+    }
+
+    /// The number of lines kept visible above and below the cursor when scrolling.
+    pub fn scroll_margin(&self) -> usize {
+        self.data
+            .as_ref()
+            .and_then(|data| if let Yaml::Integer(lines) = data[SCROLL_MARGIN_KEY] {
+                          Some(lines.max(0) as usize)
                       } else {
                           None
                       })
             .unwrap_or_else(|| {
-                self.default[REMOVE_TRAILING_WHITESPACE_KEY].as_bool()
-                    .expect("Couldn't find default remove_trailing_whitespace setting!")
+                self.default[SCROLL_MARGIN_KEY].as_i64()
+                    .expect("Couldn't find default scroll margin setting!") as usize
             })
     }
 
-    pub fn ensure_trailing_newline(&self, path: Option<&PathBuf>) -> bool {
-        if let Some(value) = self.get_editorconfig(path).and_then(|p| p.insert_final_newline) {
-            return value;
+    /// The number of columns kept visible on either side of the cursor
+    /// when scrolling long lines horizontally (i.e. without line wrapping).
+    pub fn horizontal_scroll_margin(&self) -> usize {
+        self.data
+            .as_ref()
+            .and_then(|data| if let Yaml::Integer(columns) = data[HORIZONTAL_SCROLL_MARGIN_KEY] {
+                          Some(columns.max(0) as usize)
+                      } else {
+                          None
+                      })
+            .unwrap_or_else(|| {
+                self.default[HORIZONTAL_SCROLL_MARGIN_KEY].as_i64()
+                    .expect("Couldn't find default horizontal scroll margin setting!") as usize
+            })
+    }
+
+    /// Whether large scroll jumps are animated over a few frames.
+    pub fn smooth_scrolling(&self) -> bool {
+        self.data
+            .as_ref()
+            .and_then(|data| data[SMOOTH_SCROLLING_KEY].as_bool())
+            .unwrap_or_else(|| {
+                self.default[SMOOTH_SCROLLING_KEY].as_bool()
+                    .expect("Couldn't find default smooth scrolling setting!")
+            })
+    }
+
+    /// Whether spaces, tabs, and line breaks are rendered using visible characters.
+    pub fn show_whitespace(&self) -> bool {
+        if let Some(show_whitespace) = self.show_whitespace { return show_whitespace; }
+
+        self.data
+            .as_ref()
+            .and_then(|data| data[SHOW_WHITESPACE_KEY].as_bool())
+            .unwrap_or_else(|| {
+                self.default[SHOW_WHITESPACE_KEY].as_bool()
+                    .expect("Couldn't find default show whitespace setting!")
+            })
+    }
+
+    /// Updates the in-memory show whitespace value.
+    pub fn set_show_whitespace(&mut self, show_whitespace: bool) {
+        self.show_whitespace = Some(show_whitespace);
+    }
+
+    /// The character used to render spaces when showing whitespace.
+    pub fn space_character(&self) -> &str {
+        self.whitespace_character(WHITESPACE_CHARACTERS_SPACE_KEY)
+    }
+
+    /// The character used to mark tabs when showing whitespace (or tabs).
+    pub fn tab_character(&self) -> &str {
+        self.whitespace_character(WHITESPACE_CHARACTERS_TAB_KEY)
+    }
+
+    /// The character used to mark line breaks when showing whitespace.
+    pub fn newline_character(&self) -> &str {
+        self.whitespace_character(WHITESPACE_CHARACTERS_NEWLINE_KEY)
+    }
+
+    fn whitespace_character(&self, key: &str) -> &str {
+        self.data
+            .as_ref()
+            .and_then(|data| data[WHITESPACE_CHARACTERS_KEY][key].as_str())
+            .unwrap_or_else(|| {
+                self.default[WHITESPACE_CHARACTERS_KEY][key].as_str()
+                    .expect("Couldn't find default whitespace character setting!")
+            })
+    }
+
+    /// Whether tabs are rendered with a marker, to distinguish them from spaces.
+    pub fn visible_tabs(&self) -> bool {
+        self.data
+            .as_ref()
+            .and_then(|data| data[VISIBLE_TABS_KEY].as_bool())
+            .unwrap_or_else(|| {
+                self.default[VISIBLE_TABS_KEY].as_bool()
+                    .expect("Couldn't find default visible tabs setting!")
+            })
+    }
+
+    /// How long (in milliseconds) to wait for the rest of a multi-key
+    /// binding (e.g. `g g`) before handling the keys pressed so far.
+    pub fn key_sequence_timeout(&self) -> Duration {
+        let milliseconds = self.data
+            .as_ref()
+            .and_then(|data| if let Yaml::Integer(milliseconds) = data[KEY_SEQUENCE_TIMEOUT_KEY] {
+                          Some(milliseconds.max(0) as u64)
+                      } else {
+                          None
+                      })
+            .unwrap_or_else(|| {
+                self.default[KEY_SEQUENCE_TIMEOUT_KEY].as_i64()
+                    .expect("Couldn't find default key sequence timeout setting!") as u64
+            });
+
+        Duration::from_millis(milliseconds)
+    }
+
+    /// The idle period (in seconds) after which modified buffers are saved.
+    /// Returns `None` if autosaving is disabled (i.e. the interval is zero).
+    pub fn autosave_interval(&self) -> Option<Duration> {
+        let seconds = self.data
+            .as_ref()
+            .and_then(|data| if let Yaml::Integer(seconds) = data[AUTOSAVE_INTERVAL_KEY] {
+                          Some(seconds.max(0) as u64)
+                      } else {
+                          None
+                      })
+            .unwrap_or_else(|| {
+                self.default[AUTOSAVE_INTERVAL_KEY].as_i64()
+                    .expect("Couldn't find default autosave interval setting!") as u64
+            });
+
+        if seconds > 0 {
+            Some(Duration::from_secs(seconds))
+        } else {
+            None
         }
+    }
 
+    pub fn watch_preferences(&self) -> bool {
         self.data
             .as_ref()
-            .and_then(|data| if let Yaml::Boolean(final_newline) = data[ENSURE_TRAILING_NEWLINE_KEY] {
-                          Some(final_newline)
+            .and_then(|data| if let Yaml::Boolean(enabled) = data[WATCH_PREFERENCES_KEY] {
+                          Some(enabled)
                       } else {
                           None
                       })
             .unwrap_or_else(|| {
-                self.default[ENSURE_TRAILING_NEWLINE_KEY].as_bool()
-                    .expect("Couldn't find default ensure_trailing_newline setting!")
+                self.default[WATCH_PREFERENCES_KEY].as_bool()
+                    .expect("Couldn't find default watch preferences setting!")
+            })
+    }
+
+    pub fn backup_on_save(&self) -> bool {
+        self.data
+            .as_ref()
+            .and_then(|data| if let Yaml::Boolean(enabled) = data[BACKUP_ON_SAVE_KEY] {
+                          Some(enabled)
+                      } else {
+                          None
+                      })
+            .unwrap_or_else(|| {
+                self.default[BACKUP_ON_SAVE_KEY].as_bool()
+                    .expect("Couldn't find default backup on save setting!")
             })
     }
 
-    pub fn use_editorconfig(&self) -> bool {
-        if self.editorconfig.is_none() {
-            return false;
-        }
+    /// Whether reopened files place the cursor where it was last left.
+    pub fn restore_cursor_positions(&self) -> bool {
+        self.data
+            .as_ref()
+            .and_then(|data| if let Yaml::Boolean(enabled) = data[RESTORE_CURSOR_POSITIONS_KEY] {
+                          Some(enabled)
+                      } else {
+                          None
+                      })
+            .unwrap_or_else(|| {
+                self.default[RESTORE_CURSOR_POSITIONS_KEY].as_bool()
+                    .expect("Couldn't find default restore cursor positions setting!")
+            })
+    }
+
+    /// The minimum severity of entries written to the log.
+    pub fn log_level(&self) -> LogLevel {
+        self.data
+            .as_ref()
+            .and_then(|data| data[LOG_LEVEL_KEY].as_str())
+            .and_then(parse_log_level)
+            .unwrap_or_else(|| {
+                self.default[LOG_LEVEL_KEY].as_str()
+                    .and_then(parse_log_level)
+                    .expect("Couldn't find default log level setting!")
+            })
+    }
+
+    /// The size (in bytes) beyond which the log is rotated.
+    pub fn log_size_limit(&self) -> u64 {
+        self.data
+            .as_ref()
+            .and_then(|data| if let Yaml::Integer(size) = data[LOG_SIZE_LIMIT_KEY] {
+                          Some(size.max(0) as u64)
+                      } else {
+                          None
+                      })
+            .unwrap_or_else(|| {
+                self.default[LOG_SIZE_LIMIT_KEY].as_i64()
+                    .expect("Couldn't find default log size limit setting!") as u64
+            })
+    }
+
+    pub fn tab_content(&self, path: Option<&PathBuf>) -> String {
+        if self.soft_tabs(path) {
+            format!("{:1$}", "", self.tab_width(path))
+        } else {
+            String::from("\t")
+        }
+    }
+
+    pub fn open_mode_exclusions(&self) -> Result<Option<Vec<ExclusionPattern>>> {
+        let exclusion_data = self.data
+            .as_ref()
+            .map(|data| &data[OPEN_MODE_KEY][OPEN_MODE_EXCLUSIONS_KEY]);
+
+        if let Some(exclusion_data) = exclusion_data {
+            match *exclusion_data {
+                Yaml::Array(ref exclusions) => {
+                    open::exclusions::parse(exclusions)
+                        .chain_err(|| "Failed to parse user-defined open mode exclusions")
+                        .map(Some)
+                },
+                Yaml::Boolean(_) => Ok(None),
+                _ => self.default_open_mode_exclusions(),
+            }
+        } else {
+            self.default_open_mode_exclusions()
+        }
+    }
+
+    /// Whether open mode indexes VCS directories and files ignored by git.
+    pub fn open_mode_include_ignored(&self) -> bool {
+        self.data
+            .as_ref()
+            .and_then(|data| data[OPEN_MODE_KEY][OPEN_MODE_INCLUDE_IGNORED_KEY].as_bool())
+            .unwrap_or_else(|| {
+                self.default[OPEN_MODE_KEY][OPEN_MODE_INCLUDE_IGNORED_KEY].as_bool()
+                    .expect("Couldn't find default open mode include ignored setting!")
+            })
+    }
+
+    pub fn line_comment_prefix(&self, path: &PathBuf) -> Option<String> {
+        let extension = path_extension(Some(path))?;
+
+        self.data
+            .as_ref()
+            .and_then(|data| data[TYPES_KEY][extension][LINE_COMMENT_PREFIX_KEY].as_str())
+            .or_else(|| self.default[TYPES_KEY][extension][LINE_COMMENT_PREFIX_KEY].as_str())
+            .map(|prefix| prefix.to_owned())
+    }
+
+    /// The shell command used by `buffer::format` to format files of the
+    /// specified type, which is given their content on stdin.
+    pub fn formatter(&self, path: &PathBuf) -> Option<String> {
+        let extension = path_extension(Some(path))?;
+
+        self.data
+            .as_ref()
+            .and_then(|data| data[TYPES_KEY][extension][FORMATTER_KEY].as_str())
+            .map(|formatter| formatter.to_owned())
+    }
+
+    /// Whether misspelled words are highlighted in buffers of the specified
+    /// type. Spell checking is intended for prose, so it's opt-in by type.
+    pub fn spell_check(&self, path: Option<&PathBuf>) -> bool {
+        let extension = match path_extension(path) {
+            Some(extension) => extension,
+            None => return false,
+        };
+
+        self.data
+            .as_ref()
+            .and_then(|data| data[TYPES_KEY][extension][SPELL_CHECK_KEY].as_bool())
+            .unwrap_or(false)
+    }
+
+    /// The dictionary used for spell checking, with one word per line.
+    /// Relative paths are resolved against the preferences directory.
+    pub fn spell_check_dictionary(&self) -> Result<PathBuf> {
+        let path = self.data
+            .as_ref()
+            .and_then(|data| data[SPELL_CHECK_DICTIONARY_KEY].as_str())
+            .unwrap_or_else(|| {
+                self.default[SPELL_CHECK_DICTIONARY_KEY].as_str()
+                    .expect("Couldn't find default spell check dictionary setting!")
+            });
+
+        Ok(Preferences::directory()?.join(path))
+    }
+
+    /// Whether buffers with a configured formatter are formatted before saving.
+    pub fn format_on_save(&self) -> bool {
+        self.data
+            .as_ref()
+            .and_then(|data| if let Yaml::Boolean(enabled) = data[FORMAT_ON_SAVE_KEY] {
+                          Some(enabled)
+                      } else {
+                          None
+                      })
+            .unwrap_or_else(|| {
+                self.default[FORMAT_ON_SAVE_KEY].as_bool()
+                    .expect("Couldn't find default format on save setting!")
+            })
+    }
+
+    pub fn syntax_definition_name(&self, path: &Path) -> Option<String> {
+        self.data
+            .as_ref()
+            .and_then(|data| {
+                // First try to match the file extension
+                if let Some(extension) = path.extension().and_then(|ext| ext.to_str()) {
+                    if let Some(syntax) = data[TYPES_KEY][extension][TYPES_SYNTAX_KEY].as_str() {
+                        return Some(syntax.to_owned());
+                    }
+                }
+
+                // If matching the file extension fails, try matching the whole filename
+                if let Some(path) = path.file_name().and_then(|name| name.to_str()) {
+                    if let Some(syntax) = data[TYPES_KEY][path][TYPES_SYNTAX_KEY].as_str() {
+                        return Some(syntax.to_owned());
+                    }
+                }
+
+                None
+            })
+    }
+
+    fn default_open_mode_exclusions(&self) -> Result<Option<Vec<ExclusionPattern>>> {
+        let exclusions = self.default[OPEN_MODE_KEY][OPEN_MODE_EXCLUSIONS_KEY]
+            .as_vec()
+            .chain_err(|| "Couldn't find default open mode exclusions settings!")?;
+
+        open::exclusions::parse(exclusions)
+            .chain_err(|| "Failed to parse default open mode exclusions")
+            .map(Some)
+    }
+
+    pub fn remove_trailing_whitespace(&self, path: Option<&PathBuf>) -> bool {
+        if let Some(value) = self.get_editorconfig(path).and_then(|p| p.trim_trailing_whitespace) {
+            return value;
+        }
+
+        self.data
+            .as_ref()
+            .and_then(|data| if let Yaml::Boolean(remove_trailing) = data[REMOVE_TRAILING_WHITESPACE_KEY] {
+                          Some(remove_trailing)
+                      } else {
+                          None
+                      })
+            .unwrap_or_else(|| {
+                self.default[REMOVE_TRAILING_WHITESPACE_KEY].as_bool()
+                    .expect("Couldn't find default remove_trailing_whitespace setting!")
+            })
+    }
+
+    pub fn ensure_trailing_newline(&self, path: Option<&PathBuf>) -> bool {
+        if let Some(value) = self.get_editorconfig(path).and_then(|p| p.insert_final_newline) {
+            return value;
+        }
+
+        self.data
+            .as_ref()
+            .and_then(|data| if let Yaml::Boolean(final_newline) = data[ENSURE_TRAILING_NEWLINE_KEY] {
+                          Some(final_newline)
+                      } else {
+                          None
+                      })
+            .unwrap_or_else(|| {
+                self.default[ENSURE_TRAILING_NEWLINE_KEY].as_bool()
+                    .expect("Couldn't find default ensure_trailing_newline setting!")
+            })
+    }
+
+    pub fn collapse_trailing_newlines(&self) -> bool {
+        self.data
+            .as_ref()
+            .and_then(|data| if let Yaml::Boolean(collapse) = data[COLLAPSE_TRAILING_NEWLINES_KEY] {
+                          Some(collapse)
+                      } else {
+                          None
+                      })
+            .unwrap_or_else(|| {
+                self.default[COLLAPSE_TRAILING_NEWLINES_KEY].as_bool()
+                    .expect("Couldn't find default collapse_trailing_newlines setting!")
+            })
+    }
+
+    pub fn use_editorconfig(&self) -> bool {
+        if self.editorconfig.is_none() {
+            return false;
+        }
+
+        self.data
+            .as_ref()
+            .and_then(|data| if let Yaml::Boolean(use_ec) = data[USE_EDITORCONFIG_KEY] {
+                          Some(use_ec)
+                      } else {
+                          None
+                      })
+            .unwrap_or_else(|| {
+                self.default[USE_EDITORCONFIG_KEY].as_bool()
+                    .expect("Couldn't find default use_editorconfig setting!")
+            })
+    }
+
+    /// Disable .editorconfig support on test configurations to avoid a lot of false test-failures.
+    #[cfg(test)]
+    fn get_editorconfig(&self, _path: Option<&PathBuf>) -> Option<&editorconfig::Section> {
+        None
+    }
+
+    /// Returns the applicable .editorconfig section for a specific file.
+    #[cfg(not(test))]
+    fn get_editorconfig(&self, path: Option<&PathBuf>) -> Option<&editorconfig::Section> {
+        if self.use_editorconfig() {
+            let path = path?.to_str()?;
+
+            // If the editorconfig could have rules for this file, return them.
+            self.editorconfig
+                .as_ref()?
+                .get(&*path)
+        } else {
+            None
+        }
+    }
+}
+
+/// Loads the first YAML document in the user's config file, if there is one.
+fn load_document() -> Result<Option<Yaml>> {
+    // Build a path to the config file.
+    let mut config_path =
+        get_app_root(AppDataType::UserConfig, &APP_INFO)
+            .chain_err(|| "Couldn't open application config directory")?;
+    config_path.push(FILE_NAME);
+
+    // Project preferences can be used without any of the user's own.
+    if !config_path.exists() {
+        return Ok(None);
+    }
+
+    // Open (or create) the config file.
+    let mut config_file = OpenOptions::new()
+        .read(true)
+        .open(config_path)
+        .chain_err(|| "Couldn't open config file")?;
+
+    // Read the config file's contents.
+    let mut data = String::new();
+    config_file
+        .read_to_string(&mut data)
+        .chain_err(|| "Couldn't read config file")?;
+
+    // Parse the config file's contents and get the first YAML document inside.
+    let parsed_data = YamlLoader::load_from_str(&data)
+        .chain_err(|| "Couldn't parse config file")?;
+    Ok(parsed_data.into_iter().nth(0))
+}
+
+fn load_default_document() -> Result<Yaml> {
+    YamlLoader::load_from_str(include_str!("default.yml"))
+        .chain_err(|| "Couldn't parse default config file")?
+        .into_iter().nth(0)
+        .chain_err(|| "No default preferences document found")
+}
+
+/// Merges the workspace directory's project preferences file (if there is one)
+/// over the user's preferences. A file that can't be loaded is skipped, with
+/// a warning describing the problem.
+fn apply_project_document(data: Option<Yaml>) -> (Option<Yaml>, Option<String>) {
+    let project_document = env::current_dir()
+        .chain_err(|| "Couldn't retreive current working directory")
+        .and_then(|directory| load_project_document(&directory));
+
+    match project_document {
+        Ok(Some(project_data)) => {
+            let mut merged_data = data.unwrap_or_else(|| Yaml::Hash(Hash::new()));
+            merge_document(&mut merged_data, project_data);
+
+            (Some(merged_data), None)
+        }
+        Ok(None) => (data, None),
+        Err(error) => (data, Some(format!("{}; using global preferences", error))),
+    }
+}
+
+/// Loads the first YAML document in the directory's project preferences file.
+fn load_project_document(directory: &Path) -> Result<Option<Yaml>> {
+    let path = directory.join(PROJECT_FILE_NAME);
+    if !path.exists() {
+        return Ok(None);
+    }
+
+    let data = fs::read_to_string(&path)
+        .chain_err(|| format!("Couldn't read {}", PROJECT_FILE_NAME))?;
+    let document = match YamlLoader::load_from_str(&data) {
+        Ok(documents) => documents.into_iter().nth(0),
+        Err(error) => bail!("Couldn't parse {}: {}", PROJECT_FILE_NAME, error),
+    };
+
+    match document {
+        Some(Yaml::Hash(mut hash)) => {
+            remove_untrusted_preferences(&mut hash);
+            Ok(Some(Yaml::Hash(hash)))
+        }
+        None => Ok(None),
+        Some(_) => bail!("Couldn't parse {}: expected a hash of preferences", PROJECT_FILE_NAME),
+    }
+}
+
+/// Discards preferences that run commands (formatters) or bind keys to them,
+/// so that opening a workspace (e.g. a freshly cloned repository) can't use
+/// its project preferences file to run arbitrary commands.
+fn remove_untrusted_preferences(hash: &mut Hash) {
+    for key in [FORMAT_ON_SAVE_KEY, KEYMAP_KEY].iter() {
+        hash.remove(&Yaml::String(key.to_string()));
+    }
+
+    if let Some(Yaml::Hash(types)) = hash.get_mut(&Yaml::String(TYPES_KEY.to_string())) {
+        for (_, type_preferences) in types.iter_mut() {
+            if let Yaml::Hash(type_hash) = type_preferences {
+                type_hash.remove(&Yaml::String(FORMATTER_KEY.to_string()));
+            }
+        }
+    }
+}
+
+/// Recursively merges the overrides into the document, such that nested
+/// hashes (e.g. `types`) are combined key by key, and other values replaced.
+fn merge_document(document: &mut Yaml, overrides: Yaml) {
+    match (document, overrides) {
+        (Yaml::Hash(document_hash), Yaml::Hash(override_hash)) => {
+            for (key, value) in override_hash {
+                match document_hash.get_mut(&key) {
+                    Some(document_value) => merge_document(document_value, value),
+                    None => {
+                        document_hash.insert(key, value);
+                    }
+                }
+            }
+        }
+        (document, overrides) => *document = overrides,
+    }
+}
+
+/// Loads default keymaps, merging in the provided overrides and then those
+/// in the user's keymap file, along with descriptions of skipped bindings.
+fn load_keymap(keymap_overrides: Option<&Hash>) -> Result<(KeyMap, Vec<String>)> {
+    let mut keymap = KeyMap::default()?;
+    let mut warnings = Vec::new();
+    let keymap_document = load_keymap_document(&mut warnings);
+
+    // Merge user-defined keymaps into defaults.
+    let overrides = keymap_overrides
+        .into_iter()
+        .chain(keymap_document.as_ref().and_then(|document| document.as_hash()));
+    for keymap_data in overrides {
+        let (user_keymap, user_warnings) = KeyMap::from_overrides(keymap_data);
+        keymap.merge(user_keymap);
+        warnings.extend(user_warnings);
+    }
+
+    Ok((keymap, warnings))
+}
+
+/// Loads the first YAML document in the user's keymap file, if there is one.
+fn load_keymap_document(warnings: &mut Vec<String>) -> Option<Yaml> {
+    let path = Preferences::directory().ok()?.join(KEYMAP_FILE_NAME);
+    let data = fs::read_to_string(path).ok()?;
+
+    match YamlLoader::load_from_str(&data) {
+        Ok(documents) => documents.into_iter().nth(0),
+        Err(error) => {
+            warnings.push(format!("Couldn't parse {}: {}", KEYMAP_FILE_NAME, error));
+            None
+        }
+    }
+}
+
+/// Loads a .editorconfig from workspace directory if present.
+fn load_editorconfig() -> Result<EditorConfig> {
+    let workspace_path = env::current_dir()
+        .chain_err(|| "Couldn't retreive current working directory")?;
+
+    EditorConfig::from_directory(&workspace_path)
+}
+
+fn parse_case_sensitivity(value: &str) -> Option<CaseSensitivity> {
+    match value {
+        "sensitive" => Some(CaseSensitivity::Sensitive),
+        "insensitive" => Some(CaseSensitivity::Insensitive),
+        "smart" => Some(CaseSensitivity::Smart),
+        _ => None,
+    }
+}
+
+fn parse_log_level(value: &str) -> Option<LogLevel> {
+    match value {
+        "error" => Some(LogLevel::Error),
+        "warn" => Some(LogLevel::Warn),
+        "info" => Some(LogLevel::Info),
+        "debug" => Some(LogLevel::Debug),
+        _ => None,
+    }
+}
+
+/// Maps a path to its file extension.
+fn path_extension(path: Option<&PathBuf>) -> Option<&str> {
+    path
+        .and_then(|p| p.extension().or_else(|| p.as_path().file_name()))
+        .and_then(|e| e.to_str())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ExclusionPattern, Preferences, YamlLoader};
+    use crate::models::application::modes::CaseSensitivity;
+    use crate::models::application::LogLevel;
+    use std::env;
+    use std::fs;
+    use std::path::{Path, PathBuf};
+    use std::time::Duration;
+    use crate::input::KeyMap;
+    use crate::yaml::yaml::{Hash, Yaml};
+
+    #[test]
+    fn merge_document_prefers_project_values_field_by_field() {
+        let mut data = YamlLoader::load_from_str(
+            "tab_width: 2\ntypes:\n  rs:\n    tab_width: 4\n    soft_tabs: false"
+        ).unwrap().remove(0);
+        let project_data = YamlLoader::load_from_str(
+            "theme: project_theme\ntypes:\n  rs:\n    tab_width: 8"
+        ).unwrap().remove(0);
+        super::merge_document(&mut data, project_data);
+        let preferences = Preferences::new(Some(data));
+
+        assert_eq!(preferences.theme(), "project_theme");
+        assert_eq!(preferences.tab_width(None), 2);
+        assert_eq!(preferences.tab_width(Some(&PathBuf::from("main.rs"))), 8);
+        assert!(!preferences.soft_tabs(Some(&PathBuf::from("main.rs"))));
+    }
+
+    #[test]
+    fn load_project_document_rejects_malformed_files() {
+        let directory = env::temp_dir().join("amp_project_preferences_test");
+        fs::create_dir_all(&directory).unwrap();
+        assert!(super::load_project_document(&directory).unwrap().is_none());
+
+        fs::write(directory.join(".amp.yml"), "tab_width: [2").unwrap();
+        assert!(super::load_project_document(&directory).is_err());
+
+        fs::write(directory.join(".amp.yml"), "tab_width: 8").unwrap();
+        assert!(super::load_project_document(&directory).unwrap().is_some());
+
+        fs::remove_dir_all(&directory).unwrap();
+    }
+
+    #[test]
+    fn load_project_document_ignores_preferences_that_run_commands() {
+        let directory = env::temp_dir().join("amp_untrusted_project_preferences_test");
+        fs::create_dir_all(&directory).unwrap();
+        fs::write(
+            directory.join(".amp.yml"),
+            "format_on_save: true\nkeymap:\n  normal:\n    x: buffer::format\n\
+             types:\n  rs:\n    formatter: touch pwned\n    tab_width: 8"
+        ).unwrap();
+
+        let data = super::load_project_document(&directory).unwrap();
+        fs::remove_dir_all(&directory).unwrap();
+        let preferences = Preferences::new(data);
+        let path = PathBuf::from("main.rs");
+
+        assert!(!preferences.format_on_save());
+        assert!(preferences.formatter(&path).is_none());
+        assert_eq!(preferences.tab_width(Some(&path)), 8);
+        assert!(preferences.data.as_ref().unwrap()["keymap"].is_badvalue());
+    }
+
+    #[test]
+    fn preferences_returns_user_defined_theme_name() {
+        let data = YamlLoader::load_from_str("theme: \"my_theme\"").unwrap();
+        let preferences = Preferences::new(data.into_iter().nth(0));
+
+        assert_eq!(preferences.theme(), "my_theme");
+    }
+
+    #[test]
+    fn set_theme_updates_in_memory_value() {
+        let mut preferences = Preferences::new(None);
+        preferences.set_theme("new_in_memory_theme");
+
+        assert_eq!(preferences.theme(), "new_in_memory_theme");
+    }
+
+    #[test]
+    fn preferences_returns_user_defined_search_regex() {
+        let data = YamlLoader::load_from_str("search_regex: true").unwrap();
+        let preferences = Preferences::new(data.into_iter().nth(0));
+
+        assert_eq!(preferences.search_regex(), true);
+    }
+
+    #[test]
+    fn set_search_regex_updates_in_memory_value() {
+        let data = YamlLoader::load_from_str("search_regex: true").unwrap();
+        let mut preferences = Preferences::new(data.into_iter().nth(0));
+        preferences.set_search_regex(false);
+
+        assert_eq!(preferences.search_regex(), false);
+    }
+
+    #[test]
+    fn preferences_returns_default_search_regex_when_user_defined_data_not_found() {
+        let preferences = Preferences::new(None);
+
+        assert_eq!(preferences.search_regex(), false);
+    }
+
+    #[test]
+    fn preferences_returns_user_defined_search_case_sensitivity() {
+        let data = YamlLoader::load_from_str("search_case_sensitivity: insensitive").unwrap();
+        let preferences = Preferences::new(data.into_iter().nth(0));
+
+        assert_eq!(preferences.search_case_sensitivity(), CaseSensitivity::Insensitive);
+    }
+
+    #[test]
+    fn set_search_case_sensitivity_updates_in_memory_value() {
+        let mut preferences = Preferences::new(None);
+        preferences.set_search_case_sensitivity(CaseSensitivity::Sensitive);
+
+        assert_eq!(preferences.search_case_sensitivity(), CaseSensitivity::Sensitive);
+    }
+
+    #[test]
+    fn preferences_returns_default_search_case_sensitivity_for_invalid_data() {
+        let data = YamlLoader::load_from_str("search_case_sensitivity: sometimes").unwrap();
+        let preferences = Preferences::new(data.into_iter().nth(0));
+
+        assert_eq!(preferences.search_case_sensitivity(), CaseSensitivity::Smart);
+    }
+
+    #[test]
+    fn preferences_returns_user_defined_search_whole_word() {
+        let data = YamlLoader::load_from_str("search_whole_word: true").unwrap();
+        let preferences = Preferences::new(data.into_iter().nth(0));
+
+        assert_eq!(preferences.search_whole_word(), true);
+    }
+
+    #[test]
+    fn preferences_returns_default_search_whole_word_when_user_defined_data_not_found() {
+        let preferences = Preferences::new(None);
+
+        assert_eq!(preferences.search_whole_word(), false);
+    }
+
+    #[test]
+    fn preferences_returns_default_theme_when_user_defined_data_not_found() {
+        let preferences = Preferences::new(None);
+
+        assert_eq!(preferences.theme(), "solarized_dark");
+    }
+
+    #[test]
+    fn tab_width_returns_user_defined_data() {
+        let data = YamlLoader::load_from_str("tab_width: 12").unwrap();
+        let preferences = Preferences::new(data.into_iter().nth(0));
+
+        assert_eq!(preferences.tab_width(None), 12);
+    }
+
+    #[test]
+    fn tab_width_returns_user_defined_type_specific_data() {
+        let data = YamlLoader::load_from_str("tab_width: 12\ntypes:\n  rs:\n    tab_width: 24")
+            .unwrap();
+        let preferences = Preferences::new(data.into_iter().nth(0));
+
+        assert_eq!(preferences.tab_width(Some(PathBuf::from("preferences.rs")).as_ref()),
+                   24);
+    }
+
+    #[test]
+    fn tab_width_returns_default_when_user_defined_type_specific_data_not_found() {
+        let data = YamlLoader::load_from_str("tab_width: 12").unwrap();
+        let preferences = Preferences::new(data.into_iter().nth(0));
+
+        assert_eq!(preferences.tab_width(Some(PathBuf::from("preferences.rs")).as_ref()),
+                   12);
+    }
+
+    #[test]
+    fn preferences_returns_default_tab_width_when_user_defined_data_not_found() {
+        let preferences = Preferences::new(None);
+
+        assert_eq!(preferences.tab_width(None), 2);
+    }
+
+    #[test]
+    fn soft_tabs_returns_user_defined_data() {
+        let data = YamlLoader::load_from_str("soft_tabs: false").unwrap();
+        let preferences = Preferences::new(data.into_iter().nth(0));
+
+        assert_eq!(preferences.soft_tabs(None), false);
+    }
+
+    #[test]
+    fn soft_tabs_returns_user_defined_type_specific_data() {
+        let data = YamlLoader::load_from_str("soft_tabs: true\ntypes:\n  rs:\n    soft_tabs: false").unwrap();
+        let preferences = Preferences::new(data.into_iter().nth(0));
+
+        assert_eq!(preferences.soft_tabs(Some(PathBuf::from("preferences.rs")).as_ref()), false);
+    }
+
+    #[test]
+    fn soft_tabs_returns_default_when_user_defined_type_specific_data_not_found() {
+        let data = YamlLoader::load_from_str("soft_tabs: false").unwrap();
+        let preferences = Preferences::new(data.into_iter().nth(0));
+
+        assert_eq!(preferences.soft_tabs(Some(PathBuf::from("preferences.rs")).as_ref()), false);
+    }
+
+    #[test]
+    fn preferences_returns_default_soft_tabs_when_user_defined_data_not_found() {
+        let preferences = Preferences::new(None);
+
+        assert_eq!(preferences.soft_tabs(None), true);
+    }
+
+    #[test]
+    fn non_extension_types_are_supported_for_type_specific_data() {
+        let data = YamlLoader::load_from_str("soft_tabs: true\ntypes:\n  Makefile:\n    soft_tabs: false").unwrap();
+        let preferences = Preferences::new(data.into_iter().nth(0));
+
+        assert_eq!(preferences.soft_tabs(Some(PathBuf::from("Makefile")).as_ref()), false);
+    }
+
+    #[test]
+    fn syntax_definition_name_returns_user_defined_syntax_by_extension_for_full_filename() {
+        let data = YamlLoader::load_from_str("types:\n  xyz:\n    syntax: Rust").unwrap();
+        let preferences = Preferences::new(data.into_iter().nth(0));
+
+        assert_eq!(preferences.syntax_definition_name(&Path::new("test.xyz")),
+                   Some("Rust".to_owned()));
+    }
+
+    #[test]
+    fn syntax_definition_name_returns_user_defined_syntax_by_extension_for_deep_filename() {
+        let data = YamlLoader::load_from_str("types:\n  xyz:\n    syntax: Rust").unwrap();
+        let preferences = Preferences::new(data.into_iter().nth(0));
+
+        assert_eq!(preferences.syntax_definition_name(&Path::new("src/test.xyz")),
+                   Some("Rust".to_owned()));
+    }
+
+    #[test]
+    fn syntax_definition_name_returns_user_defined_syntax_for_full_filename_without_extension() {
+        let data = YamlLoader::load_from_str("types:\n  Makefile:\n    syntax: Makefile").unwrap();
+        let preferences = Preferences::new(data.into_iter().nth(0));
+
+        assert_eq!(preferences.syntax_definition_name(&Path::new("Makefile")),
+                   Some("Makefile".to_owned()));
+    }
+
+    #[test]
+    fn syntax_definition_name_returns_user_defined_syntax_for_full_deep_filename() {
+        let data = YamlLoader::load_from_str("types:\n  Makefile:\n    syntax: Makefile").unwrap();
+        let preferences = Preferences::new(data.into_iter().nth(0));
+
+        assert_eq!(preferences.syntax_definition_name(&Path::new("src/Makefile")),
+                   Some("Makefile".to_owned()));
+    }
+
+    #[test]
+    fn syntax_definition_name_returns_user_defined_syntax_for_full_filename_with_extension() {
+        let data = YamlLoader::load_from_str("types:\n  Makefile.lib:\n    syntax: Makefile").unwrap();
+        let preferences = Preferences::new(data.into_iter().nth(0));
+
+        assert_eq!(preferences.syntax_definition_name(&Path::new("Makefile.lib")),
+                   Some("Makefile".to_owned()));
+    }
+
+    #[test]
+    fn preferences_returns_user_defined_line_length_guide() {
+        let data = YamlLoader::load_from_str("line_length_guide: 100").unwrap();
+        let preferences = Preferences::new(data.into_iter().nth(0));
+
+        assert_eq!(preferences.line_length_guides(), vec![100]);
+    }
+
+    #[test]
+    fn preferences_returns_user_defined_multiple_line_length_guides() {
+        let data = YamlLoader::load_from_str("line_length_guide: [80, 100, 120]").unwrap();
+        let preferences = Preferences::new(data.into_iter().nth(0));
+
+        assert_eq!(preferences.line_length_guides(), vec![80, 100, 120]);
+    }
+
+    #[test]
+    fn preferences_returns_user_disabled_line_length_guide() {
+        let data = YamlLoader::load_from_str("line_length_guide: false").unwrap();
+        let preferences = Preferences::new(data.into_iter().nth(0));
+
+        assert_eq!(preferences.line_length_guides(), Vec::<usize>::new());
+    }
+
+    #[test]
+    fn preferences_returns_user_default_line_length_guide() {
+        let data = YamlLoader::load_from_str("line_length_guide: true").unwrap();
+        let preferences = Preferences::new(data.into_iter().nth(0));
+
+        assert_eq!(preferences.line_length_guides(), vec![80]);
+    }
+
+    #[test]
+    fn preferences_returns_user_defined_line_wrapping() {
+        let data = YamlLoader::load_from_str("line_wrapping: false").unwrap();
+        let preferences = Preferences::new(data.into_iter().nth(0));
+
+        assert_eq!(preferences.line_wrapping(), false);
+    }
+
+    #[test]
+    fn preferences_returns_default_line_wrapping_when_user_defined_data_not_found() {
+        let preferences = Preferences::new(None);
+
+        assert_eq!(preferences.line_wrapping(), true);
+    }
+
+    #[test]
+    fn set_line_wrapping_updates_in_memory_value() {
+        let data = YamlLoader::load_from_str("line_wrapping: true").unwrap();
+        let mut preferences = Preferences::new(data.into_iter().nth(0));
+        preferences.set_line_wrapping(false);
+
+        assert_eq!(preferences.line_wrapping(), false);
+    }
+
+    #[test]
+    fn preferences_returns_user_defined_relative_line_numbers() {
+        let data = YamlLoader::load_from_str("relative_line_numbers: true").unwrap();
+        let preferences = Preferences::new(data.into_iter().nth(0));
+
+        assert_eq!(preferences.relative_line_numbers(), true);
+    }
+
+    #[test]
+    fn preferences_returns_default_relative_line_numbers_when_user_defined_data_not_found() {
+        let preferences = Preferences::new(None);
+
+        assert_eq!(preferences.relative_line_numbers(), false);
+    }
+
+    #[test]
+    fn preferences_returns_user_defined_system_clipboard() {
+        let data = YamlLoader::load_from_str("system_clipboard: false").unwrap();
+        let preferences = Preferences::new(data.into_iter().nth(0));
+
+        assert_eq!(preferences.system_clipboard(), false);
+    }
+
+    #[test]
+    fn preferences_returns_default_system_clipboard_when_user_defined_data_not_found() {
+        let preferences = Preferences::new(None);
+
+        assert_eq!(preferences.system_clipboard(), true);
+    }
+
+    #[test]
+    fn preferences_returns_user_defined_clipboard_history_size() {
+        let data = YamlLoader::load_from_str("clipboard_history_size: 3").unwrap();
+        let preferences = Preferences::new(data.into_iter().nth(0));
+
+        assert_eq!(preferences.clipboard_history_size(), 3);
+    }
+
+    #[test]
+    fn preferences_returns_default_clipboard_history_size_when_user_defined_data_not_found() {
+        let preferences = Preferences::new(None);
+
+        assert_eq!(preferences.clipboard_history_size(), 10);
+    }
+
+    #[test]
+    fn preferences_clamps_negative_clipboard_history_size_to_zero() {
+        let data = YamlLoader::load_from_str("clipboard_history_size: -1").unwrap();
+        let preferences = Preferences::new(data.into_iter().nth(0));
+
+        assert_eq!(preferences.clipboard_history_size(), 0);
+    }
+
+    #[test]
+    fn preferences_returns_user_defined_recent_files_limit() {
+        let data = YamlLoader::load_from_str("recent_files_limit: 5").unwrap();
+        let preferences = Preferences::new(data.into_iter().nth(0));
+
+        assert_eq!(preferences.recent_files_limit(), 5);
+    }
+
+    #[test]
+    fn preferences_returns_default_recent_files_limit_when_user_defined_data_not_found() {
+        let preferences = Preferences::new(None);
+
+        assert_eq!(preferences.recent_files_limit(), 50);
+    }
+
+    #[test]
+    fn preferences_returns_user_defined_hard_wrap_column() {
+        let data = YamlLoader::load_from_str("hard_wrap_column: 72").unwrap();
+        let preferences = Preferences::new(data.into_iter().nth(0));
+
+        assert_eq!(preferences.hard_wrap_column(), 72);
+    }
+
+    #[test]
+    fn preferences_returns_default_hard_wrap_column_when_user_defined_data_not_found() {
+        let preferences = Preferences::new(None);
+
+        assert_eq!(preferences.hard_wrap_column(), 80);
+    }
+
+    #[test]
+    fn preferences_returns_user_defined_line_number_format() {
+        let data = YamlLoader::load_from_str("line_number_format: \"01) \"").unwrap();
+        let preferences = Preferences::new(data.into_iter().nth(0));
 
-        self.data
-            .as_ref()
-            .and_then(|data| if let Yaml::Boolean(use_ec) = data[USE_EDITORCONFIG_KEY] {
-                          Some(use_ec)
-                      } else {
-                          None
-                      })
-            .unwrap_or_else(|| {
-                self.default[USE_EDITORCONFIG_KEY].as_bool()
-                    .expect("Couldn't find default use_editorconfig setting!")
-            })
+        assert_eq!(preferences.line_number_format(), "01) ");
     }
 
-    /// Disable .editorconfig support on test configurations to avoid a lot of false test-failures.
-    #[cfg(test)]
-    fn get_editorconfig(&self, _path: Option<&PathBuf>) -> Option<&editorconfig::Section> {
-        None
+    #[test]
+    fn preferences_returns_default_line_number_format_when_user_defined_data_not_found() {
+        let preferences = Preferences::new(None);
+
+        assert_eq!(preferences.line_number_format(), "1. ");
     }
 
-    /// Returns the applicable .editorconfig section for a specific file.
-    #[cfg(not(test))]
-    fn get_editorconfig(&self, path: Option<&PathBuf>) -> Option<&editorconfig::Section> {
-        if self.use_editorconfig() {
-            let path = path?.to_str()?;
+    #[test]
+    fn preferences_returns_user_defined_key_sequence_timeout() {
+        let data = YamlLoader::load_from_str("key_sequence_timeout: 250").unwrap();
+        let preferences = Preferences::new(data.into_iter().nth(0));
 
-            // If the editorconfig could have rules for this file, return them.
-            self.editorconfig
-                .as_ref()?
-                .get(&*path)
-        } else {
-            None
-        }
+        assert_eq!(preferences.key_sequence_timeout(), Duration::from_millis(250));
     }
-}
 
-/// Loads the first YAML document in the user's config file.
-fn load_document() -> Result<Option<Yaml>> {
-    // Build a path to the config file.
-    let mut config_path =
-        get_app_root(AppDataType::UserConfig, &APP_INFO)
-            .chain_err(|| "Couldn't open application config directory")?;
-    config_path.push(FILE_NAME);
+    #[test]
+    fn preferences_returns_default_key_sequence_timeout_when_user_defined_data_not_found() {
+        let preferences = Preferences::new(None);
 
-    // Open (or create) the config file.
-    let mut config_file = OpenOptions::new()
-        .read(true)
-        .open(config_path)
-        .chain_err(|| "Couldn't open config file")?;
+        assert_eq!(preferences.key_sequence_timeout(), Duration::from_millis(1000));
+    }
 
-    // Read the config file's contents.
-    let mut data = String::new();
-    config_file
-        .read_to_string(&mut data)
-        .chain_err(|| "Couldn't read config file")?;
+    #[test]
+    fn preferences_returns_user_defined_timestamp_format() {
+        let data = YamlLoader::load_from_str("timestamp_format: \"%d/%m/%Y\"").unwrap();
+        let preferences = Preferences::new(data.into_iter().nth(0));
 
-    // Parse the config file's contents and get the first YAML document inside.
-    let parsed_data = YamlLoader::load_from_str(&data)
-        .chain_err(|| "Couldn't parse config file")?;
-    Ok(parsed_data.into_iter().nth(0))
-}
+        assert_eq!(preferences.timestamp_format(), "%d/%m/%Y");
+    }
 
-fn load_default_document() -> Result<Yaml> {
-    YamlLoader::load_from_str(include_str!("default.yml"))
-        .chain_err(|| "Couldn't parse default config file")?
-        .into_iter().nth(0)
-        .chain_err(|| "No default preferences document found")
-}
+    #[test]
+    fn preferences_returns_default_timestamp_format_when_user_defined_data_not_found() {
+        let preferences = Preferences::new(None);
 
-/// Loads default keymaps, merging in the provided overrides.
-fn load_keymap(keymap_overrides: Option<&Hash>) -> Result<KeyMap> {
-    let mut keymap = KeyMap::default()?;
+        assert_eq!(preferences.timestamp_format(), "%Y-%m-%dT%H:%M:%SZ");
+    }
 
-    // Merge user-defined keymaps into defaults.
-    if let Some(keymap_data) = keymap_overrides {
-        KeyMap::from(keymap_data).map(|data| keymap.merge(data))?;
+    #[test]
+    fn preferences_returns_user_defined_status_line_format() {
+        let data = YamlLoader::load_from_str("status_line_format: \"%path% %line%\"").unwrap();
+        let preferences = Preferences::new(data.into_iter().nth(0));
+
+        assert_eq!(preferences.status_line_format(), "%path% %line%");
     }
 
-    Ok(keymap)
-}
+    #[test]
+    fn preferences_returns_default_status_line_format_when_user_defined_data_not_found() {
+        let preferences = Preferences::new(None);
 
-/// Loads a .editorconfig from workspace directory if present.
-fn load_editorconfig() -> Result<EditorConfig> {
-    let workspace_path = env::current_dir()
-        .chain_err(|| "Couldn't retreive current working directory")?;
+        assert_eq!(preferences.status_line_format(), " %path%%modified%  %line%:%col%");
+    }
 
-    EditorConfig::from_directory(&workspace_path)
-}
+    #[test]
+    fn preferences_returns_user_defined_auto_close_pairs() {
+        let data = YamlLoader::load_from_str("auto_close_pairs: true").unwrap();
+        let preferences = Preferences::new(data.into_iter().nth(0));
 
-/// Maps a path to its file extension.
-fn path_extension(path: Option<&PathBuf>) -> Option<&str> {
-    path
-        .and_then(|p| p.extension().or_else(|| p.as_path().file_name()))
-        .and_then(|e| e.to_str())
-}
+        assert!(preferences.auto_close_pairs());
+    }
 
-#[cfg(test)]
-mod tests {
-    use super::{ExclusionPattern, Preferences, YamlLoader};
-    use std::path::{Path, PathBuf};
-    use crate::input::KeyMap;
-    use crate::yaml::yaml::{Hash, Yaml};
+    #[test]
+    fn preferences_disables_auto_close_pairs_by_default() {
+        let preferences = Preferences::new(None);
+
+        assert!(!preferences.auto_close_pairs());
+    }
 
     #[test]
-    fn preferences_returns_user_defined_theme_name() {
-        let data = YamlLoader::load_from_str("theme: \"my_theme\"").unwrap();
+    fn preferences_returns_user_defined_highlight_trailing_whitespace() {
+        let data = YamlLoader::load_from_str("highlight_trailing_whitespace: true").unwrap();
         let preferences = Preferences::new(data.into_iter().nth(0));
 
-        assert_eq!(preferences.theme(), "my_theme");
+        assert!(preferences.highlight_trailing_whitespace());
     }
 
     #[test]
-    fn set_theme_updates_in_memory_value() {
-        let mut preferences = Preferences::new(None);
-        preferences.set_theme("new_in_memory_theme");
+    fn preferences_disables_highlight_trailing_whitespace_by_default() {
+        let preferences = Preferences::new(None);
 
-        assert_eq!(preferences.theme(), "new_in_memory_theme");
+        assert!(!preferences.highlight_trailing_whitespace());
     }
 
     #[test]
-    fn preferences_returns_default_theme_when_user_defined_data_not_found() {
+    fn preferences_returns_user_defined_visible_tabs() {
+        let data = YamlLoader::load_from_str("visible_tabs: true").unwrap();
+        let preferences = Preferences::new(data.into_iter().nth(0));
+
+        assert!(preferences.visible_tabs());
+    }
+
+    #[test]
+    fn preferences_disables_visible_tabs_by_default() {
         let preferences = Preferences::new(None);
 
-        assert_eq!(preferences.theme(), "solarized_dark");
+        assert!(!preferences.visible_tabs());
     }
 
     #[test]
-    fn tab_width_returns_user_defined_data() {
-        let data = YamlLoader::load_from_str("tab_width: 12").unwrap();
+    fn preferences_returns_user_defined_minimum_line_number_width() {
+        let data = YamlLoader::load_from_str("minimum_line_number_width: 3").unwrap();
         let preferences = Preferences::new(data.into_iter().nth(0));
 
-        assert_eq!(preferences.tab_width(None), 12);
+        assert_eq!(preferences.minimum_line_number_width(), 3);
     }
 
     #[test]
-    fn tab_width_returns_user_defined_type_specific_data() {
-        let data = YamlLoader::load_from_str("tab_width: 12\ntypes:\n  rs:\n    tab_width: 24")
-            .unwrap();
-        let preferences = Preferences::new(data.into_iter().nth(0));
+    fn preferences_returns_default_minimum_line_number_width_when_user_defined_data_not_found() {
+        let preferences = Preferences::new(None);
 
-        assert_eq!(preferences.tab_width(Some(PathBuf::from("preferences.rs")).as_ref()),
-                   24);
+        assert_eq!(preferences.minimum_line_number_width(), 1);
     }
 
     #[test]
-    fn tab_width_returns_default_when_user_defined_type_specific_data_not_found() {
-        let data = YamlLoader::load_from_str("tab_width: 12").unwrap();
+    fn preferences_returns_user_defined_scroll_margin() {
+        let data = YamlLoader::load_from_str("scroll_margin: 5").unwrap();
         let preferences = Preferences::new(data.into_iter().nth(0));
 
-        assert_eq!(preferences.tab_width(Some(PathBuf::from("preferences.rs")).as_ref()),
-                   12);
+        assert_eq!(preferences.scroll_margin(), 5);
     }
 
     #[test]
-    fn preferences_returns_default_tab_width_when_user_defined_data_not_found() {
+    fn preferences_returns_default_scroll_margin_when_user_defined_data_not_found() {
         let preferences = Preferences::new(None);
 
-        assert_eq!(preferences.tab_width(None), 2);
+        assert_eq!(preferences.scroll_margin(), 0);
     }
 
     #[test]
-    fn soft_tabs_returns_user_defined_data() {
-        let data = YamlLoader::load_from_str("soft_tabs: false").unwrap();
+    fn preferences_returns_user_defined_horizontal_scroll_margin() {
+        let data = YamlLoader::load_from_str("horizontal_scroll_margin: 10").unwrap();
         let preferences = Preferences::new(data.into_iter().nth(0));
 
-        assert_eq!(preferences.soft_tabs(None), false);
+        assert_eq!(preferences.horizontal_scroll_margin(), 10);
     }
 
     #[test]
-    fn soft_tabs_returns_user_defined_type_specific_data() {
-        let data = YamlLoader::load_from_str("soft_tabs: true\ntypes:\n  rs:\n    soft_tabs: false").unwrap();
-        let preferences = Preferences::new(data.into_iter().nth(0));
+    fn preferences_returns_default_horizontal_scroll_margin_when_user_defined_data_not_found() {
+        let preferences = Preferences::new(None);
 
-        assert_eq!(preferences.soft_tabs(Some(PathBuf::from("preferences.rs")).as_ref()), false);
+        assert_eq!(preferences.horizontal_scroll_margin(), 5);
     }
 
     #[test]
-    fn soft_tabs_returns_default_when_user_defined_type_specific_data_not_found() {
-        let data = YamlLoader::load_from_str("soft_tabs: false").unwrap();
+    fn preferences_returns_user_defined_smooth_scrolling() {
+        let data = YamlLoader::load_from_str("smooth_scrolling: true").unwrap();
         let preferences = Preferences::new(data.into_iter().nth(0));
 
-        assert_eq!(preferences.soft_tabs(Some(PathBuf::from("preferences.rs")).as_ref()), false);
+        assert!(preferences.smooth_scrolling());
     }
 
     #[test]
-    fn preferences_returns_default_soft_tabs_when_user_defined_data_not_found() {
+    fn preferences_disables_smooth_scrolling_by_default() {
         let preferences = Preferences::new(None);
 
-        assert_eq!(preferences.soft_tabs(None), true);
+        assert!(!preferences.smooth_scrolling());
     }
 
     #[test]
-    fn non_extension_types_are_supported_for_type_specific_data() {
-        let data = YamlLoader::load_from_str("soft_tabs: true\ntypes:\n  Makefile:\n    soft_tabs: false").unwrap();
-        let preferences = Preferences::new(data.into_iter().nth(0));
+    fn set_show_whitespace_updates_in_memory_value() {
+        let data = YamlLoader::load_from_str("show_whitespace: true").unwrap();
+        let mut preferences = Preferences::new(data.into_iter().nth(0));
+        assert!(preferences.show_whitespace());
 
-        assert_eq!(preferences.soft_tabs(Some(PathBuf::from("Makefile")).as_ref()), false);
+        preferences.set_show_whitespace(false);
+        assert!(!preferences.show_whitespace());
     }
 
     #[test]
-    fn syntax_definition_name_returns_user_defined_syntax_by_extension_for_full_filename() {
-        let data = YamlLoader::load_from_str("types:\n  xyz:\n    syntax: Rust").unwrap();
-        let preferences = Preferences::new(data.into_iter().nth(0));
+    fn preferences_disables_show_whitespace_by_default() {
+        let preferences = Preferences::new(None);
 
-        assert_eq!(preferences.syntax_definition_name(&Path::new("test.xyz")),
-                   Some("Rust".to_owned()));
+        assert!(!preferences.show_whitespace());
     }
 
     #[test]
-    fn syntax_definition_name_returns_user_defined_syntax_by_extension_for_deep_filename() {
-        let data = YamlLoader::load_from_str("types:\n  xyz:\n    syntax: Rust").unwrap();
+    fn preferences_returns_user_defined_whitespace_characters() {
+        let data = YamlLoader::load_from_str("whitespace_characters:\n  space: \".\"").unwrap();
         let preferences = Preferences::new(data.into_iter().nth(0));
 
-        assert_eq!(preferences.syntax_definition_name(&Path::new("src/test.xyz")),
-                   Some("Rust".to_owned()));
+        assert_eq!(preferences.space_character(), ".");
+        assert_eq!(preferences.tab_character(), "→");
+        assert_eq!(preferences.newline_character(), "¬");
     }
 
     #[test]
-    fn syntax_definition_name_returns_user_defined_syntax_for_full_filename_without_extension() {
-        let data = YamlLoader::load_from_str("types:\n  Makefile:\n    syntax: Makefile").unwrap();
+    fn preferences_returns_user_defined_autosave_interval() {
+        let data = YamlLoader::load_from_str("autosave_interval: 30").unwrap();
         let preferences = Preferences::new(data.into_iter().nth(0));
 
-        assert_eq!(preferences.syntax_definition_name(&Path::new("Makefile")),
-                   Some("Makefile".to_owned()));
+        assert_eq!(preferences.autosave_interval(), Some(Duration::from_secs(30)));
     }
 
     #[test]
-    fn syntax_definition_name_returns_user_defined_syntax_for_full_deep_filename() {
-        let data = YamlLoader::load_from_str("types:\n  Makefile:\n    syntax: Makefile").unwrap();
+    fn preferences_disables_autosave_by_default() {
+        let preferences = Preferences::new(None);
+
+        assert_eq!(preferences.autosave_interval(), None);
+    }
+
+    #[test]
+    fn default_theme_ignores_user_defined_theme() {
+        let data = YamlLoader::load_from_str("theme: \"my_theme\"").unwrap();
         let preferences = Preferences::new(data.into_iter().nth(0));
 
-        assert_eq!(preferences.syntax_definition_name(&Path::new("src/Makefile")),
-                   Some("Makefile".to_owned()));
+        assert_eq!(preferences.default_theme(), "solarized_dark");
     }
 
     #[test]
-    fn syntax_definition_name_returns_user_defined_syntax_for_full_filename_with_extension() {
-        let data = YamlLoader::load_from_str("types:\n  Makefile.lib:\n    syntax: Makefile").unwrap();
+    fn preferences_returns_user_defined_watch_preferences() {
+        let data = YamlLoader::load_from_str("watch_preferences: false").unwrap();
         let preferences = Preferences::new(data.into_iter().nth(0));
 
-        assert_eq!(preferences.syntax_definition_name(&Path::new("Makefile.lib")),
-                   Some("Makefile".to_owned()));
+        assert_eq!(preferences.watch_preferences(), false);
     }
 
     #[test]
-    fn preferences_returns_user_defined_line_length_guide() {
-        let data = YamlLoader::load_from_str("line_length_guide: 100").unwrap();
+    fn preferences_returns_default_watch_preferences_when_user_defined_data_not_found() {
+        let preferences = Preferences::new(None);
+
+        assert_eq!(preferences.watch_preferences(), true);
+    }
+
+    #[test]
+    fn preferences_returns_user_defined_backup_on_save() {
+        let data = YamlLoader::load_from_str("backup_on_save: true").unwrap();
         let preferences = Preferences::new(data.into_iter().nth(0));
 
-        assert_eq!(preferences.line_length_guides(), vec![100]);
+        assert_eq!(preferences.backup_on_save(), true);
     }
 
     #[test]
-    fn preferences_returns_user_defined_multiple_line_length_guides() {
-        let data = YamlLoader::load_from_str("line_length_guide: [80, 100, 120]").unwrap();
+    fn preferences_returns_default_backup_on_save_when_user_defined_data_not_found() {
+        let preferences = Preferences::new(None);
+
+        assert_eq!(preferences.backup_on_save(), false);
+    }
+
+    #[test]
+    fn preferences_returns_user_defined_restore_cursor_positions() {
+        let data = YamlLoader::load_from_str("restore_cursor_positions: false").unwrap();
         let preferences = Preferences::new(data.into_iter().nth(0));
 
-        assert_eq!(preferences.line_length_guides(), vec![80, 100, 120]);
+        assert_eq!(preferences.restore_cursor_positions(), false);
     }
 
     #[test]
-    fn preferences_returns_user_disabled_line_length_guide() {
-        let data = YamlLoader::load_from_str("line_length_guide: false").unwrap();
+    fn preferences_returns_default_restore_cursor_positions_when_user_defined_data_not_found() {
+        let preferences = Preferences::new(None);
+
+        assert_eq!(preferences.restore_cursor_positions(), true);
+    }
+
+    #[test]
+    fn preferences_returns_user_defined_log_level() {
+        let data = YamlLoader::load_from_str("log_level: debug").unwrap();
         let preferences = Preferences::new(data.into_iter().nth(0));
 
-        assert_eq!(preferences.line_length_guides(), Vec::<usize>::new());
+        assert_eq!(preferences.log_level(), LogLevel::Debug);
     }
 
     #[test]
-    fn preferences_returns_user_default_line_length_guide() {
-        let data = YamlLoader::load_from_str("line_length_guide: true").unwrap();
+    fn preferences_returns_default_log_level_when_user_defined_data_is_invalid() {
+        let data = YamlLoader::load_from_str("log_level: verbose").unwrap();
         let preferences = Preferences::new(data.into_iter().nth(0));
 
-        assert_eq!(preferences.line_length_guides(), vec![80]);
+        assert_eq!(preferences.log_level(), LogLevel::Info);
     }
 
     #[test]
-    fn preferences_returns_user_defined_line_wrapping() {
-        let data = YamlLoader::load_from_str("line_wrapping: false").unwrap();
+    fn preferences_returns_user_defined_log_size_limit() {
+        let data = YamlLoader::load_from_str("log_size_limit: 2048").unwrap();
         let preferences = Preferences::new(data.into_iter().nth(0));
 
-        assert_eq!(preferences.line_wrapping(), false);
+        assert_eq!(preferences.log_size_limit(), 2048);
     }
 
     #[test]
-    fn preferences_returns_default_line_wrapping_when_user_defined_data_not_found() {
+    fn preferences_returns_default_log_size_limit_when_user_defined_data_not_found() {
         let preferences = Preferences::new(None);
 
-        assert_eq!(preferences.line_wrapping(), true);
+        assert_eq!(preferences.log_size_limit(), 1_048_576);
     }
 
     #[test]
@@ -738,6 +1992,61 @@ mod tests {
         assert!(preferences.open_mode_exclusions().unwrap().is_none());
     }
 
+    #[test]
+    fn open_mode_include_ignored_returns_user_defined_value() {
+        let data = YamlLoader::load_from_str("open_mode:\n  include_ignored: true").unwrap();
+        let preferences = Preferences::new(data.into_iter().nth(0));
+
+        assert!(preferences.open_mode_include_ignored());
+    }
+
+    #[test]
+    fn open_mode_include_ignored_returns_default_when_user_defined_data_not_found() {
+        let preferences = Preferences::new(None);
+
+        assert!(!preferences.open_mode_include_ignored());
+    }
+
+    #[test]
+    fn formatter_returns_user_defined_type_specific_data() {
+        let data = YamlLoader::load_from_str("types:\n  rs:\n    formatter: rustfmt").unwrap();
+        let preferences = Preferences::new(data.into_iter().nth(0));
+
+        assert_eq!(preferences.formatter(&PathBuf::from("preferences.rs")),
+                   Some("rustfmt".to_owned()));
+        assert_eq!(preferences.formatter(&PathBuf::from("preferences.go")), None);
+    }
+
+    #[test]
+    fn spell_check_returns_user_defined_type_specific_data() {
+        let data = YamlLoader::load_from_str("types:\n  md:\n    spell_check: true").unwrap();
+        let preferences = Preferences::new(data.into_iter().nth(0));
+
+        assert!(preferences.spell_check(Some(&PathBuf::from("README.md"))));
+        assert!(!preferences.spell_check(Some(&PathBuf::from("main.rs"))));
+        assert!(!preferences.spell_check(None));
+    }
+
+    #[test]
+    fn spell_check_dictionary_resolves_relative_paths_against_preferences_directory() {
+        let data = YamlLoader::load_from_str("spell_check_dictionary: /usr/share/dict/words")
+            .unwrap();
+        let preferences = Preferences::new(data.into_iter().nth(0));
+        assert_eq!(preferences.spell_check_dictionary().unwrap(),
+                   PathBuf::from("/usr/share/dict/words"));
+
+        let preferences = Preferences::new(None);
+        assert_eq!(preferences.spell_check_dictionary().unwrap(),
+                   Preferences::directory().unwrap().join("dictionary"));
+    }
+
+    #[test]
+    fn preferences_disables_format_on_save_by_default() {
+        let preferences = Preferences::new(None);
+
+        assert!(!preferences.format_on_save());
+    }
+
     #[test]
     fn line_comment_prefix_returns_correct_default_type_specific_data() {
         let preferences = Preferences::new(None);
@@ -788,6 +2097,14 @@ mod tests {
         assert_eq!(preferences.ensure_trailing_newline(Some(&PathBuf::from("preferences.rs"))), false);
     }
 
+    #[test]
+    fn preferences_returns_user_defined_collapse_trailing_newlines() {
+        let data = YamlLoader::load_from_str("collapse_trailing_newlines: true").unwrap();
+        let preferences = Preferences::new(data.into_iter().nth(0));
+
+        assert_eq!(preferences.collapse_trailing_newlines(), true);
+    }
+
     #[test]
     fn preferences_returns_user_defined_use_editorconfig() {
         let data = YamlLoader::load_from_str("use_editorconfig: false").unwrap();
@@ -824,7 +2141,14 @@ mod tests {
             default: Yaml::Null,
             data: None,
             keymap: KeyMap::from(&Hash::new()).unwrap(),
+            keymap_warnings: Vec::new(),
+            project_warning: None,
             theme: None,
+            line_wrapping: None,
+            show_whitespace: None,
+            search_regex: None,
+            search_case_sensitivity: None,
+            search_whole_word: None,
             editorconfig: None,
         };
 
@@ -0,0 +1,67 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+/// Tracks the modification times of the files that preferences are loaded
+/// from, so that they can be reloaded when one of them is changed.
+/// Files that don't exist (yet) are tracked, too; creating one is a change.
+pub struct PreferencesWatcher {
+    files: Vec<(PathBuf, Option<SystemTime>)>,
+}
+
+impl PreferencesWatcher {
+    pub fn new(paths: Vec<PathBuf>) -> PreferencesWatcher {
+        let files = paths
+            .into_iter()
+            .map(|path| {
+                let time = modified_time(&path);
+                (path, time)
+            })
+            .collect();
+
+        PreferencesWatcher { files }
+    }
+
+    /// Whether any of the files have been modified, created, or removed
+    /// since this method was last called (or the watcher was created).
+    pub fn changed(&mut self) -> bool {
+        let mut changed = false;
+        for (path, recorded_time) in self.files.iter_mut() {
+            let current_time = modified_time(path);
+            if current_time != *recorded_time {
+                *recorded_time = current_time;
+                changed = true;
+            }
+        }
+
+        changed
+    }
+}
+
+fn modified_time(path: &Path) -> Option<SystemTime> {
+    fs::metadata(path).and_then(|metadata| metadata.modified()).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::PreferencesWatcher;
+    use std::env;
+    use std::fs;
+
+    #[test]
+    fn changed_detects_created_and_removed_files_once() {
+        let path = env::temp_dir().join("amp_preferences_watcher_test");
+        let _ = fs::remove_file(&path);
+
+        let mut watcher = PreferencesWatcher::new(vec![path.clone()]);
+        assert!(!watcher.changed());
+
+        fs::write(&path, "tab_width: 4").unwrap();
+        assert!(watcher.changed());
+        assert!(!watcher.changed());
+
+        fs::remove_file(&path).unwrap();
+        assert!(watcher.changed());
+        assert!(!watcher.changed());
+    }
+}
@@ -0,0 +1,70 @@
+use scribe::Buffer;
+use std::collections::HashMap;
+use std::fs::OpenOptions;
+
+/// Tracks which buffers have been flagged as read-only, preventing commands
+/// from modifying their content. Buffers are identified using their
+/// workspace-assigned IDs.
+#[derive(Default)]
+pub struct ReadOnlyBuffers {
+    flags: HashMap<usize, bool>,
+}
+
+impl ReadOnlyBuffers {
+    pub fn new() -> ReadOnlyBuffers {
+        ReadOnlyBuffers::default()
+    }
+
+    /// Whether the buffer is read-only. Buffers that haven't been checked
+    /// before are flagged as read-only if their file isn't writable.
+    pub fn is_read_only(&mut self, buffer: &Buffer) -> bool {
+        match buffer.id {
+            Some(id) => *self.flags.entry(id).or_insert_with(|| !writable(buffer)),
+            None => false,
+        }
+    }
+
+    /// Flips the buffer's read-only flag, returning its new value.
+    pub fn toggle(&mut self, buffer: &Buffer) -> bool {
+        let read_only = !self.is_read_only(buffer);
+        if let Some(id) = buffer.id {
+            self.flags.insert(id, read_only);
+        }
+
+        read_only
+    }
+
+    /// Discards the flag for the specified buffer. This
+    /// method should be called whenever a buffer is closed.
+    pub fn forget_buffer(&mut self, buffer_id: usize) {
+        self.flags.remove(&buffer_id);
+    }
+}
+
+// Buffers without a file (or whose file doesn't exist
+// yet) are writable; they've got nothing to protect.
+fn writable(buffer: &Buffer) -> bool {
+    match buffer.path {
+        Some(ref path) if path.exists() => OpenOptions::new().write(true).open(path).is_ok(),
+        _ => true,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ReadOnlyBuffers;
+    use scribe::Buffer;
+
+    #[test]
+    fn toggle_flips_the_read_only_flag() {
+        let mut buffer = Buffer::new();
+        buffer.id = Some(0);
+
+        let mut read_only_buffers = ReadOnlyBuffers::new();
+        assert!(!read_only_buffers.is_read_only(&buffer));
+        assert!(read_only_buffers.toggle(&buffer));
+        assert!(read_only_buffers.is_read_only(&buffer));
+        assert!(!read_only_buffers.toggle(&buffer));
+        assert!(!read_only_buffers.is_read_only(&buffer));
+    }
+}
@@ -0,0 +1,120 @@
+use crate::errors::*;
+use crate::models::application::Preferences;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+const FILE_NAME: &str = "recent_files";
+
+/// A bounded list of recently opened and saved files, ordered from least
+/// to most recent, and persisted (one path per line) to a file when a
+/// path is provided.
+pub struct RecentFiles {
+    entries: Vec<PathBuf>,
+    path: Option<PathBuf>,
+    limit: usize,
+}
+
+impl RecentFiles {
+    /// Builds a list backed by the specified file, loading any existing
+    /// entries. A missing or unreadable file yields no entries.
+    pub fn new(path: Option<PathBuf>, limit: usize) -> RecentFiles {
+        let entries = path
+            .as_ref()
+            .and_then(|path| fs::read_to_string(path).ok())
+            .map(|data| data.lines().map(PathBuf::from).collect())
+            .unwrap_or_default();
+
+        RecentFiles { entries, path, limit }
+    }
+
+    /// Loads the list from the user preferences directory.
+    pub fn load(limit: usize) -> RecentFiles {
+        RecentFiles::new(Preferences::state_path(FILE_NAME), limit)
+    }
+
+    /// Moves the path to the front of the list (adding it if necessary),
+    /// discarding the oldest entries beyond the limit, and persists the result.
+    pub fn push(&mut self, path: &Path) -> Result<()> {
+        let path = fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf());
+        self.entries.retain(|entry| *entry != path);
+        self.entries.push(path);
+        if self.entries.len() > self.limit {
+            let excess = self.entries.len() - self.limit;
+            self.entries.drain(..excess);
+        }
+
+        self.save()
+    }
+
+    /// The paths that still exist, most recent first.
+    pub fn paths(&self) -> Vec<PathBuf> {
+        self.entries
+            .iter()
+            .rev()
+            .filter(|path| path.exists())
+            .cloned()
+            .collect()
+    }
+
+    fn save(&self) -> Result<()> {
+        if let Some(ref path) = self.path {
+            let data: String = self.entries
+                .iter()
+                .map(|entry| format!("{}\n", entry.to_string_lossy()))
+                .collect();
+
+            fs::write(path, data).chain_err(|| "Couldn't write recent files file")?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::env;
+    use std::fs;
+    use super::RecentFiles;
+
+    #[test]
+    fn push_deduplicates_and_discards_the_oldest_entries_beyond_the_limit() {
+        let directory = env::temp_dir();
+        let paths: Vec<_> = (0..3)
+            .map(|index| directory.join(format!("amp_recent_files_test_{}", index)))
+            .collect();
+        for path in &paths {
+            fs::write(path, "amp").unwrap();
+        }
+        let paths: Vec<_> = paths.iter().map(|path| fs::canonicalize(path).unwrap()).collect();
+
+        let mut recent_files = RecentFiles::new(None, 2);
+        recent_files.push(&paths[0]).unwrap();
+        recent_files.push(&paths[1]).unwrap();
+        recent_files.push(&paths[0]).unwrap();
+        assert_eq!(recent_files.paths(), vec![paths[0].clone(), paths[1].clone()]);
+
+        recent_files.push(&paths[2]).unwrap();
+        assert_eq!(recent_files.paths(), vec![paths[2].clone(), paths[0].clone()]);
+
+        for path in &paths {
+            fs::remove_file(path).unwrap();
+        }
+    }
+
+    #[test]
+    fn paths_omits_files_that_no_longer_exist_and_persist_to_their_file() {
+        let path = env::temp_dir().join("amp_recent_files_list_test");
+        let _ = fs::remove_file(&path);
+        let existing_path = fs::canonicalize("Cargo.toml").unwrap();
+        let missing_path = env::temp_dir().join("amp_recent_files_missing_test");
+
+        let mut recent_files = RecentFiles::new(Some(path.clone()), 10);
+        recent_files.push(&existing_path).unwrap();
+        recent_files.push(&missing_path).unwrap();
+
+        let reloaded_files = RecentFiles::new(Some(path.clone()), 10);
+        assert_eq!(reloaded_files.paths(), vec![existing_path]);
+
+        fs::remove_file(&path).unwrap();
+    }
+}
@@ -0,0 +1,125 @@
+use crate::errors::*;
+use crate::models::application::Preferences;
+use std::fs;
+use std::path::PathBuf;
+
+const FILE_NAME: &str = "search_history";
+const MAX_ENTRIES: usize = 100;
+
+/// A bounded, ordered collection of previously accepted search queries,
+/// persisted (one query per line) to a file when a path is provided.
+pub struct SearchHistory {
+    entries: Vec<String>,
+    path: Option<PathBuf>,
+}
+
+impl SearchHistory {
+    /// Builds a history backed by the specified file, loading any
+    /// existing entries. A missing or unreadable file yields no entries.
+    pub fn new(path: Option<PathBuf>) -> SearchHistory {
+        let entries = path
+            .as_ref()
+            .and_then(|path| fs::read_to_string(path).ok())
+            .map(|data| data.lines().map(String::from).collect())
+            .unwrap_or_default();
+
+        SearchHistory { entries, path }
+    }
+
+    /// Loads the history from the user preferences directory.
+    pub fn load() -> SearchHistory {
+        SearchHistory::new(Preferences::state_path(FILE_NAME))
+    }
+
+    /// Adds a query to the history, discarding the oldest entries beyond
+    /// the size limit, and persists the result. Empty queries and repeats
+    /// of the most recent query are ignored.
+    pub fn push<T: Into<String>>(&mut self, query: T) -> Result<()> {
+        let query = query.into();
+        if query.is_empty() || self.entries.last() == Some(&query) {
+            return Ok(());
+        }
+
+        self.entries.push(query);
+        if self.entries.len() > MAX_ENTRIES {
+            let excess = self.entries.len() - MAX_ENTRIES;
+            self.entries.drain(..excess);
+        }
+
+        self.save()
+    }
+
+    /// Returns an entry by its age, where zero is the most recent query.
+    pub fn get(&self, age: usize) -> Option<&String> {
+        self.entries.iter().rev().nth(age)
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    fn save(&self) -> Result<()> {
+        if let Some(ref path) = self.path {
+            let mut data = self.entries.join("\n");
+            data.push('\n');
+
+            fs::write(path, data).chain_err(|| "Couldn't write search history file")?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::env;
+    use std::fs;
+    use super::SearchHistory;
+
+    #[test]
+    fn push_ignores_empty_and_consecutive_duplicate_queries() {
+        let mut history = SearchHistory::new(None);
+        history.push("amp").unwrap();
+        history.push("amp").unwrap();
+        history.push("").unwrap();
+        history.push("editor").unwrap();
+        history.push("amp").unwrap();
+
+        assert_eq!(history.len(), 3);
+        assert_eq!(history.get(0), Some(&String::from("amp")));
+        assert_eq!(history.get(1), Some(&String::from("editor")));
+        assert_eq!(history.get(2), Some(&String::from("amp")));
+        assert_eq!(history.get(3), None);
+    }
+
+    #[test]
+    fn push_discards_the_oldest_entries_beyond_the_size_limit() {
+        let mut history = SearchHistory::new(None);
+        for index in 0..super::MAX_ENTRIES + 1 {
+            history.push(index.to_string()).unwrap();
+        }
+
+        assert_eq!(history.len(), super::MAX_ENTRIES);
+        assert_eq!(history.get(super::MAX_ENTRIES - 1), Some(&String::from("1")));
+    }
+
+    #[test]
+    fn history_persists_entries_to_its_file() {
+        let path = env::temp_dir().join("amp_search_history_test");
+        let _ = fs::remove_file(&path);
+
+        let mut history = SearchHistory::new(Some(path.clone()));
+        history.push("amp").unwrap();
+        history.push("editor").unwrap();
+
+        let reloaded_history = SearchHistory::new(Some(path.clone()));
+        assert_eq!(reloaded_history.get(0), Some(&String::from("editor")));
+        assert_eq!(reloaded_history.get(1), Some(&String::from("amp")));
+
+        fs::remove_file(&path).unwrap();
+    }
+}
@@ -0,0 +1,128 @@
+use scribe::buffer::Position;
+
+/// Additional cursor positions in the current buffer, at which insertions and
+/// deletions made at its primary cursor are repeated. They're only meaningful
+/// relative to the primary cursor's position, so they're discarded as soon as
+/// it moves or the buffer is edited (other than by an edit that's applied at
+/// every cursor), or another buffer is selected. Buffers are identified using
+/// their workspace-assigned IDs.
+#[derive(Default)]
+pub struct SecondaryCursors {
+    buffer_id: Option<usize>,
+    primary: Option<Position>,
+    positions: Vec<Position>,
+    edited: bool,
+}
+
+impl SecondaryCursors {
+    pub fn new() -> SecondaryCursors {
+        SecondaryCursors::default()
+    }
+
+    /// The secondary cursor positions in the specified buffer.
+    pub fn positions(&self, buffer_id: Option<usize>) -> &[Position] {
+        if buffer_id.is_some() && buffer_id == self.buffer_id {
+            &self.positions
+        } else {
+            &[]
+        }
+    }
+
+    /// Adds a cursor at the specified position, tracking
+    /// the primary cursor position relative to which it was added.
+    pub fn add(&mut self, buffer_id: usize, primary: Position, position: Position) {
+        if self.buffer_id != Some(buffer_id) {
+            self.clear();
+            self.buffer_id = Some(buffer_id);
+        }
+        if position != primary && !self.positions.contains(&position) {
+            self.positions.push(position);
+        }
+
+        self.primary = Some(primary);
+        self.positions.retain(|&secondary| secondary != primary);
+    }
+
+    /// Records the positions of the primary and secondary cursors after an
+    /// edit has been applied at each of them. Cursors brought together by
+    /// the edit are merged.
+    pub fn update(&mut self, primary: Position, positions: Vec<Position>) {
+        self.primary = Some(primary);
+        self.edited = false;
+        self.positions.clear();
+        for position in positions {
+            if position != primary && !self.positions.contains(&position) {
+                self.positions.push(position);
+            }
+        }
+    }
+
+    /// Flags the buffer as having been edited, which leaves the secondary
+    /// cursors stale unless the edit is recorded as applied at each of them.
+    pub fn record_edit(&mut self) {
+        self.edited = true;
+    }
+
+    /// Discards the secondary cursors if the primary cursor (or buffer) has
+    /// changed since they were last recorded, or the buffer was edited without
+    /// them. This method should be called after every command.
+    pub fn clear_if_stale(&mut self, buffer_id: Option<usize>, primary: Option<Position>) {
+        if self.edited || buffer_id != self.buffer_id || primary != self.primary {
+            self.clear();
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.buffer_id = None;
+        self.primary = None;
+        self.positions.clear();
+        self.edited = false;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::SecondaryCursors;
+    use scribe::buffer::Position;
+
+    #[test]
+    fn clear_if_stale_discards_cursors_when_the_primary_cursor_moves() {
+        let mut cursors = SecondaryCursors::new();
+        let primary = Position { line: 1, offset: 0 };
+        cursors.add(0, primary, Position { line: 0, offset: 0 });
+
+        cursors.clear_if_stale(Some(0), Some(primary));
+        assert_eq!(cursors.positions(Some(0)), &[Position { line: 0, offset: 0 }]);
+
+        cursors.clear_if_stale(Some(0), Some(Position { line: 1, offset: 1 }));
+        assert!(cursors.positions(Some(0)).is_empty());
+    }
+
+    #[test]
+    fn clear_if_stale_discards_cursors_after_edits_not_applied_at_them() {
+        let mut cursors = SecondaryCursors::new();
+        let primary = Position { line: 1, offset: 0 };
+        cursors.add(0, primary, Position { line: 0, offset: 0 });
+
+        // An edit applied at every cursor keeps them.
+        let primary = Position { line: 1, offset: 1 };
+        cursors.record_edit();
+        cursors.update(primary, vec![Position { line: 0, offset: 1 }]);
+        cursors.clear_if_stale(Some(0), Some(primary));
+        assert_eq!(cursors.positions(Some(0)), &[Position { line: 0, offset: 1 }]);
+
+        // One that leaves the primary cursor in place doesn't.
+        cursors.record_edit();
+        cursors.clear_if_stale(Some(0), Some(primary));
+        assert!(cursors.positions(Some(0)).is_empty());
+    }
+
+    #[test]
+    fn positions_are_scoped_to_the_buffer_they_were_added_to() {
+        let mut cursors = SecondaryCursors::new();
+        cursors.add(0, Position { line: 1, offset: 0 }, Position { line: 0, offset: 0 });
+
+        assert!(cursors.positions(Some(1)).is_empty());
+        assert!(cursors.positions(None).is_empty());
+    }
+}
@@ -0,0 +1,20 @@
+use scribe::Workspace;
+use crate::view::{Colors, StatusLineData, Style, View};
+
+pub fn display(workspace: &mut Workspace, view: &mut View, message: &str) {
+    let data;
+    let mut presenter = view.build_presenter().unwrap();
+
+    if let Some(buffer) = workspace.current_buffer() {
+        data = buffer.data();
+        let _ = presenter.print_buffer(buffer, &data, None, None);
+    }
+
+    presenter.print_status_line(&[StatusLineData {
+        content: message.to_string(),
+        style: Style::Default,
+        colors: Colors::Focused,
+    }]);
+
+    presenter.present();
+}
@@ -1,29 +1,52 @@
 pub mod error;
+pub mod message;
 pub mod modes;
 
-use std::path::{Path, PathBuf};
-use scribe::Workspace;
+use std::path::PathBuf;
+use crate::models::application::SecondaryCursors;
+use scribe::{Buffer, Workspace};
+use scribe::buffer::{Position, Range};
 use crate::view::{Colors, StatusLineData, Style};
 use git2::{self, Repository, Status};
+use crate::util;
 
-fn path_as_title(path: &Path) -> String {
-    format!(" {}", path.to_string_lossy())
-}
-
-fn current_buffer_status_line_data(workspace: &mut Workspace) -> StatusLineData {
-    let modified = workspace.current_buffer().map(|b| b.modified()).unwrap_or(false);
-
-    let (content, style) = workspace.current_buffer_path().map(|path| {
-        // Determine buffer title styles based on its modification status.
-        if modified {
-            // Use an emboldened path with an asterisk.
-            let mut title = path_as_title(path);
-            title.push('*');
+/// Describes the current buffer using the configured status line format.
+/// Modified buffers are emboldened, and (by default) have an asterisk.
+fn current_buffer_status_line_data(
+    workspace: &mut Workspace,
+    format: &str,
+    mode: &str
+) -> StatusLineData {
+    let path = workspace
+        .current_buffer_path()
+        .map(|path| path.to_string_lossy().into_owned());
+    let buffer = match (workspace.current_buffer(), path) {
+        (Some(buffer), Some(path)) => Some((buffer, path)),
+        _ => None,
+    };
 
-            (title, Style::Bold)
+    let (content, style) = buffer.map(|(buffer, path)| {
+        let modified = buffer.modified();
+        let syntax = buffer.syntax_reference.as_ref().map(|syntax| syntax.name.clone());
+        // Byte offsets require a copy of the buffer's content; skip it if unused.
+        let (byte, byte_column) = if format.contains("%byte") {
+            util::byte_offsets(&buffer.data(), &*buffer.cursor)
         } else {
-            (path_as_title(path), Style::Default)
-        }
+            (0, 0)
+        };
+        let values = [
+            ("mode", mode.trim().to_string()),
+            ("path", path),
+            ("line", (buffer.cursor.line + 1).to_string()),
+            ("col", (buffer.cursor.offset + 1).to_string()),
+            ("byte", byte.to_string()),
+            ("bytecol", (byte_column + 1).to_string()),
+            ("modified", if modified { "*" } else { "" }.to_string()),
+            ("type", syntax.unwrap_or_default()),
+        ];
+        let style = if modified { Style::Bold } else { Style::Default };
+
+        (apply_status_line_format(format, &values), style)
     }).unwrap_or((String::new(), Style::Default));
 
     StatusLineData {
@@ -33,6 +56,51 @@ fn current_buffer_status_line_data(workspace: &mut Workspace) -> StatusLineData
     }
 }
 
+/// Replaces `%token%` placeholders in the format with their values.
+/// Unknown tokens (and unpaired percent signs) are left as-is.
+fn apply_status_line_format(format: &str, values: &[(&str, String)]) -> String {
+    let mut content = String::new();
+    let mut remaining = format;
+
+    while let Some(start) = remaining.find('%') {
+        content.push_str(&remaining[..start]);
+        let rest = &remaining[start + 1..];
+        let value = rest.find('%').and_then(|end| {
+            values
+                .iter()
+                .find(|(token, _)| *token == &rest[..end])
+                .map(|(_, value)| (end, value))
+        });
+
+        match value {
+            Some((end, value)) => {
+                content.push_str(value);
+                remaining = &rest[end + 1..];
+            }
+            None => {
+                content.push('%');
+                remaining = rest;
+            }
+        }
+    }
+    content.push_str(remaining);
+
+    content
+}
+
+// Single-character ranges used to highlight the buffer's secondary cursors.
+fn secondary_cursor_ranges(buffer: &Buffer, secondary_cursors: &SecondaryCursors) -> Vec<Range> {
+    secondary_cursors.positions(buffer.id)
+        .iter()
+        .map(|&position| {
+            Range::new(position, Position {
+                line: position.line,
+                offset: position.offset + 1,
+            })
+        })
+        .collect()
+}
+
 fn git_status_line_data(repo: &Option<Repository>, path: &Option<PathBuf>) -> StatusLineData {
     // Build a display value for the current buffer's git status.
     let mut content = String::new();
@@ -86,7 +154,18 @@ fn presentable_status(status: &Status) -> &str {
 #[cfg(test)]
 mod tests {
     use git2;
-    use super::presentable_status;
+    use super::{apply_status_line_format, presentable_status};
+
+    #[test]
+    fn apply_status_line_format_substitutes_known_tokens_and_keeps_others() {
+        let values = [("path", String::from("src/main.rs")), ("line", String::from("12"))];
+
+        assert_eq!(
+            apply_status_line_format(" %path%:%line% %unknown% 100%", &values),
+            " src/main.rs:12 %unknown% 100%"
+        );
+        assert_eq!(apply_status_line_format("%%path%%", &values), "%src/main.rs%");
+    }
 
     #[test]
     pub fn presentable_status_returns_untracked_when_status_is_locally_new() {
@@ -1,16 +1,19 @@
 use crate::errors::*;
-use crate::presenters::current_buffer_status_line_data;
+use crate::models::application::SecondaryCursors;
+use crate::presenters::{current_buffer_status_line_data, secondary_cursor_ranges};
 use scribe::Workspace;
 use crate::view::{Colors, StatusLineData, Style, View};
 
-pub fn display(workspace: &mut Workspace, view: &mut View) -> Result<()> {
+pub fn display(workspace: &mut Workspace, view: &mut View, secondary_cursors: &SecondaryCursors) -> Result<()> {
+    let buffer_status =
+        current_buffer_status_line_data(workspace, &view.status_line_format(), " INSERT ");
     let mut presenter = view.build_presenter()?;
-    let buffer_status = current_buffer_status_line_data(workspace);
     let buf = workspace.current_buffer().ok_or(BUFFER_MISSING)?;
     let data = buf.data();
+    let cursor_ranges = secondary_cursor_ranges(buf, secondary_cursors);
 
     // Draw the visible set of tokens to the terminal.
-    presenter.print_buffer(buf, &data, None, None)?;
+    presenter.print_buffer(buf, &data, Some(&cursor_ranges), None)?;
 
     presenter.print_status_line(&[
         StatusLineData {
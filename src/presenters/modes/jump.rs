@@ -5,8 +5,9 @@ use crate::models::application::modes::JumpMode;
 use crate::view::{Colors, StatusLineData, Style, View};
 
 pub fn display(workspace: &mut Workspace, mode: &mut JumpMode, view: &mut View) -> Result<()> {
+    let buffer_status =
+        current_buffer_status_line_data(workspace, &view.status_line_format(), " JUMP ");
     let mut presenter = view.build_presenter()?;
-    let buffer_status = current_buffer_status_line_data(workspace);
     let buf = workspace.current_buffer().ok_or(BUFFER_MISSING)?;
     let data = buf.data();
 
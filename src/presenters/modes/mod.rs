@@ -1,10 +1,18 @@
+pub mod align;
 pub mod confirm;
+pub mod filter;
 pub mod insert;
 pub mod jump;
 pub mod line_jump;
+pub mod mark;
 pub mod path;
 pub mod normal;
+pub mod replace;
+pub mod replace_character;
 pub mod search;
 pub mod search_select;
 pub mod select;
+pub mod select_block;
 pub mod select_line;
+pub mod surround;
+pub mod text_object;
@@ -1,18 +1,21 @@
 use crate::errors::*;
 use scribe::Workspace;
 use scribe::buffer::Position;
-use crate::presenters::{current_buffer_status_line_data, git_status_line_data};
+use crate::models::application::SecondaryCursors;
+use crate::presenters::{current_buffer_status_line_data, git_status_line_data, secondary_cursor_ranges};
 use git2::Repository;
 use crate::view::{Colors, StatusLineData, Style, View};
 
-pub fn display(workspace: &mut Workspace, view: &mut View, repo: &Option<Repository>) -> Result<()> {
+pub fn display(workspace: &mut Workspace, view: &mut View, repo: &Option<Repository>, secondary_cursors: &SecondaryCursors) -> Result<()> {
+    let buffer_status =
+        current_buffer_status_line_data(workspace, &view.status_line_format(), " NORMAL ");
     let mut presenter = view.build_presenter()?;
-    let buffer_status = current_buffer_status_line_data(workspace);
 
     if let Some(buf) = workspace.current_buffer() {
         // Draw the visible set of tokens to the terminal.
         let data = buf.data();
-        presenter.print_buffer(buf, &data, None, None)?;
+        let cursor_ranges = secondary_cursor_ranges(buf, secondary_cursors);
+        presenter.print_buffer(buf, &data, Some(&cursor_ranges), None)?;
 
         // Determine mode display color based on buffer modification status.
         let colors = if buf.modified() {
@@ -11,24 +11,20 @@ pub fn display(workspace: &mut Workspace, mode: &SearchMode, view: &mut View) ->
     // Draw the visible set of tokens to the terminal.
     let buffer = workspace.current_buffer().ok_or(BUFFER_MISSING)?;
     let data = buffer.data();
-    presenter.print_buffer(buffer, &data, mode.results.as_ref().map(|r| r.as_slice()), None)?;
+    // Once results have been grouped, only those are highlighted.
+    let highlights = if mode.selected_results.is_empty() {
+        mode.results.as_ref().map(|r| r.as_slice())
+    } else {
+        Some(mode.selected_results.as_slice())
+    };
+    presenter.print_buffer(buffer, &data, highlights, None)?;
 
     let mode_display = format!(" {} ", mode);
     let search_input = format!(
         " {}",
         mode.input.as_ref().unwrap_or(&String::new())
     );
-    let result_display = if mode.insert {
-        String::new()
-    } else if let Some(ref results) = mode.results {
-        if results.len() == 1 {
-            String::from("1 match")
-        } else {
-            format!("{} of {} matches", results.selected_index() + 1, results.len())
-        }
-    } else {
-        String::new()
-    };
+    let result_display = result_summary(mode);
 
     let cursor_offset =
         mode_display.graphemes(true).count() +
@@ -66,3 +62,46 @@ pub fn display(workspace: &mut Workspace, mode: &SearchMode, view: &mut View) ->
 
     Ok(())
 }
+
+// Describes the current result's position amongst the others (e.g. "3/17"),
+// which is kept up to date as the query is typed. Grouped results are
+// counted instead, since those are what copy/delete commands act on.
+fn result_summary(mode: &SearchMode) -> String {
+    if !mode.selected_results.is_empty() {
+        return format!("{} selected", mode.selected_results.len());
+    }
+
+    match mode.results {
+        Some(ref results) if results.is_empty() => String::from("No matches"),
+        Some(ref results) => format!("{}/{}", results.selected_index() + 1, results.len()),
+        None => String::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::models::application::modes::SearchMode;
+    use crate::util::SelectableVec;
+    use scribe::buffer::{Position, Range};
+    use super::result_summary;
+
+    #[test]
+    fn result_summary_shows_the_current_result_index_and_total() {
+        let mut mode = SearchMode::new(Some(String::from("amp")));
+        let result = Range::new(Position{ line: 0, offset: 0 }, Position{ line: 0, offset: 3 });
+        let mut results = SelectableVec::new(vec![result.clone(), result.clone(), result]);
+        results.select_next();
+        mode.results = Some(results);
+
+        assert_eq!(result_summary(&mode), "2/3");
+    }
+
+    #[test]
+    fn result_summary_reports_when_there_are_no_matches() {
+        let mut mode = SearchMode::new(Some(String::from("amp")));
+        assert_eq!(result_summary(&mode), "");
+
+        mode.results = Some(SelectableVec::new(Vec::new()));
+        assert_eq!(result_summary(&mode), "No matches");
+    }
+}
@@ -12,13 +12,16 @@ use unicode_segmentation::UnicodeSegmentation;
 pub fn display<T: Display>(workspace: &mut Workspace, mode: &mut dyn SearchSelectMode<T>, view: &mut View) -> Result<()> {
     let data;
     let padded_message;
+    let buffer_status = current_buffer_status_line_data(
+        workspace,
+        &view.status_line_format(),
+        &mode.to_string()
+    );
     let mut presenter = view.build_presenter()?;
     let mode_config = mode.config().clone();
     let mut padded_content = Vec::new();
     let mut remaining_lines = Vec::new();
 
-    let buffer_status = current_buffer_status_line_data(workspace);
-
     if let Some(buf) = workspace.current_buffer() {
         data = buf.data();
         presenter.print_buffer(buf, &data, None, None)?;
@@ -40,9 +43,17 @@ pub fn display<T: Display>(workspace: &mut Workspace, mode: &mut dyn SearchSelec
                    Colors::Default,
                    &padded_message);
     } else {
-        // Draw the list of search results.
-        for (line, result) in mode.results().enumerate() {
-            let (content, colors, style) = if line == mode.selected_index() {
+        // Draw the list of search results, scrolling through
+        // them if there are more than fit in the display area.
+        let scroll_offset = mode.selected_index()
+            .saturating_sub(mode_config.max_results.saturating_sub(1));
+        let visible_results = mode.results()
+            .enumerate()
+            .skip(scroll_offset)
+            .take(mode_config.max_results);
+        for (index, result) in visible_results {
+            let line = index - scroll_offset;
+            let (content, colors, style) = if index == mode.selected_index() {
                 (format!("> {}", result), Colors::Focused, Style::Bold)
             } else {
                 (format!("  {}", result), Colors::Default, Style::Default)
@@ -61,7 +72,8 @@ pub fn display<T: Display>(workspace: &mut Workspace, mode: &mut dyn SearchSelec
     }
 
     // Clear any remaining lines in the result display area.
-    for line in cmp::max(mode.results().len(), 1)..mode_config.max_results {
+    let result_lines = cmp::min(mode.results().len(), mode_config.max_results);
+    for line in cmp::max(result_lines, 1)..mode_config.max_results {
         remaining_lines.push((
            Position{ line, offset: 0 },
            Style::Default,
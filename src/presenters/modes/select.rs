@@ -6,8 +6,9 @@ use crate::presenters::current_buffer_status_line_data;
 use crate::view::{Colors, StatusLineData, Style, View};
 
 pub fn display(workspace: &mut Workspace, mode: &SelectMode, view: &mut View) -> Result<()> {
+    let buffer_status =
+        current_buffer_status_line_data(workspace, &view.status_line_format(), " SELECT ");
     let mut presenter = view.build_presenter()?;
-    let buffer_status = current_buffer_status_line_data(workspace);
     let buf = workspace.current_buffer().ok_or(BUFFER_MISSING)?;
     let selected_range = Range::new(mode.anchor, *buf.cursor.clone());
     let data = buf.data();
@@ -0,0 +1,34 @@
+use crate::errors::*;
+use scribe::Workspace;
+use scribe::buffer::Position;
+use crate::models::application::modes::SurroundMode;
+use crate::view::{Colors, StatusLineData, Style, View};
+
+pub fn display(workspace: &mut Workspace, mode: &SurroundMode, view: &mut View) -> Result<()> {
+    let mut presenter = view.build_presenter()?;
+    let buf = workspace.current_buffer().ok_or(BUFFER_MISSING)?;
+    let data = buf.data();
+    presenter.print_buffer(buf, &data, Some(&[mode.range.clone()]), None)?;
+
+    // Draw the status line as an input prompt.
+    let input_prompt = "Surround with: ";
+    presenter.print_status_line(&[
+        StatusLineData {
+            content: input_prompt.to_string(),
+            style: Style::Default,
+            colors: Colors::Default,
+        }
+    ]);
+
+    // Move the cursor to the end of the prompt.
+    let cursor_line = presenter.height() - 1;
+    presenter.set_cursor(Some(Position {
+        line: cursor_line,
+        offset: input_prompt.len(),
+    }));
+
+    // Render the changes to the screen.
+    presenter.present();
+
+    Ok(())
+}
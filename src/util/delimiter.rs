@@ -0,0 +1,138 @@
+use scribe::buffer::Position;
+
+/// Maps a delimiter to its opening and closing characters. Either side of a
+/// bracket pair identifies it; any other character (e.g. a quote) is used on
+/// both sides.
+pub fn delimiter_pair(delimiter: char) -> (char, char) {
+    match delimiter {
+        '(' | ')' => ('(', ')'),
+        '[' | ']' => ('[', ']'),
+        '{' | '}' => ('{', '}'),
+        '<' | '>' => ('<', '>'),
+        _ => (delimiter, delimiter),
+    }
+}
+
+/// Walks the graphemes, starting with the bracket being matched, and
+/// returns the position at which its nesting depth is brought back to zero.
+pub fn matching_bracket_position<'a, T>(graphemes: T, bracket: &str, partner: &str) -> Option<Position>
+    where T: Iterator<Item = (Position, &'a str)>
+{
+    let mut depth = 0;
+    for (position, grapheme) in graphemes {
+        if grapheme == bracket {
+            depth += 1;
+        } else if grapheme == partner {
+            depth -= 1;
+            if depth == 0 {
+                return Some(position);
+            }
+        }
+    }
+
+    None
+}
+
+/// Finds the positions of the innermost pair of delimiters enclosing (or at)
+/// the specified position, in content split into lines of graphemes. Pairs
+/// using the same character on both sides (i.e. quotes) can't be nested,
+/// so they're paired off in order, and only on the position's line.
+pub fn enclosing_pair(lines: &[Vec<&str>], position: Position, opener: &str, closer: &str) -> Option<(Position, Position)> {
+    if opener == closer {
+        let content = lines.get(position.line)?;
+        let offsets: Vec<usize> = content.iter()
+            .enumerate()
+            .filter(|&(_, grapheme)| *grapheme == opener)
+            .map(|(offset, _)| offset)
+            .collect();
+
+        return offsets.chunks(2)
+            .find(|pair| pair.len() == 2 && pair[0] <= position.offset && position.offset <= pair[1])
+            .map(|pair| {
+                (Position { line: position.line, offset: pair[0] },
+                 Position { line: position.line, offset: pair[1] })
+            });
+    }
+
+    // Walk backwards to the first opener that hasn't been closed. A closer
+    // at the starting position belongs to the pair we're looking for.
+    let mut depth = 0;
+    let mut opening_position = None;
+    let preceding_graphemes = lines.iter()
+        .enumerate()
+        .take(position.line + 1)
+        .rev()
+        .flat_map(|(line, graphemes)| {
+            graphemes.iter().enumerate().rev().map(move |(offset, grapheme)| {
+                (Position { line, offset }, *grapheme)
+            })
+        })
+        .skip_while(|&(current_position, _)| current_position > position);
+    for (current_position, grapheme) in preceding_graphemes {
+        if grapheme == closer && current_position != position {
+            depth += 1;
+        } else if grapheme == opener {
+            if depth == 0 {
+                opening_position = Some(current_position);
+                break;
+            }
+            depth -= 1;
+        }
+    }
+    let opening_position = opening_position?;
+
+    let following_graphemes = lines.iter()
+        .enumerate()
+        .skip(opening_position.line)
+        .flat_map(|(line, graphemes)| {
+            graphemes.iter().enumerate().map(move |(offset, grapheme)| {
+                (Position { line, offset }, *grapheme)
+            })
+        })
+        .skip_while(|&(current_position, _)| current_position < opening_position);
+
+    matching_bracket_position(following_graphemes, opener, closer)
+        .map(|closing_position| (opening_position, closing_position))
+}
+
+#[cfg(test)]
+mod tests {
+    use scribe::buffer::Position;
+    use unicode_segmentation::UnicodeSegmentation;
+
+    fn lines(content: &str) -> Vec<Vec<&str>> {
+        content.lines().map(|line| line.graphemes(true).collect()).collect()
+    }
+
+    #[test]
+    fn enclosing_pair_finds_the_innermost_pair() {
+        let content = lines("amp(editor(\nbuffer) + (x))");
+
+        assert_eq!(
+            super::enclosing_pair(&content, Position { line: 1, offset: 2 }, "(", ")"),
+            Some((Position { line: 0, offset: 10 }, Position { line: 1, offset: 6 }))
+        );
+        assert_eq!(
+            super::enclosing_pair(&content, Position { line: 1, offset: 8 }, "(", ")"),
+            Some((Position { line: 0, offset: 3 }, Position { line: 1, offset: 13 }))
+        );
+        assert_eq!(
+            super::enclosing_pair(&content, Position { line: 0, offset: 1 }, "(", ")"),
+            None
+        );
+    }
+
+    #[test]
+    fn enclosing_pair_pairs_quotes_on_the_current_line() {
+        let content = lines("\"amp\" \"editor\"");
+
+        assert_eq!(
+            super::enclosing_pair(&content, Position { line: 0, offset: 8 }, "\"", "\""),
+            Some((Position { line: 0, offset: 6 }, Position { line: 0, offset: 13 }))
+        );
+        assert_eq!(
+            super::enclosing_pair(&content, Position { line: 0, offset: 5 }, "\"", "\""),
+            None
+        );
+    }
+}
@@ -0,0 +1,151 @@
+use crate::errors::*;
+
+const BASE64_ALPHABET: &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+const HEX_DIGITS: &[u8] = b"0123456789abcdef";
+
+/// Encodes the content as padded, standard-alphabet base64.
+pub fn base64_encode(content: &str) -> String {
+    let mut encoded = String::with_capacity((content.len() + 2) / 3 * 4);
+
+    for chunk in content.as_bytes().chunks(3) {
+        let bits = chunk.iter()
+            .enumerate()
+            .fold(0u32, |bits, (index, &byte)| bits | (byte as u32) << (16 - 8 * index));
+
+        for index in 0..4 {
+            if index <= chunk.len() {
+                let sextet = (bits >> (18 - 6 * index)) & 0x3f;
+                encoded.push(BASE64_ALPHABET[sextet as usize] as char);
+            } else {
+                encoded.push('=');
+            }
+        }
+    }
+
+    encoded
+}
+
+/// Decodes standard-alphabet base64, with or without padding. Surrounding
+/// whitespace (e.g. a trailing newline) is ignored.
+pub fn base64_decode(content: &str) -> Result<String> {
+    let content = content.trim().trim_end_matches('=');
+    let mut decoded = Vec::with_capacity(content.len() * 3 / 4);
+    let mut bits = 0u32;
+    let mut bit_count = 0;
+
+    for byte in content.bytes() {
+        let sextet = BASE64_ALPHABET.iter()
+            .position(|&character| character == byte)
+            .ok_or("Content isn't valid base64")?;
+        bits = bits << 6 | sextet as u32;
+        bit_count += 6;
+
+        if bit_count >= 8 {
+            bit_count -= 8;
+            decoded.push((bits >> bit_count) as u8);
+            bits &= (1 << bit_count) - 1;
+        }
+    }
+    if bit_count >= 6 {
+        bail!("Content isn't valid base64");
+    }
+
+    String::from_utf8(decoded).chain_err(|| "Decoded base64 content isn't valid UTF-8")
+}
+
+/// Percent-encodes everything other than unreserved URL characters.
+pub fn url_encode(content: &str) -> String {
+    let mut encoded = String::with_capacity(content.len());
+
+    for byte in content.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' => {
+                encoded.push(byte as char)
+            }
+            _ => encoded.push_str(&format!("%{:02X}", byte)),
+        }
+    }
+
+    encoded
+}
+
+/// Decodes percent-encoded content. Plus signs are left as-is,
+/// since they only represent spaces in form-encoded queries.
+pub fn url_decode(content: &str) -> Result<String> {
+    let bytes = content.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut index = 0;
+
+    while index < bytes.len() {
+        if bytes[index] == b'%' {
+            let byte = bytes.get(index + 1..index + 3)
+                .and_then(|digits| decode_hex_pair(digits[0], digits[1]))
+                .ok_or("Content contains an invalid percent-encoded sequence")?;
+            decoded.push(byte);
+            index += 3;
+        } else {
+            decoded.push(bytes[index]);
+            index += 1;
+        }
+    }
+
+    String::from_utf8(decoded).chain_err(|| "Decoded URL content isn't valid UTF-8")
+}
+
+/// Encodes each byte of the content as a pair of lowercase hex digits.
+pub fn hex_encode(content: &str) -> String {
+    let mut encoded = String::with_capacity(content.len() * 2);
+
+    for byte in content.bytes() {
+        encoded.push(HEX_DIGITS[(byte >> 4) as usize] as char);
+        encoded.push(HEX_DIGITS[(byte & 0xf) as usize] as char);
+    }
+
+    encoded
+}
+
+/// Decodes pairs of hex digits (in either case). Surrounding
+/// whitespace (e.g. a trailing newline) is ignored.
+pub fn hex_decode(content: &str) -> Result<String> {
+    let content = content.trim().as_bytes();
+    if content.len() % 2 != 0 {
+        bail!("Content isn't valid hex");
+    }
+
+    let decoded = content.chunks(2)
+        .map(|digits| decode_hex_pair(digits[0], digits[1]))
+        .collect::<Option<Vec<u8>>>()
+        .ok_or("Content isn't valid hex")?;
+
+    String::from_utf8(decoded).chain_err(|| "Decoded hex content isn't valid UTF-8")
+}
+
+fn decode_hex_pair(high: u8, low: u8) -> Option<u8> {
+    let high = (high as char).to_digit(16)?;
+    let low = (low as char).to_digit(16)?;
+
+    Some((high << 4 | low) as u8)
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn base64_round_trips_content_of_every_padding_length() {
+        for (content, encoded) in &[("amp", "YW1w"), ("amp!", "YW1wIQ=="), ("amp!?", "YW1wIT8=")] {
+            assert_eq!(super::base64_encode(content), *encoded);
+            assert_eq!(super::base64_decode(encoded).unwrap(), *content);
+        }
+        assert!(super::base64_decode("YW1w*").is_err());
+    }
+
+    #[test]
+    fn url_and_hex_encodings_round_trip() {
+        assert_eq!(super::url_encode("amp editor/é"), "amp%20editor%2F%C3%A9");
+        assert_eq!(super::url_decode("amp%20editor%2F%C3%A9").unwrap(), "amp editor/é");
+        assert!(super::url_decode("amp%2").is_err());
+
+        assert_eq!(super::hex_encode("amp"), "616d70");
+        assert_eq!(super::hex_decode("616D70").unwrap(), "amp");
+        assert!(super::hex_decode("616d7").is_err());
+    }
+}
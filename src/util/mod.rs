@@ -1,12 +1,21 @@
 pub use self::selectable_vec::SelectableVec;
 
+pub mod delimiter;
+pub mod encoding;
 pub mod movement_lexer;
 mod selectable_vec;
+pub mod shell;
+pub mod time;
 pub mod token;
+pub mod word;
 
 use crate::errors::*;
 use crate::models::Application;
+use scribe::Workspace;
 use scribe::buffer::{Buffer, LineRange, Position, Range};
+use std::fs::{self, File};
+use std::path::Path;
+use unicode_segmentation::UnicodeSegmentation;
 
 /// Translates a line range to a regular range, including its last line.
 /// Handles ranges including and end line without trailing newline character.
@@ -49,6 +58,126 @@ pub fn inclusive_range(line_range: &LineRange, buffer: &mut Buffer) -> Range {
                end_position)
 }
 
+/// Moves the cursor to the specified position or, if it no longer
+/// exists, the closest one to it at the end of the buffer or line.
+pub fn move_cursor_to_nearest(buffer: &mut Buffer, position: Position) {
+    if !buffer.cursor.move_to(position) {
+        let data = buffer.data();
+        let line = position.line.min(buffer.line_count().saturating_sub(1));
+        let line_length = data
+            .lines()
+            .nth(line)
+            .map(|content| content.graphemes(true).count())
+            .unwrap_or(0);
+        buffer.cursor.move_to(Position {
+            line,
+            offset: position.offset.min(line_length),
+        });
+    }
+}
+
+/// The zero-based byte offset of the position in the data, as well as its
+/// zero-based byte offset within its line. Positions beyond the end of a line
+/// (or the data) are clamped to its end.
+pub fn byte_offsets(data: &str, position: &Position) -> (usize, usize) {
+    let mut line_start = 0;
+    for _ in 0..position.line {
+        match data[line_start..].find('\n') {
+            Some(index) => line_start += index + 1,
+            None => return (data.len(), 0),
+        }
+    }
+
+    let line = data[line_start..].split('\n').next().unwrap_or("");
+    let column = line
+        .grapheme_indices(true)
+        .nth(position.offset)
+        .map(|(index, _)| index)
+        .unwrap_or_else(|| line.trim_end_matches('\r').len());
+
+    (line_start + column, column)
+}
+
+/// Swaps the case of each character, leaving those without case untouched.
+/// Case mappings aren't always one-to-one (e.g. 'ß' becomes "SS"),
+/// so we extend with the full mapping rather than a single char.
+pub fn swap_case(content: &str) -> String {
+    let mut swapped = String::with_capacity(content.len());
+    for character in content.chars() {
+        if character.is_uppercase() {
+            swapped.extend(character.to_lowercase());
+        } else if character.is_lowercase() {
+            swapped.extend(character.to_uppercase());
+        } else {
+            swapped.push(character);
+        }
+    }
+
+    swapped
+}
+
+/// Separates `path:line` and `path:line:column` references into their path
+/// and position components, converting the 1-based line and column numbers
+/// into a (0-based) position. The column defaults to the start of the line.
+pub fn split_path_position(token: &str) -> (&str, Option<Position>) {
+    let mut path = token.trim_end_matches(':');
+    let mut numbers: Vec<usize> = Vec::new();
+    while numbers.len() < 2 {
+        let index = match path.rfind(':') {
+            Some(index) => index,
+            None => break,
+        };
+        match path[index + 1..].parse() {
+            Ok(number) => {
+                numbers.insert(0, number);
+                path = &path[..index];
+            }
+            Err(_) => break,
+        }
+    }
+
+    let position = numbers.first().map(|line: &usize| Position {
+        line: line.saturating_sub(1),
+        offset: numbers.get(1).map_or(0, |column| column.saturating_sub(1)),
+    });
+
+    (path, position)
+}
+
+/// Records the current buffer's cursor position, so
+/// that it can be restored when its file is reopened.
+pub fn record_cursor_position(app: &mut Application) -> Result<()> {
+    if let Some(buffer) = app.workspace.current_buffer() {
+        app.cursor_positions.record(buffer)?;
+    }
+
+    Ok(())
+}
+
+/// Adds the current buffer's file to the recent files list.
+pub fn record_recent_file(app: &mut Application) -> Result<()> {
+    if let Some(path) = app.workspace.current_buffer().and_then(|buffer| buffer.path.as_ref()) {
+        app.recent_files.push(path)?;
+    }
+
+    Ok(())
+}
+
+/// Moves the cursor to where it was last left in the current buffer's
+/// file (scrolling it into view), unless disabled via preferences.
+pub fn restore_cursor_position(app: &mut Application) -> Result<()> {
+    if !app.preferences.borrow().restore_cursor_positions() {
+        return Ok(());
+    }
+
+    if let Some(buffer) = app.workspace.current_buffer() {
+        app.cursor_positions.restore(buffer);
+        app.view.scroll_to_center(buffer)?;
+    }
+
+    Ok(())
+}
+
 /// Convenience method to initialize and add a buffer to the workspace.
 pub fn add_buffer(buffer: Buffer, app: &mut Application) -> Result<()> {
     app.workspace.add_buffer(buffer);
@@ -57,10 +186,149 @@ pub fn add_buffer(buffer: Buffer, app: &mut Application) -> Result<()> {
     Ok(())
 }
 
+/// Cycles through the workspace's buffers until the one with the specified
+/// ID is selected, returning false (with the original selection restored)
+/// if there's no such buffer.
+pub fn select_buffer(workspace: &mut Workspace, id: usize) -> bool {
+    let original_id = workspace.current_buffer().and_then(|buffer| buffer.id);
+
+    loop {
+        let current_id = workspace.current_buffer().and_then(|buffer| buffer.id);
+        if current_id == Some(id) {
+            return true;
+        }
+
+        workspace.next_buffer();
+        if workspace.current_buffer().and_then(|buffer| buffer.id) == original_id {
+            return false;
+        }
+    }
+}
+
+/// Runs the function with each of the workspace's buffers selected in turn,
+/// finishing with the originally selected buffer selected once again.
+pub fn for_each_buffer<F>(app: &mut Application, mut function: F) -> Result<()>
+    where F: FnMut(&mut Application) -> Result<()>
+{
+    let original_id = match app.workspace.current_buffer() {
+        Some(buffer) => buffer.id,
+        None => return Ok(()),
+    };
+
+    loop {
+        function(app)?;
+        app.workspace.next_buffer();
+        if app.workspace.current_buffer().and_then(|buffer| buffer.id) == original_id {
+            return Ok(());
+        }
+    }
+}
+
+/// Whether the workspace already has a buffer open for the specified path.
+pub fn has_buffer_for_path(app: &mut Application, path: &Path) -> Result<bool> {
+    let normalize = |path: &Path| fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf());
+    let path = normalize(path);
+    let mut found = false;
+    for_each_buffer(app, |app| {
+        let buffer = app.workspace.current_buffer().ok_or(BUFFER_MISSING)?;
+        found |= buffer.path
+            .as_ref()
+            .map(|buffer_path| normalize(buffer_path) == path)
+            .unwrap_or(false);
+
+        Ok(())
+    })?;
+
+    Ok(found)
+}
+
+/// Guards commands that modify buffer content, failing
+/// if the current buffer has been flagged as read-only.
+pub fn ensure_writable(app: &mut Application) -> Result<()> {
+    let buffer = app.workspace.current_buffer().ok_or(BUFFER_MISSING)?;
+    if app.read_only_buffers.is_read_only(buffer) {
+        bail!(BUFFER_READ_ONLY);
+    }
+
+    Ok(())
+}
+
+/// Records an edit that's just been applied to the current buffer: the cursor
+/// is added to its change list, its forward jump history is discarded, the
+/// current key sequence is flagged as a repeatable change, and secondary
+/// cursors are left stale (unless the edit is then recorded as applied at each
+/// of them). Commands call this once they've modified the buffer, rather than
+/// when they're about to, so that those that turn out to leave it untouched
+/// aren't recorded.
+pub fn record_edit(app: &mut Application) {
+    let buffer_state = app.workspace.current_buffer()
+        .and_then(|buffer| buffer.id.map(|id| (id, *buffer.cursor)));
+    if let Some((id, position)) = buffer_state {
+        app.marks.record_change(id, position);
+        app.marks.truncate_jumps(id);
+    }
+    app.last_change.record_change();
+    app.secondary_cursors.record_edit();
+}
+
+/// Copies an existing file to a sibling with a trailing tilde (e.g. `main.rs~`),
+/// ahead of it being overwritten. The copy is streamed to a temporary file and
+/// synced before being renamed into place, so that an interrupted backup never
+/// clobbers a previous one. Paths that don't refer to a file are ignored.
+pub fn backup_file(path: &Path) -> Result<()> {
+    if !path.is_file() {
+        return Ok(());
+    }
+
+    let mut backup_name = path
+        .file_name()
+        .ok_or("Couldn't determine the file name to back up")?
+        .to_os_string();
+    backup_name.push("~");
+    let backup_path = path.with_file_name(&backup_name);
+    backup_name.push(".tmp");
+    let temporary_path = path.with_file_name(&backup_name);
+
+    fs::copy(path, &temporary_path).chain_err(|| "Failed to write backup file")?;
+    File::open(&temporary_path)
+        .and_then(|file| file.sync_all())
+        .chain_err(|| "Failed to write backup file")?;
+    fs::rename(&temporary_path, &backup_path)
+        .chain_err(|| "Failed to move backup file into place")?;
+
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use scribe::Buffer;
     use scribe::buffer::{LineRange, Position, Range};
+    use std::env;
+    use std::fs;
+
+    #[test]
+    fn byte_offsets_counts_multibyte_graphemes_by_their_length() {
+        let data = "amp\nnaïve café\n";
+
+        assert_eq!(super::byte_offsets(data, &Position { line: 0, offset: 2 }), (2, 2));
+        assert_eq!(super::byte_offsets(data, &Position { line: 1, offset: 4 }), (9, 5));
+        assert_eq!(super::byte_offsets(data, &Position { line: 1, offset: 20 }), (16, 12));
+        assert_eq!(super::byte_offsets(data, &Position { line: 2, offset: 0 }), (17, 0));
+    }
+
+    #[test]
+    fn split_path_position_parses_line_and_column_suffixes() {
+        assert_eq!(super::split_path_position("src/main.rs"), ("src/main.rs", None));
+        assert_eq!(
+            super::split_path_position("src/main.rs:120"),
+            ("src/main.rs", Some(Position { line: 119, offset: 0 }))
+        );
+        assert_eq!(
+            super::split_path_position("src/main.rs:120:5:"),
+            ("src/main.rs", Some(Position { line: 119, offset: 4 }))
+        );
+        assert_eq!(super::split_path_position("notes:draft"), ("notes:draft", None));
+    }
 
     #[test]
     fn inclusive_range_works_correctly_without_trailing_newline() {
@@ -95,4 +363,24 @@ mod tests {
                                   offset: 0,
                               }));
     }
+
+    #[test]
+    fn backup_file_copies_file_to_tilde_suffixed_sibling() {
+        let path = env::temp_dir().join("amp_backup_file_test");
+        let backup_path = env::temp_dir().join("amp_backup_file_test~");
+        fs::write(&path, "amp").unwrap();
+        super::backup_file(&path).unwrap();
+
+        assert_eq!(fs::read_to_string(&backup_path).unwrap(), "amp");
+        fs::remove_file(&path).unwrap();
+        fs::remove_file(&backup_path).unwrap();
+    }
+
+    #[test]
+    fn backup_file_ignores_missing_files() {
+        let path = env::temp_dir().join("amp_backup_file_missing_test");
+        super::backup_file(&path).unwrap();
+
+        assert!(!env::temp_dir().join("amp_backup_file_missing_test~").exists());
+    }
 }
@@ -0,0 +1,65 @@
+use crate::errors::*;
+use std::io::Write;
+use std::path::Path;
+use std::process::{Command, Stdio};
+use std::thread;
+
+/// Runs the command using the system shell (from the specified directory),
+/// writing the input to its stdin and returning what it writes to stdout.
+/// Fails if the command can't be spawned, exits with a non-zero status, or
+/// produces output that isn't valid UTF-8.
+pub fn filter(command: &str, input: &str, directory: &Path) -> Result<String> {
+    let mut child = Command::new("sh")
+        .arg("-c")
+        .arg(command)
+        .current_dir(directory)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .chain_err(|| format!("Couldn't run \"{}\"", command))?;
+
+    // Write the input from a separate thread; commands that produce
+    // output before consuming all of their input would otherwise deadlock.
+    let mut stdin = child.stdin.take().ok_or("Couldn't write to the command's input")?;
+    let input = input.to_string();
+    let writer = thread::spawn(move || stdin.write_all(input.as_bytes()));
+
+    let output = child.wait_with_output()
+        .chain_err(|| format!("Couldn't run \"{}\"", command))?;
+
+    // Commands that don't read their input (e.g. `date`) can close stdin
+    // before it's been written, so write failures aren't treated as errors.
+    let _ = writer.join();
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        let reason = stderr.lines()
+            .find(|line| !line.trim().is_empty())
+            .map(|line| line.to_string())
+            .unwrap_or_else(|| output.status.to_string());
+        bail!(format!("\"{}\" failed: {}", command, reason));
+    }
+
+    String::from_utf8(output.stdout)
+        .chain_err(|| format!("\"{}\" produced output that isn't valid UTF-8", command))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::env;
+
+    #[test]
+    fn filter_returns_the_output_of_the_command() {
+        let output = super::filter("tr a-z A-Z", "amp\n", &env::temp_dir()).unwrap();
+
+        assert_eq!(output, "AMP\n");
+    }
+
+    #[test]
+    fn filter_fails_when_the_command_exits_with_a_non_zero_status() {
+        let result = super::filter("echo broken >&2; exit 1", "amp", &env::temp_dir());
+
+        assert_eq!(result.unwrap_err().to_string(), "\"echo broken >&2; exit 1\" failed: broken");
+    }
+}
@@ -0,0 +1,71 @@
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// A format for ISO 8601 UTC timestamps (e.g. 2020-01-01T00:00:00Z).
+pub const ISO_8601: &str = "%Y-%m-%dT%H:%M:%SZ";
+
+/// Formats the time (in UTC) using strftime-style specifiers: `%Y` (year),
+/// `%m` (month), `%d` (day), `%H` (hour), `%M` (minute), `%S` (second),
+/// `%s` (seconds since the Unix epoch), and `%%` (a literal percent sign).
+/// Anything else is copied as-is.
+pub fn format(time: SystemTime, format: &str) -> String {
+    let seconds = time.duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or(0);
+    let (year, month, day) = civil_date(seconds / 86_400);
+    let time_of_day = seconds % 86_400;
+
+    let mut formatted = String::with_capacity(format.len());
+    let mut characters = format.chars();
+    while let Some(character) = characters.next() {
+        if character != '%' {
+            formatted.push(character);
+            continue;
+        }
+
+        match characters.next() {
+            Some('Y') => formatted.push_str(&format!("{:04}", year)),
+            Some('m') => formatted.push_str(&format!("{:02}", month)),
+            Some('d') => formatted.push_str(&format!("{:02}", day)),
+            Some('H') => formatted.push_str(&format!("{:02}", time_of_day / 3_600)),
+            Some('M') => formatted.push_str(&format!("{:02}", time_of_day % 3_600 / 60)),
+            Some('S') => formatted.push_str(&format!("{:02}", time_of_day % 60)),
+            Some('s') => formatted.push_str(&seconds.to_string()),
+            Some('%') => formatted.push('%'),
+            Some(other) => {
+                formatted.push('%');
+                formatted.push(other);
+            }
+            None => formatted.push('%'),
+        }
+    }
+
+    formatted
+}
+
+// Converts days since the Unix epoch to a (year, month, day) date,
+// using Howard Hinnant's days-to-civil-date algorithm.
+fn civil_date(days: u64) -> (i64, i64, i64) {
+    let z = days as i64 + 719_468;
+    let era = z.div_euclid(146_097);
+    let day_of_era = z.rem_euclid(146_097);
+    let year_of_era = (day_of_era - day_of_era / 1_460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let shifted_month = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * shifted_month + 2) / 5 + 1;
+    let month = if shifted_month < 10 { shifted_month + 3 } else { shifted_month - 9 };
+    let year = year_of_era + era * 400 + if month <= 2 { 1 } else { 0 };
+
+    (year, month, day)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, UNIX_EPOCH};
+
+    #[test]
+    fn format_replaces_specifiers_with_utc_date_and_time_components() {
+        let time = UNIX_EPOCH + Duration::from_secs(951_827_696);
+
+        assert_eq!(super::format(time, "%Y-%m-%d %H:%M:%S"), "2000-02-29 12:34:56");
+        assert_eq!(super::format(time, "%s"), "951827696");
+        assert_eq!(super::format(time, "100%% %q%"), "100% %q%");
+    }
+}
@@ -0,0 +1,149 @@
+use scribe::buffer::Position;
+use unicode_segmentation::UnicodeSegmentation;
+
+#[derive(Clone, Copy, PartialEq)]
+enum Class {
+    Whitespace,
+    Word,
+    Punctuation,
+}
+
+/// Grapheme-indexed view of buffer data, used to find vim-style word
+/// boundaries. Runs of word characters (alphanumerics and underscores) and
+/// runs of other non-whitespace characters are treated as separate words.
+pub struct Words<'a> {
+    lines: Vec<Vec<&'a str>>,
+}
+
+impl<'a> Words<'a> {
+    pub fn new(data: &'a str) -> Words<'a> {
+        Words {
+            lines: data.lines().map(|line| line.graphemes(true).collect()).collect(),
+        }
+    }
+
+    /// Finds the start of the word following the position, stopping at
+    /// empty lines, which (as with vim) are considered words of their own.
+    pub fn next_word_start(&self, position: Position) -> Option<Position> {
+        let mut class = self.class(position);
+        let mut current = position;
+        loop {
+            let next = self.next_position(current)?;
+            let crossed_line = next.line != current.line;
+            current = next;
+
+            if crossed_line && self.line_is_empty(current.line) {
+                return Some(current);
+            }
+
+            let next_class = self.class(current);
+            if next_class == Class::Whitespace || (crossed_line && next_class == class) {
+                // A whitespace/line boundary ends the current word;
+                // the next non-whitespace character starts a new one.
+                class = Class::Whitespace;
+            }
+            if next_class != Class::Whitespace && next_class != class {
+                return Some(current);
+            }
+        }
+    }
+
+    /// Finds the start of the word preceding the position (or the start
+    /// of the word containing it, if it isn't already at its start).
+    pub fn previous_word_start(&self, position: Position) -> Option<Position> {
+        // Step back over any whitespace.
+        let mut current = position;
+        loop {
+            let previous = self.previous_position(current)?;
+            let crossed_line = previous.line != current.line;
+            current = previous;
+
+            if crossed_line && self.line_is_empty(current.line) {
+                return Some(current);
+            }
+            if self.class(current) != Class::Whitespace {
+                break;
+            }
+        }
+
+        // Step back to the start of the word.
+        let class = self.class(current);
+        while let Some(previous) = self.previous_position(current) {
+            if previous.line != current.line || self.class(previous) != class {
+                break;
+            }
+            current = previous;
+        }
+
+        Some(current)
+    }
+
+    /// Finds the end of the word containing the position (or the end of the
+    /// following word, if it's already at the end of one), skipping empty lines.
+    pub fn end_of_word(&self, position: Position) -> Option<Position> {
+        // Step forward over any whitespace.
+        let mut current = position;
+        loop {
+            current = self.next_position(current)?;
+            if self.class(current) != Class::Whitespace {
+                break;
+            }
+        }
+
+        // Step forward to the end of the word.
+        let class = self.class(current);
+        while let Some(next) = self.next_position(current) {
+            if next.line != current.line || self.class(next) != class {
+                break;
+            }
+            current = next;
+        }
+
+        Some(current)
+    }
+
+    // Positions beyond the end of a line (i.e. on empty lines,
+    // or at the end of a line in insert mode) are treated as whitespace.
+    fn class(&self, position: Position) -> Class {
+        let grapheme = self.lines
+            .get(position.line)
+            .and_then(|line| line.get(position.offset));
+
+        match grapheme {
+            Some(grapheme) if grapheme.chars().all(char::is_whitespace) => Class::Whitespace,
+            Some(grapheme) if grapheme.chars().all(|c| c.is_alphanumeric() || c == '_') => Class::Word,
+            Some(_) => Class::Punctuation,
+            None => Class::Whitespace,
+        }
+    }
+
+    fn line_is_empty(&self, line: usize) -> bool {
+        self.lines.get(line).map(|line| line.is_empty()).unwrap_or(true)
+    }
+
+    fn next_position(&self, position: Position) -> Option<Position> {
+        let length = self.lines.get(position.line)?.len();
+        if position.offset + 1 < length {
+            Some(Position { line: position.line, offset: position.offset + 1 })
+        } else if position.line + 1 < self.lines.len() {
+            Some(Position { line: position.line + 1, offset: 0 })
+        } else {
+            None
+        }
+    }
+
+    fn previous_position(&self, position: Position) -> Option<Position> {
+        let length = self.lines.get(position.line)?.len();
+        if position.offset > 0 && length > 0 {
+            Some(Position { line: position.line, offset: position.offset.min(length) - 1 })
+        } else if position.line > 0 {
+            let previous_line = position.line - 1;
+            Some(Position {
+                line: previous_line,
+                offset: self.lines[previous_line].len().saturating_sub(1),
+            })
+        } else {
+            None
+        }
+    }
+}
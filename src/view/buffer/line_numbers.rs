@@ -6,16 +6,32 @@ pub const PADDING_WIDTH: usize = 2;
 pub struct LineNumbers {
     current_number: usize,
     buffer_line_count_width: usize,
+    relative_line: Option<usize>,
 }
 
 impl LineNumbers {
     pub fn new(buffer: &Buffer, offset: Option<usize>) -> LineNumbers {
         LineNumbers{
             current_number: offset.unwrap_or(0),
-            buffer_line_count_width: buffer.line_count().to_string().len()
+            buffer_line_count_width: buffer.line_count().to_string().len(),
+            relative_line: None,
         }
     }
 
+    /// Numbers lines by their distance from the specified (zero-based)
+    /// line, which is itself numbered absolutely.
+    pub fn relative_to(mut self, line: usize) -> LineNumbers {
+        self.relative_line = Some(line);
+        self
+    }
+
+    /// Sizes the numbers for at least the specified number of digits,
+    /// so that the gutter doesn't change width as small buffers grow.
+    pub fn with_minimum_width(mut self, digits: usize) -> LineNumbers {
+        self.buffer_line_count_width = self.buffer_line_count_width.max(digits);
+        self
+    }
+
     pub fn width(&self) -> usize {
         self.buffer_line_count_width + PADDING_WIDTH
     }
@@ -26,10 +42,17 @@ impl Iterator for LineNumbers {
 
     fn next(&mut self) -> Option<String> {
         self.current_number += 1;
+        let number = match self.relative_line {
+            Some(line) if line + 1 != self.current_number => {
+                (line + 1).max(self.current_number) - (line + 1).min(self.current_number)
+            },
+            _ => self.current_number,
+        };
+
         Some(
             format!(
                 " {:>width$} ",
-                self.current_number,
+                number,
                 width = self.buffer_line_count_width
             )
         )
@@ -52,6 +75,21 @@ mod tests {
         assert_eq!(line_numbers.width(), 5);
     }
 
+    #[test]
+    fn width_respects_minimum_width_until_exceeded_by_buffer_line_count() {
+        let mut buffer = Buffer::new();
+        for _ in 0..98 {
+            buffer.insert("\n");
+        }
+        let mut line_numbers = LineNumbers::new(&buffer, None).with_minimum_width(3);
+        assert_eq!(line_numbers.width(), 5);
+        assert_eq!(line_numbers.next().unwrap(), "   1 ");
+
+        buffer.insert("\n\n\n\n");
+        let line_numbers = LineNumbers::new(&buffer, None).with_minimum_width(2);
+        assert_eq!(line_numbers.width(), 5);
+    }
+
     #[test]
     fn line_numbers_without_offset_start_at_one() {
         let buffer = Buffer::new();
@@ -99,6 +137,18 @@ mod tests {
         assert_eq!(next_number, 2);
     }
 
+    #[test]
+    fn relative_line_numbers_count_distance_from_relative_line() {
+        let buffer = Buffer::new();
+        let line_numbers = LineNumbers::new(&buffer, Some(3)).relative_to(5);
+        let numbers: Vec<usize> = line_numbers
+            .take(5)
+            .map(|number| number.trim().parse().unwrap())
+            .collect();
+
+        assert_eq!(numbers, vec![2, 1, 6, 1, 2]);
+    }
+
     #[test]
     fn line_numbers_are_left_padded_based_on_buffer_line_count_width() {
         let mut buffer = Buffer::new();
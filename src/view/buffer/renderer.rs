@@ -3,7 +3,7 @@ use scribe::buffer::{Buffer, Position, Range};
 use scribe::util::LineIterator;
 use crate::view::buffer::{LexemeMapper, MappedLexeme, RenderState};
 use crate::view::buffer::line_numbers::*;
-use crate::view::{Colors, RENDER_CACHE_FREQUENCY, RGBColor, Style};
+use crate::view::{Colors, RENDER_CACHE_FREQUENCY, RGBColor, SpellChecker, Style};
 use crate::view::color::to_rgb_color;
 use crate::view::terminal::{Cell, Terminal, TerminalBuffer};
 use std::borrow::Cow;
@@ -22,19 +22,27 @@ use crate::errors::*;
 pub struct BufferRenderer<'a, 'p> {
     buffer: &'a Buffer,
     buffer_position: Position,
+    column_offset: usize,
     cursor_position: Option<Position>,
     gutter_width: usize,
+    guide_offsets: Vec<usize>,
     highlights: Option<&'a [Range]>,
     stylist: Highlighter<'a>,
     current_style: ThemeStyle,
     line_numbers: LineNumbers,
+    misspellings: Vec<(usize, usize)>,
+    newline_character: Option<String>,
     preferences: &'a Preferences,
     render_cache: &'a Rc<RefCell<HashMap<usize, RenderState>>>,
     screen_position: Position,
     scroll_offset: usize,
+    space_character: Option<String>,
+    spell_checker: Option<&'a SpellChecker>,
+    tab_character: Option<String>,
     terminal: &'a dyn Terminal,
     terminal_buffer: &'a mut TerminalBuffer<'p>,
     theme: &'a Theme,
+    trailing_whitespace_offset: Option<usize>,
 }
 
 impl<'a, 'p> BufferRenderer<'a, 'p> {
@@ -44,8 +52,28 @@ impl<'a, 'p> BufferRenderer<'a, 'p> {
                render_cache: &'a Rc<RefCell<HashMap<usize, RenderState>>>,
                terminal_buffer: &'a mut TerminalBuffer<'p>
     ) -> BufferRenderer<'a, 'p> {
-        let line_numbers = LineNumbers::new(&buffer, Some(scroll_offset));
+        let mut line_numbers = LineNumbers::new(&buffer, Some(scroll_offset))
+            .with_minimum_width(preferences.minimum_line_number_width());
+        if preferences.relative_line_numbers() {
+            line_numbers = line_numbers.relative_to(buffer.cursor.line);
+        }
         let gutter_width = line_numbers.width() + 1;
+        let guide_offsets = preferences.line_length_guides().into_iter()
+            .map(|offset| gutter_width + offset)
+            .collect();
+
+        // Characters used in place of whitespace, when it's made visible.
+        let show_whitespace = preferences.show_whitespace();
+        let whitespace_character = |enabled: bool, character: &str| {
+            if enabled { Some(character.to_string()) } else { None }
+        };
+        let space_character = whitespace_character(show_whitespace, preferences.space_character());
+        let tab_character = whitespace_character(
+            show_whitespace || preferences.visible_tabs(),
+            preferences.tab_character()
+        );
+        let newline_character =
+            whitespace_character(show_whitespace, preferences.newline_character());
 
         // Build an initial style to start with,
         // which we'll modify as we highlight tokens.
@@ -54,33 +82,80 @@ impl<'a, 'p> BufferRenderer<'a, 'p> {
 
         BufferRenderer{
             buffer,
+            column_offset: 0,
             cursor_position: None,
             gutter_width,
+            guide_offsets,
             highlights,
             stylist,
             current_style,
             line_numbers,
+            misspellings: Vec::new(),
+            newline_character,
             buffer_position: Position{ line: 0, offset: 0 },
             preferences,
             render_cache,
             screen_position: Position{ line: 0, offset: 0 },
             scroll_offset,
+            space_character,
+            spell_checker: None,
+            tab_character,
             terminal,
             terminal_buffer,
             theme,
+            trailing_whitespace_offset: None,
         }
     }
 
+    /// Skips the specified number of (tab-expanded) columns at the start of
+    /// each line, to scroll long lines horizontally when they aren't wrapped.
+    pub fn scrolled_to_column(mut self, column_offset: usize) -> Self {
+        self.column_offset = column_offset;
+        self
+    }
+
+    /// Underlines words missing from the spell checker's dictionary.
+    pub fn spell_checked_with(mut self, spell_checker: Option<&'a SpellChecker>) -> Self {
+        self.spell_checker = spell_checker;
+        self
+    }
+
     fn on_cursor_line(&self) -> bool {
         self.buffer_position.line == self.buffer.cursor.line
     }
 
-    fn print_rest_of_line(&mut self) {
+    fn print_rest_of_line(&mut self, line_break: bool) {
         let on_cursor_line = self.on_cursor_line();
-        let guide_offsets = self.length_guide_offsets();
+        let mut start_offset = self.screen_position.offset
+            .saturating_sub(self.column_offset)
+            .max(self.gutter_width);
+
+        // Mark highlighted line breaks with a cell of their own, so that
+        // highlights spanning blank lines (which don't have any content
+        // to style) remain visibly contiguous. Line breaks are also
+        // given a cell of their own when whitespace is being shown.
+        let line_break_character = self.newline_character.clone().filter(|_| line_break);
+        let highlight_style = self.highlight_style();
+        if highlight_style.is_some() || line_break_character.is_some() {
+            if start_offset < self.terminal.width() {
+                let (style, colors) = highlight_style.unwrap_or_else(|| {
+                    if on_cursor_line {
+                        (Style::Default, Colors::Focused)
+                    } else {
+                        (Style::Default, Colors::Default)
+                    }
+                });
+                self.print(Position{ line: self.screen_position.line, offset: start_offset },
+                           style,
+                           colors,
+                           line_break_character.unwrap_or_else(|| String::from(" ")));
+                start_offset += 1;
+            }
+        }
 
-        for offset in self.screen_position.offset..self.terminal.width() {
-            let colors = if on_cursor_line || guide_offsets.contains(&offset) {
+        for offset in start_offset..self.terminal.width() {
+            let guide = self.guide_offsets.contains(&(offset + self.column_offset));
+            let colors = if on_cursor_line || guide {
                 Colors::Focused
             } else {
                 Colors::Default
@@ -93,16 +168,23 @@ impl<'a, 'p> BufferRenderer<'a, 'p> {
         }
     }
 
-    fn length_guide_offsets(&self) -> Vec<usize> {
-        self.preferences.line_length_guides().into_iter()
-            .map(|offset| self.gutter_width + offset)
-            .collect()
+    /// Gives syntax-highlighted content that falls on a line length guide
+    /// the guide's background, so that it spans the entire viewport.
+    fn guide_colors(&self, colors: Colors) -> Colors {
+        match colors {
+            Colors::CustomForeground(color)
+                if self.guide_offsets.contains(&self.screen_position.offset) =>
+            {
+                Colors::CustomFocusedForeground(color)
+            }
+            _ => colors,
+        }
     }
 
     fn advance_to_next_line(&mut self) {
         if self.inside_visible_content() {
             self.set_cursor();
-            self.print_rest_of_line();
+            self.print_rest_of_line(true);
 
             // It's important to only increase this once we've entered the
             // visible area. Otherwise, we're moving the screen location even
@@ -123,43 +205,63 @@ impl<'a, 'p> BufferRenderer<'a, 'p> {
     // which will compensate for scrolling, tab expansion, etc.
     fn set_cursor(&mut self) {
         if self.inside_visible_content() && *self.buffer.cursor == self.buffer_position {
-            self.cursor_position = Some(self.screen_position);
+            self.cursor_position = self.scrolled_position(self.screen_position);
+        }
+    }
+
+    /// Translates a position to account for horizontal scrolling,
+    /// if it's still visible within the buffer content area.
+    fn scrolled_position(&self, position: Position) -> Option<Position> {
+        let offset = position.offset.checked_sub(self.column_offset)?;
+        if offset < self.gutter_width || offset >= self.terminal.width() {
+            return None;
         }
+
+        Some(Position { line: position.line, offset })
     }
 
     fn current_char_style(&self, token_color: RGBColor) -> (Style, Colors) {
-        let (style, colors) = match self.highlights {
-            Some(highlight_ranges) => {
-                for range in highlight_ranges {
-                    if range.includes(&self.buffer_position) {
-                        // We're inside of one of the highlighted areas.
-                        // Return early with highlight colors.
-                        if range.includes(&self.buffer.cursor) {
-                            return (Style::Bold, Colors::SelectMode)
-                        } else {
-                            return (Style::Inverted, Colors::Default)
-                        }
-                    }
-                }
+        // Highlighted areas take precedence over other styling considerations.
+        if let Some(highlight) = self.highlight_style() {
+            return highlight;
+        }
 
-                // We aren't inside one of the highlighted areas.
-                // Fall back to other styling considerations.
-                if self.on_cursor_line() {
-                    (Style::Default, Colors::CustomFocusedForeground(token_color))
-                } else {
-                    (Style::Default, Colors::CustomForeground(token_color))
-                }
-            }
-            None => {
-                if self.on_cursor_line() {
-                    (Style::Default, Colors::CustomFocusedForeground(token_color))
-                } else {
-                    (Style::Default, Colors::CustomForeground(token_color))
-                }
-            },
-        };
+        if self.in_trailing_whitespace() {
+            return (Style::Default, Colors::Warning);
+        }
 
-        (style, colors)
+        let style = if self.misspelled() { Style::Underlined } else { Style::Default };
+        if self.on_cursor_line() {
+            (style, Colors::CustomFocusedForeground(token_color))
+        } else {
+            (style, Colors::CustomForeground(token_color))
+        }
+    }
+
+    /// The style for the current buffer position, if it's inside of one of
+    /// the highlighted areas. Highlights are matched using buffer positions,
+    /// so those starting above the visible area are styled correctly.
+    fn highlight_style(&self) -> Option<(Style, Colors)> {
+        let range = self.highlights?
+            .iter()
+            .find(|range| range.includes(&self.buffer_position))?;
+
+        if range.includes(&self.buffer.cursor) {
+            Some((Style::Bold, Colors::SelectMode))
+        } else {
+            Some((Style::Inverted, Colors::Default))
+        }
+    }
+
+    fn in_trailing_whitespace(&self) -> bool {
+        self.trailing_whitespace_offset
+            .map(|offset| self.buffer_position.offset >= offset)
+            .unwrap_or(false)
+    }
+
+    fn misspelled(&self) -> bool {
+        let offset = self.buffer_position.offset;
+        self.misspellings.iter().any(|&(start, end)| offset >= start && offset < end)
     }
 
     pub fn print_lexeme<L: Into<Cow<'p, str>>>(&mut self, lexeme: L) {
@@ -167,6 +269,12 @@ impl<'a, 'p> BufferRenderer<'a, 'p> {
             // Ignore newline characters.
             if character == "\n" { continue; }
 
+            // Spaces are replaced with a visible character, if configured.
+            let content = match (character, &self.space_character) {
+                (" ", Some(space_character)) => space_character.clone(),
+                _ => character.to_string(),
+            };
+
             self.set_cursor();
 
             // Determine the style we'll use to print.
@@ -176,7 +284,8 @@ impl<'a, 'p> BufferRenderer<'a, 'p> {
             if self.preferences.line_wrapping() && self.screen_position.offset == self.terminal.width() {
                 self.screen_position.line += 1;
                 self.screen_position.offset = self.gutter_width;
-                self.print(self.screen_position, style, color, character.to_string());
+                let colors = self.guide_colors(color);
+                self.print_content(self.screen_position, style, colors, content);
                 self.screen_position.offset += 1;
                 self.buffer_position.offset += 1;
             } else if character == "\t" {
@@ -191,14 +300,24 @@ impl<'a, 'p> BufferRenderer<'a, 'p> {
                     screen_tab_stop = self.terminal.width();
                 }
 
-                // Print the sequence of spaces and move the offset accordingly.
-                for _ in self.screen_position.offset..screen_tab_stop {
-                    self.print(self.screen_position, style, color, " ");
+                // Print the sequence of spaces and move the offset accordingly,
+                // marking the start of the tab to distinguish it from spaces.
+                let tab_start = self.screen_position.offset;
+                for _ in tab_start..screen_tab_stop {
+                    let content = match self.tab_character {
+                        Some(ref tab_character) if self.screen_position.offset == tab_start => {
+                            tab_character.clone()
+                        }
+                        _ => String::from(" "),
+                    };
+                    let colors = self.guide_colors(color);
+                    self.print_content(self.screen_position, style, colors, content);
                     self.screen_position.offset += 1;
                 }
                 self.buffer_position.offset += 1;
             } else {
-                self.print(self.screen_position, style, color, character.to_string());
+                let colors = self.guide_colors(color);
+                self.print_content(self.screen_position, style, colors, content);
                 self.screen_position.offset += 1;
                 self.buffer_position.offset += 1;
             }
@@ -234,6 +353,11 @@ impl<'a, 'p> BufferRenderer<'a, 'p> {
             .cached_render_state()
             .unwrap_or((0, RenderState::new(&highlighter, syntax_reference)));
         let (focused_style, blurred_style) = self.mapper_styles();
+        // Mapped lexemes don't correspond to the buffer's
+        // content, so there's no whitespace to highlight.
+        let highlight_trailing_whitespace =
+            self.preferences.highlight_trailing_whitespace() && lexeme_mapper.is_none();
+        let spell_checker = self.spell_checker.filter(|_| lexeme_mapper.is_none());
 
         'print: for (line_no, line) in lines {
             // Skip past lines that precede the cached render state.
@@ -242,6 +366,20 @@ impl<'a, 'p> BufferRenderer<'a, 'p> {
                     self.render_cache.borrow_mut().insert(line_no, state.clone());
                 }
 
+                if highlight_trailing_whitespace {
+                    self.trailing_whitespace_offset = trailing_whitespace_offset(line);
+                }
+
+                // Only visible lines are checked, so that the cost
+                // of spell checking doesn't grow with the buffer.
+                if let Some(spell_checker) = spell_checker {
+                    self.misspellings = if self.before_visible_content() {
+                        Vec::new()
+                    } else {
+                        spell_checker.misspellings(line)
+                    };
+                }
+
                 let events = state.parse.parse_line(line, syntax_set);
                 let styled_lexemes = HighlightIterator::new(
                     &mut state.highlight,
@@ -292,7 +430,7 @@ impl<'a, 'p> BufferRenderer<'a, 'p> {
         self.set_cursor();
 
         // One last call to this for the last line.
-        self.print_rest_of_line();
+        self.print_rest_of_line(false);
 
         // Return the cursor location. If it occurred somewhere in the buffer, it
         // will be shown at the right location. If not, it will be None and will
@@ -370,6 +508,15 @@ impl<'a, 'p> BufferRenderer<'a, 'p> {
             .map(|(k, v)| (*k, v.clone()))
     }
 
+    /// Prints buffer content, skipping any that's been scrolled past horizontally.
+    fn print_content<C>(&mut self, position: Position, style: Style, colors: Colors, content: C)
+        where C: Into<Cow<'p, str>>
+    {
+        if let Some(position) = self.scrolled_position(position) {
+            self.print(position, style, colors, content);
+        }
+    }
+
     fn print<C>(&mut self, position: Position, style: Style, colors: Colors, content: C)
         where C: Into<Cow<'p, str>>
     {
@@ -380,6 +527,18 @@ impl<'a, 'p> BufferRenderer<'a, 'p> {
     }
 }
 
+/// The offset at which a line's trailing whitespace begins, if it has any.
+fn trailing_whitespace_offset(line: &str) -> Option<usize> {
+    let content = line.trim_end_matches(|c| c == '\n' || c == '\r');
+    let trimmed_content = content.trim_end();
+
+    if trimmed_content.len() < content.len() {
+        Some(trimmed_content.graphemes(true).count())
+    } else {
+        None
+    }
+}
+
 fn has_trailing_newline(line: &str) -> bool {
     line.chars()
         .last()
@@ -391,7 +550,7 @@ fn has_trailing_newline(line: &str) -> bool {
 mod tests {
     use crate::models::application::Preferences;
     use scribe::{Buffer, Workspace};
-    use scribe::buffer::Position;
+    use scribe::buffer::{Position, Range};
     use scribe::util::LineIterator;
     use std::cell::RefCell;
     use std::collections::HashMap;
@@ -399,6 +558,7 @@ mod tests {
     use std::rc::Rc;
     use super::{BufferRenderer, LexemeMapper, MappedLexeme};
     use syntect::highlighting::ThemeSet;
+    use crate::view::{Colors, Style};
     use crate::view::terminal::*;
     use crate::yaml::yaml::YamlLoader;
 
@@ -765,4 +925,280 @@ mod tests {
             &terminal_buffer.content()[0..expected_content.len()],
             expected_content);
     }
+
+    #[test]
+    fn render_highlights_line_breaks_inside_highlighted_areas() {
+        let mut workspace = Workspace::new(Path::new(".")).unwrap();
+        let mut buffer = Buffer::new();
+        buffer.insert("amp\n\neditor\nbuffer");
+        workspace.add_buffer(buffer);
+
+        let data = workspace.current_buffer().unwrap().data();
+        let lines = LineIterator::new(&data);
+        let terminal = build_terminal().unwrap();
+        let mut terminal_buffer = TerminalBuffer::new(terminal.width(), terminal.height());
+        let theme_set = ThemeSet::load_defaults();
+        let preferences = Preferences::new(None);
+        let highlights = [Range::new(
+            Position{ line: 0, offset: 1 },
+            Position{ line: 2, offset: 3 },
+        )];
+
+        // Scroll past the start of the highlight, to
+        // ensure that it's still applied when partly off-screen.
+        BufferRenderer::new(
+            workspace.current_buffer().unwrap(),
+            Some(&highlights),
+            1,
+            &**terminal,
+            &theme_set.themes["base16-ocean.dark"],
+            &preferences,
+            &Rc::new(RefCell::new(HashMap::new())),
+            &mut terminal_buffer
+        ).render(lines, None).unwrap();
+
+        let styles: Vec<Style> = terminal_buffer.iter()
+            .filter(|&(position, _)| position.line < 2 && position.offset >= 4 && position.offset < 8)
+            .map(|(_, cell)| cell.style)
+            .collect();
+
+        // The blank line's break, "edi", and nothing beyond it.
+        assert_eq!(styles, vec![
+            Style::Inverted, Style::Default, Style::Default, Style::Default,
+            Style::Inverted, Style::Inverted, Style::Inverted, Style::Default,
+        ]);
+    }
+
+    #[test]
+    fn render_styles_the_highlight_containing_the_cursor_differently() {
+        let mut workspace = Workspace::new(Path::new(".")).unwrap();
+        let mut buffer = Buffer::new();
+        buffer.insert("amp editor amp");
+        buffer.cursor.move_to(Position{ line: 0, offset: 11 });
+        workspace.add_buffer(buffer);
+
+        let data = workspace.current_buffer().unwrap().data();
+        let lines = LineIterator::new(&data);
+        let terminal = build_terminal().unwrap();
+        let mut terminal_buffer = TerminalBuffer::new(terminal.width(), terminal.height());
+        let theme_set = ThemeSet::load_defaults();
+        let preferences = Preferences::new(None);
+        let highlights = [
+            Range::new(Position{ line: 0, offset: 0 }, Position{ line: 0, offset: 3 }),
+            Range::new(Position{ line: 0, offset: 11 }, Position{ line: 0, offset: 14 }),
+        ];
+
+        BufferRenderer::new(
+            workspace.current_buffer().unwrap(),
+            Some(&highlights),
+            0,
+            &**terminal,
+            &theme_set.themes["base16-ocean.dark"],
+            &preferences,
+            &Rc::new(RefCell::new(HashMap::new())),
+            &mut terminal_buffer
+        ).render(lines, None).unwrap();
+
+        let styles: Vec<(String, Style)> = terminal_buffer.iter()
+            .filter(|&(position, cell)| {
+                // Skip past the (emboldened) cursor line number.
+                position.line == 0 && position.offset >= 3 && cell.style != Style::Default
+            })
+            .map(|(_, cell)| (cell.content.to_string(), cell.style))
+            .collect();
+
+        assert_eq!(styles, vec![
+            (String::from("a"), Style::Inverted),
+            (String::from("m"), Style::Inverted),
+            (String::from("p"), Style::Inverted),
+            (String::from("a"), Style::Bold),
+            (String::from("m"), Style::Bold),
+            (String::from("p"), Style::Bold),
+        ]);
+    }
+
+    #[test]
+    fn render_highlights_trailing_whitespace_without_overriding_highlights() {
+        let mut workspace = Workspace::new(Path::new(".")).unwrap();
+        let mut buffer = Buffer::new();
+        buffer.insert("a b  \n  \ncd");
+        workspace.add_buffer(buffer);
+
+        let data = workspace.current_buffer().unwrap().data();
+        let lines = LineIterator::new(&data);
+        let terminal = build_terminal().unwrap();
+        let mut terminal_buffer = TerminalBuffer::new(terminal.width(), terminal.height());
+        let theme_set = ThemeSet::load_defaults();
+        let data = YamlLoader::load_from_str("highlight_trailing_whitespace: true")
+            .unwrap().into_iter().nth(0).unwrap();
+        let preferences = Preferences::new(Some(data));
+        let highlights = [Range::new(
+            Position{ line: 0, offset: 4 },
+            Position{ line: 1, offset: 0 },
+        )];
+
+        BufferRenderer::new(
+            workspace.current_buffer().unwrap(),
+            Some(&highlights),
+            0,
+            &**terminal,
+            &theme_set.themes["base16-ocean.dark"],
+            &preferences,
+            &Rc::new(RefCell::new(HashMap::new())),
+            &mut terminal_buffer
+        ).render(lines, None).unwrap();
+
+        let colors: Vec<(usize, usize)> = terminal_buffer.iter()
+            .filter(|&(_, cell)| cell.colors == Colors::Warning)
+            .map(|(position, _)| (position.line, position.offset))
+            .collect();
+
+        // The highlighted space on the first line isn't marked,
+        // nor is whitespace between words, or the last line.
+        assert_eq!(colors, vec![(0, 7), (1, 4), (1, 5)]);
+    }
+
+    #[test]
+    fn render_marks_tabs_when_visible_tabs_are_enabled() {
+        let mut workspace = Workspace::new(Path::new(".")).unwrap();
+        let mut buffer = Buffer::new();
+        buffer.insert("\tx");
+        workspace.add_buffer(buffer);
+
+        let data = workspace.current_buffer().unwrap().data();
+        let lines = LineIterator::new(&data);
+        let terminal = build_terminal().unwrap();
+        let mut terminal_buffer = TerminalBuffer::new(terminal.width(), terminal.height());
+        let theme_set = ThemeSet::load_defaults();
+        let data = YamlLoader::load_from_str("tab_width: 2\nvisible_tabs: true")
+            .unwrap().into_iter().nth(0).unwrap();
+        let preferences = Preferences::new(Some(data));
+
+        BufferRenderer::new(
+            workspace.current_buffer().unwrap(),
+            None,
+            0,
+            &**terminal,
+            &theme_set.themes["base16-ocean.dark"],
+            &preferences,
+            &Rc::new(RefCell::new(HashMap::new())),
+            &mut terminal_buffer
+        ).render(lines, None).unwrap();
+
+        let expected_content = " 1  → x";
+        assert!(terminal_buffer.content().starts_with(expected_content));
+    }
+
+
+    #[test]
+    fn render_draws_line_length_guides_behind_content() {
+        let mut workspace = Workspace::new(Path::new(".")).unwrap();
+        let mut buffer = Buffer::new();
+        buffer.insert("\namp");
+        workspace.add_buffer(buffer);
+
+        let data = workspace.current_buffer().unwrap().data();
+        let lines = LineIterator::new(&data);
+        let terminal = build_terminal().unwrap();
+        let mut terminal_buffer = TerminalBuffer::new(terminal.width(), terminal.height());
+        let theme_set = ThemeSet::load_defaults();
+        let data = YamlLoader::load_from_str("line_length_guide: [1, 4]")
+            .unwrap().into_iter().nth(0).unwrap();
+        let preferences = Preferences::new(Some(data));
+
+        BufferRenderer::new(
+            workspace.current_buffer().unwrap(),
+            None,
+            0,
+            &**terminal,
+            &theme_set.themes["base16-ocean.dark"],
+            &preferences,
+            &Rc::new(RefCell::new(HashMap::new())),
+            &mut terminal_buffer
+        ).render(lines, None).unwrap();
+
+        // Skip the first (cursor) line, which is entirely focused.
+        let focused_offsets: Vec<usize> = terminal_buffer.iter()
+            .filter(|&(position, cell)| {
+                position.line == 1 && position.offset >= 4 && match cell.colors {
+                    Colors::Focused | Colors::CustomFocusedForeground(_) => true,
+                    _ => false,
+                }
+            })
+            .map(|(position, _)| position.offset)
+            .collect();
+
+        // The guide crosses the "m" in "amp", and the blank space after it.
+        assert_eq!(focused_offsets, vec![5, 8]);
+    }
+
+
+    #[test]
+    fn render_shows_whitespace_using_configured_characters() {
+        let mut workspace = Workspace::new(Path::new(".")).unwrap();
+        let mut buffer = Buffer::new();
+        buffer.insert("a b\n\tc");
+        workspace.add_buffer(buffer);
+
+        let data = workspace.current_buffer().unwrap().data();
+        let lines = LineIterator::new(&data);
+        let terminal = build_terminal().unwrap();
+        let mut terminal_buffer = TerminalBuffer::new(terminal.width(), terminal.height());
+        let theme_set = ThemeSet::load_defaults();
+        let data = YamlLoader::load_from_str(
+            "tab_width: 2\nshow_whitespace: true\nwhitespace_characters:\n  newline: $"
+        ).unwrap().into_iter().nth(0).unwrap();
+        let preferences = Preferences::new(Some(data));
+
+        let cursor_position = BufferRenderer::new(
+            workspace.current_buffer().unwrap(),
+            None,
+            0,
+            &**terminal,
+            &theme_set.themes["base16-ocean.dark"],
+            &preferences,
+            &Rc::new(RefCell::new(HashMap::new())),
+            &mut terminal_buffer
+        ).render(lines, None).unwrap();
+
+        // Visible whitespace doesn't affect the cursor's position.
+        assert_eq!(cursor_position, Some(Position{ line: 0, offset: 4 }));
+        let expected_content = " 1  a·b$  \n 2  → c   ";
+        assert!(terminal_buffer.content().starts_with(expected_content));
+    }
+
+
+    #[test]
+    fn render_skips_columns_scrolled_past_horizontally() {
+        let mut workspace = Workspace::new(Path::new(".")).unwrap();
+        let mut buffer = Buffer::new();
+        buffer.insert("amp editor\nab\n");
+        buffer.cursor.move_to(Position{ line: 0, offset: 8 });
+        workspace.add_buffer(buffer);
+
+        let data = workspace.current_buffer().unwrap().data();
+        let lines = LineIterator::new(&data);
+        let terminal = build_terminal().unwrap();
+        let mut terminal_buffer = TerminalBuffer::new(terminal.width(), terminal.height());
+        let theme_set = ThemeSet::load_defaults();
+        let data = YamlLoader::load_from_str("line_wrapping: false")
+            .unwrap().into_iter().nth(0).unwrap();
+        let preferences = Preferences::new(Some(data));
+
+        let cursor_position = BufferRenderer::new(
+            workspace.current_buffer().unwrap(),
+            None,
+            0,
+            &**terminal,
+            &theme_set.themes["base16-ocean.dark"],
+            &preferences,
+            &Rc::new(RefCell::new(HashMap::new())),
+            &mut terminal_buffer
+        ).scrolled_to_column(4).render(lines, None).unwrap();
+
+        assert_eq!(cursor_position, Some(Position{ line: 0, offset: 8 }));
+        let expected_content = " 1  editor\n 2        \n 3        ";
+        assert!(terminal_buffer.content().starts_with(expected_content));
+    }
+
 }
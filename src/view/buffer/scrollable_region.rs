@@ -2,36 +2,54 @@ use std::sync::Arc;
 use scribe::buffer::Buffer;
 use unicode_segmentation::UnicodeSegmentation;
 use crate::view::buffer::LineNumbers;
+use crate::view::Area;
 use crate::view::terminal::Terminal;
 
+/// Offset changes of up to this many lines are never animated.
+const ANIMATION_THRESHOLD: usize = 3;
+
 /// Abstract representation of a fixed-height section of the screen.
 /// Used to determine visible ranges of lines based on previous state,
 /// explicit line focus, and common scrolling implementation behaviours.
 pub struct ScrollableRegion {
     terminal: Arc<Box<dyn Terminal + Sync + Send + 'static>>,
+    area: Option<Area>,
     line_offset: usize,
+    displayed_line_offset: usize,
+    column_offset: usize,
+    minimum_line_number_width: usize,
 }
 
 impl ScrollableRegion {
     pub fn new(terminal: Arc<Box<dyn Terminal + Sync + Send + 'static>>) -> ScrollableRegion {
         ScrollableRegion {
             terminal,
+            area: None,
             line_offset: 0,
+            displayed_line_offset: 0,
+            column_offset: 0,
+            minimum_line_number_width: 0,
         }
     }
 
     /// If necessary, moves the line offset such that the specified line is
     /// visible, using previous state to determine whether said line is at
-    /// the top or bottom of the new visible range.
-    pub fn scroll_into_view(&mut self, buffer: &Buffer) {
-        if buffer.cursor.line <= self.line_offset {
-            // Cursor is above visible range.
-            self.line_offset = buffer.cursor.line;
+    /// the top or bottom of the new visible range. The margin is the number
+    /// of lines kept visible above and below the cursor (where they exist),
+    /// limited to less than half of the region's height.
+    pub fn scroll_into_view(&mut self, buffer: &Buffer, margin: usize) {
+        let margin = margin.min(self.height().saturating_sub(1) / 2);
+
+        if buffer.cursor.line.saturating_sub(margin) <= self.line_offset {
+            // Cursor (or its margin) is above visible range.
+            self.line_offset = buffer.cursor.line.saturating_sub(margin);
         } else {
-            // Calculate and apply the absolute line
-            // offset based on the cursor location.
-            let starting_line = (buffer.cursor.line).saturating_sub(
-                self.preceding_line_count(&buffer, self.height())
+            // Calculate and apply the absolute line offset based on
+            // the cursor location, shrinking the margin near the end.
+            let last_line = buffer.line_count().saturating_sub(1).max(buffer.cursor.line);
+            let bottom_line = (buffer.cursor.line + margin).min(last_line);
+            let starting_line = bottom_line.saturating_sub(
+                self.preceding_line_count(&buffer, bottom_line, self.height())
             );
 
             if starting_line > self.line_offset {
@@ -45,7 +63,23 @@ impl ScrollableRegion {
         let limit = (self.height() as f32 / 2.0).ceil() as usize;
 
         self.line_offset = buffer.cursor.line.saturating_sub(
-            self.preceding_line_count(&buffer, limit)
+            self.preceding_line_count(&buffer, buffer.cursor.line, limit)
+        );
+    }
+
+    /// Moves the line offset such that the specified line is at the top of the
+    /// region, without scrolling more than half of its height beyond the end
+    /// of the buffer.
+    pub fn scroll_to_top(&mut self, buffer: &Buffer) {
+        let max_offset = buffer.line_count().saturating_sub(self.height() / 2);
+
+        self.line_offset = buffer.cursor.line.min(max_offset);
+    }
+
+    /// Moves the line offset such that the specified line is at the bottom of the region.
+    pub fn scroll_to_bottom(&mut self, buffer: &Buffer) {
+        self.line_offset = buffer.cursor.line.saturating_sub(
+            self.preceding_line_count(&buffer, buffer.cursor.line, self.height())
         );
     }
 
@@ -55,6 +89,67 @@ impl ScrollableRegion {
         self.line_offset
     }
 
+    /// If necessary, moves the column offset such that the specified
+    /// (tab-expanded) column is visible, keeping the margin visible on
+    /// either side of it. Used when lines aren't wrapped.
+    pub fn scroll_column_into_view(&mut self, buffer: &Buffer, column: usize, margin: usize) {
+        let gutter_width = self.gutter_width(buffer);
+        let width = self.width().saturating_sub(gutter_width);
+        if width == 0 {
+            return;
+        }
+        let margin = margin.min(width.saturating_sub(1) / 2);
+
+        if column < self.column_offset + margin {
+            self.column_offset = column.saturating_sub(margin);
+        } else if column + margin >= self.column_offset + width {
+            self.column_offset = column + margin + 1 - width;
+        }
+    }
+
+    /// The number of (tab-expanded) columns the region
+    /// has scrolled over, when lines aren't wrapped.
+    pub fn column_offset(&self) -> usize {
+        self.column_offset
+    }
+
+    pub fn reset_column_offset(&mut self) {
+        self.column_offset = 0;
+    }
+
+    /// The line offset to render. When animating, this trails behind the
+    /// actual offset after a large jump, until it's stepped into place.
+    pub fn displayed_line_offset(&mut self, animate: bool) -> usize {
+        let distance = if self.displayed_line_offset > self.line_offset {
+            self.displayed_line_offset - self.line_offset
+        } else {
+            self.line_offset - self.displayed_line_offset
+        };
+        if !animate || distance <= ANIMATION_THRESHOLD {
+            self.displayed_line_offset = self.line_offset;
+        }
+
+        self.displayed_line_offset
+    }
+
+    pub fn animating(&self) -> bool {
+        self.displayed_line_offset != self.line_offset
+    }
+
+    /// Moves the displayed line offset halfway to the actual offset,
+    /// easing into place over a few frames.
+    pub fn step_animation(&mut self) {
+        if self.displayed_line_offset > self.line_offset {
+            self.displayed_line_offset -= (self.displayed_line_offset - self.line_offset + 1) / 2;
+        } else {
+            self.displayed_line_offset += (self.line_offset - self.displayed_line_offset + 1) / 2;
+        }
+    }
+
+    pub fn finish_animation(&mut self) {
+        self.displayed_line_offset = self.line_offset;
+    }
+
     pub fn scroll_up(&mut self, amount: usize) {
         self.line_offset = match self.line_offset.checked_sub(amount) {
             Some(amount) => amount,
@@ -66,23 +161,44 @@ impl ScrollableRegion {
         self.line_offset += amount;
     }
 
-    /// Scrollable regions occupy one line short of the full
-    /// terminal height, which is reserved for the status line.
+    /// Restricts the region to an area of the terminal (i.e. a split
+    /// pane), or restores it to the full terminal when given `None`.
+    pub fn set_area(&mut self, area: Option<Area>) {
+        self.area = area;
+    }
+
+    /// Matches the minimum width of the line numbers rendered alongside
+    /// the region, which affects the width available to its content.
+    pub fn set_minimum_line_number_width(&mut self, digits: usize) {
+        self.minimum_line_number_width = digits;
+    }
+
+    // The buffer renderer adds a single-column margin
+    // to the right-hand side of the line number columns.
+    fn gutter_width(&self, buffer: &Buffer) -> usize {
+        LineNumbers::new(&buffer, None)
+            .with_minimum_width(self.minimum_line_number_width)
+            .width() + 1
+    }
+
+    fn width(&self) -> usize {
+        self.area.map(|area| area.width).unwrap_or_else(|| self.terminal.width())
+    }
+
+    /// Scrollable regions occupy one line short of the full terminal (or
+    /// pane) height, which is reserved for the status line (or a divider).
     fn height(&self) -> usize {
-        self.terminal.height() - 1
+        self.area.map(|area| area.height).unwrap_or_else(|| self.terminal.height()) - 1
     }
 
-    /// Assuming that the buffer cursor is at the bottom of the screen,
+    /// Assuming that the specified line is at the bottom of the screen,
     /// counts the number of preceding lines that can be fit above it
     /// on-screen, taking line wrapping into consideration.
-    fn preceding_line_count(&self, buffer: &Buffer, limit: usize) -> usize {
+    fn preceding_line_count(&self, buffer: &Buffer, line: usize, limit: usize) -> usize {
         let mut preceding_line_count = 0;
+        let gutter_width = self.gutter_width(buffer);
 
-        // The buffer renderer adds a single-column margin
-        // to the right-hand side of the line number columns.
-        let gutter_width = LineNumbers::new(&buffer, None).width() + 1;
-
-        let end = buffer.cursor.line + 1;
+        let end = line + 1;
         let start = end.saturating_sub(limit);
         let line_count = end - start;
 
@@ -93,7 +209,7 @@ impl ScrollableRegion {
             .take(line_count)
             .map(|line| {
                 let grapheme_count = line.graphemes(true).count().max(1) as f32;
-                let buffer_content_width = (self.terminal.width() - gutter_width) as f32;
+                let buffer_content_width = self.width().saturating_sub(gutter_width) as f32;
                 let wrapped_line_count = grapheme_count / buffer_content_width;
 
                 wrapped_line_count.ceil() as usize
@@ -130,6 +246,75 @@ mod tests {
     use crate::view::terminal::*;
     use scribe::buffer::{Buffer, Position};
 
+    #[test]
+    fn scroll_column_into_view_keeps_the_margin_visible_on_either_side_of_the_column() {
+        let terminal = build_terminal().unwrap();
+        let mut buffer = Buffer::new();
+        let mut region = ScrollableRegion::new(terminal);
+        buffer.insert("amp");
+
+        // The test terminal leaves six columns alongside the line numbers.
+        region.scroll_column_into_view(&buffer, 4, 1);
+        assert_eq!(region.column_offset(), 0);
+        region.scroll_column_into_view(&buffer, 5, 1);
+        assert_eq!(region.column_offset(), 1);
+        region.scroll_column_into_view(&buffer, 20, 1);
+        assert_eq!(region.column_offset(), 16);
+
+        region.scroll_column_into_view(&buffer, 15, 1);
+        assert_eq!(region.column_offset(), 14);
+        region.scroll_column_into_view(&buffer, 0, 1);
+        assert_eq!(region.column_offset(), 0);
+    }
+
+    #[test]
+    fn displayed_line_offset_eases_into_place_after_large_jumps() {
+        let terminal = build_terminal().unwrap();
+        let mut region = ScrollableRegion::new(terminal);
+        region.scroll_down(2);
+        assert_eq!(region.displayed_line_offset(true), 2);
+
+        region.scroll_down(20);
+        assert_eq!(region.displayed_line_offset(true), 2);
+        assert!(region.animating());
+
+        let mut offsets = Vec::new();
+        while region.animating() {
+            region.step_animation();
+            offsets.push(region.displayed_line_offset(true));
+        }
+        assert_eq!(offsets, vec![12, 17, 22]);
+
+        region.scroll_up(20);
+        assert_eq!(region.displayed_line_offset(false), 2);
+        assert!(!region.animating());
+    }
+
+    #[test]
+    fn scroll_into_view_keeps_the_margin_visible_above_and_below_the_cursor() {
+        let terminal = build_terminal().unwrap();
+        let mut buffer = Buffer::new();
+        let mut region = ScrollableRegion::new(terminal);
+        for _ in 0..30 {
+            buffer.insert("\n");
+        }
+
+        // Scroll early to keep two lines visible below the cursor.
+        buffer.cursor.move_to(Position{ line: 7, offset: 0 });
+        region.scroll_into_view(&buffer, 2);
+        assert_eq!(region.line_offset(), 1);
+
+        // Likewise for the lines above it.
+        buffer.cursor.move_to(Position{ line: 2, offset: 0 });
+        region.scroll_into_view(&buffer, 2);
+        assert_eq!(region.line_offset(), 0);
+
+        // The margin shrinks at the end of the buffer.
+        buffer.cursor.move_to(Position{ line: 30, offset: 0 });
+        region.scroll_into_view(&buffer, 2);
+        assert_eq!(region.line_offset(), 22);
+    }
+
     #[test]
     fn scroll_into_view_correctly_handles_additonal_rendered_trailing_newline() {
         let terminal = build_terminal().unwrap();
@@ -137,7 +322,7 @@ mod tests {
         let mut region = ScrollableRegion::new(terminal);
         buffer.insert("\n\n");
         buffer.cursor.move_to(Position{ line: 2, offset: 0 });
-        region.scroll_into_view(&buffer);
+        region.scroll_into_view(&buffer, 0);
         assert_eq!(region.line_offset(), 0);
     }
 
@@ -150,7 +335,7 @@ mod tests {
             buffer.insert("\n");
         }
         buffer.cursor.move_to(Position{ line: 10, offset: 0 });
-        region.scroll_into_view(&buffer);
+        region.scroll_into_view(&buffer, 0);
         assert_eq!(region.line_offset(), 2);
     }
 
@@ -163,7 +348,7 @@ mod tests {
             buffer.insert("word \n");
         }
         buffer.cursor.move_to(Position{ line: 9, offset: 0 });
-        region.scroll_into_view(&buffer);
+        region.scroll_into_view(&buffer, 0);
         assert_eq!(region.line_offset(), 1);
     }
 
@@ -177,7 +362,7 @@ mod tests {
             buffer.insert("\n");
         }
         buffer.cursor.move_to(Position{ line: 5, offset: 0 });
-        region.scroll_into_view(&buffer);
+        region.scroll_into_view(&buffer, 0);
         assert_eq!(region.line_offset(), 5);
     }
 
@@ -190,7 +375,7 @@ mod tests {
             buffer.insert("\n");
         }
         buffer.cursor.move_to(Position{ line: 9, offset: 0 });
-        region.scroll_into_view(&buffer);
+        region.scroll_into_view(&buffer, 0);
         assert_eq!(region.line_offset(), 1);
     }
 
@@ -209,7 +394,7 @@ mod tests {
         }
 
         buffer.cursor.move_to(Position{ line: 5, offset: 0 });
-        region.scroll_into_view(&buffer);
+        region.scroll_into_view(&buffer, 0);
         assert_eq!(region.line_offset(), 1);
     }
 
@@ -227,7 +412,7 @@ mod tests {
             buffer.insert("       \n");
         }
         buffer.cursor.move_to(Position{ line: 5, offset: 0 });
-        region.scroll_into_view(&buffer);
+        region.scroll_into_view(&buffer, 0);
         assert_eq!(region.line_offset(), 2);
     }
 
@@ -286,6 +471,50 @@ mod tests {
         assert_eq!(region.line_offset(), 1);
     }
 
+    #[test]
+    fn scroll_to_top_moves_cursor_line_to_top_of_region() {
+        let terminal = build_terminal().unwrap();
+        let mut buffer = Buffer::new();
+        for _ in 0..20 {
+            buffer.insert("\n");
+        }
+        buffer.cursor.move_to(Position{ line: 5, offset: 0 });
+        let mut region = ScrollableRegion::new(terminal);
+        region.scroll_to_top(&buffer);
+        assert_eq!(region.line_offset(), 5);
+    }
+
+    #[test]
+    fn scroll_to_top_limits_scrolling_beyond_end_of_buffer() {
+        let terminal = build_terminal().unwrap();
+        let mut buffer = Buffer::new();
+        for _ in 0..20 {
+            buffer.insert("\n");
+        }
+        buffer.cursor.move_to(Position{ line: 20, offset: 0 });
+        let mut region = ScrollableRegion::new(terminal);
+        region.scroll_to_top(&buffer);
+        assert_eq!(region.line_offset(), 17);
+    }
+
+    #[test]
+    fn scroll_to_bottom_moves_cursor_line_to_bottom_of_region() {
+        let terminal = build_terminal().unwrap();
+        let mut buffer = Buffer::new();
+        for _ in 0..10 {
+            buffer.insert("word \n");
+        }
+        buffer.cursor.move_to(Position{ line: 9, offset: 0 });
+        let mut region = ScrollableRegion::new(terminal);
+        region.scroll_to_bottom(&buffer);
+        assert_eq!(region.line_offset(), 1);
+
+        // Lines near the start of the buffer can't reach the bottom.
+        buffer.cursor.move_to(Position{ line: 3, offset: 0 });
+        region.scroll_to_bottom(&buffer);
+        assert_eq!(region.line_offset(), 0);
+    }
+
     #[test]
     fn scroll_down_increases_line_offset_by_amount() {
         let terminal = build_terminal().unwrap();
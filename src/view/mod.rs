@@ -4,6 +4,8 @@ mod buffer;
 mod data;
 mod event_listener;
 mod presenter;
+mod spell_checker;
+mod split;
 mod style;
 mod theme_loader;
 
@@ -13,31 +15,41 @@ pub use self::buffer::{LexemeMapper, MappedLexeme};
 pub use self::style::Style;
 pub use self::color::{Colors, RGBColor};
 pub use self::presenter::Presenter;
+pub use self::spell_checker::SpellChecker;
+pub use self::split::{Area, Pane, Split, SplitOrientation};
 pub use self::terminal::*;
 
 use crate::errors::*;
 use crate::input::Key;
 use crate::models::application::{Event, Preferences};
+use crate::util;
 use self::buffer::{RenderCache, RenderState};
-use self::buffer::ScrollableRegion;
+use self::buffer::{LineNumbers, ScrollableRegion};
 use self::event_listener::EventListener;
+use scribe::Workspace;
 use scribe::buffer::Buffer;
 use std::cmp;
 use std::collections::HashMap;
+use std::mem;
 use std::rc::Rc;
 use std::cell::RefCell;
 use std::ops::Drop;
 use std::sync::mpsc::{self, Sender, SyncSender};
 use std::sync::Arc;
+use std::time::Duration;
 use self::theme_loader::ThemeLoader;
 use syntect::highlighting::ThemeSet;
+use unicode_segmentation::UnicodeSegmentation;
 
 const RENDER_CACHE_FREQUENCY: usize = 100;
+const SCROLL_ANIMATION_FRAME_INTERVAL: Duration = Duration::from_millis(16);
 
 pub struct View {
     terminal: Arc<Box<dyn Terminal + Sync + Send + 'static>>,
     scrollable_regions: HashMap<usize, ScrollableRegion>,
     render_caches: HashMap<usize, Rc<RefCell<HashMap<usize, RenderState>>>>,
+    split: Option<Split>,
+    spell_checker: Option<std::result::Result<SpellChecker, String>>,
     pub theme_set: ThemeSet,
     preferences: Rc<RefCell<Preferences>>,
     pub last_key: Option<Key>,
@@ -60,6 +72,8 @@ impl View {
             preferences,
             scrollable_regions: HashMap::new(),
             render_caches: HashMap::new(),
+            split: None,
+            spell_checker: None,
             theme_set,
             event_channel,
             event_listener_killswitch: killswitch_tx
@@ -75,7 +89,24 @@ impl View {
     ///
 
     pub fn scroll_to_cursor(&mut self, buffer: &Buffer) -> Result<()> {
-        self.get_region(buffer)?.scroll_into_view(&buffer);
+        let margin = self.preferences.borrow().scroll_margin();
+        self.get_region(buffer)?.scroll_into_view(&buffer, margin);
+
+        // Long lines are scrolled horizontally when they aren't wrapped.
+        let (line_wrapping, horizontal_margin, tab_width) = {
+            let preferences = self.preferences.borrow();
+            (
+                preferences.line_wrapping(),
+                preferences.horizontal_scroll_margin(),
+                preferences.tab_width(buffer.path.as_ref())
+            )
+        };
+        if line_wrapping {
+            self.get_region(buffer)?.reset_column_offset();
+        } else {
+            let column = cursor_column(buffer, tab_width);
+            self.get_region(buffer)?.scroll_column_into_view(&buffer, column, horizontal_margin);
+        }
 
         Ok(())
     }
@@ -86,6 +117,18 @@ impl View {
         Ok(())
     }
 
+    pub fn scroll_to_top(&mut self, buffer: &Buffer) -> Result<()> {
+        self.get_region(buffer)?.scroll_to_top(&buffer);
+
+        Ok(())
+    }
+
+    pub fn scroll_to_bottom(&mut self, buffer: &Buffer) -> Result<()> {
+        self.get_region(buffer)?.scroll_to_bottom(&buffer);
+
+        Ok(())
+    }
+
     pub fn scroll_up(&mut self, buffer: &Buffer, amount: usize) -> Result<()> {
         self.get_region(buffer)?.scroll_up(amount);
 
@@ -116,6 +159,164 @@ impl View {
         Ok(())
     }
 
+    /// How long to wait before stepping in-progress scroll animations, if any.
+    pub fn scroll_animation_timeout(&self) -> Option<Duration> {
+        if !self.preferences.borrow().smooth_scrolling() {
+            return None;
+        }
+
+        if self.scrollable_regions.values().any(|region| region.animating()) {
+            Some(SCROLL_ANIMATION_FRAME_INTERVAL)
+        } else {
+            None
+        }
+    }
+
+    pub fn step_scroll_animations(&mut self) {
+        for region in self.scrollable_regions.values_mut() {
+            region.step_animation();
+        }
+    }
+
+    /// Skips to the end of in-progress scroll animations,
+    /// so that they never hold up further input.
+    pub fn finish_scroll_animations(&mut self) {
+        for region in self.scrollable_regions.values_mut() {
+            region.finish_animation();
+        }
+    }
+
+    pub fn set_line_offset(&mut self, buffer: &Buffer, line_offset: usize) -> Result<()> {
+        let region = self.get_region(buffer)?;
+        region.scroll_up(region.line_offset());
+        region.scroll_down(line_offset);
+
+        Ok(())
+    }
+
+    ///
+    /// Split pane methods.
+    ///
+
+    /// Divides the screen into two panes, both initially showing the buffer.
+    pub fn split(&mut self, buffer: &Buffer, orientation: SplitOrientation) -> Result<()> {
+        if self.split.is_some() {
+            bail!("The view has already been split");
+        }
+
+        let pane = Pane {
+            buffer_id: buffer_key(buffer)?,
+            cursor: *buffer.cursor,
+            line_offset: self.get_region(buffer)?.line_offset(),
+        };
+        self.split = Some(Split::new(orientation, pane));
+
+        // The focused pane is smaller than the screen it used to fill.
+        self.scroll_to_cursor(buffer)
+    }
+
+    /// Removes the unfocused pane, leaving the focused one to fill the screen.
+    pub fn close_split(&mut self) -> Result<()> {
+        self.split.take().ok_or(VIEW_NOT_SPLIT)?;
+
+        Ok(())
+    }
+
+    /// Moves focus to the unfocused pane, swapping its state for that of the
+    /// currently focused buffer. It's up to the caller to select the returned
+    /// pane's buffer, and to restore its cursor and line offset.
+    pub fn swap_panes(&mut self, buffer: &Buffer) -> Result<Pane> {
+        let line_offset = self.get_region(buffer)?.line_offset();
+        let split = self.split.as_mut().ok_or(VIEW_NOT_SPLIT)?;
+        let focused_pane = Pane {
+            buffer_id: buffer_key(buffer)?,
+            cursor: *buffer.cursor,
+            line_offset,
+        };
+        split.first_pane_focused = !split.first_pane_focused;
+        split.unfocused_cells.clear();
+
+        Ok(mem::replace(&mut split.unfocused_pane, focused_pane))
+    }
+
+    /// Renders the unfocused pane's buffer, if the view is split, so that it
+    /// can be drawn alongside the focused pane; presenters only have access
+    /// to the current buffer. The view is unsplit if the pane's buffer has
+    /// been closed.
+    pub fn render_unfocused_pane(&mut self, workspace: &mut Workspace) -> Result<()> {
+        let (pane, area) = match self.split {
+            Some(ref split) => (
+                split.unfocused_pane.clone(),
+                split.unfocused_area(self.terminal.width(), self.terminal.height())
+            ),
+            None => return Ok(()),
+        };
+
+        let focused_buffer_id = workspace.current_buffer().and_then(|buffer| buffer.id);
+        if !util::select_buffer(workspace, pane.buffer_id) {
+            self.split = None;
+            return Ok(());
+        }
+
+        let cells = {
+            // Both panes may show the same buffer; render it using the
+            // unfocused pane's cursor, restoring the original afterwards.
+            let buffer = workspace.current_buffer().ok_or("Couldn't find the unfocused pane's buffer")?;
+            let focused_cursor = *buffer.cursor;
+            buffer.cursor.move_to(pane.cursor);
+
+            let data = buffer.data();
+            let cells = self
+                .build_presenter()
+                .and_then(|presenter| presenter.render_pane(buffer, &data, pane.line_offset, area));
+            buffer.cursor.move_to(focused_cursor);
+
+            cells
+        };
+        if let Some(id) = focused_buffer_id {
+            util::select_buffer(workspace, id);
+        }
+
+        if let Some(ref mut split) = self.split {
+            split.unfocused_cells = cells?;
+        }
+
+        Ok(())
+    }
+
+    /// The template used to describe the current buffer in the status line.
+    pub fn status_line_format(&self) -> String {
+        self.preferences.borrow().status_line_format().to_string()
+    }
+
+    /// The number of columns available to buffer content (i.e. excluding
+    /// its line number gutter), beyond which lines are wrapped.
+    pub fn buffer_content_width(&self, buffer: &Buffer) -> usize {
+        let width = self.focused_area()
+            .map(|area| area.width)
+            .unwrap_or_else(|| self.terminal.width());
+
+        let minimum_width = self.preferences.borrow().minimum_line_number_width();
+        let line_numbers = LineNumbers::new(buffer, None).with_minimum_width(minimum_width);
+
+        width.saturating_sub(line_numbers.width() + 1)
+    }
+
+    /// The number of lines visible in the focused pane (or the whole
+    /// screen), excluding the status line or divider below it.
+    pub fn buffer_content_height(&self) -> usize {
+        self.focused_area()
+            .map(|area| area.height)
+            .unwrap_or_else(|| self.terminal.height())
+            .saturating_sub(1)
+    }
+
+    fn focused_area(&self) -> Option<Area> {
+        self.split
+            .as_ref()
+            .map(|split| split.focused_area(self.terminal.width(), self.terminal.height()))
+    }
+
     /// Cleans up buffer-related view data. This method
     /// should be called whenever a buffer is closed.
     pub fn forget_buffer(&mut self, buffer: &Buffer) -> Result<()> {
@@ -128,12 +329,17 @@ impl View {
     // Tries to fetch a scrollable region for the specified buffer,
     // inserting (and returning a reference to) a new one if not.
     fn get_region(&mut self, buffer: &Buffer) -> Result<&mut ScrollableRegion> {
-        Ok(self.scrollable_regions
+        let area = self.focused_area();
+        let minimum_line_number_width = self.preferences.borrow().minimum_line_number_width();
+        let region = self.scrollable_regions
             .entry(buffer_key(buffer)?)
             .or_insert(
                 ScrollableRegion::new(self.terminal.clone())
-            )
-        )
+            );
+        region.set_area(area);
+        region.set_minimum_line_number_width(minimum_line_number_width);
+
+        Ok(region)
     }
 
     fn get_render_cache(&self, buffer: &Buffer) -> Result<&Rc<RefCell<HashMap<usize, RenderState>>>> {
@@ -152,6 +358,53 @@ impl View {
         self.event_listener_killswitch = killswitch_tx;
     }
 
+    /// Reloads the built-in and user-defined themes, picking
+    /// up any that were added to the themes directory.
+    pub fn reload_themes(&mut self) -> Result<()> {
+        let theme_path = self.preferences.borrow().theme_path()?;
+        self.theme_set = ThemeLoader::new(theme_path).load()?;
+
+        Ok(())
+    }
+
+    /// The spell checker, loading its dictionary the first time it's needed.
+    /// Failures to load the dictionary are remembered, rather than retried.
+    pub fn spell_checker(&mut self) -> Result<&SpellChecker> {
+        if self.spell_checker.is_none() {
+            let spell_checker = self.preferences
+                .borrow()
+                .spell_check_dictionary()
+                .and_then(|path| SpellChecker::load(&path))
+                .map_err(|error| error.to_string());
+            self.spell_checker = Some(spell_checker);
+        }
+
+        match self.spell_checker {
+            Some(Ok(ref spell_checker)) => Ok(spell_checker),
+            Some(Err(ref error)) => bail!(error.clone()),
+            None => bail!("Spell checker not loaded"),
+        }
+    }
+
+    /// Discards the spell checker, so that its
+    /// dictionary is reloaded the next time it's needed.
+    pub fn reload_spell_checker(&mut self) {
+        self.spell_checker = None;
+    }
+
+    // The spell checker, if spell checking is enabled for
+    // the buffer's type and its dictionary has been loaded.
+    fn loaded_spell_checker(&self, buffer: &Buffer) -> Option<&SpellChecker> {
+        if !self.preferences.borrow().spell_check(buffer.path.as_ref()) {
+            return None;
+        }
+
+        match self.spell_checker {
+            Some(Ok(ref spell_checker)) => Some(spell_checker),
+            _ => None,
+        }
+    }
+
     pub fn last_key(&self) -> &Option<Key> {
         &self.last_key
     }
@@ -182,6 +435,23 @@ impl Drop for View {
     }
 }
 
+/// The cursor's offset from the start of its line,
+/// as rendered on screen (i.e. with tabs expanded).
+fn cursor_column(buffer: &Buffer, tab_width: usize) -> usize {
+    let data = buffer.data();
+    let line = data.lines().nth(buffer.cursor.line).unwrap_or("");
+
+    line.graphemes(true)
+        .take(buffer.cursor.offset)
+        .fold(0, |column, grapheme| {
+            if grapheme == "\t" {
+                (column / tab_width.max(1) + 1) * tab_width.max(1)
+            } else {
+                column + 1
+            }
+        })
+}
+
 fn buffer_key(buffer: &Buffer) -> Result<usize> {
     buffer.id.ok_or_else(|| Error::from("Buffer ID doesn't exist"))
 }
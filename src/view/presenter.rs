@@ -3,8 +3,8 @@ use crate::view::buffer::{BufferRenderer, LexemeMapper};
 use crate::view::color::{ColorMap, Colors};
 use crate::view::StatusLineData;
 use crate::view::style::Style;
-use crate::view::terminal::{Cell, TerminalBuffer};
-use crate::view::View;
+use crate::view::terminal::{Cell, PaneTerminal, TerminalBuffer};
+use crate::view::{Area, View};
 use pad::PadStr;
 use scribe::buffer::{Buffer, Position, Range};
 use scribe::util::LineIterator;
@@ -23,8 +23,12 @@ impl<'p> Presenter<'p> {
         let theme = {
             let preferences = view.preferences.borrow();
             let theme_name = preferences.theme();
+
+            // Fall back to the default theme, rather than failing to render
+            // anything at all because of a typo in the user's preferences.
             let theme = view.theme_set.themes
                 .get(theme_name)
+                .or_else(|| view.theme_set.themes.get(preferences.default_theme()))
                 .ok_or_else(|| format!("Couldn't find \"{}\" theme", theme_name))?;
             theme.clone()
         };
@@ -73,7 +77,41 @@ impl<'p> Presenter<'p> {
         &mut self, buffer: &Buffer, buffer_data: &'p str,
         highlights: Option<&[Range]>, lexeme_mapper: Option<&'p mut dyn LexemeMapper>,
     ) -> Result<()> {
-        let scroll_offset = self.view.get_region(buffer)?.line_offset();
+        let (animate, line_wrapping, spell_check) = {
+            let preferences = self.view.preferences.borrow();
+            (
+                preferences.smooth_scrolling(),
+                preferences.line_wrapping(),
+                preferences.spell_check(buffer.path.as_ref())
+            )
+        };
+        if spell_check {
+            // Load the dictionary ahead of rendering; if it's
+            // unavailable, the buffer is rendered without it.
+            let _ = self.view.spell_checker();
+        }
+        let scroll_offset = self.view.get_region(buffer)?.displayed_line_offset(animate);
+        let column_offset = if line_wrapping {
+            0
+        } else {
+            self.view.get_region(buffer)?.column_offset()
+        };
+
+        if let Some(area) = self.view.focused_area() {
+            // The view is split; confine the buffer to the focused pane,
+            // and draw the (previously rendered) unfocused pane beside it.
+            let (pane_buffer, cursor_position) = self.render_to_area(
+                buffer, buffer_data, highlights, lexeme_mapper, scroll_offset, area
+            )?;
+            for (position, cell) in pane_buffer.into_cells() {
+                self.terminal_buffer.set_cell(area.translate(position), cell);
+            }
+            self.cursor_position = cursor_position.map(|position| area.translate(position));
+            self.print_unfocused_pane();
+
+            return Ok(());
+        }
+
         let lines = LineIterator::new(buffer_data);
 
         self.cursor_position = BufferRenderer::new(
@@ -85,11 +123,78 @@ impl<'p> Presenter<'p> {
             &self.view.preferences.borrow(),
             self.view.get_render_cache(buffer)?,
             &mut self.terminal_buffer,
-        ).render(lines, lexeme_mapper)?;
+        )
+        .scrolled_to_column(column_offset)
+        .spell_checked_with(self.view.loaded_spell_checker(buffer))
+        .render(lines, lexeme_mapper)?;
 
         Ok(())
     }
 
+    /// Renders the buffer into the specified area, for display as an unfocused
+    /// pane. Its cells are positioned relative to the terminal, and own their
+    /// content, so that they can outlive both the buffer data and the presenter.
+    pub fn render_pane(&self, buffer: &Buffer, buffer_data: &'p str,
+                       scroll_offset: usize, area: Area) -> Result<Vec<(Position, Cell<'static>)>> {
+        let (pane_buffer, _) = self.render_to_area(buffer, buffer_data, None, None, scroll_offset, area)?;
+
+        Ok(pane_buffer
+            .into_cells()
+            .map(|(position, cell)| {
+                (area.translate(position), Cell {
+                    content: Cow::Owned(cell.content.into_owned()),
+                    style: cell.style,
+                    colors: cell.colors,
+                })
+            })
+            .collect())
+    }
+
+    // Renders the buffer into a terminal buffer sized to fit the area,
+    // returning it along with the cursor's position relative to the area.
+    fn render_to_area(
+        &self, buffer: &Buffer, buffer_data: &'p str, highlights: Option<&[Range]>,
+        lexeme_mapper: Option<&'p mut dyn LexemeMapper>, scroll_offset: usize, area: Area,
+    ) -> Result<(TerminalBuffer<'p>, Option<Position>)> {
+        let terminal = PaneTerminal::new(&**self.view.terminal, area);
+        let mut pane_buffer = TerminalBuffer::new(area.width, area.height);
+
+        let cursor_position = BufferRenderer::new(
+            buffer,
+            highlights,
+            scroll_offset,
+            &terminal,
+            &self.theme,
+            &self.view.preferences.borrow(),
+            self.view.get_render_cache(buffer)?,
+            &mut pane_buffer,
+        )
+        .spell_checked_with(self.view.loaded_spell_checker(buffer))
+        .render(LineIterator::new(buffer_data), lexeme_mapper)?;
+
+        Ok((pane_buffer, cursor_position))
+    }
+
+    fn print_unfocused_pane(&mut self) {
+        let (width, height) = (self.width(), self.height());
+        let split = match self.view.split {
+            Some(ref split) => split,
+            None => return,
+        };
+
+        for (position, cell) in split.unfocused_cells.iter() {
+            self.terminal_buffer.set_cell(*position, cell.clone());
+        }
+
+        let (divider_positions, divider) = split.divider(width, height);
+        for position in divider_positions {
+            self.terminal_buffer.set_cell(
+                position,
+                Cell{ content: divider.into(), style: Style::Default, colors: Colors::Default }
+            );
+        }
+    }
+
     pub fn print_status_line(&mut self, entries: &[StatusLineData]) {
         let line = self.view.terminal.height() - 1;
 
@@ -152,6 +257,16 @@ mod tests {
     use std::rc::Rc;
     use std::sync::mpsc;
 
+    #[test]
+    fn new_falls_back_to_the_default_theme_when_the_configured_one_is_missing() {
+        let preferences = Rc::new(RefCell::new(Preferences::new(None)));
+        preferences.borrow_mut().set_theme("amp_missing_theme");
+        let (tx, _) = mpsc::channel();
+        let mut view = View::new(preferences, tx).unwrap();
+
+        assert!(view.build_presenter().is_ok());
+    }
+
     #[test]
     fn print_buffer_initializes_renderer_with_cached_state() {
         let preferences = Rc::new(RefCell::new(Preferences::new(None)));
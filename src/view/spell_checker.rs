@@ -0,0 +1,177 @@
+use crate::errors::*;
+use std::cell::RefCell;
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::Path;
+use unicode_segmentation::UnicodeSegmentation;
+
+// The system word list, used when the configured dictionary doesn't exist.
+const SYSTEM_DICTIONARY_PATH: &str = "/usr/share/dict/words";
+
+// The number of checked lines to remember before starting over.
+const CACHE_LIMIT: usize = 10_000;
+
+/// A dictionary of correctly spelled words, used to find misspellings in prose.
+/// Lines are checked as they're rendered, with the results cached by content,
+/// so that large documents are only checked as they're viewed (and edited).
+pub struct SpellChecker {
+    words: HashSet<String>,
+    misspellings: RefCell<HashMap<String, Vec<(usize, usize)>>>,
+}
+
+impl SpellChecker {
+    pub fn new<I: IntoIterator<Item = String>>(words: I) -> SpellChecker {
+        SpellChecker {
+            words: words.into_iter().map(|word| word.to_lowercase()).collect(),
+            misspellings: RefCell::new(HashMap::new()),
+        }
+    }
+
+    /// Loads a dictionary with one word per line, falling
+    /// back to the system word list if it doesn't exist.
+    pub fn load(path: &Path) -> Result<SpellChecker> {
+        let path = if path.exists() { path } else { Path::new(SYSTEM_DICTIONARY_PATH) };
+        let data = fs::read_to_string(path).chain_err(|| {
+            format!("Couldn't read spelling dictionary at {}", path.to_string_lossy())
+        })?;
+
+        Ok(SpellChecker::new(data.lines().map(|word| word.trim().to_string())))
+    }
+
+    /// Whether the word is in the dictionary, ignoring case. Words
+    /// with numbers in them (e.g. versions or units) are considered
+    /// correct, since they're unlikely to be in a dictionary.
+    pub fn is_correct(&self, word: &str) -> bool {
+        word.chars().any(|c| c.is_numeric()) || self.words.contains(&word.to_lowercase())
+    }
+
+    /// The (grapheme-based) start and end offsets of the line's misspelled words.
+    pub fn misspellings(&self, line: &str) -> Vec<(usize, usize)> {
+        if let Some(misspellings) = self.misspellings.borrow().get(line) {
+            return misspellings.clone();
+        }
+
+        let misspellings: Vec<(usize, usize)> = words(line)
+            .into_iter()
+            .filter(|(_, word)| !self.is_correct(word))
+            .map(|(offset, word)| (offset, offset + word.graphemes(true).count()))
+            .collect();
+
+        let mut cache = self.misspellings.borrow_mut();
+        if cache.len() >= CACHE_LIMIT {
+            cache.clear();
+        }
+        cache.insert(line.to_string(), misspellings.clone());
+
+        misspellings
+    }
+
+    /// The word at the (grapheme-based) offset in the
+    /// line, if there is one, along with its start offset.
+    pub fn word_at(line: &str, offset: usize) -> Option<(usize, &str)> {
+        words(line)
+            .into_iter()
+            .find(|(start, word)| offset >= *start && offset < start + word.graphemes(true).count())
+    }
+
+    /// Dictionary words similar to the specified one, closest first,
+    /// capitalized to match it.
+    pub fn suggestions(&self, word: &str, limit: usize) -> Vec<String> {
+        let target: Vec<char> = word.to_lowercase().chars().collect();
+        let mut candidates: Vec<(usize, &String)> = self.words
+            .iter()
+            .filter_map(|candidate| {
+                let length = candidate.chars().count();
+                if length + 2 < target.len() || target.len() + 2 < length {
+                    return None;
+                }
+
+                let distance = edit_distance(&target, &candidate.chars().collect::<Vec<_>>());
+                if distance <= 2 { Some((distance, candidate)) } else { None }
+            })
+            .collect();
+        candidates.sort();
+
+        let capitalized = word.chars().next().map_or(false, |c| c.is_uppercase());
+        candidates
+            .into_iter()
+            .take(limit)
+            .map(|(_, candidate)| if capitalized {
+                capitalize(candidate)
+            } else {
+                candidate.clone()
+            })
+            .collect()
+    }
+}
+
+// The words in the line, along with their (grapheme-based)
+// offsets. Words are runs of letters, including apostrophes.
+fn words(line: &str) -> Vec<(usize, &str)> {
+    let mut offset = 0;
+    let mut words = Vec::new();
+    for segment in line.split_word_bounds() {
+        if segment.chars().next().map_or(false, |c| c.is_alphabetic()) {
+            words.push((offset, segment));
+        }
+        offset += segment.graphemes(true).count();
+    }
+
+    words
+}
+
+// The number of single-character insertions, deletions,
+// and substitutions needed to turn one word into another.
+fn edit_distance(first: &[char], second: &[char]) -> usize {
+    let mut previous_row: Vec<usize> = (0..=second.len()).collect();
+    for (i, first_char) in first.iter().enumerate() {
+        let mut row = vec![i + 1];
+        for (j, second_char) in second.iter().enumerate() {
+            let substitution = previous_row[j] + if first_char == second_char { 0 } else { 1 };
+            row.push(substitution.min(previous_row[j + 1] + 1).min(row[j] + 1));
+        }
+        previous_row = row;
+    }
+
+    previous_row[second.len()]
+}
+
+fn capitalize(word: &str) -> String {
+    let mut characters = word.chars();
+    match characters.next() {
+        Some(first) => first.to_uppercase().chain(characters).collect(),
+        None => String::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::SpellChecker;
+
+    fn spell_checker() -> SpellChecker {
+        SpellChecker::new(vec!["amp", "editor", "edit", "it's"].into_iter().map(String::from))
+    }
+
+    #[test]
+    fn misspellings_finds_words_missing_from_the_dictionary() {
+        let checker = spell_checker();
+
+        assert_eq!(checker.misspellings("Amp edtor, it's 2x amp"), vec![(4, 9)]);
+        assert!(checker.misspellings("amp editor").is_empty());
+    }
+
+    #[test]
+    fn word_at_finds_the_word_including_the_offset() {
+        assert_eq!(SpellChecker::word_at("amp, editor", 6), Some((5, "editor")));
+        assert_eq!(SpellChecker::word_at("amp, editor", 3), None);
+    }
+
+    #[test]
+    fn suggestions_are_ordered_by_similarity_and_match_capitalization() {
+        let checker = spell_checker();
+
+        assert_eq!(checker.suggestions("Editr", 5), vec!["Edit", "Editor"]);
+        assert_eq!(checker.suggestions("ampp", 1), vec!["amp"]);
+        assert!(checker.suggestions("xyz", 5).is_empty());
+    }
+}
@@ -0,0 +1,192 @@
+use crate::view::terminal::Cell;
+use scribe::buffer::Position;
+
+/// How the terminal is divided between the panes of a split view.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum SplitOrientation {
+    /// Panes are stacked, one above the other.
+    Horizontal,
+    /// Panes are placed side by side.
+    Vertical,
+}
+
+/// A rectangular section of the terminal. Like the terminal itself, its
+/// last row is reserved; buffers are rendered in the rows above it.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Area {
+    pub origin: Position,
+    pub width: usize,
+    pub height: usize,
+}
+
+impl Area {
+    /// Converts a position relative to the area into one relative to the terminal.
+    pub fn translate(&self, position: Position) -> Position {
+        Position {
+            line: self.origin.line + position.line,
+            offset: self.origin.offset + position.offset,
+        }
+    }
+}
+
+/// The state of the pane that doesn't have focus. The focused pane is
+/// always the workspace's current buffer, so it needs no such tracking.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Pane {
+    pub buffer_id: usize,
+    pub cursor: Position,
+    pub line_offset: usize,
+}
+
+/// A view divided between two panes, which can show different buffers,
+/// or different regions of the same buffer.
+pub struct Split {
+    pub orientation: SplitOrientation,
+    pub unfocused_pane: Pane,
+    pub first_pane_focused: bool,
+
+    // The unfocused pane's rendered content, positioned relative to the
+    // terminal. Presenters only have access to the current buffer, so
+    // this is prepared ahead of their rendering the focused pane.
+    pub unfocused_cells: Vec<(Position, Cell<'static>)>,
+}
+
+impl Split {
+    pub fn new(orientation: SplitOrientation, unfocused_pane: Pane) -> Split {
+        Split {
+            orientation,
+            unfocused_pane,
+            first_pane_focused: true,
+            unfocused_cells: Vec::new(),
+        }
+    }
+
+    pub fn focused_area(&self, width: usize, height: usize) -> Area {
+        let (first, second) = self.areas(width, height);
+
+        if self.first_pane_focused { first } else { second }
+    }
+
+    pub fn unfocused_area(&self, width: usize, height: usize) -> Area {
+        let (first, second) = self.areas(width, height);
+
+        if self.first_pane_focused { second } else { first }
+    }
+
+    /// The cells separating the panes, along with their content.
+    pub fn divider(&self, width: usize, height: usize) -> (Vec<Position>, &'static str) {
+        let (first, _) = self.areas(width, height);
+
+        match self.orientation {
+            SplitOrientation::Horizontal => {
+                let line = first.height.saturating_sub(1);
+                ((0..width).map(|offset| Position{ line, offset }).collect(), "─")
+            },
+            SplitOrientation::Vertical => {
+                let offset = first.width;
+                ((0..height.saturating_sub(1)).map(|line| Position{ line, offset }).collect(), "│")
+            },
+        }
+    }
+
+    // Divides the terminal in half. Stacked panes use the first pane's
+    // reserved row as the divider (the second uses the status line),
+    // whereas side-by-side panes are separated by a one-column divider.
+    fn areas(&self, width: usize, height: usize) -> (Area, Area) {
+        match self.orientation {
+            SplitOrientation::Horizontal => {
+                let first_height = height / 2;
+
+                (
+                    Area{ origin: Position{ line: 0, offset: 0 }, width, height: first_height },
+                    Area{
+                        origin: Position{ line: first_height, offset: 0 },
+                        width,
+                        height: height - first_height
+                    },
+                )
+            },
+            SplitOrientation::Vertical => {
+                let first_width = width / 2;
+
+                (
+                    Area{ origin: Position{ line: 0, offset: 0 }, width: first_width, height },
+                    Area{
+                        origin: Position{ line: 0, offset: first_width + 1 },
+                        width: width.saturating_sub(first_width + 1),
+                        height
+                    },
+                )
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Area, Pane, Split, SplitOrientation};
+    use scribe::buffer::Position;
+
+    fn pane() -> Pane {
+        Pane{ buffer_id: 0, cursor: Position{ line: 0, offset: 0 }, line_offset: 0 }
+    }
+
+    #[test]
+    fn horizontal_splits_stack_panes() {
+        let mut split = Split::new(SplitOrientation::Horizontal, pane());
+
+        assert_eq!(split.focused_area(80, 25), Area{
+            origin: Position{ line: 0, offset: 0 },
+            width: 80,
+            height: 12,
+        });
+        split.first_pane_focused = false;
+        assert_eq!(split.focused_area(80, 25), Area{
+            origin: Position{ line: 12, offset: 0 },
+            width: 80,
+            height: 13,
+        });
+
+        let (positions, _) = split.divider(80, 25);
+        assert_eq!(positions.len(), 80);
+        assert!(positions.iter().all(|position| position.line == 11));
+    }
+
+    #[test]
+    fn vertical_splits_place_panes_side_by_side() {
+        let split = Split::new(SplitOrientation::Vertical, pane());
+
+        assert_eq!(split.focused_area(81, 25), Area{
+            origin: Position{ line: 0, offset: 0 },
+            width: 40,
+            height: 25,
+        });
+        assert_eq!(split.unfocused_area(81, 25), Area{
+            origin: Position{ line: 0, offset: 41 },
+            width: 40,
+            height: 25,
+        });
+
+        let (positions, _) = split.divider(81, 25);
+        assert_eq!(positions.len(), 24);
+        assert!(positions.iter().all(|position| position.offset == 40));
+    }
+
+    #[test]
+    fn splits_tolerate_terminals_too_small_to_divide() {
+        for &orientation in [SplitOrientation::Horizontal, SplitOrientation::Vertical].iter() {
+            let split = Split::new(orientation, pane());
+
+            split.divider(0, 0);
+            split.divider(1, 1);
+            assert_eq!(split.unfocused_area(0, 0).width, 0);
+        }
+    }
+
+    #[test]
+    fn translate_offsets_positions_by_the_area_origin() {
+        let area = Area{ origin: Position{ line: 2, offset: 3 }, width: 10, height: 10 };
+
+        assert_eq!(area.translate(Position{ line: 1, offset: 1 }), Position{ line: 3, offset: 4 });
+    }
+}
@@ -4,6 +4,7 @@ pub enum Style {
     Bold,
     Inverted,
     Italic,
+    Underlined,
 }
 
 impl Default for Style {
@@ -32,6 +32,15 @@ impl<'c> TerminalBuffer<'c> {
         TerminalBufferIterator::new(self.width, &self.cells)
     }
 
+    /// Consumes the buffer, yielding each of its cells along with its position.
+    pub fn into_cells(self) -> impl Iterator<Item = (Position, Cell<'c>)> {
+        let width = self.width;
+
+        self.cells.into_iter().enumerate().map(move |(index, cell)| {
+            (Position{ line: index / width, offset: index % width }, cell)
+        })
+    }
+
     #[cfg(test)]
     // For testing purposes, produces a String representation of the
     // terminal buffer that can be used to assert a particular state.
@@ -1,6 +1,7 @@
 mod buffer;
 mod buffer_iterator;
 mod cell;
+mod pane_terminal;
 mod termion_terminal;
 
 #[cfg(any(test, feature = "bench"))]
@@ -15,6 +16,7 @@ use std::sync::Arc;
 pub use self::buffer::TerminalBuffer;
 pub use self::buffer_iterator::TerminalBufferIterator;
 pub use self::cell::Cell;
+pub use self::pane_terminal::PaneTerminal;
 pub use self::termion_terminal::TermionTerminal;
 
 #[cfg(any(test, feature = "bench"))]
@@ -0,0 +1,51 @@
+use crate::models::application::Event;
+use crate::view::{Area, Colors, Style};
+use crate::view::terminal::Terminal;
+use scribe::buffer::Position;
+
+/// Presents an area of a terminal as a terminal of its own, so that content
+/// can be laid out in a split pane as though it had the screen to itself.
+pub struct PaneTerminal<'a> {
+    terminal: &'a dyn Terminal,
+    area: Area,
+}
+
+impl<'a> PaneTerminal<'a> {
+    pub fn new(terminal: &'a dyn Terminal, area: Area) -> PaneTerminal<'a> {
+        PaneTerminal { terminal, area }
+    }
+}
+
+impl<'a> Terminal for PaneTerminal<'a> {
+    fn listen(&self) -> Option<Event> {
+        self.terminal.listen()
+    }
+
+    fn clear(&self) {
+        self.terminal.clear()
+    }
+
+    fn present(&self) {
+        self.terminal.present()
+    }
+
+    fn width(&self) -> usize {
+        self.area.width
+    }
+
+    fn height(&self) -> usize {
+        self.area.height
+    }
+
+    fn set_cursor(&self, position: Option<Position>) {
+        self.terminal.set_cursor(position.map(|position| self.area.translate(position)))
+    }
+
+    fn print<'b>(&self, position: &Position, style: Style, colors: Colors, content: &str) {
+        self.terminal.print(&self.area.translate(*position), style, colors, content)
+    }
+
+    fn suspend(&self) {
+        self.terminal.suspend()
+    }
+}
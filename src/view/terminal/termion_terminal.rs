@@ -160,6 +160,7 @@ impl Terminal for TermionTerminal {
                         TermEvent::Key(TermionKey::Esc) => Some(Event::Key(Key::Esc)),
                         TermEvent::Key(TermionKey::Char('\n')) => Some(Event::Key(Key::Enter)),
                         TermEvent::Key(TermionKey::Char('\t')) => Some(Event::Key(Key::Tab)),
+                        TermEvent::Key(TermionKey::BackTab) => Some(Event::Key(Key::BackTab)),
                         TermEvent::Key(TermionKey::Char(c)) => Some(Event::Key(Key::Char(c))),
                         TermEvent::Key(TermionKey::Ctrl(c)) => Some(Event::Key(Key::Ctrl(c))),
                         TermEvent::Mouse(MouseEvent::Press(MouseButton::WheelUp, _x, _y)) =>
@@ -356,5 +357,6 @@ fn map_style(style: Style) -> Option<Box<dyn Display>> {
         Style::Bold => Some(Box::new(style::Bold)),
         Style::Inverted => Some(Box::new(style::Invert)),
         Style::Italic => Some(Box::new(style::Italic)),
+        Style::Underlined => Some(Box::new(style::Underline)),
     }
 }